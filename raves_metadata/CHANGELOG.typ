@@ -12,6 +12,90 @@ This file is ordered from newest to oldest.
   - Eager parsing seems to be the same amount of "efficient" for most files, as we already gotta parse them anyway!
 - Simplify API (one `MetadataProvider`)
   - In other words, there's no longer a `MetadataProviderRaw`!
+- Add schema-aware editing to `XmpDocument`
+  - `set`, `remove`, `append_to_array`, and `set_localized_text` check new values against `raves_metadata_types`' parse table.
+  - Prefixes for new namespaces are picked automatically.
+- Add language alternative helpers (`xmp::lang`)
+  - Pick the best translation for a list of preferred locales, with BCP 47 fallback.
+  - Setting one language keeps `x-default` consistent, per XMP Part 1.
+- Add XMP path queries (`XmpDocument::query`), using the XMP Toolkit's path syntax
+- Add image regions (`XmpDocument::regions`, `XmpDocument::face_regions`)
+  - Reads MWG Regions, Microsoft Photo people tags, and IPTC Image Regions into one list of relative rectangles.
+  - The MWG Regions, MWG Keywords, Microsoft Photo, and IPTC Extension region schemas are now in the parse table.
+- Fix struct fields written as attributes on an inner `rdf:Description` being dropped
+- Add Google's camera XMP schemas (`GCamera`, `Container`, `GDepth`, `GImage`, and `GPano`) to the parse table
+  - Typed accessors in `xmp::google` read Motion Photo info, container items, and panorama cropping/pose.
+- Add Motion Photo extraction (`Jpeg::motion_photo`, `Heic::motion_photo`)
+  - Finds the embedded MP4 from Google's container XMP, Google's older MicroVideo offset, Samsung's `SEFT` trailer, or a HEIC `mpvd` box.
+  - The returned byte range can be parsed directly with `Mp4`.
+- Add Multi-Picture Format (MPF) parsing for JPEG (`Jpeg::mpf`)
+  - Lists each image's type, size, and absolute offset, along with its own Exif.
+  - The MP Index IFD reuses the Exif parser, with MPF tags in `raves_metadata_types`.
+- Add HDR gain map info (`Jpeg::gain_map`, `Heic::gain_map`, `Avif::gain_map`)
+  - Reads Ultra HDR's `hdrgm` XMP, ISO 21496-1 metadata (HEIF `tmap` items and JPEG `APP2`), and Apple's `HDRGainMap` XMP into one `GainMapInfo`. Apple's headroom gets its own field, since it isn't a log2 gain.
+  - Also gives the gain map image's byte range in the file.
+- Fix HEIF `idat` boxes being read from the wrong place
+- Add `JpegSegments`, a lossless list of a JPEG's segments before `SOS`
+  - Each segment has its marker code, offset, length, and payload.
+  - Segments can be inserted, replaced, or removed. Unchanged files are written back byte-for-byte.
+- Add `MetadataProvider::image_info` (and `AnyProvider::image_info`) for an image's dimensions, bit depth, color type, and frame count
+  - Read from JPEG `SOFn`, PNG `IHDR`/`acTL`, GIF descriptors, WebP `VP8 `/`VP8L`/`VP8X`, and the HEIF primary item's `ispe`/`pixi`.
+  - JPEGs also report whether they're progressive, arithmetic-coded, lossless, or differential.
+- Add `Png::text` and `Png::find_text` for every `tEXt`, `zTXt`, and `iTXt` chunk
+  - Each has its keyword, language, translated keyword, and decoded text. Compressed text is inflated with `miniz_oxide`.
+  - XMP is now read from compressed `iTXt` chunks, and from text chunks after the image data.
+- Read ImageMagick-style `Raw profile type ...` PNG text chunks
+  - Exif and XMP profiles are used when `eXIf` or the XMP `iTXt` chunk is missing. `Png::exif_origin` and `Png::xmp_origin` say which was used.
+  - IPTC profiles are read as IIM, so PNGs now support `MetadataProvider::iptc`. `Png::iptc_origin` gives the profile's keyword.
+  - Add `Iptc::new_iim`, which reads IIM datasets, including ones in a Photoshop image resource block.
+  - All profiles (including IPTC and ICC) are decoded in `Png::raw_profiles`.
+- Add `Png::ancillary` for typed PNG ancillary chunks
+  - Includes `tIME`, `pHYs` (with `dpi`), `sRGB`, `gAMA`, `cHRM`, and `sBIT`.
+  - HDR chunks `cICP`, `mDCv`, and `cLLi` are read too, so PQ and HLG images can be detected.
+- Add `MetadataProvider::animation_info` (and `AnyProvider::animation_info`) for frame count, per-frame delays, total duration, and loop count
+  - Read from GIF graphic control extensions and `NETSCAPE2.0`, APNG `acTL`/`fcTL`, WebP `ANIM`/`ANMF`, and AVIF `avis` tracks.
+- Fix `Gif::repeatable_blocks` always being empty
+- Expose WebP structure: `Webp::header`, `Webp::extended_header` (the full `VP8X` header), `Webp::chunks` (with offsets), and `Webp::icc_profile`
+  - Fix the XMP flag being read from the alpha bit.
+  - `EXIF` and `XMP ` chunks are now found even when the `VP8X` flags don't mention them.
+  - Odd-length chunks now skip their padding byte everywhere.
+- Expose the HEIF item graph with `Heic::items` and `Avif::items`
+  - Lists every `iinf` item with its type, name, hidden flag, and extents, plus the `pitm` primary item and all `iref` references (`cdsc`, `thmb`, `auxl`, `dimg`, `base`).
+  - Exif and XMP are now taken from the items describing the primary image, so burst and multi-image files no longer report another image's metadata.
+  - Items split into multiple extents are now joined. `HeifLikeConstructionError::ParserBugMultipleExtentsNotSupported` was removed.
+  - Fix a panic on Exif items shorter than two bytes.
+- Parse HEIF item properties into `HeifItem::properties`, with their essential flags
+  - Typed `irot`, `imir`, `clap`, `ispe`, `pixi`, `auxC`, `colr`, `av1C`, `hvcC`, and `lsel` properties. Others are kept as raw payloads.
+  - Add `HeifItem::spatial_extent` and `HeifItem::aux_type`.
+- Add `MetadataProvider::display_orientation` (and `AnyProvider::display_orientation`) for a normalized mirror-then-rotate orientation
+  - Exif `Orientation` is used by default. HEIC and AVIF use the primary item's `irot` and `imir` instead, and MP4 and MOV use the video track's `tkhd` matrix.
+  - `DisplayOrientation` converts to and from Exif `Orientation` values.
+- Add `depth_maps()` to `Heic`, `Avif`, and `Jpeg` for portrait-mode depth maps and mattes
+  - HEIF auxiliary images with depth or Apple matte `auxC` types are listed with their item, location, codec, and size.
+  - JPEG `GDepth:Data` is decoded from XMP, and `Container` items with the `Depth` semantic are located after the image.
+  - Depth parameters (format, near, far, units, and measure type) come from `GDepth` XMP.
+- Read Exif from MP4 and MOV files made by cameras
+  - Canon's `uuid` box (`CMT1`, with the `CMT2` Exif and `CMT4` GPS IFDs attached), TIFF blocks in `udta/MVTG`, and `Exif` items in a top-level `meta` box are supported.
+  - Nikon's `udta/NCTG` tags are sorted into IFD 0 and the Exif and GPS IFDs.
+  - DJI clips aren't supported, since they don't store Exif.
+- Add `Mp4::telemetry` for GoPro GPMF telemetry
+  - GPS (`GPS5` and `GPS9`), accelerometer, and gyroscope readings are scaled and timestamped from the `gpmd` track's samples.
+  - Tracks' sample tables (`stsz`, `stco`, `co64`, `stsc`, and `stts`) are now read to find each sample.
+- Add `spherical()` and `camm()` to `Mp4` and `Mov` for 360-degree video
+  - Google Spherical Video V1 (`uuid` XML) and V2 (`st3d`, `sv3d`, and `proj`) give the projection, stereo mode, initial pose, and software.
+  - Camera Motion Metadata (`camm`) tracks are decoded into timestamped packets, including GPS, orientation, gyroscope, and acceleration.
+- Read iTunes-style tags from MP4 and MOV with `ilst()`
+  - `Mp4` now accepts Apple's `M4A `, `M4B `, `M4P `, and `M4V ` brands.
+  - `data` atoms are typed as text, signed or unsigned integers, floats, or JPEG/PNG images, with helpers for the title, artist, date, description, and cover art.
+  - Freeform (`----`) tags and QuickTime `keys`-based tags (like in screen recordings) are read, too.
+- Add a `Matroska` provider for `.mkv` and `.webm` files
+  - It's detected by the EBML header (`1A 45 DF A3`) with a `matroska` or `webm` `DocType`.
+  - `info()` has the title, date, duration, and muxing/writing apps. `tracks()` has each track's codec and dimensions, and `tags()` has each `Tag` and its `SimpleTag`s.
+  - Unknown-size elements (from live recordings) are supported, and the `SeekHead` is used to find elements that a linear read missed.
+- Add `Avi` and `Wav` providers, on a RIFF layer shared with WebP
+  - Both expose XMP (from `_PMX`) and `LIST/INFO` tags through `info()`.
+  - `Wav` also reads Broadcast WAV (`bext`) chunks and `iXML`.
+  - Chunks inside nested `LIST`s are found, and odd-length chunks are padded correctly.
 
 == v0.0.4
 
@@ -48,6 +48,18 @@ pub struct Exif {
 impl Exif {
     /// Parses the given Exif blob into our `Exif` structure.
     pub fn new(input: &mut &[u8]) -> ExifFatalResult<Self> {
+        Self::new_with_group(input, IfdGroup::_0)
+    }
+
+    /// Parses a TIFF-structured blob, treating its top-level IFDs as members
+    /// of the given IFD group.
+    ///
+    /// Exif always starts with IFD 0, but other formats (like MPF) reuse the
+    /// same structure with their own tags.
+    pub(crate) fn new_with_group(
+        input: &mut &[u8],
+        first_group: IfdGroup,
+    ) -> ExifFatalResult<Self> {
         #[expect(
             suspicious_double_ref_op,
             reason = "we want to save the original slice (\"blob\") for absolute offsets"
@@ -70,7 +82,7 @@ impl Exif {
             input,
             state: State {
                 blob,
-                current_ifd: IfdGroup::_0, // we always start with IFD 0
+                current_ifd: first_group,
                 endianness: &winnow_endianness,
                 recursion_ct: 0,
                 recursion_stack: [None; RECURSION_LIMIT as usize],
@@ -3,10 +3,13 @@
 use std::sync::Arc;
 
 /// An error that occurred while parsing IPTC.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum IptcError {
     /// Failed to parse provided XMP document.
     Iptc4Xmp(Iptc4XmpError),
+
+    /// Failed to parse IIM datasets.
+    Iim(IimError),
 }
 
 /// This is an error that happened while we were parsing IPTC through XMP.
@@ -59,3 +62,31 @@ impl From<xmltree::ParseError> for Iptc4XmpError {
         Iptc4XmpError::XmlParseError(value.into())
     }
 }
+
+/// This is an error that happened while we were parsing IPTC IIM.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub enum IimError {
+    /// The data didn't start with an IIM dataset or a Photoshop image
+    /// resource.
+    NotIim,
+
+    /// The Photoshop image resource block had no IPTC resource (`0x0404`).
+    NoIptcResource,
+
+    /// A dataset was cut off.
+    Truncated,
+}
+
+impl core::fmt::Display for IimError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            IimError::NotIim => f.write_str("The data didn't start with an IIM dataset."),
+            IimError::NoIptcResource => {
+                f.write_str("The Photoshop image resource block had no IPTC resource.")
+            }
+            IimError::Truncated => f.write_str("An IIM dataset was cut off."),
+        }
+    }
+}
+
+impl core::error::Error for IimError {}
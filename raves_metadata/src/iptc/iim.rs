@@ -0,0 +1,303 @@
+//! Older files store IPTC as IIM (the Information Interchange Model).
+//!
+//! IIM is a list of datasets. Each one has a tag marker (`0x1C`), a record
+//! number, a dataset number, a length, and then its data. The properties in
+//! the IPTC Photo Metadata Standard are all in the application record (`2`).
+//!
+//! IIM is often wrapped in a Photoshop image resource block, where it's the
+//! `0x0404` resource. Both forms are accepted here.
+
+use raves_metadata_types::iptc::{IptcKey as K, IptcKeyValue as Kv};
+use winnow::{
+    Parser as _,
+    binary::{be_u8, be_u16, be_u32},
+    error::EmptyError,
+    token::take,
+};
+
+use super::{error::IimError, iptc4xmp::iptc_pair_from_simple_text_value};
+use crate::Iptc;
+
+/// Starts every IIM dataset.
+const TAG_MARKER: u8 = 0x1C;
+
+/// Starts every Photoshop image resource.
+const PHOTOSHOP_RESOURCE: &[u8] = b"8BIM";
+
+/// The Photoshop image resource holding IIM.
+const IPTC_RESOURCE_ID: u16 = 0x0404;
+
+/// The record holding the IPTC Photo Metadata Standard's properties.
+const APPLICATION_RECORD: u8 = 2;
+
+/// Every key that has an IIM dataset, in the application record.
+const KEYS: &[K] = &[
+    K::CityLegacy,
+    K::CopyrightNotice,
+    K::CountryLegacy,
+    K::CountryCodeLegacy,
+    K::Creator,
+    K::CreatorsJobtitle,
+    K::CreditLine,
+    K::DateCreated,
+    K::Description,
+    K::DescriptionWriter,
+    K::Headline,
+    K::Instructions,
+    K::IntellectualGenreLegacy,
+    K::JobId,
+    K::Keywords,
+    K::ProvinceOrStateLegacy,
+    K::SourceSupplyChain,
+    K::SubjectCodeLegacy,
+    K::SublocationLegacy,
+    K::Title,
+];
+
+/// One IIM dataset.
+struct Dataset<'input> {
+    record: u8,
+    number: u8,
+    data: &'input [u8],
+}
+
+/// Parses IIM, which may be inside a Photoshop image resource block.
+///
+/// Text is read as UTF-8 when it's valid, and as Latin-1 otherwise, since
+/// most older files don't say which one they use.
+pub fn parse_iim(raw: &[u8]) -> Result<Iptc, IimError> {
+    let iim: &[u8] = if raw.starts_with(PHOTOSHOP_RESOURCE) {
+        photoshop_iptc(raw).ok_or(IimError::NoIptcResource)?
+    } else {
+        raw
+    };
+    let datasets: Vec<Dataset> = datasets(iim)?;
+
+    let text = |data: &[u8]| -> String {
+        match core::str::from_utf8(data) {
+            Ok(s) => s.trim_end_matches('\0').into(),
+            Err(_) => data.iter().map(|&b| char::from(b)).collect(),
+        }
+    };
+    let values = |number: u8| {
+        datasets
+            .iter()
+            .filter(move |d| d.record == APPLICATION_RECORD && d.number == number)
+            .map(|d| text(d.data))
+    };
+
+    let pairs: Vec<Kv> = KEYS
+        .iter()
+        .filter_map(|key| {
+            let number: u8 = key.iim_id()?.strip_prefix("2:")?.parse().ok()?;
+            let mut values = values(number).peekable();
+            values.peek()?;
+
+            match key {
+                K::Creator => Some(Kv::Creator(values.collect())),
+                K::Keywords => Some(Kv::Keywords(values.collect())),
+                K::SubjectCodeLegacy => Some(Kv::SubjectCodeLegacy(values.collect())),
+                K::DateCreated => {
+                    let date = values.next()?;
+                    let time = time_created(&datasets);
+                    Some(Kv::DateCreated(date_created(&date, time.as_deref())))
+                }
+                _ => iptc_pair_from_simple_text_value(*key, values.next()?),
+            }
+        })
+        .collect();
+
+    Ok(Iptc { pairs })
+}
+
+/// Finds `Time Created` (`2:60`), which goes with `Date Created`.
+fn time_created(datasets: &[Dataset]) -> Option<String> {
+    datasets
+        .iter()
+        .find(|d| d.record == APPLICATION_RECORD && d.number == 60)
+        .and_then(|d| core::str::from_utf8(d.data).ok())
+        .map(Into::into)
+}
+
+/// Turns IIM's `CCYYMMDD` date and `HHMMSS±HHMM` time into an XMP date.
+///
+/// Anything that doesn't look like that is kept as-is.
+fn date_created(date: &str, time: Option<&str>) -> String {
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if date.len() != 8 || !digits(date) {
+        return date.into();
+    }
+    let date = format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..]);
+
+    match time {
+        Some(t) if t.len() == 11 && t.is_ascii() && digits(&t[..6]) && digits(&t[7..]) => format!(
+            "{date}T{}:{}:{}{}{}:{}",
+            &t[..2],
+            &t[2..4],
+            &t[4..6],
+            &t[6..7],
+            &t[7..9],
+            &t[9..],
+        ),
+        _ => date,
+    }
+}
+
+/// Reads every dataset.
+fn datasets(mut input: &[u8]) -> Result<Vec<Dataset<'_>>, IimError> {
+    let input = &mut input;
+    if input.first() != Some(&TAG_MARKER) {
+        return Err(IimError::NotIim);
+    }
+
+    let mut datasets = Vec::new();
+    while !input.is_empty() {
+        // some writers pad the end with zeroes
+        if input.first() != Some(&TAG_MARKER) {
+            log::trace!("IIM had `{}` trailing bytes. Ignoring them.", input.len());
+            break;
+        }
+
+        let dataset = (|| -> Result<Dataset, EmptyError> {
+            let (_marker, record, number, len) = (be_u8, be_u8, be_u8, be_u16).parse_next(input)?;
+
+            // the high bit means the length is in the next few bytes
+            let len: usize = if len & 0x8000 != 0 {
+                let size: usize = (len & 0x7FFF).into();
+                if size > 4 {
+                    return Err(EmptyError);
+                }
+                take(size)
+                    .parse_next(input)?
+                    .iter()
+                    .fold(0, |len, b| (len << 8) | usize::from(*b))
+            } else {
+                len.into()
+            };
+
+            Ok(Dataset {
+                record,
+                number,
+                data: take(len).parse_next(input)?,
+            })
+        })()
+        .map_err(|_| {
+            log::warn!("IIM dataset was cut off.");
+            IimError::Truncated
+        })?;
+
+        datasets.push(dataset);
+    }
+
+    Ok(datasets)
+}
+
+/// Finds the IIM resource in a Photoshop image resource block.
+fn photoshop_iptc(mut input: &[u8]) -> Option<&[u8]> {
+    let input = &mut input;
+    while input.starts_with(PHOTOSHOP_RESOURCE) {
+        let resource = (|| -> Result<(u16, &[u8]), EmptyError> {
+            let (_sig, id, name_len) = (take(4_usize), be_u16, be_u8).parse_next(input)?;
+
+            // the name is a Pascal string, padded to an even length
+            let name_len: usize = usize::from(name_len) + 1;
+            take(name_len + name_len % 2 - 1).parse_next(input)?;
+
+            // as is the data
+            let len: usize = be_u32.parse_next(input)? as usize;
+            let data: &[u8] = take(len).parse_next(input)?;
+            take(len % 2).parse_next(input)?;
+            Ok((id, data))
+        })()
+        .inspect_err(|_| log::warn!("Photoshop image resource was cut off."))
+        .ok()?;
+
+        if resource.0 == IPTC_RESOURCE_ID {
+            return Some(resource.1);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use raves_metadata_types::iptc::IptcKeyValue as Kv;
+
+    use super::parse_iim;
+    use crate::{iptc::error::IimError, util::logger};
+
+    /// Makes an application record dataset.
+    fn dataset(number: u8, data: &[u8]) -> Vec<u8> {
+        [
+            [0x1C, 2, number].as_slice(),
+            &(data.len() as u16).to_be_bytes(),
+            data,
+        ]
+        .concat()
+    }
+
+    /// Datasets become pairs, and repeated ones become lists.
+    #[test]
+    fn datasets() {
+        logger();
+
+        let iim = [
+            dataset(5, b"Sleepy cat"),
+            dataset(25, b"cat"),
+            dataset(25, b"nap"),
+            dataset(55, b"20240131"),
+            dataset(60, b"101530+0100"),
+            dataset(80, b"Caf\xE9"),
+            dataset(200, b"unknown"),
+        ]
+        .concat();
+
+        let iptc = parse_iim(&iim).unwrap();
+        assert_eq!(
+            iptc.pairs,
+            [
+                Kv::Creator(vec!["Café".into()]),
+                Kv::DateCreated("2024-01-31T10:15:30+01:00".into()),
+                Kv::Keywords(vec!["cat".into(), "nap".into()]),
+                Kv::Title("Sleepy cat".into()),
+            ]
+        );
+    }
+
+    /// Photoshop resource blocks hold IIM in resource `0x0404`.
+    #[test]
+    fn photoshop_resource() {
+        logger();
+
+        let iim = dataset(120, "A cat, asleep ☺".as_bytes());
+        let resource = |id: u16, data: &[u8]| {
+            [
+                b"8BIM".as_slice(),
+                &id.to_be_bytes(),
+                &[0, 0],
+                &(data.len() as u32).to_be_bytes(),
+                data,
+                &vec![0; data.len() % 2],
+            ]
+            .concat()
+        };
+        let block = [resource(0x03ED, &[1, 2, 3]), resource(0x0404, &iim)].concat();
+
+        let iptc = parse_iim(&block).unwrap();
+        assert_eq!(iptc.pairs, [Kv::Description("A cat, asleep ☺".into())]);
+
+        let no_iptc = resource(0x03ED, &[1, 2, 3]);
+        assert_eq!(parse_iim(&no_iptc), Err(IimError::NoIptcResource));
+    }
+
+    /// Cut off datasets are errors.
+    #[test]
+    fn truncated() {
+        logger();
+
+        let iim = dataset(5, b"Sleepy cat");
+        assert_eq!(parse_iim(&iim[..8]), Err(IimError::Truncated));
+        assert_eq!(parse_iim(b"cat"), Err(IimError::NotIim));
+    }
+}
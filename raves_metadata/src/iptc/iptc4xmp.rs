@@ -177,7 +177,7 @@ pub fn parse_xmp_for_iptc(raw_xmp: &[u8]) -> Result<Iptc, Iptc4XmpError> {
 }
 
 /// Maps a simple text value into an IPTC pair, if possible.
-fn iptc_pair_from_simple_text_value(key: K, value: impl Into<String>) -> Option<Kv> {
+pub(super) fn iptc_pair_from_simple_text_value(key: K, value: impl Into<String>) -> Option<Kv> {
     let value: String = Into::<String>::into(value);
 
     // map the Key into a KeyValue using the data we got.
@@ -3,6 +3,7 @@
 use raves_metadata_types::iptc::IptcKeyValue;
 
 pub mod error;
+mod iim;
 mod iptc4xmp;
 
 /// Parsed IPTC.
@@ -18,4 +19,10 @@ impl Iptc {
     pub fn new_xmp<B: AsRef<[u8]>>(raw: B) -> Result<Self, error::IptcError> {
         iptc4xmp::parse_xmp_for_iptc(raw.as_ref()).map_err(error::IptcError::Iptc4Xmp)
     }
+
+    /// Parses IPTC out of IIM datasets, which may be inside a Photoshop image
+    /// resource block.
+    pub fn new_iim<B: AsRef<[u8]>>(raw: B) -> Result<Self, error::IptcError> {
+        iim::parse_iim(raw.as_ref()).map_err(error::IptcError::Iim)
+    }
 }
@@ -41,6 +41,9 @@
 use crate::{
     exif::{Exif, error::ExifFatalError},
     iptc::{Iptc, error::IptcError},
+    providers::{
+        animation_info::AnimationInfo, image_info::ImageInfo, orientation::DisplayOrientation,
+    },
     xmp::{Xmp, error::XmpError},
 };
 
@@ -164,6 +167,42 @@ pub trait MetadataProvider:
     /// corrupted.
     fn xmp(&self) -> Option<Result<&Xmp, &XmpError>>;
 
+    /// Returns basic properties of the image, like its dimensions.
+    ///
+    /// These are read from the file's image header, so nothing is decoded.
+    /// This returns `None` for formats that aren't images, or if the header
+    /// couldn't be found.
+    ///
+    /// See [`providers::image_info`] for the sources used by each format.
+    fn image_info(&self) -> Option<&ImageInfo> {
+        None
+    }
+
+    /// Returns the image's animation info, like its frame delays and loop
+    /// count.
+    ///
+    /// Formats that can be animated return this for still images too, with
+    /// one frame. This returns `None` for formats that can't be animated, or
+    /// if the animation couldn't be read.
+    ///
+    /// See [`providers::animation_info`] for the sources used by each format.
+    fn animation_info(&self) -> Option<&AnimationInfo> {
+        None
+    }
+
+    /// Returns how to show the image (or video) upright.
+    ///
+    /// By default, this reads the Exif `Orientation` tag. HEIC and AVIF use
+    /// their primary item's transforms instead, and MP4 and MOV use their
+    /// video track's matrix. This returns `None` if the file doesn't say.
+    ///
+    /// See [`providers::orientation`] for each format's rules.
+    fn display_orientation(&self) -> Option<DisplayOrientation> {
+        self.exif()?
+            .ok()
+            .and_then(DisplayOrientation::from_exif_metadata)
+    }
+
     /// Indicates whether the given input matches the magic number of this
     /// provider.
     ///
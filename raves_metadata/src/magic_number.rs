@@ -46,7 +46,8 @@
 //! ```
 
 use crate::providers::{
-    avif::Avif, gif::Gif, heic::Heic, jpeg::Jpeg, mov::Mov, mp4::Mp4, png::Png, webp::Webp,
+    avi::Avi, avif::Avif, gif::Gif, heic::Heic, jpeg::Jpeg, matroska::Matroska, mov::Mov, mp4::Mp4,
+    png::Png, wav::Wav, webp::Webp,
 };
 
 /// Reminds contributors to add each provider to the `generate!()` call!
@@ -206,6 +207,66 @@ macro_rules! generate {
                     )+
                 }
             }
+
+            /// Gets basic image properties from inner
+            /// [`MetadataProvider`][`crate::MetadataProvider`].
+            ///
+            /// For more information, see:
+            ///
+            /// [`MetadataProvider::image_info`][`crate::MetadataProvider::image_info`]
+            pub fn image_info(&self) -> Option<&crate::providers::image_info::ImageInfo> {
+                match self {
+                    $(
+                        Self::$variant(maybe_inner) => {
+                            let Ok(inner) = maybe_inner else {
+                                ::log::error!("The inner provider is an error, not `Ok`. Cannot get image info.");
+                                return None;
+                            };
+                            <$provider_ty as $crate::MetadataProvider>::image_info(inner)
+                        },
+                    )+
+                }
+            }
+
+            /// Gets animation info from inner
+            /// [`MetadataProvider`][`crate::MetadataProvider`].
+            ///
+            /// For more information, see:
+            ///
+            /// [`MetadataProvider::animation_info`][`crate::MetadataProvider::animation_info`]
+            pub fn animation_info(&self) -> Option<&crate::providers::animation_info::AnimationInfo> {
+                match self {
+                    $(
+                        Self::$variant(maybe_inner) => {
+                            let Ok(inner) = maybe_inner else {
+                                ::log::error!("The inner provider is an error, not `Ok`. Cannot get animation info.");
+                                return None;
+                            };
+                            <$provider_ty as $crate::MetadataProvider>::animation_info(inner)
+                        },
+                    )+
+                }
+            }
+
+            /// Gets the display orientation from inner
+            /// [`MetadataProvider`][`crate::MetadataProvider`].
+            ///
+            /// For more information, see:
+            ///
+            /// [`MetadataProvider::display_orientation`][`crate::MetadataProvider::display_orientation`]
+            pub fn display_orientation(&self) -> Option<crate::providers::orientation::DisplayOrientation> {
+                match self {
+                    $(
+                        Self::$variant(maybe_inner) => {
+                            let Ok(inner) = maybe_inner else {
+                                ::log::error!("The inner provider is an error, not `Ok`. Cannot get display orientation.");
+                                return None;
+                            };
+                            <$provider_ty as $crate::MetadataProvider>::display_orientation(inner)
+                        },
+                    )+
+                }
+            }
         }
 
         // implement `From<SomeProvider>` for both
@@ -285,9 +346,12 @@ generate!(
     Avif => { provider_ty: Avif },
     Heic => { provider_ty: Heic },
     Jpeg => { provider_ty: Jpeg },
+    Matroska => { provider_ty: Matroska },
     Mov => { provider_ty: Mov },
     Mp4 => { provider_ty: Mp4 },
     Png => { provider_ty: Png },
     Webp => { provider_ty: Webp },
+    Avi => { provider_ty: Avi },
+    Wav => { provider_ty: Wav },
     Gif => { provider_ty: Gif },
 );
@@ -0,0 +1,90 @@
+//! Animation properties, like frame timings and loop counts.
+//!
+//! These come from each format's animation chunks, so no image data is
+//! decoded:
+//!
+//! - GIF: graphic control extensions (delays) and the `NETSCAPE2.0`
+//!   application extension (loop count)
+//! - APNG: the `acTL` and `fcTL` chunks
+//! - WebP: the `ANIM` and `ANMF` chunks
+//! - AVIF: the `avis` image sequence track, in the `moov` box
+
+use core::time::Duration;
+
+/// Timing info for an animated (or still) image.
+///
+/// Get this from [`crate::MetadataProvider::animation_info`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnimationInfo {
+    /// The number of frames.
+    ///
+    /// This is `1` for still images.
+    pub frame_count: u32,
+
+    /// How long each frame is shown, in order.
+    ///
+    /// Frames without a known delay use [`Duration::ZERO`]. This is empty for
+    /// still images.
+    pub frame_delays: Vec<Duration>,
+
+    /// How many times the animation plays.
+    pub loop_count: LoopCount,
+}
+
+/// How many times an animation plays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LoopCount {
+    /// The animation repeats forever.
+    Infinite,
+
+    /// The animation plays this many times in total, then stops.
+    ///
+    /// GIF stores the number of _extra_ plays, so a GIF with a `NETSCAPE2.0`
+    /// loop count of `2` plays three times.
+    Finite(u32),
+}
+
+impl AnimationInfo {
+    /// Info for a still image: one frame, played once.
+    pub(crate) fn still() -> Self {
+        Self {
+            frame_count: 1,
+            frame_delays: Vec::new(),
+            loop_count: LoopCount::Finite(1),
+        }
+    }
+
+    /// Whether the image has more than one frame.
+    pub fn is_animated(&self) -> bool {
+        self.frame_count > 1
+    }
+
+    /// The time to play every frame once.
+    ///
+    /// This ignores the loop count.
+    pub fn total_duration(&self) -> Duration {
+        self.frame_delays.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::{AnimationInfo, LoopCount};
+
+    #[test]
+    fn still_and_animated() {
+        let still = AnimationInfo::still();
+        assert!(!still.is_animated());
+        assert_eq!(still.total_duration(), Duration::ZERO);
+
+        let animated = AnimationInfo {
+            frame_count: 3,
+            frame_delays: vec![Duration::from_millis(100); 3],
+            loop_count: LoopCount::Infinite,
+        };
+        assert!(animated.is_animated());
+        assert_eq!(animated.total_duration(), Duration::from_millis(300));
+    }
+}
@@ -0,0 +1,226 @@
+//! Provider implementation for AVI (Audio Video Interleave) files.
+//!
+//! AVI is a RIFF file with the `AVI ` form type. Its metadata is in `LIST/INFO`
+//! tags, and XMP is in a `_PMX` chunk:
+//!
+//! ```text
+//! RIFF AVI
+//! ├── LIST hdrl   (headers)
+//! ├── LIST INFO   (tags)
+//! ├── LIST movi   (the media itself)
+//! ├── idx1
+//! └── _PMX        (XMP)
+//! ```
+//!
+//! `_PMX` is only looked for at the top level and in the `hdrl` and `INFO`
+//! lists. `movi` holds every frame, so searching it would read the whole file.
+
+use crate::{
+    MetadataProvider,
+    exif::{Exif, error::ExifFatalError},
+    providers::{
+        riff_info::{self, RiffInfo},
+        shared::riff::{self, RiffHeader},
+    },
+    xmp::{Xmp, error::XmpError},
+};
+
+/// An AVI (Audio Video Interleave) file.
+#[derive(Clone, Debug)]
+pub struct Avi {
+    /// From the `LIST/INFO` chunk.
+    info: Option<RiffInfo>,
+
+    /// From the `_PMX` chunk.
+    xmp: Option<Result<Xmp, XmpError>>,
+}
+
+impl Avi {
+    /// Returns the `LIST/INFO` tags, like the title and software.
+    ///
+    /// See [`crate::providers::riff_info`].
+    pub fn info(&self) -> Option<&RiffInfo> {
+        self.info.as_ref()
+    }
+}
+
+impl MetadataProvider for Avi {
+    type ConstructionError = AviConstructionError;
+
+    fn magic_number(input: &[u8]) -> bool {
+        riff::header(&mut &input[..]).is_ok_and(|header| &header.form_type == b"AVI ")
+    }
+
+    /// Reads the given data as an AVI file.
+    fn new(
+        input: &impl AsRef<[u8]>,
+    ) -> Result<Self, <Self as MetadataProvider>::ConstructionError> {
+        let mut input: &[u8] = input.as_ref();
+
+        let header: RiffHeader =
+            riff::header(&mut input).map_err(|_| AviConstructionError::NoHeader)?;
+        if &header.form_type != b"AVI " {
+            return Err(AviConstructionError::NotAnAvi(header.form_type));
+        }
+
+        Ok(Self {
+            info: riff_info::find(input),
+            xmp: find_xmp(input).map(Xmp::new_from_bytes),
+        })
+    }
+
+    /// AVI doesn't store Exif in a standard way.
+    fn exif(&self) -> Option<Result<&Exif, &ExifFatalError>> {
+        None
+    }
+
+    fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
+        self.xmp.as_ref().map(|r| r.as_ref())
+    }
+}
+
+/// The chunk type for XMP.
+const XMP_CHUNK: [u8; 4] = *b"_PMX";
+
+/// The `LIST`s that can hold a `_PMX` chunk, besides the top level.
+const XMP_LISTS: &[[u8; 4]] = &[*b"hdrl", *b"INFO"];
+
+/// Finds the `_PMX` chunk, without looking through the frames in `movi`.
+fn find_xmp(input: &[u8]) -> Option<&[u8]> {
+    riff::chunks(input).find_map(|(chunk, payload)| match chunk.fourcc {
+        XMP_CHUNK => Some(payload),
+        riff::LIST => riff::list(payload)
+            .filter(|(list_type, _)| XMP_LISTS.contains(list_type))
+            .and_then(|(_, contents)| riff::find_chunk(contents, &XMP_CHUNK)),
+        _ => None,
+    })
+}
+
+/// An error that occurred when parsing an AVI.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub enum AviConstructionError {
+    /// Failed to find the required RIFF header.
+    NoHeader,
+
+    /// The RIFF header's form type wasn't `AVI `. It holds the form type it
+    /// had instead.
+    NotAnAvi([u8; 4]),
+}
+
+impl core::fmt::Display for AviConstructionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AviConstructionError::NoHeader => {
+                f.write_str("The required RIFF header was not found.")
+            }
+
+            AviConstructionError::NotAnAvi(form_type) => write!(
+                f,
+                "The RIFF header indicated that this file was not an AVI. \
+                Instead, it's a: `{}`",
+                String::from_utf8_lossy(form_type),
+            ),
+        }
+    }
+}
+
+impl core::error::Error for AviConstructionError {}
+
+#[cfg(test)]
+mod tests {
+    use raves_metadata_types::xmp::{XmpElement, XmpPrimitive, XmpValue};
+
+    use crate::{
+        MetadataProvider as _,
+        magic_number::MagicNumber,
+        providers::{
+            avi::{Avi, AviConstructionError},
+            shared::riff::tests::{chunk, riff},
+        },
+        util::logger,
+    };
+
+    #[test]
+    fn info_and_xmp() {
+        logger();
+
+        const XMP: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"><rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/" dc:format="video/avi"/></rdf:RDF></x:xmpmeta>"#;
+
+        let hdrl = chunk(
+            b"LIST",
+            &[b"hdrl".as_slice(), &chunk(b"avih", &[0; 56])].concat(),
+        );
+        let info = chunk(
+            b"LIST",
+            &[
+                b"INFO".as_slice(),
+                &chunk(b"ISFT", b"Lavf61.1.100\0"),
+                &chunk(b"INAM", b"Dashcam\0"),
+            ]
+            .concat(),
+        );
+        let movi = chunk(
+            b"LIST",
+            &[b"movi".as_slice(), &chunk(b"00dc", &[1, 2, 3])].concat(),
+        );
+        let file = riff(
+            b"AVI ",
+            &[hdrl, info, movi, chunk(b"_PMX", XMP.as_bytes())].concat(),
+        );
+
+        assert_eq!(MagicNumber::new(&file), Some(MagicNumber::Avi));
+        let avi = Avi::new(&file).unwrap();
+
+        let info = avi.info().expect("has `LIST/INFO`");
+        assert_eq!(info.software(), Some("Lavf61.1.100"));
+        assert_eq!(info.name(), Some("Dashcam"));
+        assert_eq!(info.get(b"ICMT"), None);
+
+        let xmp = avi.xmp().expect("has `_PMX`").expect("XMP should parse");
+        assert_eq!(
+            xmp.document().values_ref(),
+            &[XmpElement {
+                namespace: "http://purl.org/dc/elements/1.1/".into(),
+                prefix: "dc".into(),
+                name: "format".into(),
+                value: XmpValue::Simple(XmpPrimitive::Text("video/avi".into())),
+            }]
+        );
+    }
+
+    /// `_PMX` chunks in `movi` are frame data, not the file's XMP.
+    #[test]
+    fn xmp_outside_movi() {
+        logger();
+
+        let movi = chunk(
+            b"LIST",
+            &[b"movi".as_slice(), &chunk(b"_PMX", b"not XMP")].concat(),
+        );
+        let file = riff(b"AVI ", &movi);
+        assert!(Avi::new(&file).unwrap().xmp().is_none());
+
+        let hdrl = chunk(
+            b"LIST",
+            &[b"hdrl".as_slice(), &chunk(b"_PMX", b"<x:xmpmeta")].concat(),
+        );
+        let file = riff(b"AVI ", &[movi, hdrl].concat());
+        assert!(Avi::new(&file).unwrap().xmp().is_some());
+    }
+
+    #[test]
+    fn other_form_types() {
+        logger();
+
+        let wav = riff(b"WAVE", &[]);
+        assert!(!Avi::magic_number(&wav));
+        assert_eq!(
+            Avi::new(&wav).unwrap_err(),
+            AviConstructionError::NotAnAvi(*b"WAVE")
+        );
+        assert_eq!(
+            Avi::new(b"RIFX").unwrap_err(),
+            AviConstructionError::NoHeader
+        );
+    }
+}
@@ -2,7 +2,18 @@
 
 use crate::{
     MetadataProvider,
-    providers::shared::bmff::heif::{HeifLike, HeifLikeConstructionError},
+    providers::{
+        animation_info::AnimationInfo,
+        depth_map::DepthMap,
+        gain_map::GainMapInfo,
+        heif_items::HeifItems,
+        image_info::ImageInfo,
+        orientation::DisplayOrientation,
+        shared::bmff::{
+            heif::{HeifLike, HeifLikeConstructionError},
+            sequence,
+        },
+    },
 };
 
 /// Supported brands for AVIF files.
@@ -12,6 +23,34 @@ pub const SUPPORTED_AVIF_BRANDS: &[[u8; 4]] = &[*b"avif", *b"avis"];
 #[derive(Clone, Debug)]
 pub struct Avif {
     heic_like: HeifLike,
+
+    /// Timing info from the `avis` image sequence, or a still image.
+    animation_info: AnimationInfo,
+}
+
+impl Avif {
+    /// Returns the HDR gain map's parameters and location, if there is one.
+    ///
+    /// These come from an ISO 21496-1 `tmap` item. See
+    /// [`crate::providers::gain_map`].
+    pub fn gain_map(&self) -> Option<&GainMapInfo> {
+        self.heic_like.gain_map.as_ref()
+    }
+
+    /// Returns the depth maps and mattes in this AVIF, like those from
+    /// portrait-mode photos.
+    ///
+    /// See [`crate::providers::depth_map`].
+    pub fn depth_maps(&self) -> &[DepthMap] {
+        &self.heic_like.depth_maps
+    }
+
+    /// Returns every item in this AVIF, and how they're linked.
+    ///
+    /// See [`crate::providers::heif_items`].
+    pub fn items(&self) -> &HeifItems {
+        &self.heic_like.items
+    }
 }
 
 impl MetadataProvider for Avif {
@@ -25,8 +64,17 @@ impl MetadataProvider for Avif {
     fn new(
         input: &impl AsRef<[u8]>,
     ) -> Result<Self, <Self as MetadataProvider>::ConstructionError> {
-        HeifLike::parse(&mut input.as_ref(), SUPPORTED_AVIF_BRANDS)
-            .map(|heic_like| Avif { heic_like })
+        let input: &[u8] = input.as_ref();
+        let heic_like = HeifLike::parse(&mut &input[..], SUPPORTED_AVIF_BRANDS)?;
+
+        // animated AVIFs store their frames as a track, like a video
+        let animation_info: AnimationInfo =
+            sequence::animation_info(input).unwrap_or_else(AnimationInfo::still);
+
+        Ok(Avif {
+            heic_like,
+            animation_info,
+        })
     }
 
     fn exif(&self) -> Option<Result<&crate::exif::Exif, &crate::exif::error::ExifFatalError>> {
@@ -36,6 +84,20 @@ impl MetadataProvider for Avif {
     fn xmp(&self) -> Option<Result<&crate::xmp::Xmp, &crate::xmp::error::XmpError>> {
         self.heic_like.xmp.as_ref().map(|r| r.as_ref())
     }
+
+    fn image_info(&self) -> Option<&ImageInfo> {
+        self.heic_like.image_info.as_ref()
+    }
+
+    /// HEIF readers must apply the primary item's `irot` and `imir`
+    /// properties, so Exif `Orientation` is ignored.
+    fn display_orientation(&self) -> Option<DisplayOrientation> {
+        self.heic_like.display_orientation()
+    }
+
+    fn animation_info(&self) -> Option<&AnimationInfo> {
+        Some(&self.animation_info)
+    }
 }
 
 #[cfg(test)]
@@ -49,7 +111,16 @@ mod tests {
         xmp::{XmpElement, XmpPrimitive, XmpValue},
     };
 
-    use crate::{MetadataProvider as _, exif::Ifd, providers::avif::Avif, util::logger};
+    use crate::{
+        MetadataProvider as _,
+        exif::Ifd,
+        providers::{
+            avif::Avif,
+            heif_properties::HeifProperty,
+            image_info::{ColorType, ImageInfo},
+        },
+        util::logger,
+    };
 
     #[test]
     fn sample_img_meta_after_img_blob_should_parse() {
@@ -225,4 +296,57 @@ mod tests {
 
         // parse exif
     }
+
+    /// The primary item has `ispe` and `pixi` properties.
+    #[test]
+    fn big_buck_bunny_image_info() {
+        logger();
+
+        let bytes = include_bytes!("../../assets/providers/avif/bbb_4k.avif");
+        let file: Avif = Avif::new(bytes).unwrap();
+
+        assert_eq!(
+            file.image_info(),
+            Some(&ImageInfo {
+                width: 3840,
+                height: 2160,
+                bit_depth: Some(8),
+                color_type: Some(ColorType::YCbCr),
+                channels: Some(3),
+                frame_count: Some(1),
+                jpeg: None,
+            })
+        );
+
+        // its Exif item describes the primary image
+        let items = file.items();
+        assert_eq!(
+            items.primary_item().map(|item| item.item_type),
+            Some(Some(*b"av01"))
+        );
+        assert_eq!(
+            items
+                .metadata_for(1)
+                .map(|item| item.id)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+
+        // its `av1C` agrees with `pixi`
+        let av1 = items
+            .item(1)
+            .and_then(|item| {
+                item.properties.iter().find_map(|p| match p.property {
+                    HeifProperty::Av1Config(ref config) => Some(config),
+                    _ => None,
+                })
+            })
+            .expect("has `av1C`");
+        assert_eq!(av1.bit_depth, 8);
+        assert_eq!(av1.chroma_subsampling, (true, true));
+
+        // it's a still image, so there's no `moov` box
+        let animation = file.animation_info().expect("AVIFs have animation info");
+        assert!(!animation.is_animated());
+    }
 }
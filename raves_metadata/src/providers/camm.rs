@@ -0,0 +1,276 @@
+//! Camera Motion Metadata (CAMM), from Street View-style 360-degree videos.
+//!
+//! It's stored in a track whose samples have the `camm` format. Each sample
+//! is one little-endian packet: two reserved bytes, a packet type, then its
+//! data.
+//!
+//! See <https://developers.google.com/streetview/publish/camm-spec>.
+
+use core::time::Duration;
+
+use winnow::{
+    Parser as _,
+    binary::{le_f32, le_f64, le_i32, le_u16},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::providers::shared::bmff::{sample_table, track};
+
+/// One CAMM packet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CammPacket {
+    /// When the packet applies, from the start of the video.
+    pub time: Duration,
+
+    /// What the packet says.
+    pub data: CammData,
+}
+
+/// What a CAMM packet says.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CammData {
+    /// The camera's orientation, as an angle-axis rotation in radians.
+    AngleAxis([f32; 3]),
+
+    /// How long each pixel was exposed, and the rolling shutter's skew, in
+    /// nanoseconds.
+    ExposureTime {
+        pixel_exposure_time: i32,
+        rolling_shutter_skew_time: i32,
+    },
+
+    /// Rotation rates, in radians per second.
+    Gyroscope([f32; 3]),
+
+    /// Acceleration, in meters per second squared.
+    Acceleration([f32; 3]),
+
+    /// Position, in meters, from some local origin.
+    Position([f32; 3]),
+
+    /// A minimal GPS reading.
+    Gps {
+        /// In degrees.
+        latitude: f64,
+
+        /// In degrees.
+        longitude: f64,
+
+        /// In meters, above the WGS 84 ellipsoid.
+        altitude: f64,
+    },
+
+    /// A full GPS reading.
+    GpsFull(CammGps),
+
+    /// The magnetic field, in microteslas.
+    MagneticField([f32; 3]),
+}
+
+/// A full CAMM GPS reading.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CammGps {
+    /// Seconds since the GPS epoch (1980-01-06).
+    pub gps_time: f64,
+
+    /// `0` for no fix, `2` for 2D, or `3` for 3D.
+    pub fix_type: i32,
+
+    /// In degrees.
+    pub latitude: f64,
+
+    /// In degrees.
+    pub longitude: f64,
+
+    /// In meters, above the WGS 84 ellipsoid.
+    pub altitude: f32,
+
+    /// In meters.
+    pub horizontal_accuracy: f32,
+
+    /// In meters.
+    pub vertical_accuracy: f32,
+
+    /// In meters per second.
+    pub velocity_east: f32,
+
+    /// In meters per second.
+    pub velocity_north: f32,
+
+    /// In meters per second.
+    pub velocity_up: f32,
+
+    /// In meters per second.
+    pub speed_accuracy: f32,
+}
+
+/// Finds the CAMM track, then reads each of its packets.
+///
+/// Returns `None` if there's no CAMM track.
+pub(crate) fn find(input: &[u8]) -> Option<Vec<CammPacket>> {
+    let trak: &[u8] =
+        track::tracks(input).find(|trak| track::sample_entry(trak) == Some(*b"camm"))?;
+    let timescale: u32 = track::timescale(trak)?;
+    let samples: Vec<sample_table::Sample> = sample_table::samples(trak)?;
+    log::trace!("Found CAMM track with `{}` samples.", samples.len());
+
+    let packets: Vec<CammPacket> = samples
+        .into_iter()
+        .filter_map(|sample| {
+            let start: usize = usize::try_from(sample.offset).ok()?;
+            let end: usize = start.checked_add(usize::try_from(sample.size).ok()?)?;
+            let Some(mut packet) = input.get(start..end) else {
+                log::warn!("CAMM sample was outside the file. sample: {sample:?}");
+                return None;
+            };
+
+            Some(CammPacket {
+                time: Duration::from_secs_f64(sample.start as f64 / f64::from(timescale)),
+                data: data(&mut packet)?,
+            })
+        })
+        .collect();
+
+    Some(packets)
+}
+
+/// Reads one packet's data.
+fn data(input: &mut &[u8]) -> Option<CammData> {
+    let (_, packet_type) = (take::<_, _, EmptyError>(2_usize), le_u16)
+        .parse_next(input)
+        .ok()?;
+
+    let vector = |input: &mut &[u8]| {
+        (le_f32, le_f32, le_f32)
+            .map(|(x, y, z)| [x, y, z])
+            .parse_next(input)
+    };
+
+    let data: Result<CammData, EmptyError> = match packet_type {
+        0 => vector(input).map(CammData::AngleAxis),
+        1 => (le_i32, le_i32).parse_next(input).map(
+            |(pixel_exposure_time, rolling_shutter_skew_time)| CammData::ExposureTime {
+                pixel_exposure_time,
+                rolling_shutter_skew_time,
+            },
+        ),
+        2 => vector(input).map(CammData::Gyroscope),
+        3 => vector(input).map(CammData::Acceleration),
+        4 => vector(input).map(CammData::Position),
+        5 => (le_f64, le_f64, le_f64)
+            .parse_next(input)
+            .map(|(latitude, longitude, altitude)| CammData::Gps {
+                latitude,
+                longitude,
+                altitude,
+            }),
+        6 => (
+            (le_f64, le_i32, le_f64, le_f64),
+            (le_f32, le_f32, le_f32),
+            (le_f32, le_f32, le_f32, le_f32),
+        )
+            .parse_next(input)
+            .map(
+                |(
+                    (gps_time, fix_type, latitude, longitude),
+                    (altitude, horizontal_accuracy, vertical_accuracy),
+                    (velocity_east, velocity_north, velocity_up, speed_accuracy),
+                )| {
+                    CammData::GpsFull(CammGps {
+                        gps_time,
+                        fix_type,
+                        latitude,
+                        longitude,
+                        altitude,
+                        horizontal_accuracy,
+                        vertical_accuracy,
+                        velocity_east,
+                        velocity_north,
+                        velocity_up,
+                        speed_accuracy,
+                    })
+                },
+            ),
+        7 => vector(input).map(CammData::MagneticField),
+        other => {
+            log::debug!("Skipping unknown CAMM packet type `{other}`.");
+            return None;
+        }
+    };
+
+    data.inspect_err(|_| log::warn!("CAMM packet of type `{packet_type}` was too short."))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        providers::shared::bmff::tests::{bx, words},
+        util::logger,
+    };
+
+    use super::{CammData, CammPacket};
+
+    #[test]
+    fn packets() {
+        logger();
+
+        let gps: Vec<u8> = [
+            [0_u8, 0, 5, 0].as_slice(),
+            &37.5_f64.to_le_bytes(),
+            &(-122.25_f64).to_le_bytes(),
+            &10.0_f64.to_le_bytes(),
+        ]
+        .concat();
+        let accel: Vec<u8> = [
+            [0_u8, 0, 3, 0].as_slice(),
+            &[0.0_f32, 9.75, 0.5].map(f32::to_le_bytes).concat(),
+        ]
+        .concat();
+        let samples: Vec<u8> = [gps.as_slice(), &accel].concat();
+
+        // the samples are at the start of the file, in one chunk
+        let stsd: Vec<u8> = [
+            [0_u8, 0, 0, 0, 0, 0, 0, 1].as_slice(),
+            &bx(b"camm", &[0, 0, 0, 0, 0, 0, 0, 1]),
+        ]
+        .concat();
+        let stbl = bx(
+            b"stbl",
+            &[
+                bx(b"stsd", &stsd),
+                words(b"stts", &[0, 1, 2, 50]),
+                words(b"stsc", &[0, 1, 1, 2, 1]),
+                words(b"stsz", &[0, 0, 2, gps.len() as u32, accel.len() as u32]),
+                words(b"stco", &[0, 1, 8]),
+            ]
+            .concat(),
+        );
+        let mdia = bx(
+            b"mdia",
+            &[words(b"mdhd", &[0, 0, 0, 100, 100, 0]), bx(b"minf", &stbl)].concat(),
+        );
+        let file: Vec<u8> = [bx(b"mdat", &samples), bx(b"moov", &bx(b"trak", &mdia))].concat();
+
+        assert_eq!(
+            super::find(&file),
+            Some(vec![
+                CammPacket {
+                    time: Duration::ZERO,
+                    data: CammData::Gps {
+                        latitude: 37.5,
+                        longitude: -122.25,
+                        altitude: 10.0
+                    },
+                },
+                CammPacket {
+                    time: Duration::from_millis(500),
+                    data: CammData::Acceleration([0.0, 9.75, 0.5]),
+                },
+            ])
+        );
+    }
+}
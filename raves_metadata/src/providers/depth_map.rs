@@ -0,0 +1,517 @@
+//! Depth maps and mattes from portrait-mode photos.
+//!
+//! These are extra images stored next to the main one. There are a few ways
+//! to store them:
+//!
+//! - HEIF files (like HEIC) use auxiliary image items, which point at their
+//!   main image with an `auxl` reference. Their `auxC` property says what
+//!   they are, like `urn:mpeg:hevc:2015:auxid:2` (depth) or Apple's
+//!   `urn:com:apple:photo:2018:aux:portraiteffectsmatte`.
+//! - Older Google cameras put a base64 depth image in `GDepth:Data`, in the
+//!   JPEG's (extended) XMP.
+//! - Newer Google cameras append the depth image to the JPEG, and list it in
+//!   `Container:Directory` with the `Depth` semantic.
+//!
+//! Depth parameters (like the near and far planes) come from the `GDepth`
+//! XMP namespace, wherever it describes the depth image.
+
+use core::ops::Range;
+
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use raves_metadata_types::xmp::{XmpPrimitive, XmpValue};
+
+use crate::{
+    MetadataProvider as _,
+    providers::jpeg::Jpeg,
+    xmp::{Xmp, XmpDocument, error::XmpError},
+};
+
+/// The `GDepth` namespace.
+const G_DEPTH: &str = "http://ns.google.com/photos/1.0/depthmap/";
+
+/// The `Container:Item` semantic for a depth image.
+const DEPTH_SEMANTIC: &str = "Depth";
+
+/// A depth map (or matte) image, and what's known about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepthMap {
+    /// What the image holds.
+    pub kind: DepthMapKind,
+
+    /// Where the image was found.
+    pub source: DepthMapSource,
+
+    /// The image's bytes in the file, if they're stored in one piece.
+    pub location: Option<Range<usize>>,
+
+    /// The image's bytes, when they aren't stored in the file directly.
+    ///
+    /// `GDepth` stores them as base64 in XMP, so they're decoded here.
+    pub data: Option<Vec<u8>>,
+
+    /// How the image is coded.
+    pub codec: Option<DepthMapCodec>,
+
+    /// The image's width and height, in pixels.
+    pub dimensions: Option<(u32, u32)>,
+
+    /// How to turn the image's values into distances.
+    pub parameters: Option<DepthParameters>,
+}
+
+/// What a depth map image holds.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DepthMapKind {
+    /// Distance from the camera.
+    Depth,
+
+    /// Inverse distance from the camera.
+    Disparity,
+
+    /// Apple's portrait effects matte, which separates people from the
+    /// background.
+    PortraitEffectsMatte,
+
+    /// Another Apple matte, like `semanticskinmatte` or `semanticskymatte`.
+    SemanticMatte(String),
+}
+
+/// Where a depth map was found.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DepthMapSource {
+    /// A HEIF auxiliary image item.
+    HeifItem {
+        item_id: u32,
+
+        /// The image this one belongs to, from its `auxl` reference.
+        for_item_id: Option<u32>,
+
+        /// The `auxC` URN, like `urn:mpeg:hevc:2015:auxid:2`.
+        aux_type: String,
+    },
+
+    /// `GDepth:Data` in XMP.
+    GDepth,
+
+    /// An item in Google's `Container:Directory`.
+    GoogleContainer,
+}
+
+/// How a depth map image is coded.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DepthMapCodec {
+    /// A HEIF item type, like `hvc1`, `av01`, or `grid`.
+    HeifItemType([u8; 4]),
+
+    /// A MIME type, like `image/jpeg`.
+    Mime(String),
+}
+
+/// Depth parameters, from the `GDepth` XMP namespace.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DepthParameters {
+    /// How values map to distances.
+    pub format: Option<DepthFormat>,
+
+    /// The distance of the nearest value.
+    pub near: Option<f64>,
+
+    /// The distance of the farthest value.
+    pub far: Option<f64>,
+
+    /// The unit of `near` and `far`.
+    pub units: Option<DepthUnits>,
+
+    /// How distance is measured, like `OpticalAxis` or `OpticRay`.
+    pub measure_type: Option<String>,
+}
+
+/// How depth values map to distances.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DepthFormat {
+    /// Values are spread evenly over inverse distance.
+    RangeInverse,
+
+    /// Values are spread evenly over distance.
+    RangeLinear,
+
+    /// Any other format.
+    Other(String),
+}
+
+/// The unit of depth distances.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DepthUnits {
+    Meters,
+    Diopters,
+
+    /// Any other unit.
+    Other(String),
+}
+
+impl DepthMapKind {
+    /// Classifies a HEIF `auxC` URN.
+    ///
+    /// Returns `None` for auxiliary images that aren't depth or mattes, like
+    /// alpha planes and gain maps.
+    pub(crate) fn from_aux_type(aux_type: &str) -> Option<Self> {
+        match aux_type {
+            "urn:mpeg:hevc:2015:auxid:2" | "urn:mpeg:mpegB:cicp:systems:auxiliary:depth" => {
+                Some(Self::Depth)
+            }
+
+            apple if apple.starts_with("urn:com:apple:photo:") => {
+                match apple.rsplit(':').next().unwrap_or_default() {
+                    "depth" => Some(Self::Depth),
+                    "disparity" => Some(Self::Disparity),
+                    "portraiteffectsmatte" => Some(Self::PortraitEffectsMatte),
+                    matte if matte.ends_with("matte") => Some(Self::SemanticMatte(matte.into())),
+                    _ => None,
+                }
+            }
+
+            _ => None,
+        }
+    }
+}
+
+impl DepthParameters {
+    /// Reads depth parameters from `GDepth` XMP.
+    ///
+    /// Returns `None` if there aren't any.
+    pub(crate) fn from_xmp(doc: &XmpDocument) -> Option<Self> {
+        let text = |name| text(&doc.get(G_DEPTH, name)?.value);
+        let real = |name| real(&doc.get(G_DEPTH, name)?.value);
+
+        let parameters = Self {
+            format: text("Format").map(|format| match format.as_str() {
+                "RangeInverse" => DepthFormat::RangeInverse,
+                "RangeLinear" => DepthFormat::RangeLinear,
+                _ => DepthFormat::Other(format),
+            }),
+            near: real("Near"),
+            far: real("Far"),
+            units: text("Units").map(|units| match units.as_str() {
+                "Meters" => DepthUnits::Meters,
+                "Diopters" => DepthUnits::Diopters,
+                _ => DepthUnits::Other(units),
+            }),
+            measure_type: text("MeasureType"),
+        };
+
+        (parameters != Self::default()).then_some(parameters)
+    }
+}
+
+/// Finds the depth maps in a JPEG.
+///
+/// `GDepth:Data` is decoded from the primary image's XMP. `Container` items
+/// come one after another, starting after the primary image (and its
+/// padding).
+pub(crate) fn find_in_jpeg(
+    input: &[u8],
+    image_end: usize,
+    xmp: Option<&XmpDocument>,
+) -> Vec<DepthMap> {
+    let Some(doc) = xmp else {
+        return Vec::new();
+    };
+    let mut depth_maps = Vec::new();
+
+    // `GDepth`, which is usually in extended XMP
+    if let Some(encoded) = doc.get(G_DEPTH, "Data").and_then(|e| text(&e.value)) {
+        match decode_base64(&encoded) {
+            Some(data) => depth_maps.push(DepthMap {
+                kind: DepthMapKind::Depth,
+                source: DepthMapSource::GDepth,
+                location: None,
+                codec: doc
+                    .get(G_DEPTH, "Mime")
+                    .and_then(|e| text(&e.value))
+                    .map(DepthMapCodec::Mime),
+                dimensions: EmbeddedImage::parse(&data).dimensions,
+                data: Some(data),
+                parameters: DepthParameters::from_xmp(doc),
+            }),
+            None => log::warn!("`GDepth:Data` wasn't valid base64."),
+        }
+    }
+
+    // `Container:Directory`
+    for (item, range) in doc.container_item_ranges(image_end) {
+        if item.semantic != DEPTH_SEMANTIC {
+            continue;
+        }
+
+        let image: Option<&[u8]> = input.get(range.clone());
+        if image.is_none() {
+            log::warn!("Depth container item was outside the file.");
+        }
+        let embedded = image.map(EmbeddedImage::parse).unwrap_or_default();
+
+        depth_maps.push(DepthMap {
+            kind: DepthMapKind::Depth,
+            source: DepthMapSource::GoogleContainer,
+            location: image.map(|_| range),
+            data: None,
+            codec: Some(DepthMapCodec::Mime(item.mime)),
+            dimensions: embedded.dimensions,
+            // the depth image can describe itself
+            parameters: embedded.parameters,
+        });
+    }
+
+    depth_maps
+}
+
+/// What a depth image says about itself.
+#[derive(Default)]
+struct EmbeddedImage {
+    dimensions: Option<(u32, u32)>,
+    parameters: Option<DepthParameters>,
+}
+
+impl EmbeddedImage {
+    /// Parses a depth image once, whatever format it's in.
+    ///
+    /// JPEGs are parsed without looking for their own attached images, so
+    /// nested containers can't make us recurse.
+    fn parse(image: &[u8]) -> Self {
+        let parameters = |xmp: Option<Result<&Xmp, &XmpError>>| {
+            xmp?.ok()
+                .and_then(|xmp| DepthParameters::from_xmp(xmp.document()))
+        };
+
+        let (image_info, parameters) = if Jpeg::magic_number(image) {
+            let Ok(jpeg) = Jpeg::new_embedded(image) else {
+                return Self::default();
+            };
+            (jpeg.image_info().copied(), parameters(jpeg.xmp()))
+        } else {
+            let Some(provider) = crate::parse(&image) else {
+                return Self::default();
+            };
+            (provider.image_info().copied(), parameters(provider.xmp()))
+        };
+
+        Self {
+            dimensions: image_info.map(|info| (info.width, info.height)),
+            parameters,
+        }
+    }
+}
+
+/// Reads depth parameters from the XMP describing a HEIF depth image.
+pub(crate) fn parameters_from_xmp_bytes(xmp: &[u8]) -> Option<DepthParameters> {
+    Xmp::new_from_bytes(xmp)
+        .ok()
+        .and_then(|xmp| DepthParameters::from_xmp(xmp.document()))
+}
+
+/// Grabs some text.
+fn text(value: &XmpValue) -> Option<String> {
+    match value {
+        XmpValue::Simple(XmpPrimitive::Text(t)) => Some(t.clone()),
+        _ => None,
+    }
+}
+
+/// Grabs a real number, even when it was stored as an integer or text.
+fn real(value: &XmpValue) -> Option<f64> {
+    match value {
+        XmpValue::Simple(XmpPrimitive::Real(r)) => Some(*r),
+        XmpValue::Simple(XmpPrimitive::Integer(i)) => Some(*i as f64),
+        XmpValue::Simple(XmpPrimitive::Text(t)) => t.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Decodes standard base64, ignoring whitespace.
+///
+/// Padding is optional.
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    const ENGINE: GeneralPurpose = GeneralPurpose::new(
+        &alphabet::STANDARD,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+
+    let chars: Vec<u8> = encoded
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    ENGINE.decode(chars).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MetadataProvider as _, providers::jpeg::Jpeg, util::logger};
+
+    use super::{
+        DepthFormat, DepthMapCodec, DepthMapKind, DepthMapSource, DepthUnits, decode_base64,
+    };
+
+    /// A grayscale JPEG with no image data, but with a size and XMP.
+    fn jpeg(width: u16, height: u16, xmp: &str) -> Vec<u8> {
+        let app1 = [b"http://ns.adobe.com/xap/1.0/\0", xmp.as_bytes()].concat();
+        let sof0 = [
+            [8].as_slice(),
+            &height.to_be_bytes(),
+            &width.to_be_bytes(),
+            &[1, 1, 0x11, 0],
+        ]
+        .concat();
+
+        let mut jpeg = vec![0xFF, 0xD8];
+        for (marker, payload) in [(0xE1, app1), (0xC0, sof0)] {
+            jpeg.extend([0xFF, marker]);
+            jpeg.extend((payload.len() as u16 + 2).to_be_bytes());
+            jpeg.extend(payload);
+        }
+        jpeg.extend([0xFF, 0xD9]);
+        jpeg
+    }
+
+    /// XMP listing a depth image of the given length, with depth parameters.
+    fn container_xmp(depth_len: usize) -> String {
+        format!(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:GDepth="http://ns.google.com/photos/1.0/depthmap/"
+        xmlns:Container="http://ns.google.com/photos/1.0/container/"
+        xmlns:Item="http://ns.google.com/photos/1.0/container/item/"
+      GDepth:Format="RangeLinear"
+      GDepth:Near="0.25"
+      GDepth:Far="8"
+      GDepth:Units="Meters">
+      <Container:Directory>
+        <rdf:Seq>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Mime="image/jpeg" Item:Semantic="Primary"/>
+          </rdf:li>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Mime="image/jpeg" Item:Semantic="Depth" Item:Length="{depth_len}"/>
+          </rdf:li>
+        </rdf:Seq>
+      </Container:Directory>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>"#
+        )
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(decode_base64("aGk=").as_deref(), Some(b"hi".as_slice()));
+        assert_eq!(
+            decode_base64("aGVs\nbG8").as_deref(),
+            Some(b"hello".as_slice())
+        );
+        assert_eq!(decode_base64("a"), None);
+        assert_eq!(decode_base64("a$==="), None);
+    }
+
+    #[test]
+    fn aux_types() {
+        assert_eq!(
+            DepthMapKind::from_aux_type("urn:mpeg:hevc:2015:auxid:2"),
+            Some(DepthMapKind::Depth)
+        );
+        assert_eq!(
+            DepthMapKind::from_aux_type("urn:com:apple:photo:2018:aux:portraiteffectsmatte"),
+            Some(DepthMapKind::PortraitEffectsMatte)
+        );
+        assert_eq!(
+            DepthMapKind::from_aux_type("urn:com:apple:photo:2019:aux:semanticskinmatte"),
+            Some(DepthMapKind::SemanticMatte("semanticskinmatte".into()))
+        );
+
+        // alpha and gain maps aren't depth
+        assert_eq!(
+            DepthMapKind::from_aux_type("urn:mpeg:hevc:2015:auxid:1"),
+            None
+        );
+        assert_eq!(
+            DepthMapKind::from_aux_type("urn:com:apple:photo:2020:aux:hdrgainmap"),
+            None
+        );
+    }
+
+    /// This file has a `GDepth` image in its extended XMP.
+    #[test]
+    fn gdepth_jpeg() {
+        logger();
+
+        let file = include_bytes!("../../assets/providers/jpeg/exiv2-bug922.jpg");
+        let jpeg = Jpeg::new(file).unwrap();
+
+        let [depth] = jpeg.depth_maps() else {
+            panic!("expected one depth map");
+        };
+        assert_eq!(depth.source, DepthMapSource::GDepth);
+        assert_eq!(depth.codec, Some(DepthMapCodec::Mime("image/png".into())));
+        assert!(
+            depth
+                .data
+                .as_ref()
+                .is_some_and(|d| d.starts_with(b"\x89PNG"))
+        );
+        assert_eq!(depth.dimensions, Some((1536, 2048)));
+
+        let parameters = depth.parameters.as_ref().expect("has parameters");
+        assert_eq!(parameters.near, Some(10.917767524719238));
+        assert_eq!(parameters.format, Some(DepthFormat::RangeInverse));
+        assert_eq!(parameters.far, Some(38.58317565917969));
+        assert_eq!(parameters.units, None);
+    }
+
+    /// Newer Google cameras append the depth image, which describes itself.
+    #[test]
+    fn container_jpeg() {
+        logger();
+
+        let depth = jpeg(
+            32,
+            24,
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"><rdf:Description rdf:about="" xmlns:GDepth="http://ns.google.com/photos/1.0/depthmap/" GDepth:Format="RangeInverse" GDepth:Near="0.5" GDepth:Far="4" GDepth:Units="Meters"/></rdf:RDF></x:xmpmeta>"#,
+        );
+        let primary = jpeg(64, 48, &container_xmp(depth.len()));
+        let file = [primary.as_slice(), &depth].concat();
+
+        let jpeg = Jpeg::new(&file).unwrap();
+        let [depth_map] = jpeg.depth_maps() else {
+            panic!("expected one depth map");
+        };
+        assert_eq!(depth_map.source, DepthMapSource::GoogleContainer);
+        assert_eq!(depth_map.location, Some(primary.len()..file.len()));
+        assert_eq!(depth_map.dimensions, Some((32, 24)));
+
+        let parameters = depth_map.parameters.as_ref().expect("has parameters");
+        assert_eq!(parameters.format, Some(DepthFormat::RangeInverse));
+        assert_eq!((parameters.near, parameters.far), (Some(0.5), Some(4.0)));
+        assert_eq!(parameters.units, Some(DepthUnits::Meters));
+    }
+
+    /// Depth images that list their own depth images aren't searched, so
+    /// deeply nested files are quick to parse.
+    #[test]
+    fn nested_containers() {
+        logger();
+
+        let mut file = jpeg(8, 8, "");
+        for _ in 0..20 {
+            let primary = jpeg(16, 16, &container_xmp(file.len()));
+            file = [primary, file].concat();
+        }
+
+        let jpeg = Jpeg::new(&file).unwrap();
+        let [depth_map] = jpeg.depth_maps() else {
+            panic!("expected one depth map");
+        };
+        assert_eq!(depth_map.dimensions, Some((16, 16)));
+        assert_eq!(depth_map.parameters.as_ref().and_then(|p| p.far), Some(8.0));
+    }
+}
@@ -0,0 +1,762 @@
+//! HDR gain maps let a viewer turn a standard (SDR) image into an HDR one.
+//!
+//! A gain map is a second, usually smaller, image stored in the same file.
+//! Its parameters say how much each pixel may be brightened. There are a few
+//! ways to store them:
+//!
+//! - Ultra HDR (Android) and Adobe use the `hdrgm` XMP namespace on the gain
+//!   map image. In JPEG, the gain map is found with MPF or `Container` XMP.
+//! - ISO 21496-1 stores the parameters in binary. HEIF files (like AVIF) use a
+//!   `tmap` item, and JPEG files use an `APP2` segment on the gain map image.
+//! - Apple stores an auxiliary `HDRGainMap` image in HEIC (or an MPF image in
+//!   JPEG), which is described by XMP in the `HDRGainMap` namespace.
+//!
+//! All of these become one [`GainMapInfo`]. Its values use the units from the
+//! `hdrgm` namespace, so most of them are in stops (log2).
+
+use core::ops::Range;
+
+use raves_metadata_types::xmp::{XmpElement, XmpPrimitive, XmpValue};
+use winnow::{
+    Parser as _,
+    binary::{be_i32, be_u8, be_u16, be_u32},
+    error::EmptyError,
+};
+
+use crate::{
+    providers::jpeg::Mpf,
+    xmp::{Xmp, XmpDocument},
+};
+
+/// The `hdrgm` namespace, used by Ultra HDR and Adobe.
+const HDRGM: &str = "http://ns.adobe.com/hdr-gain-map/1.0/";
+
+/// Apple's gain map namespace.
+const APPLE_HDR_GAIN_MAP: &str = "http://ns.apple.com/HDRGainMap/1.0/";
+
+/// The `Container:Item` semantic for an Ultra HDR gain map.
+const GAIN_MAP_SEMANTIC: &str = "GainMap";
+
+/// Parameters for an HDR gain map, along with where its image is.
+///
+/// Values with three entries are per color channel (red, green, blue). When a
+/// file only gives one value, it's used for all three.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GainMapInfo {
+    /// Where the parameters came from.
+    pub source: GainMapSource,
+
+    /// The smallest gain in the gain map, in stops.
+    ///
+    /// This is the log2 of the minimum content boost.
+    pub gain_map_min: [f64; 3],
+
+    /// The largest gain in the gain map, in stops.
+    ///
+    /// This is the log2 of the maximum content boost. Apple's gain maps don't
+    /// have one, so see [`GainMapInfo::apple_headroom`] for those.
+    pub gain_map_max: [f64; 3],
+
+    /// The gamma applied to the gain map's values.
+    pub gamma: [f64; 3],
+
+    /// An offset added to the SDR image's pixels.
+    pub offset_sdr: [f64; 3],
+
+    /// An offset added to the HDR image's pixels.
+    pub offset_hdr: [f64; 3],
+
+    /// The display headroom where the gain map starts to apply, in stops.
+    pub hdr_capacity_min: f64,
+
+    /// The display headroom where the gain map fully applies, in stops.
+    pub hdr_capacity_max: f64,
+
+    /// Whether the base image is the HDR one (and the gain map makes SDR).
+    pub base_rendition_is_hdr: bool,
+
+    /// Apple's `HDRGainMapHeadroom`, for [`GainMapSource::Apple`] gain maps.
+    ///
+    /// Apple scales pixels linearly by this headroom, instead of
+    /// interpolating a log2 gain between `gain_map_min` and `gain_map_max`.
+    /// One can't be turned into the other, so it's given as-is. The rest of
+    /// an Apple gain map's parameters are left at values that don't boost
+    /// anything.
+    pub apple_headroom: Option<f64>,
+
+    /// Where the gain map image is in the file, in bytes.
+    ///
+    /// This is `None` when the image couldn't be found, or when it isn't
+    /// stored as one piece of the file.
+    pub location: Option<Range<usize>>,
+}
+
+/// A way of storing gain map parameters.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub enum GainMapSource {
+    /// XMP using the `hdrgm` namespace (Ultra HDR and Adobe).
+    Hdrgm,
+
+    /// ISO 21496-1 binary metadata, like a HEIF `tmap` item.
+    Iso21496,
+
+    /// XMP using Apple's `HDRGainMap` namespace.
+    Apple,
+}
+
+impl GainMapInfo {
+    /// The smallest content boost, as a linear multiplier.
+    pub fn min_content_boost(&self) -> [f64; 3] {
+        self.gain_map_min.map(f64::exp2)
+    }
+
+    /// The largest content boost, as a linear multiplier.
+    pub fn max_content_boost(&self) -> [f64; 3] {
+        self.gain_map_max.map(f64::exp2)
+    }
+
+    /// Reads gain map parameters from `hdrgm` or Apple `HDRGainMap` XMP.
+    pub(crate) fn from_xmp(doc: &XmpDocument) -> Option<Self> {
+        Self::from_hdrgm(doc).or_else(|| Self::from_apple(doc))
+    }
+
+    /// Reads `hdrgm` parameters.
+    ///
+    /// `GainMapMax` and `HDRCapacityMax` are required. The rest use the
+    /// defaults from Adobe's specification.
+    fn from_hdrgm(doc: &XmpDocument) -> Option<Self> {
+        let channels_or = |name, default| match doc.get(HDRGM, name) {
+            Some(element) => channels(element),
+            None => Some([default; 3]),
+        };
+        let scalar = |name, default| match doc.get(HDRGM, name) {
+            Some(element) => real(&element.value),
+            None => Some(default),
+        };
+
+        let gain_map_max = doc.get(HDRGM, "GainMapMax").and_then(channels)?;
+        let hdr_capacity_max = doc.get(HDRGM, "HDRCapacityMax").map(|e| real(&e.value))??;
+
+        Some(Self {
+            source: GainMapSource::Hdrgm,
+            gain_map_min: channels_or("GainMapMin", 0.0)?,
+            gain_map_max,
+            gamma: channels_or("Gamma", 1.0)?,
+            offset_sdr: channels_or("OffsetSDR", 1.0 / 64.0)?,
+            offset_hdr: channels_or("OffsetHDR", 1.0 / 64.0)?,
+            hdr_capacity_min: scalar("HDRCapacityMin", 0.0)?,
+            hdr_capacity_max,
+            base_rendition_is_hdr: doc.get(HDRGM, "BaseRenditionIsHDR").is_some_and(|e| {
+                match &e.value {
+                    XmpValue::Simple(XmpPrimitive::Boolean(b)) => *b,
+                    XmpValue::Simple(XmpPrimitive::Text(t)) => t.trim() == "True",
+                    _ => false,
+                }
+            }),
+            apple_headroom: None,
+            location: None,
+        })
+    }
+
+    /// Reads Apple's `HDRGainMap` parameters.
+    ///
+    /// Apple only gives a headroom, which goes in `apple_headroom`.
+    fn from_apple(doc: &XmpDocument) -> Option<Self> {
+        let headroom = doc
+            .get(APPLE_HDR_GAIN_MAP, "HDRGainMapHeadroom")
+            .and_then(|e| real(&e.value))?;
+
+        Some(Self {
+            source: GainMapSource::Apple,
+            gain_map_min: [0.0; 3],
+            gain_map_max: [0.0; 3],
+            gamma: [1.0; 3],
+            offset_sdr: [0.0; 3],
+            offset_hdr: [0.0; 3],
+            hdr_capacity_min: 0.0,
+            hdr_capacity_max: 0.0,
+            base_rendition_is_hdr: false,
+            apple_headroom: Some(headroom),
+            location: None,
+        })
+    }
+
+    /// Reads ISO 21496-1 binary gain map metadata.
+    ///
+    /// This starts at the `minimum_version` field, so remove any box version
+    /// (like `tmap`'s) first.
+    pub(crate) fn from_iso_21496(mut input: &[u8]) -> Option<Self> {
+        let input = &mut input;
+        let parsed = (|| -> Result<Self, EmptyError> {
+            let minimum_version: u16 = be_u16.parse_next(input)?;
+            if minimum_version != 0 {
+                log::warn!("Unsupported ISO 21496-1 version: `{minimum_version}`");
+                return Err(EmptyError);
+            }
+            let _writer_version: u16 = be_u16.parse_next(input)?;
+
+            let flags: u8 = be_u8.parse_next(input)?;
+            let channel_count = if flags & 0x80 != 0 { 3 } else { 1 };
+            let use_common_denominator = flags & 0x08 != 0;
+
+            // each value is a fraction. they might all share a denominator
+            let common_denominator: Option<u32> = if use_common_denominator {
+                Some(be_u32.parse_next(input)?)
+            } else {
+                None
+            };
+            let unsigned = |input: &mut &[u8]| -> Result<f64, EmptyError> {
+                let n = be_u32.parse_next(input)?;
+                let d = match common_denominator {
+                    Some(d) => d,
+                    None => be_u32.parse_next(input)?,
+                };
+                fraction(n as f64, d)
+            };
+            let signed = |input: &mut &[u8]| -> Result<f64, EmptyError> {
+                let n = be_i32.parse_next(input)?;
+                let d = match common_denominator {
+                    Some(d) => d,
+                    None => be_u32.parse_next(input)?,
+                };
+                fraction(n as f64, d)
+            };
+
+            let base_hdr_headroom = unsigned(input)?;
+            let alternate_hdr_headroom = unsigned(input)?;
+
+            let mut gain_map_min = [0.0; 3];
+            let mut gain_map_max = [0.0; 3];
+            let mut gamma = [1.0; 3];
+            let mut base_offset = [0.0; 3];
+            let mut alternate_offset = [0.0; 3];
+            for c in 0..channel_count {
+                gain_map_min[c] = signed(input)?;
+                gain_map_max[c] = signed(input)?;
+                gamma[c] = unsigned(input)?;
+                base_offset[c] = signed(input)?;
+                alternate_offset[c] = signed(input)?;
+            }
+
+            // one channel applies to all three
+            if channel_count == 1 {
+                for values in [
+                    &mut gain_map_min,
+                    &mut gain_map_max,
+                    &mut gamma,
+                    &mut base_offset,
+                    &mut alternate_offset,
+                ] {
+                    *values = [values[0]; 3];
+                }
+            }
+
+            // the base image is HDR when it has more headroom
+            let base_rendition_is_hdr = base_hdr_headroom > alternate_hdr_headroom;
+            let (offset_sdr, offset_hdr, hdr_capacity_min, hdr_capacity_max) =
+                if base_rendition_is_hdr {
+                    (
+                        alternate_offset,
+                        base_offset,
+                        alternate_hdr_headroom,
+                        base_hdr_headroom,
+                    )
+                } else {
+                    (
+                        base_offset,
+                        alternate_offset,
+                        base_hdr_headroom,
+                        alternate_hdr_headroom,
+                    )
+                };
+
+            Ok(Self {
+                source: GainMapSource::Iso21496,
+                gain_map_min,
+                gain_map_max,
+                gamma,
+                offset_sdr,
+                offset_hdr,
+                hdr_capacity_min,
+                hdr_capacity_max,
+                base_rendition_is_hdr,
+                apple_headroom: None,
+                location: None,
+            })
+        })();
+
+        parsed
+            .inspect_err(|_| log::warn!("Failed to parse ISO 21496-1 gain map metadata."))
+            .ok()
+    }
+}
+
+/// Finds the gain map in a JPEG.
+///
+/// Candidate images come from MPF and from `Container` XMP items with the
+/// `GainMap` semantic. `segments` finds an image's XMP and ISO 21496-1
+/// payloads.
+pub(crate) fn find_in_jpeg<'input>(
+    input: &'input [u8],
+    image_end: usize,
+    xmp: Option<&XmpDocument>,
+    mpf: Option<&Mpf>,
+    segments: impl Fn(&'input [u8]) -> (Option<&'input [u8]>, Option<&'input [u8]>),
+) -> Option<GainMapInfo> {
+    let from_mpf = mpf
+        .into_iter()
+        .flat_map(|mpf| mpf.images.iter().skip(1))
+        .map(|image| image.offset..image.offset.saturating_add(image.size as usize));
+
+    let from_container = xmp
+        .map(|doc| doc.container_item_ranges(image_end))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(item, _)| item.semantic == GAIN_MAP_SEMANTIC)
+        .map(|(_, range)| range);
+
+    let found = from_mpf.chain(from_container).find_map(|range| {
+        let (xmp, iso) = segments(input.get(range.clone())?);
+
+        let info = xmp
+            .and_then(|x| Xmp::new_from_bytes(x).ok())
+            .and_then(|x| GainMapInfo::from_xmp(x.document()))
+            .or_else(|| iso.and_then(GainMapInfo::from_iso_21496))?;
+
+        Some(GainMapInfo {
+            location: Some(range),
+            ..info
+        })
+    });
+
+    // some files keep the parameters with the primary image
+    found.or_else(|| xmp.and_then(GainMapInfo::from_xmp))
+}
+
+/// Grabs a real number, even when it was stored as an integer or text.
+fn real(value: &XmpValue) -> Option<f64> {
+    match value {
+        XmpValue::Simple(XmpPrimitive::Real(r)) => Some(*r),
+        XmpValue::Simple(XmpPrimitive::Integer(i)) => Some(*i as f64),
+        XmpValue::Simple(XmpPrimitive::Text(t)) => t.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Grabs one real number per channel.
+///
+/// A single value applies to all channels. Otherwise, there must be three.
+fn channels(element: &XmpElement) -> Option<[f64; 3]> {
+    match &element.value {
+        XmpValue::OrderedArray(items) | XmpValue::UnorderedArray(items) => {
+            let values: Vec<f64> = items
+                .iter()
+                .map(|i| real(&i.value))
+                .collect::<Option<_>>()?;
+            match values.as_slice() {
+                [v] => Some([*v; 3]),
+                [r, g, b] => Some([*r, *g, *b]),
+                _ => {
+                    log::warn!("Gain map value had `{}` channels.", values.len());
+                    None
+                }
+            }
+        }
+        other => real(other).map(|v| [v; 3]),
+    }
+}
+
+/// Makes a fraction, failing on a zero denominator.
+fn fraction(numerator: f64, denominator: u32) -> Result<f64, EmptyError> {
+    if denominator == 0 {
+        log::warn!("Gain map fraction had a zero denominator.");
+        return Err(EmptyError);
+    }
+    Ok(numerator / denominator as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        MetadataProvider as _,
+        providers::{
+            avif::Avif,
+            gain_map::{GainMapInfo, GainMapSource},
+            heic::Heic,
+            jpeg::Jpeg,
+            shared::bmff::tests::{bx, full},
+        },
+        util::logger,
+    };
+
+    /// A JPEG with no image data, but with the given XMP.
+    fn jpeg(xmp: &str) -> Vec<u8> {
+        let payload = [b"http://ns.adobe.com/xap/1.0/\0", xmp.as_bytes()].concat();
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend((payload.len() as u16 + 2).to_be_bytes());
+        jpeg.extend(payload);
+        jpeg.extend([0xFF, 0xD9]);
+        jpeg
+    }
+
+    /// Ultra HDR lists its gain map in `Container:Directory`, and the gain
+    /// map image has the parameters.
+    #[test]
+    fn ultra_hdr_jpeg() {
+        logger();
+
+        let gain_map = jpeg(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:hdrgm="http://ns.adobe.com/hdr-gain-map/1.0/"
+      hdrgm:Version="1.0"
+      hdrgm:GainMapMax="2.5"
+      hdrgm:Gamma="1"
+      hdrgm:HDRCapacityMin="0"
+      hdrgm:HDRCapacityMax="2.5"
+      hdrgm:BaseRenditionIsHDR="False">
+      <hdrgm:GainMapMin>
+        <rdf:Seq>
+          <rdf:li>0</rdf:li>
+          <rdf:li>0.5</rdf:li>
+          <rdf:li>1</rdf:li>
+        </rdf:Seq>
+      </hdrgm:GainMapMin>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>"#,
+        );
+
+        let primary = jpeg(&format!(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:hdrgm="http://ns.adobe.com/hdr-gain-map/1.0/"
+        xmlns:Container="http://ns.google.com/photos/1.0/container/"
+        xmlns:Item="http://ns.google.com/photos/1.0/container/item/"
+      hdrgm:Version="1.0">
+      <Container:Directory>
+        <rdf:Seq>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Mime="image/jpeg" Item:Semantic="Primary"/>
+          </rdf:li>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Mime="image/jpeg" Item:Semantic="GainMap" Item:Length="{}"/>
+          </rdf:li>
+        </rdf:Seq>
+      </Container:Directory>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>"#,
+            gain_map.len()
+        ));
+
+        let file = [primary.as_slice(), &gain_map].concat();
+        let info = Jpeg::new(&file).unwrap().gain_map().cloned();
+
+        assert_eq!(
+            info,
+            Some(GainMapInfo {
+                source: GainMapSource::Hdrgm,
+                gain_map_min: [0.0, 0.5, 1.0],
+                gain_map_max: [2.5; 3],
+                gamma: [1.0; 3],
+                offset_sdr: [1.0 / 64.0; 3],
+                offset_hdr: [1.0 / 64.0; 3],
+                hdr_capacity_min: 0.0,
+                hdr_capacity_max: 2.5,
+                base_rendition_is_hdr: false,
+                apple_headroom: None,
+                location: Some(primary.len()..file.len()),
+            })
+        );
+        assert_eq!(info.unwrap().max_content_boost(), [2.5_f64.exp2(); 3]);
+    }
+
+    /// ISO 21496-1 may share one denominator and give three channels.
+    #[test]
+    fn iso_21496_common_denominator() {
+        logger();
+
+        let mut metadata = Vec::new();
+        metadata.extend(0_u16.to_be_bytes()); // minimum version
+        metadata.extend(0_u16.to_be_bytes()); // writer version
+        metadata.push(0x80 | 0x08); // multichannel, common denominator
+        metadata.extend(4_u32.to_be_bytes()); // denominator
+
+        // the base image has more headroom, so it's the HDR one
+        metadata.extend(8_u32.to_be_bytes());
+        metadata.extend(0_u32.to_be_bytes());
+        for c in 0..3_i32 {
+            metadata.extend((-c).to_be_bytes()); // min
+            metadata.extend(8_i32.to_be_bytes()); // max
+            metadata.extend(4_u32.to_be_bytes()); // gamma
+            metadata.extend(1_i32.to_be_bytes()); // base offset
+            metadata.extend(2_i32.to_be_bytes()); // alternate offset
+        }
+
+        let info = GainMapInfo::from_iso_21496(&metadata).unwrap();
+        assert_eq!(info.gain_map_min, [0.0, -0.25, -0.5]);
+        assert_eq!(info.gain_map_max, [2.0; 3]);
+        assert_eq!(info.gamma, [1.0; 3]);
+        assert!(info.base_rendition_is_hdr);
+        assert_eq!(info.offset_sdr, [0.5; 3]);
+        assert_eq!(info.offset_hdr, [0.25; 3]);
+        assert_eq!((info.hdr_capacity_min, info.hdr_capacity_max), (0.0, 2.0));
+
+        // truncated data isn't parsed
+        assert_eq!(GainMapInfo::from_iso_21496(&metadata[..20]), None);
+    }
+
+    /// HEIF files use a `tmap` item derived from the base and gain map
+    /// images.
+    #[test]
+    fn avif_tmap_item() {
+        logger();
+
+        let mut tmap = vec![0]; // box version
+        tmap.extend(0_u16.to_be_bytes());
+        tmap.extend(0_u16.to_be_bytes());
+        tmap.push(0); // one channel, separate denominators
+        for (n, d) in [(0_u32, 1_u32), (3, 1)] {
+            tmap.extend(n.to_be_bytes());
+            tmap.extend(d.to_be_bytes());
+        }
+        for (n, d) in [(0_i32, 1_u32), (5, 2), (1, 1), (1, 64), (1, 64)] {
+            tmap.extend(n.to_be_bytes());
+            tmap.extend(d.to_be_bytes());
+        }
+
+        let base = b"base";
+        let gain_map = b"gain";
+
+        // items: `#1` is the base, `#2` is the gain map, and `#3` is `tmap`
+        let meta = |mdat_start: u32| {
+            let infe = |id: u16, ty: &[u8; 4]| {
+                full(
+                    b"infe",
+                    2,
+                    0,
+                    &[id.to_be_bytes().as_slice(), &[0, 0], ty, b"\0"].concat(),
+                )
+            };
+            let iinf = full(
+                b"iinf",
+                0,
+                0,
+                &[
+                    3_u16.to_be_bytes().as_slice(),
+                    &infe(1, b"av01"),
+                    &infe(2, b"av01"),
+                    &infe(3, b"tmap"),
+                ]
+                .concat(),
+            );
+
+            let mut iloc = vec![0x44, 0x00];
+            iloc.extend(3_u16.to_be_bytes());
+            let mut offset = mdat_start;
+            for (id, len) in [(1_u16, base.len()), (2, gain_map.len()), (3, tmap.len())] {
+                iloc.extend(id.to_be_bytes());
+                iloc.extend(0_u16.to_be_bytes()); // data reference index
+                iloc.extend(1_u16.to_be_bytes()); // extent count
+                iloc.extend(offset.to_be_bytes());
+                iloc.extend((len as u32).to_be_bytes());
+                offset += len as u32;
+            }
+            let iloc = full(b"iloc", 0, 0, &iloc);
+
+            let dimg = bx(b"dimg", &[0, 3, 0, 2, 0, 1, 0, 2]);
+            let iref = full(b"iref", 0, 0, &dimg);
+
+            full(b"meta", 0, 0, &[iinf, iloc, iref].concat())
+        };
+
+        let ftyp = bx(b"ftyp", b"avif\0\0\0\0mif1avif");
+        let mdat_start = (ftyp.len() + meta(0).len() + 8) as u32;
+        let mdat = bx(b"mdat", &[base.as_slice(), gain_map, &tmap].concat());
+        let file = [ftyp, meta(mdat_start), mdat].concat();
+
+        let info = Avif::new(&file).unwrap().gain_map().cloned().unwrap();
+        assert_eq!(info.source, GainMapSource::Iso21496);
+        assert_eq!(info.gain_map_max, [2.5; 3]);
+        assert_eq!(info.offset_sdr, [1.0 / 64.0; 3]);
+        assert_eq!(info.hdr_capacity_max, 3.0);
+        assert!(!info.base_rendition_is_hdr);
+
+        let location = info.location.unwrap();
+        assert_eq!(&file[location], gain_map);
+    }
+
+    /// `tmap` items may be stored in `idat`, which comes before `mdat`.
+    #[test]
+    fn avif_tmap_in_idat() {
+        logger();
+
+        let mut tmap = vec![0]; // box version
+        tmap.extend(0_u16.to_be_bytes());
+        tmap.extend(0_u16.to_be_bytes());
+        tmap.push(0); // one channel, separate denominators
+        for (n, d) in [
+            (0_u32, 1_u32),
+            (2, 1),
+            (0, 1),
+            (4, 1),
+            (1, 1),
+            (0, 1),
+            (0, 1),
+        ] {
+            tmap.extend(n.to_be_bytes());
+            tmap.extend(d.to_be_bytes());
+        }
+
+        let base = b"base";
+        let gain_map = b"gain";
+
+        // items: `#1` is the base, `#2` is the gain map, and `#3` is `tmap`,
+        // which is in `idat`
+        let meta = |mdat_start: u32| {
+            let infe = |id: u16, ty: &[u8; 4]| {
+                full(
+                    b"infe",
+                    2,
+                    0,
+                    &[id.to_be_bytes().as_slice(), &[0, 0], ty, b"\0"].concat(),
+                )
+            };
+            let iinf = full(
+                b"iinf",
+                0,
+                0,
+                &[
+                    3_u16.to_be_bytes().as_slice(),
+                    &infe(1, b"av01"),
+                    &infe(2, b"av01"),
+                    &infe(3, b"tmap"),
+                ]
+                .concat(),
+            );
+
+            let mut iloc = vec![0x44, 0x00];
+            iloc.extend(3_u16.to_be_bytes());
+            let extents = [
+                (1_u16, 0_u16, mdat_start, base.len()),
+                (2, 0, mdat_start + base.len() as u32, gain_map.len()),
+                (3, 1, 0, tmap.len()),
+            ];
+            for (id, construction_method, offset, len) in extents {
+                iloc.extend(id.to_be_bytes());
+                iloc.extend(construction_method.to_be_bytes());
+                iloc.extend(0_u16.to_be_bytes()); // data reference index
+                iloc.extend(1_u16.to_be_bytes()); // extent count
+                iloc.extend(offset.to_be_bytes());
+                iloc.extend((len as u32).to_be_bytes());
+            }
+            let iloc = full(b"iloc", 1, 0, &iloc);
+
+            let dimg = bx(b"dimg", &[0, 3, 0, 2, 0, 1, 0, 2]);
+            let iref = full(b"iref", 0, 0, &dimg);
+
+            full(
+                b"meta",
+                0,
+                0,
+                &[iinf, iloc, iref, bx(b"idat", &tmap)].concat(),
+            )
+        };
+
+        let ftyp = bx(b"ftyp", b"avif\0\0\0\0mif1avif");
+        let mdat_start = (ftyp.len() + meta(0).len() + 8) as u32;
+        let mdat = bx(b"mdat", &[base.as_slice(), gain_map].concat());
+        let file = [ftyp, meta(mdat_start), mdat].concat();
+
+        let info = Avif::new(&file).unwrap().gain_map().cloned().unwrap();
+        assert_eq!(info.source, GainMapSource::Iso21496);
+        assert_eq!(info.gain_map_max, [4.0; 3]);
+        assert_eq!(info.hdr_capacity_max, 2.0);
+
+        let location = info.location.unwrap();
+        assert_eq!(&file[location], gain_map);
+    }
+
+    /// Apple's HEIC gain map is its own image, which an XMP item describes.
+    #[test]
+    fn heic_apple_gain_map_item() {
+        logger();
+
+        let xmp = br#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"><rdf:Description rdf:about="" xmlns:HDRGainMap="http://ns.apple.com/HDRGainMap/1.0/" HDRGainMap:HDRGainMapVersion="65536" HDRGainMap:HDRGainMapHeadroom="1.5"/></rdf:RDF></x:xmpmeta>"#;
+        let base = b"base";
+        let gain_map = b"gain";
+
+        // items: `#1` is the base, `#2` is the gain map, and `#3` is its XMP
+        let meta = |mdat_start: u32| {
+            let infe = |id: u16, ty: &[u8; 4], mime: &[u8]| {
+                full(
+                    b"infe",
+                    2,
+                    0,
+                    &[id.to_be_bytes().as_slice(), &[0, 0], ty, b"\0", mime].concat(),
+                )
+            };
+            let iinf = full(
+                b"iinf",
+                0,
+                0,
+                &[
+                    3_u16.to_be_bytes().as_slice(),
+                    &infe(1, b"hvc1", b""),
+                    &infe(2, b"hvc1", b""),
+                    &infe(3, b"mime", b"application/rdf+xml\0\0"),
+                ]
+                .concat(),
+            );
+
+            let mut iloc = vec![0x44, 0x00];
+            iloc.extend(3_u16.to_be_bytes());
+            let mut offset = mdat_start;
+            for (id, len) in [(1_u16, base.len()), (2, gain_map.len()), (3, xmp.len())] {
+                iloc.extend(id.to_be_bytes());
+                iloc.extend(0_u16.to_be_bytes()); // data reference index
+                iloc.extend(1_u16.to_be_bytes()); // extent count
+                iloc.extend(offset.to_be_bytes());
+                iloc.extend((len as u32).to_be_bytes());
+                offset += len as u32;
+            }
+            let iloc = full(b"iloc", 0, 0, &iloc);
+
+            let refs = [
+                bx(b"auxl", &[0, 2, 0, 1, 0, 1]),
+                bx(b"cdsc", &[0, 3, 0, 1, 0, 2]),
+            ]
+            .concat();
+            let iref = full(b"iref", 0, 0, &refs);
+
+            full(b"meta", 0, 0, &[iinf, iloc, iref].concat())
+        };
+
+        let ftyp = bx(b"ftyp", b"heic\0\0\0\0mif1heic");
+        let mdat_start = (ftyp.len() + meta(0).len() + 8) as u32;
+        let mdat = bx(b"mdat", &[base.as_slice(), gain_map, xmp].concat());
+        let file = [ftyp, meta(mdat_start), mdat].concat();
+
+        let info = Heic::new(&file).unwrap().gain_map().cloned().unwrap();
+        assert_eq!(info.source, GainMapSource::Apple);
+        assert_eq!(info.apple_headroom, Some(1.5));
+        assert_eq!(info.max_content_boost(), [1.0; 3]);
+        assert!(!info.base_rendition_is_hdr);
+
+        let location = info.location.unwrap();
+        assert_eq!(&file[location], gain_map);
+    }
+
+    /// Regular images don't have gain maps.
+    #[test]
+    fn no_gain_map() {
+        logger();
+
+        let file = include_bytes!("../../assets/providers/jpeg/Calico_Cat_Asleep.jpg");
+        assert_eq!(Jpeg::new(file).unwrap().gain_map(), None);
+    }
+}
@@ -3,11 +3,17 @@
 pub mod block;
 pub mod error;
 
+use core::time::Duration;
+
 use winnow::Parser;
 
 use crate::{
     MetadataProvider,
     exif::{Exif, error::ExifFatalError},
+    providers::{
+        animation_info::{AnimationInfo, LoopCount},
+        image_info::{ColorType, ImageInfo},
+    },
     xmp::{Xmp, error::XmpError},
 };
 use block::{
@@ -44,6 +50,13 @@ pub struct Gif {
     /// May not be parsed yet, but the bytes are in there regardless, if the
     /// GIF blob had any XMP to provide.
     xmp: Option<Result<Xmp, XmpError>>,
+
+    /// Size and frame count, from the logical screen and image descriptors.
+    image_info: ImageInfo,
+
+    /// Frame delays and loop count, from the graphic control extensions and
+    /// the `NETSCAPE2.0` application extension.
+    animation_info: AnimationInfo,
 }
 
 /// Any block in the GIF file after the header, logical screen descriptor, and
@@ -407,12 +420,57 @@ impl MetadataProvider for Gif {
             }
         }
 
+        // each image descriptor is one frame, shown for its graphic control
+        // extension's delay (in hundredths of a second)
+        let frame_delays: Vec<Duration> = repeatable_blocks
+            .iter()
+            .filter_map(|block| match block {
+                RepeatableBlock::Graphic {
+                    graphic_control_extension,
+                    suffix: RepeatableGraphicBlock::Image { .. },
+                } => Some(Duration::from_millis(
+                    graphic_control_extension
+                        .as_ref()
+                        .map_or(0, |gce| u64::from(gce.delay_time) * 10),
+                )),
+                _ => None,
+            })
+            .collect();
+        let frame_count: usize = frame_delays.len();
+
+        let image_info = ImageInfo {
+            width: logical_screen_descriptor.logical_screen_width.into(),
+            height: logical_screen_descriptor.logical_screen_height.into(),
+
+            // the global color table has `2^(size + 1)` entries, so each index
+            // uses `size + 1` bits
+            bit_depth: logical_screen_descriptor
+                .global_color_table_flag
+                .then_some(logical_screen_descriptor.size_of_global_color_table + 1),
+            color_type: Some(ColorType::Indexed),
+            channels: Some(1),
+            frame_count: Some(frame_count.try_into().unwrap_or(u32::MAX)),
+            jpeg: None,
+        };
+
+        let animation_info = AnimationInfo {
+            frame_count: image_info.frame_count.unwrap_or_default(),
+            loop_count: loop_count(&repeatable_blocks),
+            frame_delays: if frame_count > 1 {
+                frame_delays
+            } else {
+                Vec::new()
+            },
+        };
+
         Ok(Gif {
             header,
             logical_screen_descriptor,
             global_color_table,
-            repeatable_blocks: vec![],
+            repeatable_blocks,
             xmp,
+            image_info,
+            animation_info,
         })
     }
 
@@ -423,13 +481,57 @@ impl MetadataProvider for Gif {
     fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
         self.xmp.as_ref().map(|r| r.as_ref())
     }
+
+    fn image_info(&self) -> Option<&ImageInfo> {
+        Some(&self.image_info)
+    }
+
+    fn animation_info(&self) -> Option<&AnimationInfo> {
+        Some(&self.animation_info)
+    }
+}
+
+/// Finds the loop count in a `NETSCAPE2.0` (or `ANIMEXTS1.0`) application
+/// extension.
+///
+/// Its data is a sub-block ID of `1`, then a little-endian `u16`. Zero means
+/// "loop forever". Without the extension, the GIF plays once.
+fn loop_count(repeatable_blocks: &[RepeatableBlock]) -> LoopCount {
+    let loops: Option<u16> = repeatable_blocks.iter().find_map(|block| match block {
+        RepeatableBlock::ApplicationExtension(ext)
+            if matches!(
+                (
+                    &ext.application_identifier,
+                    &ext.application_authentication_code
+                ),
+                (b"NETSCAPE", b"2.0") | (b"ANIMEXTS", b"1.0")
+            ) =>
+        {
+            match ext.application_data.as_slice() {
+                [0x01, low, high, ..] => Some(u16::from_le_bytes([*low, *high])),
+                _ => None,
+            }
+        }
+        _ => None,
+    });
+
+    match loops {
+        Some(0) => LoopCount::Infinite,
+        Some(extra) => LoopCount::Finite(u32::from(extra) + 1),
+        None => LoopCount::Finite(1),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use core::time::Duration;
+
     use raves_metadata_types::xmp::XmpElement;
 
-    use crate::{MetadataProvider, magic_number::AnyProvider, util::logger};
+    use crate::{
+        MetadataProvider, magic_number::AnyProvider, providers::animation_info::LoopCount,
+        util::logger,
+    };
 
     #[test]
     fn sample_gif() {
@@ -445,6 +547,20 @@ mod tests {
 
         let gif = super::Gif::new(&GIF_FROM_GIFLIB).unwrap();
         println!("{gif:?}");
+
+        let info = gif.image_info().expect("GIFs always have image info");
+        assert_eq!((info.width, info.height), (10, 10));
+        assert_eq!(info.bit_depth, Some(2));
+        assert_eq!(info.frame_count, Some(1));
+
+        let animation = gif
+            .animation_info()
+            .expect("GIFs always have animation info");
+        assert!(!animation.is_animated());
+        assert_eq!(animation.loop_count, LoopCount::Finite(1));
+
+        // XMP is the only block that's removed
+        assert_eq!(gif.repeatable_blocks.len(), 1);
     }
 
     #[test]
@@ -462,6 +578,18 @@ mod tests {
 
         let gif = gif.expect("should not error on parsing");
 
+        let info = gif.image_info().expect("GIFs always have image info");
+        assert_eq!((info.width, info.height), (684, 37));
+        assert_eq!(info.frame_count, Some(3));
+
+        let animation = gif
+            .animation_info()
+            .expect("GIFs always have animation info");
+        assert!(animation.is_animated());
+        assert_eq!(animation.frame_delays, [Duration::from_millis(100); 3]);
+        assert_eq!(animation.total_duration(), Duration::from_millis(300));
+        assert_eq!(animation.loop_count, LoopCount::Infinite);
+
         let xmp = gif
             .xmp()
             .expect("XMP should be present")
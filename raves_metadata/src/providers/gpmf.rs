@@ -0,0 +1,532 @@
+//! GoPro's telemetry, stored as GPMF in a timed metadata track.
+//!
+//! GoPro cameras record GPS, accelerometer, and gyroscope readings alongside
+//! the video. They're in a track whose samples have the `gpmd` format, where
+//! each sample covers a slice of the video (usually one second).
+//!
+//! Each sample is a tree of KLV (key, length, value) entries:
+//!
+//! ```text
+//! DEVC              (a device, like the camera)
+//! ├── DVNM          (its name, like `HERO9 Black`)
+//! └── STRM          (a stream of readings)
+//!     ├── STNM      (the stream's name)
+//!     ├── SCAL      (divisors for each value)
+//!     ├── GPSF      (GPS fix)
+//!     ├── GPSP      (GPS precision)
+//!     └── GPS5      (the readings themselves)
+//! ```
+//!
+//! Every key is followed by a type, a struct size, and a repeat count. The
+//! value is padded to four bytes.
+//!
+//! See <https://github.com/gopro/gpmf-parser>.
+
+use core::time::Duration;
+
+use winnow::{
+    Parser as _,
+    binary::{be_u16, u8},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::providers::shared::bmff::{sample_table, track};
+
+/// Telemetry from a GoPro video.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Telemetry {
+    /// The camera's name, like `HERO9 Black`.
+    pub device_name: Option<String>,
+
+    /// GPS readings, from `GPS5` or `GPS9` streams.
+    pub gps: Vec<GpsSample>,
+
+    /// Accelerometer readings, in meters per second squared.
+    pub accelerometer: Vec<ImuSample>,
+
+    /// Gyroscope readings, in radians per second.
+    pub gyroscope: Vec<ImuSample>,
+}
+
+/// One GPS reading.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpsSample {
+    /// When the reading was taken, from the start of the video.
+    pub time: Duration,
+
+    /// In degrees.
+    pub latitude: f64,
+
+    /// In degrees.
+    pub longitude: f64,
+
+    /// In meters, above the WGS 84 ellipsoid.
+    pub altitude: f64,
+
+    /// Ground speed, in meters per second.
+    pub speed_2d: f64,
+
+    /// Speed including vertical movement, in meters per second.
+    pub speed_3d: f64,
+
+    /// The GPS fix: `0` for none, `2` for 2D, or `3` for 3D.
+    pub fix: Option<u32>,
+
+    /// The dilution of precision, where lower is better. Under 5 is good.
+    pub precision: Option<f64>,
+}
+
+/// One accelerometer or gyroscope reading.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImuSample {
+    /// When the reading was taken, from the start of the video.
+    pub time: Duration,
+
+    /// The reading on each axis, in the order the camera stores them.
+    ///
+    /// Most cameras use `(Y, -X, Z)` or `(Z, X, Y)`, relative to the
+    /// camera's body.
+    pub values: [f64; 3],
+}
+
+/// Finds the GPMF track in an MP4, then decodes each of its samples.
+///
+/// Returns `None` if there's no GPMF track.
+pub(crate) fn find_in_mp4(input: &[u8]) -> Option<Telemetry> {
+    let trak: &[u8] =
+        track::tracks(input).find(|trak| track::sample_entry(trak) == Some(*b"gpmd"))?;
+    let timescale: u32 = track::timescale(trak)?;
+    let samples: Vec<sample_table::Sample> = sample_table::samples(trak)?;
+    log::trace!("Found GPMF track with `{}` samples.", samples.len());
+
+    let time = |units: u64| Duration::from_secs_f64(units as f64 / f64::from(timescale));
+
+    let mut telemetry = Telemetry::default();
+    for sample in samples {
+        let data: Option<&[u8]> = usize::try_from(sample.offset).ok().and_then(|start| {
+            input.get(start..start.checked_add(usize::try_from(sample.size).ok()?)?)
+        });
+        let Some(data) = data else {
+            log::warn!("GPMF sample was outside the file. sample: {sample:?}");
+            continue;
+        };
+
+        telemetry.add_sample(data, time(sample.start), time(u64::from(sample.duration)));
+    }
+
+    Some(telemetry)
+}
+
+impl Telemetry {
+    /// Adds the readings from one GPMF sample.
+    fn add_sample(&mut self, data: &[u8], start: Duration, duration: Duration) {
+        for devc in klvs(data).filter(|klv| klv.key == *b"DEVC") {
+            for entry in devc.children() {
+                match &entry.key {
+                    b"DVNM" if self.device_name.is_none() => self.device_name = entry.text(),
+                    b"STRM" => {
+                        self.add_stream(&entry.children().collect::<Vec<_>>(), start, duration)
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// Adds the readings from one stream.
+    ///
+    /// The readings are spread evenly over the sample's duration.
+    fn add_stream(&mut self, stream: &[Klv], start: Duration, duration: Duration) {
+        let find = |key: &[u8; 4]| stream.iter().find(|klv| klv.key == *key);
+        let types: Option<Vec<u8>> = find(b"TYPE").and_then(Klv::text).map(String::into_bytes);
+        let scale: Vec<f64> = find(b"SCAL")
+            .map(|scal| scal.rows(None).into_iter().flatten().collect())
+            .unwrap_or_default();
+
+        let Some(readings) = stream
+            .iter()
+            .find(|klv| [*b"GPS5", *b"GPS9", *b"ACCL", *b"GYRO"].contains(&klv.key))
+        else {
+            return;
+        };
+        let rows: Vec<Vec<f64>> = readings
+            .rows(types.as_deref())
+            .into_iter()
+            .map(|row| scaled(row, &scale))
+            .collect();
+
+        let count: u32 = u32::try_from(rows.len()).unwrap_or(u32::MAX).max(1);
+        let time = |i: usize| start + duration * u32::try_from(i).unwrap_or(u32::MAX) / count;
+
+        match &readings.key {
+            b"GPS5" => {
+                // the fix and precision are for the whole stream
+                let fix: Option<u32> = find(b"GPSF")
+                    .and_then(|gpsf| gpsf.rows(None).first()?.first().copied())
+                    .map(|fix| fix as u32);
+                let precision: Option<f64> = find(b"GPSP")
+                    .and_then(|gpsp| gpsp.rows(None).first()?.first().copied())
+                    .map(|precision| precision / 100.0);
+
+                self.gps.extend(
+                    rows.iter()
+                        .enumerate()
+                        .filter_map(|(i, row)| gps_sample(time(i), row, fix, precision)),
+                );
+            }
+
+            // newer cameras put the fix and precision in each reading
+            b"GPS9" => self
+                .gps
+                .extend(rows.iter().enumerate().filter_map(|(i, row)| {
+                    gps_sample(
+                        time(i),
+                        row,
+                        row.get(8).map(|fix| *fix as u32),
+                        row.get(7).copied(),
+                    )
+                })),
+
+            key => {
+                let samples = rows.iter().enumerate().filter_map(|(i, row)| {
+                    Some(ImuSample {
+                        time: time(i),
+                        values: row.get(..3)?.try_into().ok()?,
+                    })
+                });
+
+                if key == b"ACCL" {
+                    self.accelerometer.extend(samples);
+                } else {
+                    self.gyroscope.extend(samples);
+                }
+            }
+        }
+    }
+}
+
+/// Makes a GPS reading from its first five values.
+fn gps_sample(
+    time: Duration,
+    row: &[f64],
+    fix: Option<u32>,
+    precision: Option<f64>,
+) -> Option<GpsSample> {
+    let [latitude, longitude, altitude, speed_2d, speed_3d] = *row.get(..5)? else {
+        return None;
+    };
+
+    Some(GpsSample {
+        time,
+        latitude,
+        longitude,
+        altitude,
+        speed_2d,
+        speed_3d,
+        fix,
+        precision,
+    })
+}
+
+/// Divides each value by its `SCAL` entry.
+///
+/// There's either one divisor for every value, or one for each.
+fn scaled(mut row: Vec<f64>, scale: &[f64]) -> Vec<f64> {
+    for (i, value) in row.iter_mut().enumerate() {
+        let divisor: Option<f64> = match scale {
+            [one] => Some(*one),
+            many => many.get(i).copied(),
+        };
+
+        if let Some(divisor) = divisor.filter(|d| *d != 0.0) {
+            *value /= divisor;
+        }
+    }
+
+    row
+}
+
+/// One KLV entry.
+#[derive(Clone, Debug)]
+struct Klv<'input> {
+    key: [u8; 4],
+
+    /// The type of each value, like `l` for `i32`, or `0` for nested
+    /// entries.
+    value_type: u8,
+
+    /// The size of each struct (or "row"), in bytes.
+    struct_size: u8,
+
+    /// The value, without its padding.
+    data: &'input [u8],
+}
+
+/// Reads a list of KLV entries.
+fn klvs(mut input: &[u8]) -> impl Iterator<Item = Klv<'_>> {
+    core::iter::from_fn(move || {
+        let Ok::<_, EmptyError>((key, value_type, struct_size, repeat)) =
+            (take(4_usize), u8, u8, be_u16).parse_next(&mut input)
+        else {
+            return None;
+        };
+
+        let len: usize = usize::from(struct_size) * usize::from(repeat);
+        let padded: usize = len.next_multiple_of(4);
+        let Some(data) = input.get(..len) else {
+            log::warn!("GPMF entry was longer than its data.");
+            return None;
+        };
+        input = input.get(padded..).unwrap_or_default();
+
+        Some(Klv {
+            key: key.try_into().ok()?,
+            value_type,
+            struct_size,
+            data,
+        })
+    })
+}
+
+impl<'input> Klv<'input> {
+    /// Reads nested entries.
+    fn children(&self) -> impl Iterator<Item = Klv<'input>> {
+        let data: &[u8] = if self.value_type == 0 { self.data } else { &[] };
+        klvs(data)
+    }
+
+    /// Reads the value as text, without any trailing NULs.
+    fn text(&self) -> Option<String> {
+        if self.value_type != b'c' {
+            return None;
+        }
+        let text: &[u8] = self.data.split(|b| *b == 0).next().unwrap_or_default();
+        str::from_utf8(text).ok().map(str::to_string)
+    }
+
+    /// Reads each struct's numbers.
+    ///
+    /// Complex (`?`) values use the stream's `TYPE` for each field's type.
+    fn rows(&self, complex_types: Option<&[u8]>) -> Vec<Vec<f64>> {
+        let field_types: Vec<u8> = match self.value_type {
+            b'?' => match complex_types {
+                Some(types) => types.to_vec(),
+                None => {
+                    log::debug!("GPMF complex value had no `TYPE`.");
+                    return Vec::new();
+                }
+            },
+            value_type => match type_size(value_type) {
+                Some(size) => vec![value_type; usize::from(self.struct_size) / size],
+                None => return Vec::new(),
+            },
+        };
+
+        self.data
+            .chunks_exact(usize::from(self.struct_size).max(1))
+            .filter_map(|mut row| {
+                field_types
+                    .iter()
+                    .map(|field_type| number(&mut row, *field_type))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The size of a numeric type, in bytes.
+fn type_size(value_type: u8) -> Option<usize> {
+    Some(match value_type {
+        b'b' | b'B' => 1,
+        b's' | b'S' => 2,
+        b'l' | b'L' | b'f' | b'q' => 4,
+        b'd' | b'j' | b'J' | b'Q' => 8,
+        _ => return None,
+    })
+}
+
+/// Reads one big-endian number of the given type.
+fn number(input: &mut &[u8], value_type: u8) -> Option<f64> {
+    let size: usize = type_size(value_type)?;
+    let bytes: &[u8] = input.get(..size)?;
+    *input = &input[size..];
+
+    let mut be = [0_u8; 8];
+    be[8 - size..].copy_from_slice(bytes);
+    let unsigned: u64 = u64::from_be_bytes(be);
+
+    Some(match value_type {
+        b'b' => f64::from(unsigned as u8 as i8),
+        b's' => f64::from(unsigned as u16 as i16),
+        b'l' => f64::from(unsigned as u32 as i32),
+        b'j' => unsigned as i64 as f64,
+        b'f' => f64::from(f32::from_bits(unsigned as u32)),
+        b'd' => f64::from_bits(unsigned),
+
+        // fixed point: 15.16 and 31.32
+        b'q' => f64::from(unsigned as u32 as i32) / 65536.0,
+        b'Q' => unsigned as i64 as f64 / 4_294_967_296.0,
+
+        _ => unsigned as f64,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use core::time::Duration;
+
+    use super::Telemetry;
+
+    /// Makes a KLV entry, with padding.
+    fn klv(key: &[u8; 4], value_type: u8, struct_size: u8, data: &[u8]) -> Vec<u8> {
+        let repeat: u16 = (data.len() / usize::from(struct_size)) as u16;
+        let padding: usize = data.len().next_multiple_of(4) - data.len();
+        [
+            key.as_slice(),
+            &[value_type, struct_size],
+            &repeat.to_be_bytes(),
+            data,
+            &vec![0; padding],
+        ]
+        .concat()
+    }
+
+    /// Makes a nested KLV entry.
+    fn nested(key: &[u8; 4], children: &[Vec<u8>]) -> Vec<u8> {
+        klv(key, 0, 1, &children.concat())
+    }
+
+    /// Makes big-endian `i32`s.
+    fn longs(values: &[i32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    /// A sample with two GPS readings and two accelerometer readings.
+    pub(crate) fn sample() -> Vec<u8> {
+        let gps = nested(
+            b"STRM",
+            &[
+                klv(b"STNM", b'c', 1, b"GPS (Lat., Long., Alt., 2D, 3D speed)"),
+                klv(b"GPSF", b'L', 4, &3_u32.to_be_bytes()),
+                klv(b"GPSP", b'S', 2, &250_u16.to_be_bytes()),
+                klv(
+                    b"SCAL",
+                    b'l',
+                    4,
+                    &longs(&[10_000_000, 10_000_000, 1000, 1000, 100]),
+                ),
+                klv(
+                    b"GPS5",
+                    b'l',
+                    20,
+                    &longs(&[
+                        337_976_000,
+                        -1_183_700_000,
+                        12_500,
+                        2_000,
+                        250, //
+                        337_976_100,
+                        -1_183_700_100,
+                        12_600,
+                        2_100,
+                        260,
+                    ]),
+                ),
+            ],
+        );
+        let accl = nested(
+            b"STRM",
+            &[
+                klv(b"SCAL", b's', 2, &418_i16.to_be_bytes()),
+                klv(
+                    b"ACCL",
+                    b's',
+                    6,
+                    &[418_i16, 0, -836, 0, 418, 0]
+                        .iter()
+                        .flat_map(|v| v.to_be_bytes())
+                        .collect::<Vec<_>>(),
+                ),
+            ],
+        );
+
+        nested(
+            b"DEVC",
+            &[
+                klv(b"DVID", b'L', 4, &1_u32.to_be_bytes()),
+                klv(b"DVNM", b'c', 1, b"HERO9 Black"),
+                gps,
+                accl,
+            ],
+        )
+    }
+
+    #[test]
+    fn gps_and_accelerometer() {
+        let mut telemetry = Telemetry::default();
+        telemetry.add_sample(&sample(), Duration::from_secs(1), Duration::from_secs(1));
+
+        assert_eq!(telemetry.device_name.as_deref(), Some("HERO9 Black"));
+
+        let [first, second] = telemetry.gps.as_slice() else {
+            panic!("expected two GPS readings: {:?}", telemetry.gps);
+        };
+        assert_eq!(first.time, Duration::from_secs(1));
+        assert_eq!(second.time, Duration::from_millis(1500));
+        assert_eq!(first.latitude, 33.7976);
+        assert_eq!(first.longitude, -118.37);
+        assert_eq!(first.altitude, 12.5);
+        assert_eq!(first.speed_3d, 2.5);
+        assert_eq!(first.fix, Some(3));
+        assert_eq!(first.precision, Some(2.5));
+
+        let accel: Vec<[f64; 3]> = telemetry.accelerometer.iter().map(|s| s.values).collect();
+        assert_eq!(accel, [[1.0, 0.0, -2.0], [0.0, 1.0, 0.0]]);
+        assert!(telemetry.gyroscope.is_empty());
+    }
+
+    /// `GPS9` uses a complex type, described by `TYPE`.
+    #[test]
+    fn gps9() {
+        let gps9: Vec<u8> = [
+            longs(&[
+                337_976_000,
+                -1_183_700_000,
+                12_500,
+                2_000,
+                250,
+                9000,
+                3_600_000,
+            ]),
+            [150_u16, 3].iter().flat_map(|v| v.to_be_bytes()).collect(),
+        ]
+        .concat();
+        let sample = nested(
+            b"DEVC",
+            &[nested(
+                b"STRM",
+                &[
+                    klv(b"TYPE", b'c', 1, b"lllllllSS"),
+                    klv(
+                        b"SCAL",
+                        b'l',
+                        4,
+                        &longs(&[10_000_000, 10_000_000, 1000, 1000, 100, 1, 1000, 100, 1]),
+                    ),
+                    klv(b"GPS9", b'?', 32, &gps9),
+                ],
+            )],
+        );
+
+        let mut telemetry = Telemetry::default();
+        telemetry.add_sample(&sample, Duration::ZERO, Duration::from_secs(1));
+
+        let [gps] = telemetry.gps.as_slice() else {
+            panic!("expected one GPS reading: {:?}", telemetry.gps);
+        };
+        assert_eq!(gps.latitude, 33.7976);
+        assert_eq!(gps.fix, Some(3));
+        assert_eq!(gps.precision, Some(1.5));
+    }
+}
@@ -7,7 +7,15 @@
 
 use crate::{
     MetadataProvider,
-    providers::shared::bmff::heif::{HeifLike, HeifLikeConstructionError},
+    providers::{
+        depth_map::DepthMap,
+        gain_map::GainMapInfo,
+        heif_items::HeifItems,
+        image_info::ImageInfo,
+        motion_photo::{self, MotionPhoto},
+        orientation::DisplayOrientation,
+        shared::bmff::heif::{HeifLike, HeifLikeConstructionError},
+    },
 };
 
 const SUPPORTED_HEIC_BRANDS: &[[u8; 4]] = &[*b"heic"];
@@ -16,6 +24,41 @@ const SUPPORTED_HEIC_BRANDS: &[[u8; 4]] = &[*b"heic"];
 #[derive(Clone, Debug)]
 pub struct Heic {
     heic_like: HeifLike,
+    motion_photo: Option<MotionPhoto>,
+}
+
+impl Heic {
+    /// Returns the video inside this HEIC, if it's a Motion Photo.
+    ///
+    /// The video is stored in a top-level `mpvd` box. See
+    /// [`crate::providers::motion_photo`].
+    pub fn motion_photo(&self) -> Option<&MotionPhoto> {
+        self.motion_photo.as_ref()
+    }
+
+    /// Returns the HDR gain map's parameters and location, if there is one.
+    ///
+    /// These come from an ISO 21496-1 `tmap` item, or from the XMP on
+    /// Apple's `HDRGainMap` auxiliary image. See
+    /// [`crate::providers::gain_map`].
+    pub fn gain_map(&self) -> Option<&GainMapInfo> {
+        self.heic_like.gain_map.as_ref()
+    }
+
+    /// Returns the depth maps and mattes in this HEIC, like those from
+    /// portrait-mode photos.
+    ///
+    /// See [`crate::providers::depth_map`].
+    pub fn depth_maps(&self) -> &[DepthMap] {
+        &self.heic_like.depth_maps
+    }
+
+    /// Returns every item in this HEIC, and how they're linked.
+    ///
+    /// See [`crate::providers::heif_items`].
+    pub fn items(&self) -> &HeifItems {
+        &self.heic_like.items
+    }
 }
 
 impl MetadataProvider for Heic {
@@ -29,8 +72,20 @@ impl MetadataProvider for Heic {
     fn new(
         input: &impl AsRef<[u8]>,
     ) -> Result<Self, <Self as MetadataProvider>::ConstructionError> {
-        HeifLike::parse(&mut input.as_ref(), SUPPORTED_HEIC_BRANDS)
-            .map(|heic_like| Heic { heic_like })
+        let input: &[u8] = input.as_ref();
+        let heic_like = HeifLike::parse(&mut &*input, SUPPORTED_HEIC_BRANDS)?;
+
+        let xmp = heic_like
+            .xmp
+            .as_ref()
+            .and_then(|r| r.as_ref().ok())
+            .map(|x| x.document());
+        let motion_photo = motion_photo::find_in_heic(input, xmp);
+
+        Ok(Heic {
+            heic_like,
+            motion_photo,
+        })
     }
 
     fn exif(&self) -> Option<Result<&crate::exif::Exif, &crate::exif::error::ExifFatalError>> {
@@ -40,6 +95,16 @@ impl MetadataProvider for Heic {
     fn xmp(&self) -> Option<Result<&crate::xmp::Xmp, &crate::xmp::error::XmpError>> {
         self.heic_like.xmp.as_ref().map(|r| r.as_ref())
     }
+
+    fn image_info(&self) -> Option<&ImageInfo> {
+        self.heic_like.image_info.as_ref()
+    }
+
+    /// HEIF readers must apply the primary item's `irot` and `imir`
+    /// properties, so Exif `Orientation` is ignored.
+    fn display_orientation(&self) -> Option<DisplayOrientation> {
+        self.heic_like.display_orientation()
+    }
 }
 
 #[cfg(test)]
@@ -50,7 +115,11 @@ mod tests {
         tags::{ExifIfdTag, KnownTag},
     };
 
-    use crate::{MetadataProvider, providers::heic::Heic, util::logger};
+    use crate::{
+        MetadataProvider,
+        providers::{heic::Heic, heif_properties::HeifProperty},
+        util::logger,
+    };
 
     #[test]
     fn nokia_conformance_file_c034_heic_parses_despite_malformed_exif_tiff_header_offset() {
@@ -61,6 +130,36 @@ mod tests {
         // parse it into heic
         let file: Heic = Heic::new(&blob).expect("parse as heic");
 
+        // its primary item has a size, but no pixel info
+        let info = file.image_info().expect("primary item has `ispe`");
+        assert_eq!((info.width, info.height), (1280, 720));
+        assert_eq!(info.bit_depth, None);
+
+        // its Exif item describes the primary image
+        let items = file.items();
+        assert_eq!(items.primary_item_id, Some(1002));
+        assert_eq!(
+            items
+                .metadata_for(1002)
+                .map(|item| item.id)
+                .collect::<Vec<_>>(),
+            [1004]
+        );
+
+        // and it's 8-bit HEVC
+        let primary = items.primary_item().expect("has primary item");
+        assert_eq!(primary.spatial_extent(), Some((1280, 720)));
+        let hevc = primary
+            .properties
+            .iter()
+            .find_map(|p| match p.property {
+                HeifProperty::HevcConfig(config) => Some((p.essential, config)),
+                _ => None,
+            })
+            .expect("has `hvcC`");
+        assert!(hevc.0, "`hvcC` is essential");
+        assert_eq!((hevc.1.bit_depth_luma, hevc.1.chroma_format_idc), (8, 1));
+
         // it should only have exif
         assert!(file.iptc().is_none(), "iptc unsupported");
         assert!(file.xmp().is_none(), "xmp not present in file");
@@ -0,0 +1,197 @@
+//! The item graph inside HEIF-like files, like HEIC and AVIF.
+//!
+//! Everything in a HEIF file's `meta` box is an "item": images, image tiles,
+//! thumbnails, Exif, XMP, and so on. Items are linked with typed references
+//! in the `iref` box:
+//!
+//! - `cdsc`: "content describes". Metadata points at the image it describes.
+//! - `thmb`: a thumbnail points at its full-size image.
+//! - `auxl`: an auxiliary image (like alpha, depth, or a gain map) points at
+//!   its main image.
+//! - `dimg`: a derived image (like a `grid`) points at its source images.
+//! - `base`: a pre-derived image points at the images it was made from.
+//!
+//! The `pitm` box says which image to show. Burst and derived-image files
+//! have several images, so metadata should only be read from the items that
+//! describe the primary one.
+
+use core::ops::Range;
+
+use crate::providers::heif_properties::{HeifItemProperty, HeifProperty};
+
+/// Every item in a HEIF-like file, and how they're linked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeifItems {
+    /// The ID of the primary item, from the `pitm` box.
+    pub primary_item_id: Option<u32>,
+
+    /// Every item in the `iinf` box, in order.
+    pub items: Vec<HeifItem>,
+
+    /// Every reference in the `iref` box, in order.
+    pub references: Vec<HeifItemReference>,
+}
+
+/// One item, from an `infe` entry and its `iloc` location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeifItem {
+    pub id: u32,
+
+    /// The item's type, like `hvc1`, `av01`, `grid`, or `Exif`.
+    ///
+    /// Very old files (with `infe` versions 0 and 1) don't have one.
+    pub item_type: Option<[u8; 4]>,
+
+    /// The item's name, which is usually empty.
+    pub name: String,
+
+    /// The MIME type of `mime` items, like `application/rdf+xml` for XMP.
+    pub content_type: Option<String>,
+
+    /// Whether the item is hidden, meaning it shouldn't be shown by itself.
+    pub hidden: bool,
+
+    /// Where the item's data is stored, if it has a location.
+    pub storage: Option<HeifItemStorage>,
+
+    /// The byte ranges holding the item's data, in order.
+    ///
+    /// These are relative to [`HeifItem::storage`]. The item's data is every
+    /// range, joined together.
+    pub extents: Vec<Range<u64>>,
+
+    /// The item's properties, from the `iprp` box, in association order.
+    ///
+    /// See [`crate::providers::heif_properties`].
+    pub properties: Vec<HeifItemProperty>,
+}
+
+/// Where an item's data is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HeifItemStorage {
+    /// In the file, so extents are offsets from its start.
+    File,
+
+    /// In the `idat` box, so extents are offsets from its payload's start.
+    ItemData,
+
+    /// In other items, which this library doesn't read.
+    Item,
+}
+
+/// One typed reference, from an item to other items.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HeifItemReference {
+    pub kind: HeifReferenceKind,
+    pub from_item_id: u32,
+
+    /// The referenced items, in order.
+    pub to_item_ids: Vec<u32>,
+}
+
+/// The type of an item reference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HeifReferenceKind {
+    /// `cdsc`: the item describes the referenced items.
+    ContentDescription,
+
+    /// `thmb`: the item is a thumbnail of the referenced image.
+    Thumbnail,
+
+    /// `auxl`: the item is an auxiliary image for the referenced image.
+    Auxiliary,
+
+    /// `dimg`: the item is derived from the referenced images.
+    DerivedImage,
+
+    /// `base`: the item was pre-derived from the referenced images.
+    Base,
+
+    /// Any other reference type.
+    Other([u8; 4]),
+}
+
+impl From<[u8; 4]> for HeifReferenceKind {
+    fn from(value: [u8; 4]) -> Self {
+        match &value {
+            b"cdsc" => Self::ContentDescription,
+            b"thmb" => Self::Thumbnail,
+            b"auxl" => Self::Auxiliary,
+            b"dimg" => Self::DerivedImage,
+            b"base" => Self::Base,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl HeifItem {
+    /// Returns the item's size, in pixels, from its `ispe` property.
+    ///
+    /// This is the size before any transforms, like rotation.
+    pub fn spatial_extent(&self) -> Option<(u32, u32)> {
+        self.properties.iter().find_map(|p| match p.property {
+            HeifProperty::SpatialExtent { width, height } => Some((width, height)),
+            _ => None,
+        })
+    }
+
+    /// Returns what kind of auxiliary image this is, from its `auxC`
+    /// property.
+    pub fn aux_type(&self) -> Option<&str> {
+        self.properties.iter().find_map(|p| match p.property {
+            HeifProperty::AuxiliaryType { ref aux_type, .. } => Some(aux_type.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl HeifItems {
+    /// Finds an item by its ID.
+    pub fn item(&self, id: u32) -> Option<&HeifItem> {
+        self.items.iter().find(|item| item.id == id)
+    }
+
+    /// Returns the primary item, which is the image to show.
+    pub fn primary_item(&self) -> Option<&HeifItem> {
+        self.primary_item_id.and_then(|id| self.item(id))
+    }
+
+    /// Returns the items that `from_item_id` references with this kind.
+    ///
+    /// For example, a `grid` item's [`HeifReferenceKind::DerivedImage`]
+    /// references are its tiles.
+    pub fn references_from(&self, from_item_id: u32, kind: HeifReferenceKind) -> &[u32] {
+        self.references
+            .iter()
+            .find(|r| r.kind == kind && r.from_item_id == from_item_id)
+            .map(|r| r.to_item_ids.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the items that reference `to_item_id` with this kind.
+    pub fn referencing(
+        &self,
+        to_item_id: u32,
+        kind: HeifReferenceKind,
+    ) -> impl Iterator<Item = &HeifItem> {
+        self.references
+            .iter()
+            .filter(move |r| r.kind == kind && r.to_item_ids.contains(&to_item_id))
+            .filter_map(|r| self.item(r.from_item_id))
+    }
+
+    /// Returns the metadata items (like Exif and XMP) describing an image.
+    pub fn metadata_for(&self, image_id: u32) -> impl Iterator<Item = &HeifItem> {
+        self.referencing(image_id, HeifReferenceKind::ContentDescription)
+    }
+
+    /// Returns the thumbnails of an image.
+    pub fn thumbnails_of(&self, image_id: u32) -> impl Iterator<Item = &HeifItem> {
+        self.referencing(image_id, HeifReferenceKind::Thumbnail)
+    }
+
+    /// Returns the auxiliary images (like alpha or depth) of an image.
+    pub fn auxiliary_images_of(&self, image_id: u32) -> impl Iterator<Item = &HeifItem> {
+        self.referencing(image_id, HeifReferenceKind::Auxiliary)
+    }
+}
@@ -0,0 +1,375 @@
+//! Item properties in HEIF-like files, like HEIC and AVIF.
+//!
+//! Properties describe items. They're stored once in the `ipco` box, then
+//! associated with items in `ipma` boxes. Some properties describe how an
+//! image is coded (like `av1C` or `pixi`), and others are transforms that
+//! must be applied before showing it (like `irot` and `imir`).
+//!
+//! Each item's properties are in [`crate::providers::heif_items::HeifItem`].
+
+use winnow::{
+    Parser as _,
+    binary::{be_i32, be_u16, be_u32, u8},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::providers::shared::{bmff::BoxType, parse_nul_terminated_str};
+
+/// A property associated with an item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeifItemProperty {
+    /// Whether readers must understand this property to use the item.
+    pub essential: bool,
+
+    pub property: HeifProperty,
+}
+
+/// One item property, from the `ipco` box.
+///
+/// Transforms (`clap`, `irot`, and `imir`) are applied in the order they're
+/// associated with the item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeifProperty {
+    /// `irot`: rotate the image anti-clockwise, in steps of 90 degrees.
+    Rotation { anticlockwise_degrees: u16 },
+
+    /// `imir`: mirror the image.
+    Mirror(HeifMirror),
+
+    /// `clap`: crop the image.
+    CleanAperture(HeifCleanAperture),
+
+    /// `ispe`: the image's size, in pixels, before any transforms.
+    SpatialExtent { width: u32, height: u32 },
+
+    /// `pixi`: the bit depth of each channel.
+    PixelInformation { bits_per_channel: Vec<u8> },
+
+    /// `auxC`: what an auxiliary image is, like alpha or depth.
+    AuxiliaryType {
+        /// A URN, like `urn:mpeg:mpegB:cicp:systems:auxiliary:alpha`.
+        aux_type: String,
+
+        /// Extra info, whose format depends on `aux_type`.
+        aux_subtype: Vec<u8>,
+    },
+
+    /// `colr`: the image's color space.
+    Colour(HeifColour),
+
+    /// `av1C`: how an AV1 image is coded.
+    Av1Config(Av1Config),
+
+    /// `hvcC`: how an HEVC image is coded.
+    HevcConfig(HevcConfig),
+
+    /// `lsel`: which layer of a layered image to show.
+    LayerSelector { layer_id: u16 },
+
+    /// Any other property, or one that was malformed.
+    Other {
+        /// The property's box type. UUID boxes are `uuid`.
+        box_type: [u8; 4],
+
+        /// The property's payload, without its box header.
+        payload: Vec<u8>,
+    },
+}
+
+/// The axis an image is mirrored on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HeifMirror {
+    /// Mirrored on the vertical axis, so left and right are swapped.
+    Vertical,
+
+    /// Mirrored on the horizontal axis, so top and bottom are swapped.
+    Horizontal,
+}
+
+/// A crop, centered on the image (plus an offset).
+///
+/// Each value is a fraction, as `(numerator, denominator)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HeifCleanAperture {
+    pub width: (u32, u32),
+    pub height: (u32, u32),
+
+    /// How far the crop's center is from the image's center, horizontally.
+    pub horizontal_offset: (i32, u32),
+
+    /// How far the crop's center is from the image's center, vertically.
+    pub vertical_offset: (i32, u32),
+}
+
+/// A color space, from a `colr` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeifColour {
+    /// `nclx`: coding-independent code points, from ITU-T H.273.
+    Nclx {
+        colour_primaries: u16,
+        transfer_characteristics: u16,
+        matrix_coefficients: u16,
+        full_range: bool,
+    },
+
+    /// `rICC` or `prof`: an ICC profile.
+    Icc(Vec<u8>),
+
+    /// Any other color type.
+    Other([u8; 4]),
+}
+
+/// The start of an `av1C` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Av1Config {
+    pub seq_profile: u8,
+    pub seq_level_idx_0: u8,
+    pub seq_tier_0: bool,
+
+    /// The bit depth, which is 8, 10, or 12.
+    pub bit_depth: u8,
+    pub monochrome: bool,
+
+    /// Whether chroma is subsampled horizontally and vertically.
+    pub chroma_subsampling: (bool, bool),
+
+    /// Any OBUs after the header, like a sequence header.
+    pub config_obus: Vec<u8>,
+}
+
+/// The start of an `hvcC` property.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HevcConfig {
+    pub general_profile_idc: u8,
+    pub general_tier_flag: bool,
+    pub general_level_idc: u8,
+
+    /// `0` is monochrome, `1` is 4:2:0, `2` is 4:2:2, and `3` is 4:4:4.
+    pub chroma_format_idc: u8,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+}
+
+impl HeifProperty {
+    /// Parses a property from its box type and payload.
+    ///
+    /// Malformed properties become [`HeifProperty::Other`].
+    pub(crate) fn new(box_type: &BoxType, payload: &[u8]) -> Self {
+        let BoxType::Id(box_type) = *box_type else {
+            return Self::Other {
+                box_type: *b"uuid",
+                payload: payload.to_vec(),
+            };
+        };
+
+        let parsed: Option<Self> = match &box_type {
+            b"irot" => payload.first().map(|angle| Self::Rotation {
+                anticlockwise_degrees: u16::from(angle & 0b11) * 90,
+            }),
+            b"imir" => payload.first().map(|axis| {
+                Self::Mirror(match axis & 1 {
+                    0 => HeifMirror::Vertical,
+                    _ => HeifMirror::Horizontal,
+                })
+            }),
+            b"clap" => clean_aperture(payload).map(Self::CleanAperture),
+            b"ispe" => (take::<_, _, EmptyError>(4_usize), be_u32, be_u32)
+                .parse_peek(payload)
+                .ok()
+                .map(|(_, (_, width, height))| Self::SpatialExtent { width, height }),
+            b"pixi" => match payload {
+                [_, _, _, _, count, bits @ ..] => {
+                    bits.get(..usize::from(*count))
+                        .map(|bits| Self::PixelInformation {
+                            bits_per_channel: bits.to_vec(),
+                        })
+                }
+                _ => None,
+            },
+            b"auxC" => auxiliary_type(payload),
+            b"colr" => colour(payload).map(Self::Colour),
+            b"av1C" => av1_config(payload).map(Self::Av1Config),
+            b"hvcC" => hevc_config(payload).map(Self::HevcConfig),
+            b"lsel" => be_u16::<_, EmptyError>
+                .parse_peek(payload)
+                .ok()
+                .map(|(_, layer_id)| Self::LayerSelector { layer_id }),
+            _ => {
+                return Self::Other {
+                    box_type,
+                    payload: payload.to_vec(),
+                };
+            }
+        };
+
+        parsed.unwrap_or_else(|| {
+            log::warn!(
+                "Item property `{}` was malformed.",
+                core::str::from_utf8(&box_type).unwrap_or("not UTF-8")
+            );
+            Self::Other {
+                box_type,
+                payload: payload.to_vec(),
+            }
+        })
+    }
+}
+
+/// Parses a `clap` property, which is eight 32-bit numbers.
+fn clean_aperture(mut input: &[u8]) -> Option<HeifCleanAperture> {
+    let (width_n, width_d, height_n, height_d) = (be_u32::<_, EmptyError>, be_u32, be_u32, be_u32)
+        .parse_next(&mut input)
+        .ok()?;
+    let (horizontal_n, horizontal_d, vertical_n, vertical_d) =
+        (be_i32::<_, EmptyError>, be_u32, be_i32, be_u32)
+            .parse_next(&mut input)
+            .ok()?;
+
+    Some(HeifCleanAperture {
+        width: (width_n, width_d),
+        height: (height_n, height_d),
+        horizontal_offset: (horizontal_n, horizontal_d),
+        vertical_offset: (vertical_n, vertical_d),
+    })
+}
+
+/// Parses an `auxC` property: a full box, a string, then the subtype.
+fn auxiliary_type(mut input: &[u8]) -> Option<HeifProperty> {
+    let input = &mut input;
+    take::<_, _, EmptyError>(4_usize).parse_next(input).ok()?;
+    let aux_type: &str = parse_nul_terminated_str.parse_next(input).ok()?;
+
+    Some(HeifProperty::AuxiliaryType {
+        aux_type: aux_type.to_string(),
+        aux_subtype: input.to_vec(),
+    })
+}
+
+/// Parses a `colr` property, which starts with its color type.
+fn colour(payload: &[u8]) -> Option<HeifColour> {
+    let (colour_type, rest) = payload.split_first_chunk::<4>()?;
+
+    Some(match colour_type {
+        b"nclx" => {
+            let (_, (colour_primaries, transfer_characteristics, matrix_coefficients, flags)) =
+                (be_u16::<_, EmptyError>, be_u16, be_u16, u8)
+                    .parse_peek(rest)
+                    .ok()?;
+            HeifColour::Nclx {
+                colour_primaries,
+                transfer_characteristics,
+                matrix_coefficients,
+                full_range: flags & 0x80 != 0,
+            }
+        }
+        b"rICC" | b"prof" => HeifColour::Icc(rest.to_vec()),
+        other => HeifColour::Other(*other),
+    })
+}
+
+/// Parses an `av1C` property's four header bytes, then its OBUs.
+fn av1_config(payload: &[u8]) -> Option<Av1Config> {
+    let (header, config_obus) = payload.split_first_chunk::<4>()?;
+    let [_marker_and_version, profile_and_level, flags, _] = *header;
+
+    let high_bitdepth = flags & 0b0100_0000 != 0;
+    let twelve_bit = flags & 0b0010_0000 != 0;
+    Some(Av1Config {
+        seq_profile: profile_and_level >> 5,
+        seq_level_idx_0: profile_and_level & 0b1_1111,
+        seq_tier_0: flags & 0b1000_0000 != 0,
+        bit_depth: match (high_bitdepth, twelve_bit) {
+            (true, true) => 12,
+            (true, false) => 10,
+            (false, _) => 8,
+        },
+        monochrome: flags & 0b0001_0000 != 0,
+        chroma_subsampling: (flags & 0b1000 != 0, flags & 0b100 != 0),
+        config_obus: config_obus.to_vec(),
+    })
+}
+
+/// Parses the start of an `hvcC` property, up to the chroma bit depth.
+fn hevc_config(payload: &[u8]) -> Option<HevcConfig> {
+    let header: &[u8; 19] = payload.first_chunk()?;
+
+    Some(HevcConfig {
+        general_profile_idc: header[1] & 0b1_1111,
+        general_tier_flag: header[1] & 0b10_0000 != 0,
+        general_level_idc: header[12],
+        chroma_format_idc: header[16] & 0b11,
+        bit_depth_luma: (header[17] & 0b111) + 8,
+        bit_depth_chroma: (header[18] & 0b111) + 8,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::providers::shared::bmff::BoxType;
+
+    use super::{Av1Config, HeifColour, HeifMirror, HeifProperty};
+
+    fn parse(box_type: &[u8; 4], payload: &[u8]) -> HeifProperty {
+        HeifProperty::new(&BoxType::Id(*box_type), payload)
+    }
+
+    #[test]
+    fn transforms() {
+        assert_eq!(
+            parse(b"irot", &[3]),
+            HeifProperty::Rotation {
+                anticlockwise_degrees: 270
+            }
+        );
+        assert_eq!(
+            parse(b"imir", &[1]),
+            HeifProperty::Mirror(HeifMirror::Horizontal)
+        );
+    }
+
+    #[test]
+    fn coding_properties() {
+        assert_eq!(
+            parse(b"av1C", &[0x81, 0x08, 0b0100_1100, 0]),
+            HeifProperty::Av1Config(Av1Config {
+                seq_profile: 0,
+                seq_level_idx_0: 8,
+                seq_tier_0: false,
+                bit_depth: 10,
+                monochrome: false,
+                chroma_subsampling: (true, true),
+                config_obus: vec![],
+            })
+        );
+
+        assert_eq!(
+            parse(b"colr", b"nclx\0\x09\0\x10\0\x09\x80"),
+            HeifProperty::Colour(HeifColour::Nclx {
+                colour_primaries: 9,
+                transfer_characteristics: 16,
+                matrix_coefficients: 9,
+                full_range: true,
+            })
+        );
+
+        assert_eq!(
+            parse(b"auxC", b"\0\0\0\0urn:mpeg:hevc:2015:auxid:1\0"),
+            HeifProperty::AuxiliaryType {
+                aux_type: "urn:mpeg:hevc:2015:auxid:1".into(),
+                aux_subtype: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_property() {
+        assert_eq!(
+            parse(b"ispe", &[0, 0, 0, 0, 1]),
+            HeifProperty::Other {
+                box_type: *b"ispe",
+                payload: vec![0, 0, 0, 0, 1]
+            }
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! iTunes-style tags, like a song's title or a video's cover art.
+//!
+//! MP4-family files (including M4A and M4V) and QuickTime movies store these
+//! in an `ilst` box. There are two layouts:
+//!
+//! ```text
+//! moov
+//! ├── udta
+//! │   └── meta         (iTunes, with an `mdir` handler)
+//! │       └── ilst
+//! │           ├── ©nam (each key is a box...)
+//! │           │   └── data
+//! │           └── ----  (...or a freeform `mean` and `name`)
+//! │               ├── mean
+//! │               ├── name
+//! │               └── data
+//! └── meta             (QuickTime, with an `mdta` handler)
+//!     ├── keys         (names like `com.apple.quicktime.title`)
+//!     └── ilst         (boxes are numbered after each key)
+//! ```
+//!
+//! Each `data` box starts with a type, which says how its value is stored.
+
+use winnow::{Parser as _, binary::be_u32, error::EmptyError, token::take};
+
+use crate::providers::shared::bmff::{BoxType, child, children};
+
+/// A title.
+const TITLE: [u8; 4] = *b"\xA9nam";
+
+/// An artist.
+const ARTIST: [u8; 4] = *b"\xA9ART";
+
+/// A release or recording date.
+const DATE: [u8; 4] = *b"\xA9day";
+
+/// A description.
+const DESCRIPTION: [u8; 4] = *b"desc";
+
+/// Cover art.
+const COVER_ART: [u8; 4] = *b"covr";
+
+/// The tags in a file's `ilst` boxes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ilst {
+    /// Each tag, in file order.
+    pub entries: Vec<IlstEntry>,
+}
+
+/// One tag, with its values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IlstEntry {
+    /// The tag's name.
+    pub key: IlstKey,
+
+    /// The tag's values. Most tags have one, but `covr` can have several.
+    pub values: Vec<IlstValue>,
+}
+
+/// A tag's name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IlstKey {
+    /// An iTunes box type, like `©nam` or `covr`.
+    Atom([u8; 4]),
+
+    /// An iTunes freeform (`----`) tag, like `com.apple.iTunes` and `iTunNORM`.
+    Freeform { mean: String, name: String },
+
+    /// A QuickTime key, like `com.apple.quicktime.title`.
+    Keyed(String),
+}
+
+/// A tag's value, from its `data` box.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IlstValue {
+    /// UTF-8 or UTF-16 text.
+    Text(String),
+
+    /// A big-endian signed integer.
+    Integer(i64),
+
+    /// A big-endian unsigned integer.
+    ///
+    /// These can use all 8 bytes, so they won't always fit in an `i64`.
+    Unsigned(u64),
+
+    /// A big-endian float.
+    Float(f64),
+
+    /// A JPEG image.
+    Jpeg(Vec<u8>),
+
+    /// A PNG image.
+    Png(Vec<u8>),
+
+    /// Any other type, like the binary `trkn` (track number) value.
+    Other { data_type: u32, data: Vec<u8> },
+}
+
+impl Ilst {
+    /// Finds the first entry with the given key.
+    pub fn get(&self, key: &IlstKey) -> Option<&IlstEntry> {
+        self.entries.iter().find(|entry| entry.key == *key)
+    }
+
+    /// Finds the first text value of an iTunes box type, like `©nam`.
+    pub fn text(&self, atom: [u8; 4]) -> Option<&str> {
+        self.get(&IlstKey::Atom(atom))?
+            .values
+            .iter()
+            .find_map(|value| match value {
+                IlstValue::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+    }
+
+    /// The title (`©nam`).
+    pub fn title(&self) -> Option<&str> {
+        self.text(TITLE)
+    }
+
+    /// The artist (`©ART`).
+    pub fn artist(&self) -> Option<&str> {
+        self.text(ARTIST)
+    }
+
+    /// The date (`©day`), usually in ISO 8601 format or just a year.
+    pub fn date(&self) -> Option<&str> {
+        self.text(DATE)
+    }
+
+    /// The description (`desc`).
+    pub fn description(&self) -> Option<&str> {
+        self.text(DESCRIPTION)
+    }
+
+    /// Every cover art image (`covr`).
+    pub fn cover_art(&self) -> impl Iterator<Item = &IlstValue> {
+        self.get(&IlstKey::Atom(COVER_ART))
+            .into_iter()
+            .flat_map(|entry| &entry.values)
+            .filter(|value| matches!(value, IlstValue::Jpeg(_) | IlstValue::Png(_)))
+    }
+
+    /// Finds a freeform (`----`) tag's values.
+    pub fn freeform(&self, mean: &str, name: &str) -> Option<&[IlstValue]> {
+        self.get(&IlstKey::Freeform {
+            mean: mean.into(),
+            name: name.into(),
+        })
+        .map(|entry| entry.values.as_slice())
+    }
+}
+
+/// Reads the tags in `moov/udta/meta` and `moov/meta`.
+///
+/// Returns `None` if there's no `ilst` box.
+pub(crate) fn find(input: &[u8]) -> Option<Ilst> {
+    let moov: &[u8] = child(input, *b"moov")?;
+    let itunes: Option<&[u8]> = child(moov, *b"udta").and_then(|udta| child(udta, *b"meta"));
+    let quicktime: Option<&[u8]> = child(moov, *b"meta");
+
+    let mut found = false;
+    let mut ilst = Ilst::default();
+    for meta in [itunes, quicktime].into_iter().flatten().map(meta_children) {
+        let Some(boxes) = child(meta, *b"ilst") else {
+            continue;
+        };
+        found = true;
+
+        let keys: Vec<String> = child(meta, *b"keys").map(keys).unwrap_or_default();
+        ilst.entries
+            .extend(children(boxes).filter_map(|(ty, payload)| entry(ty, payload, &keys)));
+    }
+
+    found.then_some(ilst)
+}
+
+/// Skips the full box header on iTunes `meta` boxes.
+///
+/// QuickTime's `meta` box isn't a full box, so its first child (`hdlr`) comes
+/// right away.
+fn meta_children(meta: &[u8]) -> &[u8] {
+    match meta.get(4..8) {
+        Some(b"hdlr") => meta,
+        _ => meta.get(4..).unwrap_or_default(),
+    }
+}
+
+/// Reads the key names from a `keys` box.
+///
+/// It's a full box, an entry count, then each key's size (including its
+/// header), namespace, and name.
+fn keys(mut payload: &[u8]) -> Vec<String> {
+    let input = &mut payload;
+    let Ok::<_, EmptyError>((_, count)) = (take(4_usize), be_u32).parse_next(input) else {
+        log::warn!("`keys` box was too short.");
+        return Vec::new();
+    };
+
+    core::iter::from_fn(|| {
+        let (size, _namespace) = (be_u32::<_, EmptyError>, take(4_usize))
+            .parse_next(input)
+            .ok()?;
+        let name: &[u8] = take::<_, _, EmptyError>(size.checked_sub(8)?)
+            .parse_next(input)
+            .ok()?;
+        Some(String::from_utf8_lossy(name).into_owned())
+    })
+    .take(count as usize)
+    .collect()
+}
+
+/// Reads one `ilst` entry.
+///
+/// QuickTime-style entries are numbered after their key, starting at one.
+fn entry(box_type: BoxType, payload: &[u8], keys: &[String]) -> Option<IlstEntry> {
+    let BoxType::Id(id) = box_type else {
+        return None;
+    };
+
+    let string = |box_type: [u8; 4]| {
+        // `mean` and `name` are full boxes
+        child(payload, box_type)
+            .and_then(|b| b.get(4..))
+            .map(|s| String::from_utf8_lossy(s).into_owned())
+    };
+
+    let key: IlstKey = if id == *b"----" {
+        IlstKey::Freeform {
+            mean: string(*b"mean").unwrap_or_default(),
+            name: string(*b"name").unwrap_or_default(),
+        }
+    } else if !keys.is_empty() {
+        let index: usize = (u32::from_be_bytes(id) as usize).checked_sub(1)?;
+        let Some(name) = keys.get(index) else {
+            log::warn!("`ilst` entry `{index}` had no key.");
+            return None;
+        };
+        IlstKey::Keyed(name.clone())
+    } else {
+        IlstKey::Atom(id)
+    };
+
+    let values: Vec<IlstValue> = children(payload)
+        .filter(|(ty, _)| *ty == BoxType::Id(*b"data"))
+        .filter_map(|(_, data)| value(data))
+        .collect();
+
+    Some(IlstEntry { key, values })
+}
+
+/// Reads a `data` box's value.
+///
+/// It starts with a type (whose top byte must be zero) and a locale.
+fn value(mut data: &[u8]) -> Option<IlstValue> {
+    let input = &mut data;
+    let (data_type, _locale) = (be_u32::<_, EmptyError>, be_u32)
+        .parse_next(input)
+        .inspect_err(|_| log::warn!("`data` box was too short."))
+        .ok()?;
+    let bytes: &[u8] = input;
+
+    Some(match data_type {
+        1 => IlstValue::Text(String::from_utf8_lossy(bytes).into_owned()),
+        2 => IlstValue::Text(
+            char::decode_utf16(
+                bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]])),
+            )
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect(),
+        ),
+        13 => IlstValue::Jpeg(bytes.to_vec()),
+        14 => IlstValue::Png(bytes.to_vec()),
+        21 if (1..=8).contains(&bytes.len()) => {
+            // sign-extend it
+            let fill: u8 = if bytes[0] & 0x80 != 0 { 0xFF } else { 0 };
+            let mut be = [fill; 8];
+            be[8 - bytes.len()..].copy_from_slice(bytes);
+            IlstValue::Integer(i64::from_be_bytes(be))
+        }
+        22 if (1..=8).contains(&bytes.len()) => {
+            let mut be = [0; 8];
+            be[8 - bytes.len()..].copy_from_slice(bytes);
+            IlstValue::Unsigned(u64::from_be_bytes(be))
+        }
+        23 => IlstValue::Float(f64::from(f32::from_be_bytes(bytes.try_into().ok()?))),
+        24 => IlstValue::Float(f64::from_be_bytes(bytes.try_into().ok()?)),
+        _ => IlstValue::Other {
+            data_type,
+            data: bytes.to_vec(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{providers::shared::bmff::tests::bx, util::logger};
+
+    use super::{IlstKey, IlstValue};
+
+    /// Makes a `data` box.
+    fn data(data_type: u32, value: &[u8]) -> Vec<u8> {
+        bx(
+            b"data",
+            &[data_type.to_be_bytes().as_slice(), &[0; 4], value].concat(),
+        )
+    }
+
+    /// Screen recordings use QuickTime keys.
+    #[test]
+    fn quicktime_keys() {
+        logger();
+
+        let key = |name: &[u8]| bx(b"mdta", name);
+        let keys = bx(
+            b"keys",
+            &[
+                [0_u8, 0, 0, 0, 0, 0, 0, 2].as_slice(),
+                &key(b"com.apple.quicktime.title"),
+                &key(b"com.apple.quicktime.rating.user"),
+            ]
+            .concat(),
+        );
+        let ilst = bx(
+            b"ilst",
+            &[
+                bx(&1_u32.to_be_bytes(), &data(1, b"Screen Recording")),
+                bx(&2_u32.to_be_bytes(), &data(23, &4.5_f32.to_be_bytes())),
+            ]
+            .concat(),
+        );
+        let hdlr = bx(b"hdlr", &[[0_u8; 8].as_slice(), b"mdta", &[0; 13]].concat());
+        let file = bx(b"moov", &bx(b"meta", &[hdlr, keys, ilst].concat()));
+
+        let ilst = super::find(&file).unwrap();
+        let title = ilst.get(&IlstKey::Keyed("com.apple.quicktime.title".into()));
+        assert_eq!(
+            title.map(|entry| entry.values.as_slice()),
+            Some([IlstValue::Text("Screen Recording".into())].as_slice())
+        );
+        assert_eq!(ilst.entries[1].values, [IlstValue::Float(4.5)]);
+    }
+
+    #[test]
+    fn integers_and_utf16() {
+        assert_eq!(
+            super::value(&[0, 0, 0, 21, 0, 0, 0, 0, 0xFF, 0xFE]),
+            Some(IlstValue::Integer(-2))
+        );
+        assert_eq!(
+            super::value(&[0, 0, 0, 22, 0, 0, 0, 0, 0xFF, 0xFE]),
+            Some(IlstValue::Unsigned(0xFFFE))
+        );
+        assert_eq!(
+            super::value(&[&[0, 0, 0, 22, 0, 0, 0, 0][..], &[0xFF; 8]].concat()),
+            Some(IlstValue::Unsigned(u64::MAX))
+        );
+        assert_eq!(
+            super::value(&[0, 0, 0, 2, 0, 0, 0, 0, 0, b'h', 0, b'i']),
+            Some(IlstValue::Text("hi".into()))
+        );
+    }
+}
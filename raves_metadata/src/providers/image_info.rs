@@ -0,0 +1,144 @@
+//! Basic image properties, like dimensions and bit depth.
+//!
+//! These come from each format's image header, so they're available without
+//! decoding any image data:
+//!
+//! - JPEG: the `SOFn` (start of frame) segment
+//! - PNG: the `IHDR` chunk
+//! - GIF: the logical screen descriptor and image descriptors
+//! - WebP: the `VP8`, `VP8L`, or `VP8X` chunk
+//! - HEIC/AVIF: the primary item's `ispe` and `pixi` properties
+
+/// Basic properties of an image.
+///
+/// Get these from [`crate::MetadataProvider::image_info`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageInfo {
+    /// The image's width, in pixels.
+    ///
+    /// For animations, this is the size of the canvas.
+    pub width: u32,
+
+    /// The image's height, in pixels.
+    ///
+    /// JPEG allows this to be `0` when the height is defined later, in a
+    /// `DNL` segment. We don't look for that segment.
+    pub height: u32,
+
+    /// The number of bits in each sample (one channel of one pixel).
+    ///
+    /// For indexed images, this is the bits in each palette index.
+    pub bit_depth: Option<u8>,
+
+    /// How pixels are stored, if the format says.
+    pub color_type: Option<ColorType>,
+
+    /// The number of channels (or JPEG components) in each pixel.
+    pub channels: Option<u8>,
+
+    /// The number of frames in the image.
+    ///
+    /// This is `1` for still images.
+    pub frame_count: Option<u32>,
+
+    /// Info about how a JPEG was encoded.
+    ///
+    /// This is only present for JPEG files.
+    pub jpeg: Option<JpegFrameInfo>,
+}
+
+/// How an image's pixels are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ColorType {
+    /// One luminance channel.
+    Grayscale,
+
+    /// Luminance and alpha channels.
+    GrayscaleAlpha,
+
+    /// Red, green, and blue channels.
+    Rgb,
+
+    /// Red, green, blue, and alpha channels.
+    Rgba,
+
+    /// Each pixel is an index into a palette.
+    Indexed,
+
+    /// Luma and two chroma channels, as most JPEGs use.
+    YCbCr,
+
+    /// Cyan, magenta, yellow, and black channels.
+    ///
+    /// JPEG files with four components are reported as this, even if they're
+    /// actually YCCK.
+    Cmyk,
+}
+
+/// Info from a JPEG's `SOFn` (start of frame) marker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct JpegFrameInfo {
+    /// The `SOFn` marker code, from `0xC0` to `0xCF`.
+    pub marker_code: u8,
+
+    /// Whether the image uses progressive coding.
+    pub progressive: bool,
+
+    /// Whether the image uses arithmetic coding instead of Huffman coding.
+    pub arithmetic: bool,
+
+    /// Whether the image is lossless.
+    pub lossless: bool,
+
+    /// Whether the image uses differential (hierarchical) coding.
+    pub differential: bool,
+}
+
+impl JpegFrameInfo {
+    /// Creates frame info from a marker code.
+    ///
+    /// Returns `None` if the marker code isn't `SOFn`. Note that `DHT`
+    /// (`0xC4`), `JPG` (`0xC8`), and `DAC` (`0xCC`) are in the same range, but
+    /// aren't frame markers.
+    pub fn new(marker_code: u8) -> Option<Self> {
+        if !(0xC0..=0xCF).contains(&marker_code) || [0xC4, 0xC8, 0xCC].contains(&marker_code) {
+            return None;
+        }
+
+        // the low two bits give the process, and bit 2 marks differential
+        // frames. bit 3 marks arithmetic coding
+        let process: u8 = marker_code & 0b0011;
+        Some(Self {
+            marker_code,
+            progressive: process == 2,
+            arithmetic: marker_code & 0b1000 != 0,
+            lossless: process == 3,
+            differential: marker_code & 0b0100 != 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JpegFrameInfo;
+
+    #[test]
+    fn jpeg_frame_markers() {
+        let baseline = JpegFrameInfo::new(0xC0).unwrap();
+        assert!(!baseline.progressive && !baseline.arithmetic && !baseline.lossless);
+
+        let progressive = JpegFrameInfo::new(0xC2).unwrap();
+        assert!(progressive.progressive && !progressive.arithmetic);
+
+        let arithmetic_progressive = JpegFrameInfo::new(0xCA).unwrap();
+        assert!(arithmetic_progressive.progressive && arithmetic_progressive.arithmetic);
+
+        let differential_lossless = JpegFrameInfo::new(0xC7).unwrap();
+        assert!(differential_lossless.lossless && differential_lossless.differential);
+
+        for not_a_frame in [0xC4, 0xC8, 0xCC, 0xDB, 0xE1] {
+            assert_eq!(JpegFrameInfo::new(not_a_frame), None);
+        }
+    }
+}
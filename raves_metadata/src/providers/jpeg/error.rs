@@ -201,3 +201,69 @@ impl core::fmt::Display for JpegConstructionError {
 }
 
 impl core::error::Error for JpegConstructionError {}
+
+/// An error that occurred while editing a JPEG's segments.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub enum JpegSegmentError {
+    /// This marker code can't be used for a segment.
+    ///
+    /// `SOI`, `EOI`, and `SOS` are handled by [`super::JpegSegments`] itself,
+    /// and `0` and `255` aren't valid marker codes.
+    MarkerCodeDisallowed(u8),
+
+    /// A standalone marker (like `RSTn`) was given a payload.
+    StandaloneMarkerWithPayload {
+        /// The standalone marker's code.
+        marker_code: u8,
+    },
+
+    /// The payload is too long to fit in a segment.
+    ///
+    /// Segments store their length in a `u16`, which includes the two length
+    /// bytes.
+    PayloadTooLong {
+        /// The payload's length, in bytes.
+        len: usize,
+    },
+
+    /// There's no segment at this index.
+    IndexOutOfBounds {
+        /// The requested index.
+        index: usize,
+
+        /// The number of segments.
+        len: usize,
+    },
+}
+
+impl core::fmt::Display for JpegSegmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MarkerCodeDisallowed(code) => write!(
+                f,
+                "JPEG segments can't use this marker code. got: `{code:x?}`"
+            ),
+
+            Self::StandaloneMarkerWithPayload { marker_code } => write!(
+                f,
+                "Standalone JPEG markers can't have a payload. \
+                marker code: `{marker_code:x?}`"
+            ),
+
+            Self::PayloadTooLong { len } => write!(
+                f,
+                "JPEG segment payload is too long. \
+                expected: at most `{}` bytes; \
+                got: `{len}` bytes",
+                u16::MAX - 2
+            ),
+
+            Self::IndexOutOfBounds { index, len } => write!(
+                f,
+                "No JPEG segment at index `{index}`. There are `{len}` segments."
+            ),
+        }
+    }
+}
+
+impl core::error::Error for JpegSegmentError {}
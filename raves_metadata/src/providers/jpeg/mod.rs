@@ -6,19 +6,76 @@
 use crate::{
     MetadataProvider,
     exif::{Exif, error::ExifFatalError},
+    providers::{
+        depth_map::DepthMap, gain_map::GainMapInfo, image_info::ImageInfo,
+        motion_photo::MotionPhoto,
+    },
     xmp::{Xmp, error::XmpError},
 };
 
 mod error;
+mod mpf;
 mod parse;
+mod segments;
 
-pub use error::JpegConstructionError;
+pub use error::{JpegConstructionError, JpegSegmentError};
+pub use mpf::{Mpf, MpfImage, MpfImageKind};
+pub use segments::{JpegSegment, JpegSegments};
 
 /// A JPEG file.
 #[derive(Clone, Debug)]
 pub struct Jpeg {
     exif: Option<Result<Exif, ExifFatalError>>,
     xmp: Option<Result<Xmp, XmpError>>,
+    image_info: Option<ImageInfo>,
+    mpf: Option<Result<Mpf, ExifFatalError>>,
+    motion_photo: Option<MotionPhoto>,
+    gain_map: Option<GainMapInfo>,
+    depth_maps: Vec<DepthMap>,
+}
+
+impl Jpeg {
+    /// Returns the Multi-Picture Format (MPF) info, if this JPEG has any.
+    ///
+    /// MPF lists the other images stored in the file, like stereo pairs,
+    /// large thumbnails, and Ultra HDR gain maps. See [`Mpf`].
+    pub fn mpf(&self) -> Option<Result<&Mpf, &ExifFatalError>> {
+        self.mpf.as_ref().map(|r| r.as_ref())
+    }
+
+    /// Returns the video inside this JPEG, if it's a Motion Photo.
+    ///
+    /// Google's format (including the older "MicroVideo" one) and Samsung's
+    /// trailer are both supported. See [`crate::providers::motion_photo`].
+    pub fn motion_photo(&self) -> Option<&MotionPhoto> {
+        self.motion_photo.as_ref()
+    }
+
+    /// Returns the HDR gain map's parameters and location, if there is one.
+    ///
+    /// Ultra HDR (`hdrgm`), ISO 21496-1, and Apple gain maps are supported.
+    /// See [`crate::providers::gain_map`].
+    pub fn gain_map(&self) -> Option<&GainMapInfo> {
+        self.gain_map.as_ref()
+    }
+
+    /// Returns the depth maps in this JPEG, like those from portrait-mode
+    /// photos.
+    ///
+    /// Google's `GDepth` XMP and `Container` depth images are supported. See
+    /// [`crate::providers::depth_map`].
+    pub fn depth_maps(&self) -> &[DepthMap] {
+        &self.depth_maps
+    }
+
+    /// Parses a JPEG stored inside another file, like a depth image.
+    ///
+    /// This doesn't look for Motion Photos, gain maps, or depth maps. Those
+    /// belong to the outer file, and looking for them here would let crafted
+    /// files nest images without end.
+    pub(crate) fn new_embedded(input: &[u8]) -> Result<Self, JpegConstructionError> {
+        parse::parse(input, false)
+    }
 }
 
 impl MetadataProvider for Jpeg {
@@ -31,7 +88,7 @@ impl MetadataProvider for Jpeg {
     fn new(
         input: &impl AsRef<[u8]>,
     ) -> Result<Self, <Self as MetadataProvider>::ConstructionError> {
-        parse::parse(input.as_ref())
+        parse::parse(input.as_ref(), true)
     }
 
     fn exif(&self) -> Option<Result<&Exif, &ExifFatalError>> {
@@ -41,6 +98,10 @@ impl MetadataProvider for Jpeg {
     fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
         self.xmp.as_ref().map(|r| r.as_ref())
     }
+
+    fn image_info(&self) -> Option<&ImageInfo> {
+        self.image_info.as_ref()
+    }
 }
 
 #[cfg(test)]
@@ -54,7 +115,14 @@ mod tests {
         xmp::{XmpElement, XmpPrimitive, XmpValue},
     };
 
-    use crate::{MetadataProvider, providers::jpeg::Jpeg, util::logger};
+    use crate::{
+        MetadataProvider,
+        providers::{
+            image_info::{ColorType, ImageInfo, JpegFrameInfo},
+            jpeg::Jpeg,
+        },
+        util::logger,
+    };
 
     #[test]
     fn real_jpeg_no_meta() {
@@ -210,5 +278,60 @@ mod tests {
                 .iter()
                 .any(|v| v.name == "Data" && v.prefix == "GImage")
         );
+
+        // google's depth map properties should be typed
+        assert_eq!(
+            xmp.document()
+                .get("http://ns.google.com/photos/1.0/depthmap/", "Near")
+                .map(|v| &v.value),
+            Some(&XmpValue::Simple(XmpPrimitive::Real(10.917767524719238)))
+        );
+    }
+
+    #[test]
+    fn real_jpeg_image_info() {
+        logger();
+
+        let file = include_bytes!("../../../assets/providers/jpeg/Cat-in-da-hat.jpg");
+        let jpeg = Jpeg::new(file).unwrap();
+
+        assert_eq!(
+            jpeg.image_info(),
+            Some(&ImageInfo {
+                width: 640,
+                height: 480,
+                bit_depth: Some(8),
+                color_type: Some(ColorType::YCbCr),
+                channels: Some(3),
+                frame_count: Some(1),
+                jpeg: JpegFrameInfo::new(0xC0),
+            })
+        );
+    }
+
+    #[test]
+    fn progressive_grayscale_image_info() {
+        logger();
+
+        #[rustfmt::skip]
+        let file: &[u8] = &[
+            0xFF, 0xD8, // SOI
+            0xFF, 0xC2, 0x00, 0x0B, // SOF2, len
+            0x0C, // precision
+            0x00, 0x20, // height
+            0x00, 0x10, // width
+            0x01, // component count
+            0x01, 0x11, 0x00, // component spec
+            0xFF, 0xD9, // EOI
+        ];
+        let info = Jpeg::new(&file).unwrap().image_info().copied().unwrap();
+
+        assert_eq!((info.width, info.height), (16, 32));
+        assert_eq!(info.bit_depth, Some(12));
+        assert_eq!(info.color_type, Some(ColorType::Grayscale));
+
+        let frame = info.jpeg.unwrap();
+        assert!(frame.progressive);
+        assert!(!frame.arithmetic);
     }
 }
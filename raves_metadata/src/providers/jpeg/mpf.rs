@@ -0,0 +1,352 @@
+//! The Multi-Picture Format (MPF, CIPA DC-007) stores extra images after a
+//! JPEG's primary image.
+//!
+//! Stereo cameras, Ultra HDR gain maps, and many "large thumbnail" previews
+//! use it. The primary image's `APP2` segment holds an MP Index IFD, which
+//! uses the same structure as Exif. That IFD lists each image with its type,
+//! size, and offset.
+
+use raves_metadata_types::exif::{
+    Endianness, FieldData, FieldTag,
+    ifd::IfdGroup,
+    primitives::Primitive,
+    tags::{KnownTag, MpfTag},
+};
+use winnow::{
+    Parser as _,
+    binary::{Endianness as WinnowEndianness, u16, u32},
+    error::EmptyError,
+};
+
+use crate::exif::{Exif, error::ExifFatalError};
+
+/// The MPF information found in a JPEG file.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub struct Mpf {
+    /// The parsed MP Index IFD, along with any MP Attribute IFD after it.
+    ///
+    /// Its fields use the [`IfdGroup::Mpf`] group.
+    pub index: Exif,
+
+    /// Each image listed in the MP Index IFD.
+    ///
+    /// The first one is the primary image.
+    pub images: Vec<MpfImage>,
+}
+
+/// One image described by MPF.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub struct MpfImage {
+    /// The raw 24-bit type code from the MP entry.
+    pub type_code: u32,
+
+    /// What kind of image this is, from `type_code`.
+    pub kind: MpfImageKind,
+
+    /// Whether this image is a dependent parent image.
+    pub dependent_parent: bool,
+
+    /// Whether this image is a dependent child image.
+    pub dependent_child: bool,
+
+    /// Whether this image is the representative image.
+    pub representative: bool,
+
+    /// The size of the image, in bytes.
+    pub size: u32,
+
+    /// Where the image starts in the file, in bytes.
+    ///
+    /// The primary image is always at `0`.
+    pub offset: usize,
+
+    /// The entry numbers of this image's dependent images.
+    ///
+    /// A value of `0` means there's no dependent image.
+    pub dependent_images: (u16, u16),
+
+    /// The image's own Exif, if it has any.
+    pub exif: Option<Result<Exif, ExifFatalError>>,
+}
+
+/// A kind of image stored with MPF.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub enum MpfImageKind {
+    /// The baseline MP primary image.
+    BaselinePrimary,
+
+    /// A large thumbnail, up to VGA size.
+    LargeThumbnailVga,
+
+    /// A large thumbnail, up to Full HD size.
+    LargeThumbnailFullHd,
+
+    /// One frame of a panorama.
+    MultiFramePanorama,
+
+    /// One frame of a disparity (stereo) image.
+    MultiFrameDisparity,
+
+    /// One frame of a multi-angle image.
+    MultiFrameMultiAngle,
+
+    /// The image's type is undefined.
+    ///
+    /// Ultra HDR gain maps use this.
+    Undefined,
+
+    /// A type code this crate doesn't know about.
+    Other(u32),
+}
+
+impl From<u32> for MpfImageKind {
+    fn from(type_code: u32) -> Self {
+        match type_code {
+            0x03_0000 => Self::BaselinePrimary,
+            0x01_0001 => Self::LargeThumbnailVga,
+            0x01_0002 => Self::LargeThumbnailFullHd,
+            0x02_0001 => Self::MultiFramePanorama,
+            0x02_0002 => Self::MultiFrameDisparity,
+            0x02_0003 => Self::MultiFrameMultiAngle,
+            0x00_0000 => Self::Undefined,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// Each MP entry is 16 bytes long.
+const MP_ENTRY_LEN: usize = 16;
+
+/// Parses MPF from an `APP2` payload.
+///
+/// `blob` is the payload after its `MPF\0` signature. `blob_start` is where
+/// that blob starts in `file`, since MP entry offsets are relative to it.
+///
+/// `find_exif` grabs an Exif blob from one of the images.
+pub(crate) fn parse(
+    file: &[u8],
+    blob: &[u8],
+    blob_start: usize,
+    find_exif: impl Fn(&[u8]) -> Option<&[u8]>,
+) -> Result<Mpf, ExifFatalError> {
+    let index = Exif::new_with_group(&mut &*blob, IfdGroup::Mpf)?;
+
+    let winnow_endianness = match index.endianness {
+        Endianness::Little => WinnowEndianness::Little,
+        Endianness::Big => WinnowEndianness::Big,
+    };
+
+    // the MP entries are stored as one big `Undefined` field
+    let entries: Vec<u8> = index
+        .ifds
+        .first()
+        .into_iter()
+        .flat_map(|ifd| ifd.fields.iter().flatten())
+        .find(|f| f.tag == FieldTag::Known(KnownTag::MpfTag(MpfTag::MpEntry)))
+        .map(|f| match &f.data {
+            FieldData::List { list, .. } => list
+                .iter()
+                .filter_map(|p| match p {
+                    Primitive::Undefined(b) => Some(*b),
+                    _ => None,
+                })
+                .collect(),
+            FieldData::Primitive(Primitive::Undefined(b)) => vec![*b],
+            _ => Vec::new(),
+        })
+        .unwrap_or_else(|| {
+            log::warn!("MPF index didn't have any MP entries.");
+            Vec::new()
+        });
+
+    if !entries.len().is_multiple_of(MP_ENTRY_LEN) {
+        log::warn!(
+            "MP entry field wasn't a multiple of `{MP_ENTRY_LEN}` bytes. len: `{}`",
+            entries.len()
+        );
+    }
+
+    let images = entries
+        .chunks_exact(MP_ENTRY_LEN)
+        .filter_map(|mut entry| {
+            let (attribute, size, relative_offset, dep_1, dep_2) = (
+                u32(winnow_endianness),
+                u32(winnow_endianness),
+                u32(winnow_endianness),
+                u16(winnow_endianness),
+                u16(winnow_endianness),
+            )
+                .parse_next(&mut entry)
+                .inspect_err(|_: &EmptyError| log::warn!("Failed to read MP entry."))
+                .ok()?;
+
+            // the primary image always uses a zero offset. the others are
+            // relative to the MPF blob
+            let offset: usize = match relative_offset {
+                0 => 0,
+                o => blob_start.checked_add(o as usize)?,
+            };
+
+            let exif = offset
+                .checked_add(size as usize)
+                .and_then(|end| file.get(offset..end))
+                .or_else(|| {
+                    log::warn!("MPF image went out of bounds! offset: `{offset}`, size: `{size}`");
+                    None
+                })
+                .and_then(&find_exif)
+                .map(|mut raw| Exif::new(&mut raw));
+
+            let type_code = attribute & 0x00FF_FFFF;
+            Some(MpfImage {
+                type_code,
+                kind: MpfImageKind::from(type_code),
+                dependent_parent: attribute & (1 << 31) != 0,
+                dependent_child: attribute & (1 << 30) != 0,
+                representative: attribute & (1 << 29) != 0,
+                size,
+                offset,
+                dependent_images: (dep_1, dep_2),
+                exif,
+            })
+        })
+        .collect();
+
+    Ok(Mpf { index, images })
+}
+
+#[cfg(test)]
+mod tests {
+    use raves_metadata_types::exif::{
+        FieldData, FieldTag,
+        primitives::Primitive,
+        tags::{Ifd0Tag, KnownTag, MpfTag},
+    };
+
+    use crate::{
+        MetadataProvider as _,
+        providers::jpeg::{Jpeg, MpfImageKind},
+        util::logger,
+    };
+
+    /// A segment with the given marker code and payload.
+    fn segment(marker_code: u8, payload: &[u8]) -> Vec<u8> {
+        let mut s = vec![0xFF, marker_code];
+        s.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        s.extend_from_slice(payload);
+        s
+    }
+
+    /// A big-endian Exif blob with only an `ImageWidth` field.
+    fn exif(width: u16) -> Vec<u8> {
+        let mut e = b"Exif\0\0MM\0\x2a\0\0\0\x08".to_vec();
+        e.extend_from_slice(&1_u16.to_be_bytes());
+        e.extend_from_slice(&[0x01, 0x00, 0x00, 0x03, 0, 0, 0, 1]);
+        e.extend_from_slice(&width.to_be_bytes());
+        e.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        e
+    }
+
+    /// A big-endian MPF blob listing two images.
+    fn mpf(primary_size: u32, second_size: u32, second_offset: u32) -> Vec<u8> {
+        let mut m = b"MPF\0MM\0\x2a\0\0\0\x08".to_vec();
+        m.extend_from_slice(&3_u16.to_be_bytes());
+
+        // MPFVersion, NumberOfImages, MPEntry
+        m.extend_from_slice(&[0xB0, 0x00, 0x00, 0x07, 0, 0, 0, 4]);
+        m.extend_from_slice(b"0100");
+        m.extend_from_slice(&[0xB0, 0x01, 0x00, 0x04, 0, 0, 0, 1, 0, 0, 0, 2]);
+        m.extend_from_slice(&[0xB0, 0x02, 0x00, 0x07, 0, 0, 0, 32, 0, 0, 0, 50]);
+        m.extend_from_slice(&[0, 0, 0, 0]);
+
+        // the entries themselves
+        m.extend_from_slice(&0x2003_0000_u32.to_be_bytes());
+        m.extend_from_slice(&primary_size.to_be_bytes());
+        m.extend_from_slice(&[0; 8]);
+        m.extend_from_slice(&0_u32.to_be_bytes());
+        m.extend_from_slice(&second_size.to_be_bytes());
+        m.extend_from_slice(&second_offset.to_be_bytes());
+        m.extend_from_slice(&[0; 4]);
+        m
+    }
+
+    fn width(exif: &crate::exif::Exif) -> Option<&FieldData> {
+        exif.ifds[0]
+            .fields
+            .iter()
+            .flatten()
+            .find(|f| f.tag == FieldTag::Known(KnownTag::Ifd0Tag(Ifd0Tag::ImageWidth)))
+            .map(|f| &f.data)
+    }
+
+    #[test]
+    fn two_images() {
+        logger();
+
+        // the second image (ex: a gain map) goes after the primary's `EOI`
+        let mut second = vec![0xFF, 0xD8];
+        second.extend(segment(0xE1, &exif(20)));
+        second.extend([0xFF, 0xD9]);
+
+        // the primary image's length doesn't depend on the MPF values
+        let primary_len = 2 + exif(10).len() + 4 + mpf(0, 0, 0).len() + 4 + 2;
+        let mpf_blob_start = 2 + exif(10).len() + 4 + 4 + 4;
+
+        let mut file = vec![0xFF, 0xD8];
+        file.extend(segment(0xE1, &exif(10)));
+        file.extend(segment(
+            0xE2,
+            &mpf(
+                primary_len as u32,
+                second.len() as u32,
+                (primary_len - mpf_blob_start) as u32,
+            ),
+        ));
+        file.extend([0xFF, 0xD9]);
+        assert_eq!(file.len(), primary_len);
+        file.extend(&second);
+
+        let jpeg = Jpeg::new(&file).unwrap();
+        let mpf = jpeg.mpf().unwrap().unwrap();
+
+        // the index should use the MPF tag table
+        assert!(mpf.index.ifds[0].fields.iter().flatten().any(|f| f.tag
+            == FieldTag::Known(KnownTag::MpfTag(MpfTag::NumberOfImages))
+            && f.data == FieldData::Primitive(Primitive::Long(2))));
+
+        let [primary, gain_map] = mpf.images.as_slice() else {
+            panic!("expected two images. got: {:#?}", mpf.images);
+        };
+
+        assert_eq!(primary.kind, MpfImageKind::BaselinePrimary);
+        assert_eq!(primary.type_code, 0x03_0000);
+        assert!(primary.representative);
+        assert_eq!(primary.offset, 0);
+        assert_eq!(primary.size as usize, primary_len);
+        assert_eq!(
+            width(primary.exif.as_ref().unwrap().as_ref().unwrap()),
+            Some(&FieldData::Primitive(Primitive::Short(10)))
+        );
+
+        assert_eq!(gain_map.kind, MpfImageKind::Undefined);
+        assert!(!gain_map.representative);
+        assert_eq!(gain_map.offset, primary_len);
+        assert_eq!(
+            &file[gain_map.offset..gain_map.offset + gain_map.size as usize],
+            second.as_slice()
+        );
+        assert_eq!(
+            width(gain_map.exif.as_ref().unwrap().as_ref().unwrap()),
+            Some(&FieldData::Primitive(Primitive::Short(20)))
+        );
+    }
+
+    #[test]
+    fn no_mpf() {
+        logger();
+
+        let file = include_bytes!("../../../assets/providers/jpeg/Calico_Cat_Asleep.jpg");
+        let jpeg = Jpeg::new(file).unwrap();
+        assert!(jpeg.mpf().is_none());
+    }
+}
@@ -9,25 +9,51 @@ use winnow::{
 
 use crate::{
     exif::{Exif, error::ExifFatalError},
+    providers::{
+        depth_map, gain_map,
+        image_info::{ColorType, ImageInfo, JpegFrameInfo},
+        jpeg::mpf,
+        motion_photo,
+    },
     xmp::{Xmp, error::XmpError, get_rdf_descriptions},
 };
 
-use super::{Jpeg, JpegConstructionError};
+use super::{Jpeg, JpegConstructionError, Mpf};
 
 /// A marker code indicating that an APP1 marker is present.
 const APP1_MARKER_CODE: u8 = 0xE1;
 
+/// A marker code indicating that an APP2 marker is present.
+const APP2_MARKER_CODE: u8 = 0xE2;
+
+/// The signature at the start of an APP1 Exif payload.
+const EXIF_SIG: &[u8] = b"Exif\0\0";
+
+/// The signature at the start of an APP1 StandardXMP payload.
+const XMP_SIG: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// The signature at the start of an APP2 MPF payload.
+const MPF_SIG: &[u8] = b"MPF\0";
+
+/// The signature at the start of an APP2 ISO 21496-1 gain map payload.
+const ISO_21496_SIG: &[u8] = b"urn:iso:std:iso:ts:21496:-1\0";
+
 /// The first marker code, `SOI` (start of image).
-const SOI_MARKER_CODE: u8 = 0xD8;
+pub(super) const SOI_MARKER_CODE: u8 = 0xD8;
 
 /// The last marker code, `EOI` (end of image).
-const EOI_MARKER_CODE: u8 = 0xD9;
+pub(super) const EOI_MARKER_CODE: u8 = 0xD9;
 
 /// The start of scan code, `SOS`.
-const SOS_MARKER_CODE: u8 = 0xDA;
+pub(super) const SOS_MARKER_CODE: u8 = 0xDA;
+
+/// Markers that don't have a length or payload.
+pub(super) const STANDALONE_MARKERS: &[u8] = &[
+    0xD0, 0xD1, 0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7, 0xD8, 0xD9, 0x01,
+];
 
 /// A part of a JPEG file.
-enum Marker {
+pub(super) enum Marker {
     /// A marker with no data.
     Standalone {
         /// An identifier for a marker.
@@ -72,7 +98,12 @@ struct JpegXmpExtended {
 }
 
 /// Attempts to parse a JPEG file.
-pub fn parse(input: &[u8]) -> Result<Jpeg, JpegConstructionError> {
+///
+/// When `find_attachments` is set, this also looks for the Motion Photo, gain
+/// map, and depth maps stored alongside the image.
+pub fn parse(input: &[u8], find_attachments: bool) -> Result<Jpeg, JpegConstructionError> {
+    // keep the whole file around, since Motion Photos append data after `EOI`
+    let original_input: &[u8] = input;
     let input: &mut &[u8] = &mut &*input;
 
     // take first marker, which should be `SOI`
@@ -90,6 +121,8 @@ pub fn parse(input: &[u8]) -> Result<Jpeg, JpegConstructionError> {
 
     let mut exif: Option<Vec<u8>> = None;
     let mut xmp: Option<JpegXmp> = None;
+    let mut mpf_blob: Option<(&[u8], usize)> = None;
+    let mut image_info: Option<ImageInfo> = None;
 
     // loop until the end of the file.
     while !input.is_empty() {
@@ -169,6 +202,7 @@ pub fn parse(input: &[u8]) -> Result<Jpeg, JpegConstructionError> {
                 log::trace!("Got full marker! code: `{marker_code:x?}`, len: `{len}`");
 
                 let remaining_input_len: u64 = input.len() as u64;
+                let payload_start: usize = original_input.len() - input.len();
                 let payload: &mut &[u8] =
                     &mut take(len as usize)
                         .parse_next(input)
@@ -186,11 +220,32 @@ pub fn parse(input: &[u8]) -> Result<Jpeg, JpegConstructionError> {
                             }
                         })?;
 
+                // `SOFn` says how large the image is.
+                //
+                // only the first one counts, since hierarchical files have
+                // one for each frame
+                if image_info.is_none()
+                    && let Some(frame) = JpegFrameInfo::new(marker_code)
+                {
+                    log::trace!("Found start of frame in JPEG!");
+                    image_info = frame_header(payload, frame);
+                }
+
+                // APP2 can contain MPF, which lists the other images in the
+                // file
+                if marker_code == APP2_MARKER_CODE && payload.starts_with(MPF_SIG) {
+                    log::trace!("Found MPF in JPEG!");
+
+                    if mpf_blob.is_none() {
+                        mpf_blob = Some((&payload[MPF_SIG.len()..], payload_start + MPF_SIG.len()));
+                    } else {
+                        log::warn!("Found more than one MPF payload in JPEG...");
+                    }
+                }
+
                 // APP1 can contain Exif and XMP.
                 //
                 // define strings for both, then check for them!
-                const EXIF_SIG: &[u8] = b"Exif\0\0";
-                const XMP_SIG: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
                 const XMP_EXT_SIG: &[u8] = b"http://ns.adobe.com/xmp/extension/\0";
                 if marker_code == APP1_MARKER_CODE {
                     // exif
@@ -361,7 +416,124 @@ pub fn parse(input: &[u8]) -> Result<Jpeg, JpegConstructionError> {
 
     let exif: Option<Result<Exif, ExifFatalError>> = exif.map(|r| Exif::new(&mut r.as_slice()));
 
-    Ok(Jpeg { exif, xmp })
+    let mpf: Option<Result<Mpf, ExifFatalError>> = mpf_blob.map(|(blob, blob_start)| {
+        mpf::parse(original_input, blob, blob_start, |image| {
+            find_app_segment(image, APP1_MARKER_CODE, EXIF_SIG)
+        })
+    });
+
+    if !find_attachments {
+        return Ok(Jpeg {
+            exif,
+            xmp,
+            image_info,
+            mpf,
+            motion_photo: None,
+            gain_map: None,
+            depth_maps: Vec::new(),
+        });
+    }
+
+    // anything after `EOI` might be a Motion Photo's video
+    let image_end: usize = original_input.len() - input.len();
+    let xmp_document = xmp
+        .as_ref()
+        .and_then(|r| r.as_ref().ok())
+        .map(|x| x.document());
+    let motion_photo = motion_photo::find_in_jpeg(original_input, image_end, xmp_document);
+
+    // gain maps are stored as other images, either listed in MPF or XMP
+    let gain_map = gain_map::find_in_jpeg(
+        original_input,
+        image_end,
+        xmp_document,
+        mpf.as_ref().and_then(|r| r.as_ref().ok()),
+        |image| {
+            (
+                find_app_segment(image, APP1_MARKER_CODE, XMP_SIG),
+                find_app_segment(image, APP2_MARKER_CODE, ISO_21496_SIG),
+            )
+        },
+    );
+
+    // depth maps are in XMP, or stored after the image like gain maps
+    let depth_maps = depth_map::find_in_jpeg(original_input, image_end, xmp_document);
+
+    Ok(Jpeg {
+        exif,
+        xmp,
+        image_info,
+        mpf,
+        motion_photo,
+        gain_map,
+        depth_maps,
+    })
+}
+
+/// Reads the image's size and components from a `SOFn` payload.
+fn frame_header(payload: &[u8], frame: JpegFrameInfo) -> Option<ImageInfo> {
+    let (_, (precision, height, width, components)) = (u8, be_u16, be_u16, u8)
+        .parse_peek(payload)
+        .map_err(|_: EmptyError| log::warn!("JPEG frame header was too short."))
+        .ok()?;
+
+    Some(ImageInfo {
+        width: width.into(),
+        height: height.into(),
+        bit_depth: Some(precision),
+        color_type: match components {
+            1 => Some(ColorType::Grayscale),
+            3 => Some(ColorType::YCbCr),
+            4 => Some(ColorType::Cmyk),
+            _ => None,
+        },
+        channels: Some(components),
+        frame_count: Some(1),
+        jpeg: Some(frame),
+    })
+}
+
+/// Finds one application segment's payload in a JPEG image, without parsing
+/// anything else.
+///
+/// The payload must start with `sig`, which is removed. This is used for the
+/// other images in a file (like MPF images), which each have their own
+/// metadata.
+fn find_app_segment<'input>(
+    mut input: &'input [u8],
+    app_marker_code: u8,
+    sig: &[u8],
+) -> Option<&'input [u8]> {
+    let input = &mut input;
+
+    match marker(input).ok()? {
+        Marker::Standalone { marker_code } if marker_code == SOI_MARKER_CODE => (),
+        _ => {
+            log::warn!("Image didn't start with `SOI`.");
+            return None;
+        }
+    }
+
+    // metadata must come before the image data, so we can stop at `SOS`
+    loop {
+        match marker(input).ok()? {
+            Marker::Standalone { marker_code } if marker_code == EOI_MARKER_CODE => return None,
+            Marker::Standalone { .. } => (),
+            Marker::Full { marker_code, .. } if marker_code == SOS_MARKER_CODE => return None,
+            Marker::Full { marker_code, len } => {
+                let payload: &[u8] = take(len as usize)
+                    .parse_next(input)
+                    .map_err(|_: EmptyError| log::warn!("Image ran out of data."))
+                    .ok()?;
+
+                if marker_code == app_marker_code
+                    && let Some(payload) = payload.strip_prefix(sig)
+                {
+                    return Some(payload);
+                }
+            }
+        }
+    }
 }
 
 pub fn magic_number(input: &[u8]) -> bool {
@@ -378,7 +550,7 @@ pub fn magic_number(input: &[u8]) -> bool {
 }
 
 /// Tries to parse out a [`Marker`].
-fn marker(input: &mut &[u8]) -> Result<Marker, JpegConstructionError> {
+pub(super) fn marker(input: &mut &[u8]) -> Result<Marker, JpegConstructionError> {
     // each marker must begin with one `0xFF` byte.
     //
     // let's see if that happened...
@@ -420,9 +592,6 @@ fn marker(input: &mut &[u8]) -> Result<Marker, JpegConstructionError> {
     // the length of that payload).
     //
     // for that reason, early return if we encounter one...
-    const STANDALONE_MARKERS: &[u8] = &[
-        0xD0, 0xD1, 0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7, 0xD8, 0xD9, 0x01,
-    ];
     if STANDALONE_MARKERS.contains(&marker_code) {
         return Ok(Marker::Standalone { marker_code });
     }
@@ -0,0 +1,394 @@
+//! A lossless model of a JPEG file's segments.
+//!
+//! Everything before the first `SOS` marker is kept as a list of segments,
+//! and everything after (the scan data, `EOI`, and any trailer) is kept as one
+//! blob. Segments can be added, replaced, or removed, then written back out.
+//!
+//! When nothing was changed, the written file is identical to the input.
+
+use winnow::{Parser as _, error::EmptyError, token::take};
+
+use super::{
+    JpegConstructionError, JpegSegmentError,
+    parse::{
+        EOI_MARKER_CODE, Marker, SOI_MARKER_CODE, SOS_MARKER_CODE, STANDALONE_MARKERS, marker,
+    },
+};
+
+/// The segments of a JPEG file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct JpegSegments {
+    /// Extra `0xFF` fill bytes before the `SOI` marker.
+    soi_fill_bytes: usize,
+
+    /// Each segment between `SOI` and the first `SOS`, in order.
+    segments: Vec<JpegSegment>,
+
+    /// Everything from the first `SOS` marker onward.
+    remainder: Vec<u8>,
+}
+
+/// One marker segment in a JPEG file, like `APP1` or `COM`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct JpegSegment {
+    /// The marker code, like `0xE1` for `APP1`.
+    marker_code: u8,
+
+    /// The segment's payload, without its marker or length bytes.
+    payload: Vec<u8>,
+
+    /// Where this segment's marker started in the original file.
+    offset: Option<usize>,
+
+    /// Extra `0xFF` fill bytes before the marker code.
+    fill_bytes: usize,
+}
+
+impl JpegSegments {
+    /// Splits a JPEG file into its segments.
+    pub fn new(input: &[u8]) -> Result<Self, JpegConstructionError> {
+        let original_input = input;
+        let input: &mut &[u8] = &mut &*input;
+        let position = |input: &[u8]| original_input.len() - input.len();
+
+        // the file must start with `SOI`
+        let soi_fill_bytes = match marker(input)? {
+            Marker::Standalone { marker_code } if marker_code == SOI_MARKER_CODE => {
+                position(input) - 2
+            }
+            Marker::Standalone { marker_code } | Marker::Full { marker_code, .. } => {
+                log::error!("JPEG didn't start with `SOI`. got: `{marker_code:x?}`");
+                return Err(JpegConstructionError::FirstMarkerWasNotSoi { marker_code });
+            }
+        };
+
+        let mut segments = Vec::new();
+        while !input.is_empty() {
+            let offset = position(input);
+
+            match marker(input)? {
+                // the rest of the file is image data (or we're already done)
+                Marker::Full { marker_code, .. } if marker_code == SOS_MARKER_CODE => {
+                    return Ok(Self {
+                        soi_fill_bytes,
+                        segments,
+                        remainder: original_input[offset..].to_vec(),
+                    });
+                }
+                Marker::Standalone { marker_code } if marker_code == EOI_MARKER_CODE => {
+                    return Ok(Self {
+                        soi_fill_bytes,
+                        segments,
+                        remainder: original_input[offset..].to_vec(),
+                    });
+                }
+
+                Marker::Standalone { marker_code } => segments.push(JpegSegment {
+                    marker_code,
+                    payload: Vec::new(),
+                    offset: Some(offset),
+                    fill_bytes: position(input) - offset - 2,
+                }),
+
+                Marker::Full { marker_code, len } => {
+                    let fill_bytes = position(input) - offset - 4;
+                    let remaining_input_len = input.len() as u64;
+                    let payload: &[u8] = take(len).parse_next(input).map_err(|_: EmptyError| {
+                        log::error!("JPEG segment ran out of data. code: `{marker_code:x?}`");
+                        JpegConstructionError::NoDataForPayload {
+                            marker_code,
+                            original_len: len,
+                            remaining_input_len,
+                        }
+                    })?;
+
+                    segments.push(JpegSegment {
+                        marker_code,
+                        payload: payload.to_vec(),
+                        offset: Some(offset),
+                        fill_bytes,
+                    });
+                }
+            }
+        }
+
+        log::warn!("JPEG ended without `SOS` or `EOI`.");
+        Ok(Self {
+            soi_fill_bytes,
+            segments,
+            remainder: Vec::new(),
+        })
+    }
+
+    /// The segments between `SOI` and the first `SOS`, in file order.
+    pub fn segments(&self) -> &[JpegSegment] {
+        &self.segments
+    }
+
+    /// Everything from the first `SOS` marker onward.
+    ///
+    /// This includes the entropy-coded image data, `EOI`, and any data
+    /// appended after it.
+    pub fn remainder(&self) -> &[u8] {
+        &self.remainder
+    }
+
+    /// Finds the first segment with the given marker code whose payload starts
+    /// with `signature`.
+    ///
+    /// For example, Exif is in `APP1` (`0xE1`) with the `Exif\0\0` signature.
+    pub fn position(&self, marker_code: u8, signature: &[u8]) -> Option<usize> {
+        self.segments
+            .iter()
+            .position(|s| s.marker_code == marker_code && s.payload.starts_with(signature))
+    }
+
+    /// Inserts a segment at `index`, moving the later ones back.
+    pub fn insert(&mut self, index: usize, segment: JpegSegment) -> Result<(), JpegSegmentError> {
+        if index > self.segments.len() {
+            return Err(JpegSegmentError::IndexOutOfBounds {
+                index,
+                len: self.segments.len(),
+            });
+        }
+
+        self.segments.insert(index, segment);
+        Ok(())
+    }
+
+    /// Replaces the segment at `index`, returning the old one.
+    pub fn replace(
+        &mut self,
+        index: usize,
+        segment: JpegSegment,
+    ) -> Result<JpegSegment, JpegSegmentError> {
+        let len = self.segments.len();
+        let old = self
+            .segments
+            .get_mut(index)
+            .ok_or(JpegSegmentError::IndexOutOfBounds { index, len })?;
+
+        Ok(core::mem::replace(old, segment))
+    }
+
+    /// Removes the segment at `index`, returning it.
+    pub fn remove(&mut self, index: usize) -> Result<JpegSegment, JpegSegmentError> {
+        if index >= self.segments.len() {
+            return Err(JpegSegmentError::IndexOutOfBounds {
+                index,
+                len: self.segments.len(),
+            });
+        }
+
+        Ok(self.segments.remove(index))
+    }
+
+    /// Writes the file back out.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let segments_len: usize = self.segments.iter().map(|s| s.payload.len() + 4).sum();
+        let mut bytes = Vec::with_capacity(2 + segments_len + self.remainder.len());
+
+        bytes.extend(core::iter::repeat_n(0xFF, self.soi_fill_bytes));
+        bytes.extend([0xFF, SOI_MARKER_CODE]);
+        for segment in &self.segments {
+            segment.write(&mut bytes);
+        }
+        bytes.extend(&self.remainder);
+
+        bytes
+    }
+}
+
+impl JpegSegment {
+    /// Creates a new segment.
+    ///
+    /// Standalone markers (`RSTn` and `TEM`) must have an empty payload.
+    pub fn new(marker_code: u8, payload: Vec<u8>) -> Result<Self, JpegSegmentError> {
+        if [
+            0x00,
+            0xFF,
+            SOI_MARKER_CODE,
+            EOI_MARKER_CODE,
+            SOS_MARKER_CODE,
+        ]
+        .contains(&marker_code)
+        {
+            return Err(JpegSegmentError::MarkerCodeDisallowed(marker_code));
+        }
+
+        if STANDALONE_MARKERS.contains(&marker_code) && !payload.is_empty() {
+            return Err(JpegSegmentError::StandaloneMarkerWithPayload { marker_code });
+        }
+
+        if payload.len() > (u16::MAX - 2) as usize {
+            return Err(JpegSegmentError::PayloadTooLong { len: payload.len() });
+        }
+
+        Ok(Self {
+            marker_code,
+            payload,
+            offset: None,
+            fill_bytes: 0,
+        })
+    }
+
+    /// The marker code, like `0xE1` for `APP1` or `0xFE` for `COM`.
+    pub fn marker_code(&self) -> u8 {
+        self.marker_code
+    }
+
+    /// The segment's payload, without its marker or length bytes.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Where this segment's marker started in the original file.
+    ///
+    /// Segments you've added don't have an offset.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// The segment's length field.
+    ///
+    /// Like in the file, this includes the two length bytes, but not the
+    /// marker. Standalone markers don't have a length.
+    pub fn length(&self) -> Option<u16> {
+        (!self.is_standalone()).then_some(self.payload.len() as u16 + 2)
+    }
+
+    /// Whether this is a standalone marker, without a length or payload.
+    pub fn is_standalone(&self) -> bool {
+        STANDALONE_MARKERS.contains(&self.marker_code)
+    }
+
+    /// Appends this segment's bytes.
+    fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.extend(core::iter::repeat_n(0xFF, self.fill_bytes));
+        bytes.extend([0xFF, self.marker_code]);
+        if let Some(length) = self.length() {
+            bytes.extend(length.to_be_bytes());
+            bytes.extend(&self.payload);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        providers::jpeg::{JpegSegment, JpegSegmentError, JpegSegments},
+        util::logger,
+    };
+
+    const FILES: &[&[u8]] = &[
+        include_bytes!("../../../assets/providers/jpeg/Cat-in-da-hat.jpg"),
+        include_bytes!("../../../assets/providers/jpeg/Calico_Cat_Asleep.jpg"),
+        include_bytes!("../../../assets/providers/jpeg/exiv2-bug922.jpg"),
+    ];
+
+    #[test]
+    fn unchanged_files_are_identical() {
+        logger();
+
+        for file in FILES {
+            let segments = JpegSegments::new(file).unwrap();
+            assert!(!segments.segments().is_empty());
+            assert!(segments.remainder().starts_with(&[0xFF, 0xDA]));
+            assert_eq!(segments.to_bytes(), *file);
+        }
+    }
+
+    #[test]
+    fn offsets_and_lengths() {
+        logger();
+
+        let file = FILES[1];
+        let segments = JpegSegments::new(file).unwrap();
+
+        for segment in segments.segments() {
+            let offset = segment.offset().unwrap();
+            assert_eq!(file[offset..offset + 2], [0xFF, segment.marker_code()]);
+
+            let length = segment.length().unwrap() as usize;
+            assert_eq!(file[offset + 2..offset + 4], (length as u16).to_be_bytes());
+            assert_eq!(&file[offset + 4..offset + 2 + length], segment.payload());
+        }
+
+        let exif = segments.position(0xE1, b"Exif\0\0").unwrap();
+        assert!(segments.segments()[exif].payload().starts_with(b"Exif"));
+    }
+
+    #[test]
+    fn edit_segments() {
+        logger();
+
+        let file = FILES[1];
+        let mut segments = JpegSegments::new(file).unwrap();
+        let original_len = segments.segments().len();
+
+        // add a comment after `SOI`
+        let comment = JpegSegment::new(0xFE, b"hello".to_vec()).unwrap();
+        segments.insert(0, comment.clone()).unwrap();
+        assert_eq!(comment.offset(), None);
+
+        // replace the Exif, then remove the XMP
+        let exif = segments.position(0xE1, b"Exif\0\0").unwrap();
+        let new_exif = JpegSegment::new(0xE1, b"Exif\0\0MM\0\x2a\0\0\0\x08\0\0".to_vec());
+        let old_exif = segments.replace(exif, new_exif.unwrap()).unwrap();
+        assert!(old_exif.offset().is_some());
+
+        let xmp = segments.position(0xE1, b"http://ns.adobe.com/xap/1.0/\0");
+        segments.remove(xmp.unwrap()).unwrap();
+
+        // the written file should have the same changes
+        let written = segments.to_bytes();
+        assert_eq!(&written[..11], b"\xFF\xD8\xFF\xFE\x00\x07hello");
+        assert!(written.ends_with(segments.remainder()));
+
+        let reparsed = JpegSegments::new(&written).unwrap();
+        assert_eq!(reparsed.segments().len(), original_len);
+        assert_eq!(reparsed.segments()[0].payload(), b"hello");
+        assert_eq!(
+            reparsed.position(0xE1, b"http://ns.adobe.com/xap/1.0/\0"),
+            None
+        );
+        assert_eq!(reparsed.to_bytes(), written);
+    }
+
+    #[test]
+    fn bad_edits() {
+        logger();
+
+        assert_eq!(
+            JpegSegment::new(0xDA, Vec::new()),
+            Err(JpegSegmentError::MarkerCodeDisallowed(0xDA))
+        );
+        assert_eq!(
+            JpegSegment::new(0xD0, vec![1]),
+            Err(JpegSegmentError::StandaloneMarkerWithPayload { marker_code: 0xD0 })
+        );
+        assert_eq!(
+            JpegSegment::new(0xFE, vec![0; 65534]),
+            Err(JpegSegmentError::PayloadTooLong { len: 65534 })
+        );
+
+        let mut segments = JpegSegments::new(FILES[0]).unwrap();
+        let len = segments.segments().len();
+        assert_eq!(
+            segments.remove(len),
+            Err(JpegSegmentError::IndexOutOfBounds { index: len, len })
+        );
+    }
+
+    /// Fill bytes before markers are kept.
+    #[test]
+    fn fill_bytes() {
+        logger();
+
+        let file = b"\xFF\xD8\xFF\xFF\xFE\x00\x04hi\xFF\xD9";
+        let segments = JpegSegments::new(file).unwrap();
+        assert_eq!(segments.segments()[0].offset(), Some(2));
+        assert_eq!(segments.remainder(), b"\xFF\xD9");
+        assert_eq!(segments.to_bytes(), file);
+    }
+}
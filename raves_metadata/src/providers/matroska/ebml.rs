@@ -0,0 +1,265 @@
+//! A small reader for EBML, the binary format Matroska and WebM are built on.
+//!
+//! Each element has an ID, a size, and its data:
+//!
+//! ```text
+//! [id: 1-4 byte vint, with its marker bit][size: 1-8 byte vint][data]
+//! ```
+//!
+//! A "vint" stores its length in its first byte's leading zeros. Master
+//! elements (like `Segment`) hold more elements, while others hold a value.
+//!
+//! See <https://www.rfc-editor.org/rfc/rfc8794>.
+
+use winnow::{Parser as _, binary::u8 as byte, error::EmptyError, token::take};
+
+/// The EBML header, at the start of every file.
+pub const EBML: u32 = 0x1A45_DFA3;
+
+/// The EBML header's `DocType`, like `webm` or `matroska`.
+pub const DOC_TYPE: u32 = 0x4282;
+
+/// The `Segment`, which holds everything after the header.
+pub const SEGMENT: u32 = 0x1853_8067;
+
+/// Elements that live right inside the `Segment`.
+///
+/// These end any unknown-size element that's inside the `Segment`, like a
+/// `Cluster` from a live recording.
+const SEGMENT_CHILDREN: &[u32] = &[
+    0x114D_9B74, // SeekHead
+    0x1549_A966, // Info
+    0x1654_AE6B, // Tracks
+    0x1F43_B675, // Cluster
+    0x1C53_BB6B, // Cues
+    0x1941_A469, // Attachments
+    0x1043_A770, // Chapters
+    0x1254_C367, // Tags
+];
+
+/// One EBML element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Element<'input> {
+    /// The element's ID, including its marker bit, like `0x1A45DFA3`.
+    pub id: u32,
+
+    /// Whether the element didn't say how long it was.
+    ///
+    /// Its data ends at the next element that can't be inside it.
+    pub unknown_size: bool,
+
+    /// The element's data.
+    pub data: &'input [u8],
+}
+
+/// Reads each element in `input`, in order.
+///
+/// This stops at the end of `input`, or at the first malformed element.
+pub fn elements(mut input: &[u8]) -> impl Iterator<Item = Element<'_>> {
+    core::iter::from_fn(move || element(&mut input))
+}
+
+/// Finds the first child with the given ID.
+pub fn child(input: &[u8], id: u32) -> Option<&[u8]> {
+    elements(input).find(|e| e.id == id).map(|e| e.data)
+}
+
+/// Reads one element.
+pub fn element<'input>(input: &mut &'input [u8]) -> Option<Element<'input>> {
+    if input.is_empty() {
+        return None;
+    }
+
+    let Some((id, size)) = header(input) else {
+        log::warn!("Malformed EBML element header. Stopping here.");
+        return None;
+    };
+
+    let len: usize = match size {
+        Some(size) => match usize::try_from(size) {
+            Ok(size) if size <= input.len() => size,
+            _ => {
+                log::warn!(
+                    "EBML element `{id:#X}` was longer than its input. Truncating it. \
+                    size: `{size}`, input len: `{}`",
+                    input.len()
+                );
+                input.len()
+            }
+        },
+        None => unknown_size_len(id, input),
+    };
+
+    let data: &[u8] = take::<_, _, EmptyError>(len).parse_next(input).ok()?;
+    Some(Element {
+        id,
+        unknown_size: size.is_none(),
+        data,
+    })
+}
+
+/// Reads an element's ID and size.
+///
+/// The size is `None` when it's unknown.
+fn header(input: &mut &[u8]) -> Option<(u32, Option<u64>)> {
+    let (id_len, id) = vint(input, 4)?;
+
+    // IDs keep their marker bit
+    let id: u32 = (id | 1 << (7 * id_len)) as u32;
+
+    let (size_len, size) = vint(input, 8)?;
+    let unknown: bool = size == (1 << (7 * size_len)) - 1;
+    Some((id, (!unknown).then_some(size)))
+}
+
+/// Reads a variable-length integer, without its marker bit.
+///
+/// Returns its length in bytes, too.
+fn vint(input: &mut &[u8], max_len: u32) -> Option<(u32, u64)> {
+    let first: u8 = byte::<_, EmptyError>.parse_next(input).ok()?;
+    let len: u32 = first.leading_zeros() + 1;
+    if len > max_len {
+        return None;
+    }
+
+    let rest: &[u8] = take::<_, _, EmptyError>(len - 1).parse_next(input).ok()?;
+    let value: u64 = rest
+        .iter()
+        .fold(u64::from(first) & (0xFF >> len), |value, b| {
+            value << 8 | u64::from(*b)
+        });
+    Some((len, value))
+}
+
+/// Finds where an unknown-size element ends.
+///
+/// That's at the first element that can't be its child: another top-level
+/// element, or, inside the `Segment`, one of the `Segment`'s children.
+fn unknown_size_len(id: u32, data: &[u8]) -> usize {
+    let ends_element = |child: u32| {
+        child == EBML
+            || child == SEGMENT
+            || (id != EBML && id != SEGMENT && SEGMENT_CHILDREN.contains(&child))
+    };
+
+    let mut rest: &[u8] = data;
+    loop {
+        let before: &[u8] = rest;
+        let end: usize = data.len() - before.len();
+        match header(&mut rest) {
+            Some((child, _)) if ends_element(child) => return end,
+            Some((_, Some(size))) => {
+                let skipped = usize::try_from(size)
+                    .ok()
+                    .and_then(|size| take::<_, _, EmptyError>(size).parse_next(&mut rest).ok());
+                if skipped.is_none() {
+                    return data.len();
+                }
+            }
+
+            // nested unknown sizes (or garbage) run to the end
+            _ => return data.len(),
+        }
+    }
+}
+
+/// Reads an unsigned integer element's value.
+///
+/// They're big-endian, from zero to eight bytes long.
+pub fn uint(data: &[u8]) -> Option<u64> {
+    (data.len() <= 8).then(|| data.iter().fold(0, |value, b| value << 8 | u64::from(*b)))
+}
+
+/// Reads a signed integer element's value.
+pub fn int(data: &[u8]) -> Option<i64> {
+    let value: u64 = uint(data)?;
+    let unused_bits: u32 = 64 - 8 * data.len() as u32;
+
+    // sign-extend it
+    Some(match unused_bits {
+        64 => 0,
+        _ => (value << unused_bits) as i64 >> unused_bits,
+    })
+}
+
+/// Reads a float element's value, which is four or eight bytes long.
+///
+/// An empty float is zero.
+pub fn float(data: &[u8]) -> Option<f64> {
+    match data.len() {
+        0 => Some(0.0),
+        4 => Some(f64::from(f32::from_be_bytes(data.try_into().ok()?))),
+        8 => Some(f64::from_be_bytes(data.try_into().ok()?)),
+        other => {
+            log::warn!("EBML float had an invalid length: `{other}`");
+            None
+        }
+    }
+}
+
+/// Reads a string element's value.
+///
+/// Strings may be padded with NUL bytes, which are removed.
+pub fn string(data: &[u8]) -> String {
+    let end: usize = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).into_owned()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Element, elements};
+
+    /// Makes an element with a known size.
+    pub(crate) fn el(id: u32, data: &[u8]) -> Vec<u8> {
+        let id_bytes = id.to_be_bytes();
+        let id_start: usize = id_bytes.iter().position(|b| *b != 0).unwrap_or(3);
+
+        // an eight-byte size always fits
+        let size: u64 = data.len() as u64 | 1 << 56;
+        [&id_bytes[id_start..], &size.to_be_bytes(), data].concat()
+    }
+
+    #[test]
+    fn vints() {
+        // `0x81` is one byte, `0x40 0x02` is two
+        let input: &[u8] = &[0xEC, 0x81, 0xAA, 0xEC, 0x40, 0x02, 0xBB, 0xCC];
+        let found: Vec<Element> = elements(input).collect();
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].id, found[0].data), (0xEC, [0xAA].as_slice()));
+        assert_eq!(
+            (found[1].id, found[1].data),
+            (0xEC, [0xBB, 0xCC].as_slice())
+        );
+
+        assert_eq!(super::uint(&[0x01, 0x00]), Some(256));
+        assert_eq!(super::int(&[0xFF, 0xFE]), Some(-2));
+        assert_eq!(super::float(&1.5_f32.to_be_bytes()), Some(1.5));
+        assert_eq!(super::string(b"webm\0\0"), "webm");
+    }
+
+    /// Live recordings don't know how long their `Segment` and `Cluster`s are.
+    #[test]
+    fn unknown_sizes() {
+        let cluster: Vec<u8> = [
+            [
+                0x1F, 0x43, 0xB6, 0x75, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            ]
+            .as_slice(),
+            &el(0xE7, &[0]),       // Timestamp
+            &el(0xA3, &[1, 2, 3]), // SimpleBlock
+        ]
+        .concat();
+        let tags: Vec<u8> = el(0x1254_C367, &[]);
+        let segment: Vec<u8> =
+            [[0x18, 0x53, 0x80, 0x67, 0xFF].as_slice(), &cluster, &tags].concat();
+
+        let segment: Element = elements(&segment).next().unwrap();
+        assert!(segment.unknown_size);
+
+        let children: Vec<Element> = elements(segment.data).collect();
+        let ids: Vec<u32> = children.iter().map(|e| e.id).collect();
+        assert_eq!(ids, [0x1F43_B675, 0x1254_C367]);
+        assert!(children[0].unknown_size);
+        assert_eq!(elements(children[0].data).count(), 2);
+    }
+}
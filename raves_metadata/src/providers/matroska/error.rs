@@ -0,0 +1,35 @@
+/// An error that occurred when parsing a Matroska or WebM file.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub enum MatroskaConstructionError {
+    /// The file didn't start with an EBML header.
+    NoEbmlHeader,
+
+    /// The EBML header's `DocType` wasn't `matroska` or `webm`.
+    NotMatroska(String),
+
+    /// There was no `Segment` after the EBML header.
+    NoSegment,
+}
+
+impl core::fmt::Display for MatroskaConstructionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatroskaConstructionError::NoEbmlHeader => {
+                f.write_str("The file didn't start with an EBML header.")
+            }
+
+            MatroskaConstructionError::NotMatroska(doc_type) => write!(
+                f,
+                "The EBML header's `DocType` indicated that this file \
+                isn't Matroska or WebM. Instead, it's: `{doc_type}`",
+            ),
+
+            MatroskaConstructionError::NoSegment => f.write_str(
+                "The Matroska file didn't contain a `Segment`. \
+                Can't continue parsing.",
+            ),
+        }
+    }
+}
+
+impl core::error::Error for MatroskaConstructionError {}
@@ -0,0 +1,781 @@
+//! Provider implementation for Matroska (`.mkv`) and WebM (`.webm`) files.
+//!
+//! Both are EBML documents, only differing in their `DocType`. Their metadata
+//! lives in the `Segment`:
+//!
+//! ```text
+//! EBML            (header, with the `DocType`)
+//! Segment
+//! ├── SeekHead    (where the other elements are)
+//! ├── Info        (title, date, duration, and the apps that wrote it)
+//! ├── Tracks
+//! │   └── TrackEntry
+//! ├── Cluster...  (the media itself)
+//! └── Tags
+//!     └── Tag
+//!         ├── Targets
+//!         └── SimpleTag
+//! ```
+//!
+//! See <https://www.matroska.org/technical/elements.html>.
+
+pub mod ebml;
+pub mod error;
+
+use core::time::Duration;
+use std::time::SystemTime;
+
+use crate::{
+    MetadataProvider,
+    exif::{Exif, error::ExifFatalError},
+    xmp::{Xmp, error::XmpError},
+};
+
+use self::error::MatroskaConstructionError;
+
+/// Element IDs that we read.
+mod id {
+    pub const SEEK_HEAD: u32 = 0x114D_9B74;
+    pub const SEEK: u32 = 0x4DBB;
+    pub const SEEK_ID: u32 = 0x53AB;
+    pub const SEEK_POSITION: u32 = 0x53AC;
+
+    pub const INFO: u32 = 0x1549_A966;
+    pub const TIMESTAMP_SCALE: u32 = 0x2A_D7B1;
+    pub const DURATION: u32 = 0x4489;
+    pub const DATE_UTC: u32 = 0x4461;
+    pub const TITLE: u32 = 0x7BA9;
+    pub const MUXING_APP: u32 = 0x4D80;
+    pub const WRITING_APP: u32 = 0x5741;
+
+    pub const TRACKS: u32 = 0x1654_AE6B;
+    pub const TRACK_ENTRY: u32 = 0xAE;
+    pub const TRACK_NUMBER: u32 = 0xD7;
+    pub const TRACK_UID: u32 = 0x73C5;
+    pub const TRACK_TYPE: u32 = 0x83;
+    pub const CODEC_ID: u32 = 0x86;
+    pub const NAME: u32 = 0x536E;
+    pub const LANGUAGE: u32 = 0x22_B59C;
+    pub const VIDEO: u32 = 0xE0;
+    pub const PIXEL_WIDTH: u32 = 0xB0;
+    pub const PIXEL_HEIGHT: u32 = 0xBA;
+    pub const DISPLAY_WIDTH: u32 = 0x54B0;
+    pub const DISPLAY_HEIGHT: u32 = 0x54BA;
+    pub const AUDIO: u32 = 0xE1;
+    pub const SAMPLING_FREQUENCY: u32 = 0xB5;
+    pub const CHANNELS: u32 = 0x9F;
+
+    pub const TAGS: u32 = 0x1254_C367;
+    pub const TAG: u32 = 0x7373;
+    pub const TARGETS: u32 = 0x63C0;
+    pub const TARGET_TYPE_VALUE: u32 = 0x68CA;
+    pub const TARGET_TYPE: u32 = 0x63CA;
+    pub const TAG_TRACK_UID: u32 = 0x63C5;
+    pub const SIMPLE_TAG: u32 = 0x67C8;
+    pub const TAG_NAME: u32 = 0x45A3;
+    pub const TAG_LANGUAGE: u32 = 0x447A;
+    pub const TAG_STRING: u32 = 0x4487;
+    pub const TAG_BINARY: u32 = 0x4485;
+}
+
+/// Seconds from the Unix epoch to Matroska's (2001-01-01T00:00:00 UTC).
+const MATROSKA_EPOCH: u64 = 978_307_200;
+
+/// How deep we'll go into nested `SimpleTag`s.
+///
+/// Matroska doesn't limit nesting, but muxers only nest a level or two, like
+/// a `URL` under an `ARTIST`. Sixteen leaves room for hand-written tags, and
+/// keeps the recursion in [`simple_tags`] well short of the stack's end.
+const MAX_SIMPLE_TAG_DEPTH: usize = 16;
+
+/// A Matroska or WebM file.
+#[derive(Clone, Debug)]
+pub struct Matroska {
+    doc_type: String,
+    info: Option<SegmentInfo>,
+    tracks: Vec<TrackEntry>,
+    tags: Vec<Tag>,
+}
+
+/// The `Segment`'s `Info` element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentInfo {
+    /// How many nanoseconds each timestamp tick lasts.
+    ///
+    /// This defaults to one millisecond.
+    pub timestamp_scale: u64,
+
+    /// How long the file plays for.
+    pub duration: Option<Duration>,
+
+    /// When the file was made.
+    pub date_utc: Option<SystemTime>,
+
+    /// The file's title.
+    pub title: Option<String>,
+
+    /// The library that wrote the file, like `libwebm-0.3.0.0`.
+    pub muxing_app: Option<String>,
+
+    /// The app that wrote the file, like `Chrome`.
+    pub writing_app: Option<String>,
+}
+
+/// One of the file's tracks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackEntry {
+    /// The number that blocks use to refer to this track.
+    pub number: Option<u64>,
+
+    /// A unique ID, which `Tags` use to refer to this track.
+    pub uid: Option<u64>,
+
+    /// What kind of track it is.
+    pub kind: TrackKind,
+
+    /// The codec, like `V_VP9` or `A_OPUS`.
+    pub codec_id: Option<String>,
+
+    /// The track's name.
+    pub name: Option<String>,
+
+    /// The track's language, like `eng`.
+    pub language: Option<String>,
+
+    /// For video tracks, the frames' size.
+    pub video: Option<VideoTrack>,
+
+    /// For audio tracks, the sample rate and channels.
+    pub audio: Option<AudioTrack>,
+}
+
+/// What kind of track it is, from its `TrackType`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Subtitle,
+
+    /// Any other track type, like buttons or metadata.
+    Other(u64),
+}
+
+/// A video track's frame size, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VideoTrack {
+    pub pixel_width: u64,
+    pub pixel_height: u64,
+
+    /// The size to show the video at, if it's not the pixel size.
+    ///
+    /// This is in pixels unless the file says otherwise.
+    pub display_width: Option<u64>,
+    pub display_height: Option<u64>,
+}
+
+/// An audio track's format.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AudioTrack {
+    /// Samples per second, in Hz.
+    pub sampling_frequency: f64,
+
+    /// The number of channels.
+    pub channels: u64,
+}
+
+/// One `Tag`, which applies its `SimpleTag`s to some targets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tag {
+    /// What the tags apply to.
+    pub targets: TagTargets,
+
+    /// The tags themselves.
+    pub simple_tags: Vec<SimpleTag>,
+}
+
+/// What a `Tag` applies to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TagTargets {
+    /// The level it applies at, like `50` for a whole movie or album, or `30`
+    /// for a track or song.
+    ///
+    /// This defaults to `50`.
+    pub target_type_value: u64,
+
+    /// A name for the level, like `MOVIE`.
+    pub target_type: Option<String>,
+
+    /// The tracks it applies to, by their [`TrackEntry::uid`].
+    ///
+    /// When empty, it applies to every track.
+    pub track_uids: Vec<u64>,
+}
+
+/// A tag's name and value, like `ARTIST` and `Barrett`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SimpleTag {
+    /// The tag's name, which is usually in capital letters.
+    pub name: String,
+
+    /// The value's language, like `und` or `eng`.
+    pub language: Option<String>,
+
+    /// The tag's value, if it has one.
+    pub value: Option<SimpleTagValue>,
+
+    /// Tags about this one, like the `URL` of an `ARTIST`.
+    pub children: Vec<SimpleTag>,
+}
+
+/// A `SimpleTag`'s value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SimpleTagValue {
+    String(String),
+    Binary(Vec<u8>),
+}
+
+impl Matroska {
+    /// Returns the EBML header's `DocType`: `webm` or `matroska`.
+    pub fn doc_type(&self) -> &str {
+        &self.doc_type
+    }
+
+    /// Returns the `Info` element, with the file's title, date, and duration.
+    pub fn info(&self) -> Option<&SegmentInfo> {
+        self.info.as_ref()
+    }
+
+    /// Returns each track, in file order.
+    pub fn tracks(&self) -> &[TrackEntry] {
+        &self.tracks
+    }
+
+    /// Returns each `Tag`, in file order.
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    /// Finds the first string value of a tag with the given name, like
+    /// `ENCODER`.
+    ///
+    /// This checks every `Tag`, no matter its targets, and nested tags, too.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        fn find<'a>(tags: &'a [SimpleTag], name: &str) -> Option<&'a str> {
+            tags.iter().find_map(|tag| match tag.value {
+                Some(SimpleTagValue::String(ref value)) if tag.name == name => Some(value.as_str()),
+                _ => find(&tag.children, name),
+            })
+        }
+
+        self.tags
+            .iter()
+            .find_map(|tag| find(&tag.simple_tags, name))
+    }
+}
+
+impl MetadataProvider for Matroska {
+    type ConstructionError = MatroskaConstructionError;
+
+    fn magic_number(input: &[u8]) -> bool {
+        doc_type(&mut &input[..]).is_ok()
+    }
+
+    /// Reads the given data as a Matroska or WebM file.
+    fn new(
+        input: &impl AsRef<[u8]>,
+    ) -> Result<Self, <Self as MetadataProvider>::ConstructionError> {
+        parse(input.as_ref())
+    }
+
+    /// Matroska doesn't store Exif.
+    fn exif(&self) -> Option<Result<&Exif, &ExifFatalError>> {
+        None
+    }
+
+    /// Matroska doesn't store XMP.
+    fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
+        None
+    }
+}
+
+/// Reads the EBML header's `DocType`, if it's Matroska or WebM.
+///
+/// A missing `DocType` means `matroska`.
+fn doc_type(input: &mut &[u8]) -> Result<String, MatroskaConstructionError> {
+    let header: ebml::Element = ebml::element(input)
+        .filter(|e| e.id == ebml::EBML)
+        .ok_or(MatroskaConstructionError::NoEbmlHeader)?;
+
+    let doc_type: String =
+        ebml::child(header.data, ebml::DOC_TYPE).map_or_else(|| "matroska".into(), ebml::string);
+    match doc_type.as_str() {
+        "webm" | "matroska" => Ok(doc_type),
+        _ => Err(MatroskaConstructionError::NotMatroska(doc_type)),
+    }
+}
+
+/// Parses out metadata from a Matroska file.
+fn parse(mut input: &[u8]) -> Result<Matroska, MatroskaConstructionError> {
+    let doc_type: String = doc_type(&mut input)?;
+
+    let segment: &[u8] = ebml::elements(input)
+        .find(|e| e.id == ebml::SEGMENT)
+        .map(|e| e.data)
+        .ok_or(MatroskaConstructionError::NoSegment)?;
+
+    // the `Segment`'s children, with their position in it
+    let mut children: Vec<(usize, ebml::Element)> = Vec::new();
+    let mut rest: &[u8] = segment;
+    while let Some(child) = {
+        let position: usize = segment.len() - rest.len();
+        ebml::element(&mut rest).map(|child| (position, child))
+    } {
+        children.push(child);
+    }
+
+    // the `SeekHead` can point to elements we missed, like `Tags` after a
+    // malformed `Cluster`.
+    //
+    // it can also point to another `SeekHead`, which we follow. each position
+    // is only read once, so loops between them end
+    let mut seeks: Vec<usize> = children
+        .iter()
+        .filter(|(_, e)| e.id == id::SEEK_HEAD)
+        .flat_map(|(_, e)| seek_positions(e.data))
+        .collect();
+    let mut next: usize = 0;
+    while let Some(&position) = seeks.get(next) {
+        next += 1;
+        if children.iter().any(|(p, _)| *p == position) {
+            continue;
+        }
+        match segment
+            .get(position..)
+            .and_then(|mut s| ebml::element(&mut s))
+        {
+            Some(child) => {
+                if child.id == id::SEEK_HEAD {
+                    seeks.extend(seek_positions(child.data));
+                }
+                children.push((position, child));
+            }
+            None => log::warn!("`SeekHead` pointed outside the `Segment`. position: `{position}`"),
+        }
+    }
+
+    let find = |element_id: u32| children.iter().filter(move |(_, e)| e.id == element_id);
+
+    Ok(Matroska {
+        doc_type,
+        info: find(id::INFO).next().map(|(_, e)| info(e.data)),
+        tracks: find(id::TRACKS)
+            .flat_map(|(_, e)| ebml::elements(e.data))
+            .filter(|e| e.id == id::TRACK_ENTRY)
+            .map(|e| track(e.data))
+            .collect(),
+        tags: find(id::TAGS)
+            .flat_map(|(_, e)| ebml::elements(e.data))
+            .filter(|e| e.id == id::TAG)
+            .map(|e| tag(e.data))
+            .collect(),
+    })
+}
+
+/// Lists the `Segment` positions of the elements we read, from a `SeekHead`.
+///
+/// This includes other `SeekHead`s.
+fn seek_positions(seek_head: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    ebml::elements(seek_head)
+        .filter(|e| e.id == id::SEEK)
+        .filter_map(|seek| {
+            let seek_id: u32 = ebml::uint(ebml::child(seek.data, id::SEEK_ID)?)? as u32;
+            let position: u64 = ebml::uint(ebml::child(seek.data, id::SEEK_POSITION)?)?;
+            [id::SEEK_HEAD, id::INFO, id::TRACKS, id::TAGS]
+                .contains(&seek_id)
+                .then(|| usize::try_from(position).ok())?
+        })
+}
+
+/// Reads the `Info` element.
+fn info(data: &[u8]) -> SegmentInfo {
+    let get = |element_id: u32| ebml::child(data, element_id);
+
+    let timestamp_scale: u64 = get(id::TIMESTAMP_SCALE)
+        .and_then(ebml::uint)
+        .filter(|scale| *scale != 0)
+        .unwrap_or(1_000_000);
+
+    // the duration is in timestamp ticks
+    let duration: Option<Duration> = get(id::DURATION)
+        .and_then(ebml::float)
+        .and_then(|ticks| Duration::try_from_secs_f64(ticks * timestamp_scale as f64 / 1e9).ok());
+
+    // the date is in nanoseconds from the Matroska epoch
+    let date_utc: Option<SystemTime> = get(id::DATE_UTC).and_then(ebml::int).and_then(|nanos| {
+        let epoch: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(MATROSKA_EPOCH);
+        let offset = Duration::from_nanos(nanos.unsigned_abs());
+        if nanos < 0 {
+            epoch.checked_sub(offset)
+        } else {
+            epoch.checked_add(offset)
+        }
+    });
+
+    SegmentInfo {
+        timestamp_scale,
+        duration,
+        date_utc,
+        title: get(id::TITLE).map(ebml::string),
+        muxing_app: get(id::MUXING_APP).map(ebml::string),
+        writing_app: get(id::WRITING_APP).map(ebml::string),
+    }
+}
+
+/// Reads a `TrackEntry`.
+fn track(data: &[u8]) -> TrackEntry {
+    let get = |element_id: u32| ebml::child(data, element_id);
+    let uint = |data: &[u8], element_id: u32| ebml::child(data, element_id).and_then(ebml::uint);
+
+    TrackEntry {
+        number: uint(data, id::TRACK_NUMBER),
+        uid: uint(data, id::TRACK_UID),
+        kind: match uint(data, id::TRACK_TYPE).unwrap_or_default() {
+            1 => TrackKind::Video,
+            2 => TrackKind::Audio,
+            17 => TrackKind::Subtitle,
+            other => TrackKind::Other(other),
+        },
+        codec_id: get(id::CODEC_ID).map(ebml::string),
+        name: get(id::NAME).map(ebml::string),
+        language: get(id::LANGUAGE).map(ebml::string),
+        video: get(id::VIDEO).and_then(|video| {
+            Some(VideoTrack {
+                pixel_width: uint(video, id::PIXEL_WIDTH)?,
+                pixel_height: uint(video, id::PIXEL_HEIGHT)?,
+                display_width: uint(video, id::DISPLAY_WIDTH),
+                display_height: uint(video, id::DISPLAY_HEIGHT),
+            })
+        }),
+        audio: get(id::AUDIO).map(|audio| AudioTrack {
+            sampling_frequency: ebml::child(audio, id::SAMPLING_FREQUENCY)
+                .and_then(ebml::float)
+                .unwrap_or(8000.0),
+            channels: uint(audio, id::CHANNELS).unwrap_or(1),
+        }),
+    }
+}
+
+/// Reads a `Tag`.
+fn tag(data: &[u8]) -> Tag {
+    let targets: Option<&[u8]> = ebml::child(data, id::TARGETS);
+    let uint = |element_id: u32| {
+        targets
+            .and_then(|t| ebml::child(t, element_id))
+            .and_then(ebml::uint)
+    };
+
+    Tag {
+        targets: TagTargets {
+            target_type_value: uint(id::TARGET_TYPE_VALUE).unwrap_or(50),
+            target_type: targets
+                .and_then(|t| ebml::child(t, id::TARGET_TYPE))
+                .map(ebml::string),
+            track_uids: targets
+                .into_iter()
+                .flat_map(ebml::elements)
+                .filter(|e| e.id == id::TAG_TRACK_UID)
+                .filter_map(|e| ebml::uint(e.data))
+                .collect(),
+        },
+        simple_tags: simple_tags(data, 0),
+    }
+}
+
+/// Reads the `SimpleTag`s in a `Tag` or another `SimpleTag`.
+///
+/// `depth` is how many `SimpleTag`s hold `data`.
+fn simple_tags(data: &[u8], depth: usize) -> Vec<SimpleTag> {
+    if depth >= MAX_SIMPLE_TAG_DEPTH {
+        log::warn!("`SimpleTag`s were nested too deeply. Not going any further.");
+        return Vec::new();
+    }
+
+    ebml::elements(data)
+        .filter(|e| e.id == id::SIMPLE_TAG)
+        .map(|e| {
+            let get = |element_id: u32| ebml::child(e.data, element_id);
+            SimpleTag {
+                name: get(id::TAG_NAME).map(ebml::string).unwrap_or_default(),
+                language: get(id::TAG_LANGUAGE).map(ebml::string),
+                value: get(id::TAG_STRING)
+                    .map(|s| SimpleTagValue::String(ebml::string(s)))
+                    .or_else(|| get(id::TAG_BINARY).map(|b| SimpleTagValue::Binary(b.to_vec()))),
+                children: simple_tags(e.data, depth + 1),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::time::SystemTime;
+
+    use crate::{
+        MetadataProvider as _,
+        magic_number::{AnyProvider, MagicNumber},
+        providers::matroska::{
+            Matroska, SimpleTagValue, TrackKind, VideoTrack, ebml::tests::el,
+            error::MatroskaConstructionError,
+        },
+        util::logger,
+    };
+
+    /// Makes an EBML header with the given `DocType`.
+    fn header(doc_type: &[u8]) -> Vec<u8> {
+        el(
+            0x1A45_DFA3,
+            &[el(0x4286, &[1]), el(0x4282, doc_type)].concat(),
+        )
+    }
+
+    /// Makes a `Seek` to the given `Segment` position.
+    ///
+    /// It's a fixed size, whatever the position is.
+    fn seek(seek_id: &[u8], position: usize) -> Vec<u8> {
+        el(
+            0x4DBB,
+            &[
+                el(0x53AB, seek_id),
+                el(0x53AC, &(position as u64).to_be_bytes()),
+            ]
+            .concat(),
+        )
+    }
+
+    /// A WebM screen recording, with its `Tags` found through the `SeekHead`.
+    fn webm() -> Vec<u8> {
+        let info = el(
+            0x1549_A966,
+            &[
+                el(0x2A_D7B1, &1_000_000_u32.to_be_bytes()),
+                el(0x4489, &2500.0_f64.to_be_bytes()),
+                el(0x4461, &(86_400_000_000_000_i64).to_be_bytes()),
+                el(0x7BA9, b"Screen recording"),
+                el(0x4D80, b"Chrome"),
+                el(0x5741, b"Chrome\0"),
+            ]
+            .concat(),
+        );
+        let tracks = el(
+            0x1654_AE6B,
+            &el(
+                0xAE,
+                &[
+                    el(0xD7, &[1]),
+                    el(0x73C5, &[0x42]),
+                    el(0x83, &[1]),
+                    el(0x86, b"V_VP9"),
+                    el(
+                        0xE0,
+                        &[el(0xB0, &[0x07, 0x80]), el(0xBA, &[0x04, 0x38])].concat(),
+                    ),
+                ]
+                .concat(),
+            ),
+        );
+        let tags = el(
+            0x1254_C367,
+            &el(
+                0x7373,
+                &[
+                    el(0x63C0, &el(0x68CA, &[30])),
+                    el(
+                        0x67C8,
+                        &[
+                            el(0x45A3, b"ARTIST"),
+                            el(0x4487, b"Barrett"),
+                            el(
+                                0x67C8,
+                                &[el(0x45A3, b"URL"), el(0x4487, b"https://example.com")].concat(),
+                            ),
+                        ]
+                        .concat(),
+                    ),
+                    el(
+                        0x67C8,
+                        &[el(0x45A3, b"THUMB"), el(0x4485, &[1, 2])].concat(),
+                    ),
+                ]
+                .concat(),
+            ),
+        );
+
+        // a broken `Cluster` hides the `Tags` from a linear read
+        let broken_cluster: &[u8] = &[0x1F, 0x43, 0xB6, 0x75, 0x00];
+
+        // `SeekHead`s are a fixed size here, so positions are easy to find
+        let seek_head_len: usize =
+            el(0x114D_9B74, &[seek(&[0; 4], 0), seek(&[0; 4], 0)].concat()).len();
+        let tags_position: usize = seek_head_len + info.len() + tracks.len() + broken_cluster.len();
+        let seek_head = el(
+            0x114D_9B74,
+            &[
+                seek(&[0x15, 0x49, 0xA9, 0x66], seek_head_len),
+                seek(&[0x12, 0x54, 0xC3, 0x67], tags_position),
+            ]
+            .concat(),
+        );
+
+        let segment: Vec<u8> = [seek_head, info, tracks, broken_cluster.to_vec(), tags].concat();
+        [header(b"webm"), el(0x1853_8067, &segment)].concat()
+    }
+
+    #[test]
+    fn webm_screen_recording() {
+        logger();
+
+        let file: Vec<u8> = webm();
+        assert_eq!(crate::get(&file), Some(MagicNumber::Matroska));
+        let Some(AnyProvider::Matroska(Ok(mkv))) = crate::parse(&file) else {
+            panic!("should parse as Matroska");
+        };
+        assert_eq!(mkv.doc_type(), "webm");
+
+        let info = mkv.info().expect("has `Info`");
+        assert_eq!(info.duration, Some(Duration::from_millis(2500)));
+        assert_eq!(
+            info.date_utc,
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(978_307_200 + 86_400))
+        );
+        assert_eq!(info.title.as_deref(), Some("Screen recording"));
+        assert_eq!(info.muxing_app.as_deref(), Some("Chrome"));
+        assert_eq!(info.writing_app.as_deref(), Some("Chrome"));
+
+        let [track] = mkv.tracks() else {
+            panic!("should have one track");
+        };
+        assert_eq!(track.kind, TrackKind::Video);
+        assert_eq!(track.codec_id.as_deref(), Some("V_VP9"));
+        assert_eq!(
+            track.video,
+            Some(VideoTrack {
+                pixel_width: 1920,
+                pixel_height: 1080,
+                display_width: None,
+                display_height: None,
+            })
+        );
+
+        let [tag] = mkv.tags() else {
+            panic!("should have one tag, from the `SeekHead`");
+        };
+        assert_eq!(tag.targets.target_type_value, 30);
+        assert_eq!(tag.simple_tags.len(), 2);
+        assert_eq!(
+            tag.simple_tags[1].value,
+            Some(SimpleTagValue::Binary(vec![1, 2]))
+        );
+        assert_eq!(mkv.tag("ARTIST"), Some("Barrett"));
+        assert_eq!(mkv.tag("URL"), Some("https://example.com"));
+    }
+
+    #[test]
+    fn doc_types() {
+        logger();
+
+        // a missing `DocType` means `matroska`
+        let file: Vec<u8> = [el(0x1A45_DFA3, &[]), el(0x1853_8067, &[])].concat();
+        assert!(Matroska::magic_number(&file));
+        assert_eq!(Matroska::new(&file).unwrap().doc_type(), "matroska");
+
+        let other: Vec<u8> = header(b"other");
+        assert!(!Matroska::magic_number(&other));
+        assert_eq!(
+            Matroska::new(&other).unwrap_err(),
+            MatroskaConstructionError::NotMatroska("other".into())
+        );
+        assert_eq!(
+            Matroska::new(&header(b"webm")).unwrap_err(),
+            MatroskaConstructionError::NoSegment
+        );
+    }
+
+    /// A `SeekHead` can point to another one, which points to the `Tags`.
+    ///
+    /// The second one also points back to the first, which is ignored.
+    #[test]
+    fn chained_seek_heads() {
+        logger();
+
+        let seek_head_id: &[u8] = &[0x11, 0x4D, 0x9B, 0x74];
+        let tags = el(
+            0x1254_C367,
+            &el(
+                0x7373,
+                &el(
+                    0x67C8,
+                    &[el(0x45A3, b"TITLE"), el(0x4487, b"Chained")].concat(),
+                ),
+            ),
+        );
+        let broken_cluster: &[u8] = &[0x1F, 0x43, 0xB6, 0x75, 0x00];
+
+        // the first `SeekHead` only knows about the second one, after the
+        // broken `Cluster`
+        let first_len: usize = el(0x114D_9B74, &seek(seek_head_id, 0)).len();
+        let second_position: usize = first_len + broken_cluster.len();
+        let first = el(0x114D_9B74, &seek(seek_head_id, second_position));
+
+        let second_len: usize =
+            el(0x114D_9B74, &[seek(&[0; 4], 0), seek(&[0; 4], 0)].concat()).len();
+        let second = el(
+            0x114D_9B74,
+            &[
+                seek(seek_head_id, 0),
+                seek(&[0x12, 0x54, 0xC3, 0x67], second_position + second_len),
+            ]
+            .concat(),
+        );
+
+        let segment: Vec<u8> = [first, broken_cluster.to_vec(), second, tags].concat();
+        let file = [header(b"matroska"), el(0x1853_8067, &segment)].concat();
+
+        let mkv = Matroska::new(&file).unwrap();
+        assert_eq!(mkv.tag("TITLE"), Some("Chained"));
+    }
+
+    /// Deeply nested `SimpleTag`s are cut off, instead of overflowing the
+    /// stack.
+    #[test]
+    fn deeply_nested_simple_tags() {
+        logger();
+
+        let mut simple_tag: Vec<u8> = Vec::new();
+        for level in (0..5_000).rev() {
+            let name = format!("LEVEL_{level}");
+            simple_tag = el(
+                0x67C8,
+                &[el(0x45A3, name.as_bytes()), el(0x4487, b"x"), simple_tag].concat(),
+            );
+        }
+        let tags = el(0x1254_C367, &el(0x7373, &simple_tag));
+        let file = [header(b"matroska"), el(0x1853_8067, &tags)].concat();
+
+        let mkv = Matroska::new(&file).unwrap();
+        let [tag] = mkv.tags() else {
+            panic!("should have one tag");
+        };
+
+        let mut depth = 0;
+        let mut simple_tags = tag.simple_tags.as_slice();
+        while let [simple_tag] = simple_tags {
+            depth += 1;
+            simple_tags = &simple_tag.children;
+        }
+        assert_eq!(depth, super::MAX_SIMPLE_TAG_DEPTH);
+
+        assert_eq!(mkv.tag("LEVEL_15"), Some("x"));
+        assert_eq!(mkv.tag("LEVEL_16"), None);
+    }
+}
@@ -10,13 +10,29 @@
 //! allows a consistent interface for obtaining metadata from different media
 //! sources.
 
+pub mod animation_info;
+pub mod avi;
 pub mod avif;
+pub mod camm;
+pub mod depth_map;
+pub mod gain_map;
 pub mod gif;
+pub mod gpmf;
 pub mod heic;
+pub mod heif_items;
+pub mod heif_properties;
+pub mod ilst;
+pub mod image_info;
 pub mod jpeg;
+pub mod matroska;
+pub mod motion_photo;
 pub mod mov;
 pub mod mp4;
+pub mod orientation;
 pub mod png;
+pub mod riff_info;
+pub mod spherical;
+pub mod wav;
 pub mod webp;
 
 mod shared;
@@ -0,0 +1,429 @@
+//! Motion Photos are still images with a short video stored inside.
+//!
+//! Phones store them in a few different ways:
+//!
+//! - Google's Motion Photo format appends an MP4 after a JPEG's `EOI` marker.
+//!   Then, it describes where that is with `Container:Directory` in XMP.
+//!   Older Google phones used `GCamera:MicroVideoOffset` instead.
+//! - Samsung appends the MP4 inside a trailer, which ends with a `SEFT`
+//!   directory.
+//! - HEIC files store the MP4 in a top-level `mpvd` box.
+//!
+//! No matter the convention, [`MotionPhoto::video`] is a byte range in the
+//! original file. Slicing the file with it gives an MP4 that
+//! [`Mp4`](crate::providers::mp4::Mp4) can parse.
+
+use core::ops::Range;
+
+use winnow::{
+    Parser as _,
+    binary::{le_u16, le_u32},
+    error::EmptyError,
+    token::{literal, take},
+};
+
+use crate::{
+    providers::shared::bmff::{BoxHeader, BoxType},
+    xmp::XmpDocument,
+};
+
+/// The video inside a Motion Photo.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MotionPhoto {
+    /// Where the MP4 video is in the file, in bytes.
+    pub video: Range<usize>,
+
+    /// Where the still image sits in the video, in microseconds.
+    ///
+    /// This comes from XMP, so it's `None` if the file doesn't say.
+    pub presentation_timestamp_us: Option<i64>,
+
+    /// How the video was stored.
+    pub convention: MotionPhotoConvention,
+}
+
+/// A way of storing a Motion Photo's video.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub enum MotionPhotoConvention {
+    /// Google's Motion Photo format, with a `Container:Directory` in XMP.
+    GoogleContainer,
+
+    /// Google's older "MicroVideo" format, with `GCamera:MicroVideoOffset`.
+    GoogleMicroVideo,
+
+    /// Samsung's trailer, which ends with a `SEFT` directory.
+    SamsungTrailer,
+
+    /// A top-level `mpvd` box in a HEIC file.
+    HeicMpvd,
+}
+
+/// Finds the video in a JPEG Motion Photo.
+///
+/// `image_end` is where the JPEG's `EOI` marker ends. The video must start
+/// after that.
+pub(crate) fn find_in_jpeg(
+    input: &[u8],
+    image_end: usize,
+    xmp: Option<&XmpDocument>,
+) -> Option<MotionPhoto> {
+    let valid = |video: &Range<usize>| {
+        let is_valid = video.start >= image_end && is_mp4(input.get(video.clone()));
+        if !is_valid {
+            log::warn!("Motion Photo video range didn't point to an MP4. range: `{video:?}`");
+        }
+        is_valid
+    };
+
+    let (video, convention) = google_container(input.len(), xmp)
+        .filter(valid)
+        .map(|v| (v, MotionPhotoConvention::GoogleContainer))
+        .or_else(|| {
+            micro_video(input.len(), xmp)
+                .filter(valid)
+                .map(|v| (v, MotionPhotoConvention::GoogleMicroVideo))
+        })
+        .or_else(|| {
+            samsung_trailer(input)
+                .filter(valid)
+                .map(|v| (v, MotionPhotoConvention::SamsungTrailer))
+        })?;
+
+    Some(MotionPhoto {
+        video,
+        presentation_timestamp_us: timestamp(xmp),
+        convention,
+    })
+}
+
+/// Finds the video in a HEIC Motion Photo, which is in an `mpvd` box.
+pub(crate) fn find_in_heic(input: &[u8], xmp: Option<&XmpDocument>) -> Option<MotionPhoto> {
+    let mut remaining: &[u8] = input;
+
+    while !remaining.is_empty() {
+        let header = BoxHeader::new(&mut remaining)
+            .inspect_err(|e| log::trace!("Stopped looking for `mpvd` box. err: {e}"))
+            .ok()?;
+        let payload_start = input.len() - remaining.len();
+
+        if header.box_type == BoxType::Id(*b"mpvd") {
+            let payload_len = header
+                .payload_len()
+                .and_then(|len| usize::try_from(len).ok())
+                .unwrap_or(remaining.len());
+            let video = payload_start..payload_start.checked_add(payload_len)?;
+
+            if !is_mp4(input.get(video.clone())) {
+                log::warn!("Found an `mpvd` box, but it didn't hold an MP4.");
+                return None;
+            }
+
+            return Some(MotionPhoto {
+                video,
+                presentation_timestamp_us: timestamp(xmp),
+                convention: MotionPhotoConvention::HeicMpvd,
+            });
+        }
+
+        header.eat_payload(&mut remaining)?;
+    }
+
+    None
+}
+
+/// Grabs the presentation timestamp from XMP, if there is one.
+fn timestamp(xmp: Option<&XmpDocument>) -> Option<i64> {
+    xmp?.motion_photo_info()?.presentation_timestamp_us
+}
+
+/// Finds the `MotionPhoto` item in a Google container.
+///
+/// Items after the primary image are appended to the file in directory
+/// order, so we walk backward from the end of the file.
+fn google_container(file_len: usize, xmp: Option<&XmpDocument>) -> Option<Range<usize>> {
+    let items = xmp?.container_items();
+
+    // the first item is the primary image, which doesn't take up space here
+    let mut end = file_len;
+    for item in items.iter().skip(1).rev() {
+        end = end.checked_sub(usize::try_from(item.padding).ok()?)?;
+        let start = end.checked_sub(usize::try_from(item.length).ok()?)?;
+
+        if item.semantic == "MotionPhoto" {
+            return Some(start..end);
+        }
+
+        end = start;
+    }
+
+    None
+}
+
+/// Finds the video with the older `GCamera:MicroVideoOffset` property.
+///
+/// The offset is measured from the end of the file.
+fn micro_video(file_len: usize, xmp: Option<&XmpDocument>) -> Option<Range<usize>> {
+    let offset = xmp?.motion_photo_info()?.micro_video_offset?;
+    let start = file_len.checked_sub(usize::try_from(offset).ok()?)?;
+    Some(start..file_len)
+}
+
+/// Finds the video in a Samsung trailer.
+///
+/// The trailer ends with a little-endian directory length and `SEFT`. Before
+/// those is the `SEFH` directory, which lists data blocks by their distance
+/// back from the directory's start.
+fn samsung_trailer(input: &[u8]) -> Option<Range<usize>> {
+    const NAME: &[u8] = b"MotionPhoto_Data";
+
+    let footer_start = input.len().checked_sub(8)?;
+    let footer = &mut &input[footer_start..];
+    let dir_len: u32 = le_u32::<_, EmptyError>.parse_next(footer).ok()?;
+    literal::<_, _, EmptyError>(b"SEFT")
+        .parse_next(footer)
+        .ok()?;
+    log::trace!("Found Samsung trailer! directory len: `{dir_len}`");
+
+    let dir_start = footer_start.checked_sub(usize::try_from(dir_len).ok()?)?;
+    let dir = &mut &input[dir_start..footer_start];
+    let (_, _version, count) = (literal(b"SEFH"), le_u32, le_u32)
+        .parse_next(dir)
+        .map_err(|_: EmptyError| log::warn!("Samsung trailer had a malformed `SEFH` directory."))
+        .ok()?;
+
+    for _ in 0..count {
+        let Ok::<_, EmptyError>((_, _ty, offset, len)) =
+            (le_u16, le_u16, le_u32, le_u32).parse_next(dir)
+        else {
+            log::warn!("Samsung trailer's `SEFH` directory ended early.");
+            return None;
+        };
+
+        let block_start = dir_start.checked_sub(usize::try_from(offset).ok()?)?;
+        let block_end = block_start.checked_add(usize::try_from(len).ok()?)?;
+        let block = &mut input.get(block_start..block_end)?;
+
+        // each block starts with its name
+        let Ok::<_, EmptyError>((_, _, name_len)) = (le_u16, le_u16, le_u32).parse_next(block)
+        else {
+            continue;
+        };
+        let Ok::<_, EmptyError>(name) = take(name_len).parse_next(block) else {
+            continue;
+        };
+
+        if name == NAME {
+            return Some((block_end - block.len())..block_end);
+        }
+    }
+
+    None
+}
+
+/// Checks whether some bytes look like an MP4 file (i.e., start with `ftyp`).
+fn is_mp4(bytes: Option<&[u8]>) -> bool {
+    bytes.and_then(|b| b.get(4..8)) == Some(b"ftyp")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        MetadataProvider as _,
+        providers::{
+            heic::Heic,
+            jpeg::Jpeg,
+            motion_photo::{MotionPhoto, MotionPhotoConvention},
+            mp4::Mp4,
+        },
+        util::logger,
+    };
+
+    /// A tiny MP4 file: just an `ftyp` box and a `free` box.
+    fn mp4() -> Vec<u8> {
+        let mut mp4 = Vec::new();
+        mp4.extend(24_u32.to_be_bytes());
+        mp4.extend(b"ftypisom");
+        mp4.extend(0x200_u32.to_be_bytes());
+        mp4.extend(b"isommp42");
+        mp4.extend(12_u32.to_be_bytes());
+        mp4.extend(b"free1234");
+        mp4
+    }
+
+    /// A JPEG with no image data, but with the given XMP.
+    fn jpeg(xmp: Option<&str>) -> Vec<u8> {
+        let mut jpeg = vec![0xFF, 0xD8];
+
+        if let Some(xmp) = xmp {
+            let payload = [b"http://ns.adobe.com/xap/1.0/\0", xmp.as_bytes()].concat();
+            jpeg.extend([0xFF, 0xE1]);
+            jpeg.extend((payload.len() as u16 + 2).to_be_bytes());
+            jpeg.extend(payload);
+        }
+
+        jpeg.extend([0xFF, 0xD9]);
+        jpeg
+    }
+
+    #[test]
+    fn google_jpeg() {
+        logger();
+
+        let video = mp4();
+        let xmp = format!(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:GCamera="http://ns.google.com/photos/1.0/camera/"
+        xmlns:Container="http://ns.google.com/photos/1.0/container/"
+        xmlns:Item="http://ns.google.com/photos/1.0/container/item/"
+      GCamera:MotionPhoto="1"
+      GCamera:MotionPhotoVersion="1"
+      GCamera:MotionPhotoPresentationTimestampUs="1500000">
+      <Container:Directory>
+        <rdf:Seq>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Mime="image/jpeg" Item:Semantic="Primary" Item:Length="0" Item:Padding="0"/>
+          </rdf:li>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Mime="video/mp4" Item:Semantic="MotionPhoto" Item:Length="{}"/>
+          </rdf:li>
+        </rdf:Seq>
+      </Container:Directory>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>"#,
+            video.len()
+        );
+
+        let image = jpeg(Some(&xmp));
+        let file = [image.as_slice(), &video].concat();
+
+        let motion_photo = Jpeg::new(&file).unwrap().motion_photo().cloned();
+        assert_eq!(
+            motion_photo,
+            Some(MotionPhoto {
+                video: image.len()..file.len(),
+                presentation_timestamp_us: Some(1_500_000),
+                convention: MotionPhotoConvention::GoogleContainer,
+            })
+        );
+
+        // the video should parse as an mp4
+        Mp4::new(&&file[motion_photo.unwrap().video]).expect("video is an mp4");
+    }
+
+    #[test]
+    fn google_micro_video_jpeg() {
+        logger();
+
+        let video = mp4();
+        let xmp = format!(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description xmlns:GCamera="http://ns.google.com/photos/1.0/camera/"
+      GCamera:MicroVideo="1"
+      GCamera:MicroVideoVersion="1"
+      GCamera:MicroVideoOffset="{}" />
+</rdf:RDF>"#,
+            video.len()
+        );
+
+        let image = jpeg(Some(&xmp));
+        let file = [image.as_slice(), &video].concat();
+
+        let motion_photo = Jpeg::new(&file).unwrap().motion_photo().cloned().unwrap();
+        assert_eq!(motion_photo.video, image.len()..file.len());
+        assert_eq!(
+            motion_photo.convention,
+            MotionPhotoConvention::GoogleMicroVideo
+        );
+        assert_eq!(motion_photo.presentation_timestamp_us, None);
+    }
+
+    #[test]
+    fn samsung_jpeg() {
+        logger();
+
+        let video = mp4();
+        let image = jpeg(None);
+
+        // the data block, with its name
+        let name = b"MotionPhoto_Data";
+        let mut block = vec![0x00, 0x00, 0x30, 0x0A];
+        block.extend((name.len() as u32).to_le_bytes());
+        block.extend(name);
+        block.extend(&video);
+
+        // the directory, which points back at the block
+        let mut dir = b"SEFH".to_vec();
+        dir.extend(106_u32.to_le_bytes());
+        dir.extend(1_u32.to_le_bytes());
+        dir.extend([0x00, 0x00, 0x30, 0x0A]);
+        dir.extend((block.len() as u32).to_le_bytes());
+        dir.extend((block.len() as u32).to_le_bytes());
+
+        let mut file = [image.as_slice(), &block, &dir].concat();
+        file.extend((dir.len() as u32).to_le_bytes());
+        file.extend(b"SEFT");
+
+        let video_start = image.len() + 8 + name.len();
+        let motion_photo = Jpeg::new(&file).unwrap().motion_photo().cloned();
+        assert_eq!(
+            motion_photo,
+            Some(MotionPhoto {
+                video: video_start..video_start + video.len(),
+                presentation_timestamp_us: None,
+                convention: MotionPhotoConvention::SamsungTrailer,
+            })
+        );
+
+        Mp4::new(&&file[motion_photo.unwrap().video]).expect("video is an mp4");
+    }
+
+    #[test]
+    fn heic_mpvd_box() {
+        logger();
+
+        let video = mp4();
+
+        let mut file = Vec::new();
+        file.extend(24_u32.to_be_bytes());
+        file.extend(b"ftypheic");
+        file.extend(0_u32.to_be_bytes());
+        file.extend(b"mif1heic");
+        file.extend((video.len() as u32 + 8).to_be_bytes());
+        file.extend(b"mpvd");
+        let video_start = file.len();
+        file.extend(&video);
+
+        let motion_photo = Heic::new(&file).unwrap().motion_photo().cloned();
+        assert_eq!(
+            motion_photo,
+            Some(MotionPhoto {
+                video: video_start..file.len(),
+                presentation_timestamp_us: None,
+                convention: MotionPhotoConvention::HeicMpvd,
+            })
+        );
+
+        Mp4::new(&&file[motion_photo.unwrap().video]).expect("video is an mp4");
+    }
+
+    /// Plain JPEGs aren't Motion Photos.
+    #[test]
+    fn no_motion_photo() {
+        logger();
+
+        let file = include_bytes!("../../assets/providers/jpeg/Calico_Cat_Asleep.jpg");
+        assert_eq!(Jpeg::new(file).unwrap().motion_photo(), None);
+
+        // the XMP can't point at something that's not an MP4, either
+        let xmp = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description xmlns:GCamera="http://ns.google.com/photos/1.0/camera/"
+      GCamera:MicroVideo="1"
+      GCamera:MicroVideoOffset="8" />
+</rdf:RDF>"#;
+        let file = [jpeg(Some(xmp)).as_slice(), b"not mp4!"].concat();
+        assert_eq!(Jpeg::new(&file).unwrap().motion_photo(), None);
+    }
+}
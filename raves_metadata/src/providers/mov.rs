@@ -8,16 +8,65 @@ use winnow::{Parser, error::EmptyError, token::take};
 
 use crate::{
     MetadataProvider,
-    providers::shared::bmff::{BoxHeader, BoxSize, BoxType, XMP_BOX_ID, XMP_UUID, ftyp::FtypBox},
+    exif::{Exif, error::ExifFatalError},
+    providers::{
+        camm::{self, CammPacket},
+        ilst::{self, Ilst},
+        orientation::DisplayOrientation,
+        shared::bmff::{
+            BoxHeader, BoxSize, BoxType, XMP_BOX_ID, XMP_UUID, exif::find_exif, ftyp::FtypBox,
+            track,
+        },
+        spherical::{self, SphericalVideo},
+    },
     xmp::{Xmp, error::XmpError},
 };
 
 /// A QuickTime File Format (QTFF) movie file.
 ///
-/// Contains XMP metadata exclusively.
+/// Contains XMP, and sometimes Exif from the camera.
 #[derive(Clone, Debug)]
 pub struct Mov {
+    /// From a camera maker's atom, like `udta/NCTG`.
+    exif: Option<Result<Exif, ExifFatalError>>,
+
     xmp: Option<Result<Xmp, XmpError>>,
+
+    /// From the video track's `tkhd` matrix.
+    display_orientation: Option<DisplayOrientation>,
+
+    /// From Google's Spherical Video metadata.
+    spherical: Option<SphericalVideo>,
+
+    /// From the `camm` track.
+    camm: Option<Vec<CammPacket>>,
+
+    /// From iTunes-style or QuickTime `ilst` boxes.
+    ilst: Option<Ilst>,
+}
+
+impl Mov {
+    /// Returns how this 360-degree video is stored, if it's one.
+    ///
+    /// See [`crate::providers::spherical`].
+    pub fn spherical(&self) -> Option<&SphericalVideo> {
+        self.spherical.as_ref()
+    }
+
+    /// Returns the Camera Motion Metadata (CAMM) packets, if there's a CAMM
+    /// track.
+    ///
+    /// See [`crate::providers::camm`].
+    pub fn camm(&self) -> Option<&[CammPacket]> {
+        self.camm.as_deref()
+    }
+
+    /// Returns the iTunes-style tags, like the title or cover art.
+    ///
+    /// See [`crate::providers::ilst`].
+    pub fn ilst(&self) -> Option<&Ilst> {
+        self.ilst.as_ref()
+    }
 }
 
 /// Parses the `ftyp` atom from the QuickTime file, if possible.
@@ -115,11 +164,22 @@ fn parse(mut input: &[u8]) -> Result<Mov, MovConstructionError> {
     // check the type of the file (should be a MOV)
     parse_ftyp(input)?;
 
+    let display_orientation: Option<DisplayOrientation> = track::video_orientation(input);
+    let exif: Option<Result<Exif, ExifFatalError>> = find_exif(input);
+    let spherical: Option<SphericalVideo> = spherical::find(input);
+    let camm: Option<Vec<CammPacket>> = camm::find(input);
+    let ilst: Option<Ilst> = ilst::find(input);
+
     // check all the other boxes until we find what we want!
     let xmp: Option<&[u8]> = parse_atoms_until_xmp(&mut input);
 
     Ok(Mov {
+        exif,
         xmp: xmp.map(Xmp::new_from_bytes),
+        display_orientation,
+        spherical,
+        camm,
+        ilst,
     })
 }
 
@@ -294,13 +354,17 @@ impl MetadataProvider for Mov {
         parse(input.as_ref())
     }
 
-    fn exif(&self) -> Option<Result<&crate::exif::Exif, &crate::exif::error::ExifFatalError>> {
-        None
+    fn exif(&self) -> Option<Result<&Exif, &ExifFatalError>> {
+        self.exif.as_ref().map(|r| r.as_ref())
     }
 
     fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
         self.xmp.as_ref().map(|r| r.as_ref())
     }
+
+    fn display_orientation(&self) -> Option<DisplayOrientation> {
+        self.display_orientation
+    }
 }
 
 /// An error that occurred when parsing a QuickTime/`MOV` file.
@@ -341,6 +405,8 @@ mod tests {
 
         let bytes = include_bytes!("../../assets/providers/mov/QuickTime.mov");
         let mov: Mov = Mov::new(bytes).expect("mov should parse correctly");
+        assert!(mov.spherical().is_none());
+        assert!(mov.camm().is_none());
 
         let xmp = mov
             .xmp()
@@ -8,14 +8,72 @@ use winnow::{
 
 use crate::{
     MetadataProvider,
-    providers::shared::bmff::{BoxHeader, BoxType, XMP_UUID, ftyp::FtypBox},
+    exif::{Exif, error::ExifFatalError},
+    providers::{
+        camm::{self, CammPacket},
+        gpmf::{self, Telemetry},
+        ilst::{self, Ilst},
+        orientation::DisplayOrientation,
+        shared::bmff::{BoxHeader, BoxType, XMP_UUID, exif::find_exif, ftyp::FtypBox, track},
+        spherical::{self, SphericalVideo},
+    },
     xmp::{Xmp, error::XmpError},
 };
 
 /// An MPEG-4 (MP4) file.
 #[derive(Clone, Debug)]
 pub struct Mp4 {
+    /// From a camera maker's box, like Canon's `uuid` or `udta/NCTG`.
+    exif: Option<Result<Exif, ExifFatalError>>,
+
     xmp: Option<Result<Xmp, XmpError>>,
+
+    /// From the video track's `tkhd` matrix.
+    display_orientation: Option<DisplayOrientation>,
+
+    /// From GoPro's `gpmd` track.
+    telemetry: Option<Telemetry>,
+
+    /// From Google's Spherical Video metadata.
+    spherical: Option<SphericalVideo>,
+
+    /// From the `camm` track.
+    camm: Option<Vec<CammPacket>>,
+
+    /// From iTunes-style or QuickTime `ilst` boxes.
+    ilst: Option<Ilst>,
+}
+
+impl Mp4 {
+    /// Returns the GPS, accelerometer, and gyroscope readings, if this is a
+    /// GoPro video.
+    ///
+    /// See [`crate::providers::gpmf`].
+    pub fn telemetry(&self) -> Option<&Telemetry> {
+        self.telemetry.as_ref()
+    }
+
+    /// Returns how this 360-degree video is stored, if it's one.
+    ///
+    /// See [`crate::providers::spherical`].
+    pub fn spherical(&self) -> Option<&SphericalVideo> {
+        self.spherical.as_ref()
+    }
+
+    /// Returns the Camera Motion Metadata (CAMM) packets, if there's a CAMM
+    /// track.
+    ///
+    /// See [`crate::providers::camm`].
+    pub fn camm(&self) -> Option<&[CammPacket]> {
+        self.camm.as_deref()
+    }
+
+    /// Returns the iTunes-style tags, like the title or cover art.
+    ///
+    /// See [`crate::providers::ilst`].
+    pub fn ilst(&self) -> Option<&Ilst> {
+        self.ilst.as_ref()
+    }
 }
 
 impl MetadataProvider for Mp4 {
@@ -34,13 +92,17 @@ impl MetadataProvider for Mp4 {
         parse(input.as_ref())
     }
 
-    fn exif(&self) -> Option<Result<&crate::exif::Exif, &crate::exif::error::ExifFatalError>> {
-        None
+    fn exif(&self) -> Option<Result<&Exif, &ExifFatalError>> {
+        self.exif.as_ref().map(|r| r.as_ref())
     }
 
     fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
         self.xmp.as_ref().map(|r| r.as_ref())
     }
+
+    fn display_orientation(&self) -> Option<DisplayOrientation> {
+        self.display_orientation
+    }
 }
 
 /// Parses out the initial filetype information box (`ftyp`).
@@ -56,7 +118,12 @@ fn parse_ftyp(input: &[u8]) -> Result<(), Mp4ConstructionError> {
     })?;
 
     // ensure the format is MP4
-    const MP4_FORMATS: &[&[u8; 4]] = &[b"iso2", b"isom", b"mp41", b"mp42"];
+    //
+    // Apple's `M4A ` (audio), `M4B ` (audiobook), `M4P ` (protected), and
+    // `M4V ` (video) brands are MP4 files, too
+    const MP4_FORMATS: &[&[u8; 4]] = &[
+        b"iso2", b"isom", b"mp41", b"mp42", b"M4A ", b"M4B ", b"M4P ", b"M4V ",
+    ];
     let major_is_mp4 = MP4_FORMATS.contains(&&first_box.major_brand);
     let compat_with_mp4 = MP4_FORMATS
         .iter()
@@ -84,11 +151,24 @@ fn parse(mut input: &[u8]) -> Result<Mp4, Mp4ConstructionError> {
     // ensure we're working with an MP4 file...
     parse_ftyp(input)?;
 
+    let display_orientation: Option<DisplayOrientation> = track::video_orientation(input);
+    let exif: Option<Result<Exif, ExifFatalError>> = find_exif(input);
+    let telemetry: Option<Telemetry> = gpmf::find_in_mp4(input);
+    let spherical: Option<SphericalVideo> = spherical::find(input);
+    let camm: Option<Vec<CammPacket>> = camm::find(input);
+    let ilst: Option<Ilst> = ilst::find(input);
+
     // check all the other boxes until we find what we want!
     let raw_xmp_bytes = parse_boxes_until_xmp(&mut input);
 
     Ok(Mp4 {
+        exif,
         xmp: raw_xmp_bytes.map(Xmp::new_from_bytes),
+        display_orientation,
+        telemetry,
+        spherical,
+        camm,
+        ilst,
     })
 }
 
@@ -192,7 +272,126 @@ impl core::fmt::Display for Mp4ConstructionError {
 mod tests {
     use raves_metadata_types::xmp::{XmpElement, XmpPrimitive, XmpValue};
 
-    use crate::{MetadataProvider, providers::mp4::Mp4, util::logger};
+    use crate::{
+        MetadataProvider,
+        magic_number::MagicNumber,
+        providers::{
+            gpmf,
+            ilst::{IlstKey, IlstValue},
+            mp4::Mp4,
+            orientation::DisplayOrientation,
+            shared::bmff::tests::{bx, words},
+        },
+        util::logger,
+    };
+
+    /// A GoPro-like video with one second of telemetry, and no video.
+    #[test]
+    fn gopro_telemetry() {
+        logger();
+
+        let ftyp = bx(b"ftyp", b"mp41\0\0\0\0mp41isom");
+        let sample: Vec<u8> = gpmf::tests::sample();
+        let mdat = bx(b"mdat", &sample);
+        let sample_offset: u32 = (ftyp.len() + 8) as u32;
+
+        let stsd: Vec<u8> = [
+            [0_u8, 0, 0, 0, 0, 0, 0, 1].as_slice(),
+            &bx(b"gpmd", &[0, 0, 0, 0, 0, 0, 0, 1]),
+        ]
+        .concat();
+        let stbl = bx(
+            b"stbl",
+            &[
+                bx(b"stsd", &stsd),
+                words(b"stts", &[0, 1, 1, 1000]),
+                words(b"stsc", &[0, 1, 1, 1, 1]),
+                words(b"stsz", &[0, 0, 1, sample.len() as u32]),
+                words(b"stco", &[0, 1, sample_offset]),
+            ]
+            .concat(),
+        );
+        let mdia = bx(
+            b"mdia",
+            &[
+                words(b"mdhd", &[0, 0, 0, 1000, 1000, 0]),
+                bx(b"hdlr", &[[0_u8; 8].as_slice(), b"meta", &[0; 13]].concat()),
+                bx(b"minf", &stbl),
+            ]
+            .concat(),
+        );
+        let moov = bx(b"moov", &bx(b"trak", &mdia));
+        let file: Vec<u8> = [ftyp, mdat, moov].concat();
+
+        let mp4 = Mp4::new(&file).unwrap();
+        let telemetry = mp4.telemetry().expect("has a GPMF track");
+        assert_eq!(telemetry.gps.len(), 2);
+        assert_eq!(telemetry.gps[1].time.as_millis(), 500);
+        assert_eq!(telemetry.accelerometer.len(), 2);
+    }
+
+    /// Voice memos are M4A files with iTunes-style tags.
+    #[test]
+    fn m4a_tags() {
+        logger();
+
+        let data = |data_type: u32, value: &[u8]| {
+            bx(
+                b"data",
+                &[data_type.to_be_bytes().as_slice(), &[0; 4], value].concat(),
+            )
+        };
+        let freeform: Vec<u8> = [
+            bx(b"mean", b"\0\0\0\0com.apple.iTunes"),
+            bx(b"name", b"\0\0\0\0voice-memo-uuid"),
+            data(1, b"1234-abcd"),
+        ]
+        .concat();
+        let ilst = bx(
+            b"ilst",
+            &[
+                bx(b"\xA9nam", &data(1, b"Grocery list")),
+                bx(b"\xA9day", &data(1, b"2025-06-01T09:30:00Z")),
+                bx(b"desc", &data(1, b"Don't forget the eggs")),
+                bx(b"tmpo", &data(21, &[0, 120])),
+                bx(
+                    b"covr",
+                    &[data(13, b"\xFF\xD8jpeg"), data(14, b"\x89PNG")].concat(),
+                ),
+                bx(b"----", &freeform),
+            ]
+            .concat(),
+        );
+        let hdlr = bx(b"hdlr", &[[0_u8; 8].as_slice(), b"mdir", &[0; 13]].concat());
+        let meta = bx(b"meta", &[[0_u8; 4].as_slice(), &hdlr, &ilst].concat());
+        let moov = bx(b"moov", &bx(b"udta", &meta));
+        let file: Vec<u8> = [bx(b"ftyp", b"M4A \0\0\0\0M4A isom"), moov].concat();
+
+        assert_eq!(MagicNumber::new(&file), Some(MagicNumber::Mp4));
+
+        let mp4 = Mp4::new(&file).expect("M4A is an MP4 brand");
+        let tags = mp4.ilst().expect("has an `ilst` box");
+        assert_eq!(tags.title(), Some("Grocery list"));
+        assert_eq!(tags.date(), Some("2025-06-01T09:30:00Z"));
+        assert_eq!(tags.description(), Some("Don't forget the eggs"));
+        assert_eq!(tags.artist(), None);
+        assert_eq!(
+            tags.get(&IlstKey::Atom(*b"tmpo"))
+                .map(|e| e.values.as_slice()),
+            Some([IlstValue::Integer(120)].as_slice())
+        );
+        assert_eq!(
+            tags.cover_art().collect::<Vec<_>>(),
+            [
+                &IlstValue::Jpeg(b"\xFF\xD8jpeg".to_vec()),
+                &IlstValue::Png(b"\x89PNG".to_vec()),
+            ]
+        );
+        assert_eq!(
+            tags.freeform("com.apple.iTunes", "voice-memo-uuid"),
+            Some([IlstValue::Text("1234-abcd".into())].as_slice())
+        );
+    }
 
     #[test]
     fn parse_real_mp4() {
@@ -202,6 +401,18 @@ mod tests {
 
         let mp4: Mp4 = Mp4::new(&bytes).expect("parsing mp4 should work");
 
+        // its video track isn't rotated
+        assert_eq!(
+            mp4.display_orientation(),
+            Some(DisplayOrientation::IDENTITY)
+        );
+
+        // no camera made this one, so there's no Exif or telemetry
+        assert!(mp4.exif().is_none());
+        assert!(mp4.telemetry().is_none());
+        assert!(mp4.spherical().is_none());
+        assert!(mp4.camm().is_none());
+
         let xmp = mp4
             .xmp()
             .expect("this file has XMP embedded")
@@ -0,0 +1,285 @@
+//! How to turn a stored image (or video) upright for display.
+//!
+//! Each format stores this differently:
+//!
+//! - JPEG, PNG, WebP, and friends use the Exif `Orientation` tag (1 to 8).
+//! - HEIC and AVIF use the primary item's `irot` and `imir` properties. The
+//!   decoder must apply those, so any Exif `Orientation` only describes the
+//!   image before they're applied, and is ignored.
+//! - MP4 and MOV use the video track's `tkhd` transformation matrix.
+//!
+//! All of these become one [`DisplayOrientation`].
+
+use raves_metadata_types::exif::{
+    FieldData, FieldTag,
+    primitives::Primitive,
+    tags::{Ifd0Tag, KnownTag},
+};
+
+use crate::{
+    exif::Exif,
+    providers::heif_properties::{HeifItemProperty, HeifMirror, HeifProperty},
+};
+
+/// How to show an image upright: mirror it, then rotate it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DisplayOrientation {
+    /// Whether to mirror the image left-to-right first.
+    pub mirrored: bool,
+
+    /// How far to rotate the image clockwise, after mirroring.
+    pub rotation: Rotation,
+}
+
+/// A clockwise rotation, in steps of 90 degrees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+impl Rotation {
+    /// Returns the rotation in clockwise degrees.
+    pub const fn degrees(self) -> u16 {
+        match self {
+            Self::None => 0,
+            Self::Clockwise90 => 90,
+            Self::Clockwise180 => 180,
+            Self::Clockwise270 => 270,
+        }
+    }
+
+    /// Makes a rotation from clockwise degrees, which must be a multiple of
+    /// 90.
+    pub const fn from_degrees(degrees: i32) -> Option<Self> {
+        match degrees.rem_euclid(360) {
+            0 => Some(Self::None),
+            90 => Some(Self::Clockwise90),
+            180 => Some(Self::Clockwise180),
+            270 => Some(Self::Clockwise270),
+            _ => None,
+        }
+    }
+
+    /// Adds another clockwise rotation.
+    const fn add(self, degrees: i32) -> Self {
+        match Self::from_degrees(self.degrees() as i32 + degrees) {
+            Some(rotation) => rotation,
+            None => self,
+        }
+    }
+}
+
+impl DisplayOrientation {
+    /// No change: the image is already upright.
+    pub const IDENTITY: Self = Self {
+        mirrored: false,
+        rotation: Rotation::None,
+    };
+
+    /// Makes an orientation from an Exif `Orientation` value.
+    ///
+    /// Returns `None` for values outside 1 to 8.
+    pub const fn from_exif(orientation: u16) -> Option<Self> {
+        let (mirrored, rotation) = match orientation {
+            1 => (false, Rotation::None),
+            2 => (true, Rotation::None),
+            3 => (false, Rotation::Clockwise180),
+            4 => (true, Rotation::Clockwise180),
+            5 => (true, Rotation::Clockwise270),
+            6 => (false, Rotation::Clockwise90),
+            7 => (true, Rotation::Clockwise90),
+            8 => (false, Rotation::Clockwise270),
+            _ => return None,
+        };
+
+        Some(Self { mirrored, rotation })
+    }
+
+    /// Returns the matching Exif `Orientation` value, from 1 to 8.
+    pub const fn to_exif(self) -> u16 {
+        match (self.mirrored, self.rotation) {
+            (false, Rotation::None) => 1,
+            (true, Rotation::None) => 2,
+            (false, Rotation::Clockwise180) => 3,
+            (true, Rotation::Clockwise180) => 4,
+            (true, Rotation::Clockwise270) => 5,
+            (false, Rotation::Clockwise90) => 6,
+            (true, Rotation::Clockwise90) => 7,
+            (false, Rotation::Clockwise270) => 8,
+        }
+    }
+
+    /// Whether the displayed image's width and height are swapped.
+    pub const fn swaps_dimensions(self) -> bool {
+        matches!(
+            self.rotation,
+            Rotation::Clockwise90 | Rotation::Clockwise270
+        )
+    }
+
+    /// Rotates clockwise after this orientation.
+    pub const fn then_rotate(self, clockwise_degrees: i32) -> Self {
+        Self {
+            mirrored: self.mirrored,
+            rotation: self.rotation.add(clockwise_degrees),
+        }
+    }
+
+    /// Mirrors left-to-right after this orientation.
+    ///
+    /// Mirroring after a rotation is the same as mirroring first, then
+    /// rotating the other way.
+    pub const fn then_mirror(self) -> Self {
+        Self {
+            mirrored: !self.mirrored,
+            rotation: Rotation::None.add(-(self.rotation.degrees() as i32)),
+        }
+    }
+
+    /// Reads the `Orientation` tag from Exif's first IFD.
+    pub fn from_exif_metadata(exif: &Exif) -> Option<Self> {
+        let tag = FieldTag::Known(KnownTag::Ifd0Tag(Ifd0Tag::Orientation));
+        let field = exif
+            .ifds
+            .first()?
+            .fields
+            .iter()
+            .flatten()
+            .find(|field| field.tag == tag)?;
+
+        let orientation: u16 = match field.data {
+            FieldData::Primitive(Primitive::Short(value)) => value,
+            FieldData::List { ref list, .. } => match list.as_slice() {
+                [Primitive::Short(value)] => *value,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        Self::from_exif(orientation).or_else(|| {
+            log::warn!("Exif `Orientation` had an unknown value: `{orientation}`");
+            None
+        })
+    }
+
+    /// Applies HEIF transform properties (`irot` and `imir`), in order.
+    ///
+    /// Other properties, like `clap`, don't change the orientation.
+    pub fn from_heif_properties<'a>(
+        properties: impl IntoIterator<Item = &'a HeifItemProperty>,
+    ) -> Self {
+        properties
+            .into_iter()
+            .fold(Self::IDENTITY, |orientation, p| match p.property {
+                HeifProperty::Rotation {
+                    anticlockwise_degrees,
+                } => orientation.then_rotate(-i32::from(anticlockwise_degrees)),
+                HeifProperty::Mirror(HeifMirror::Vertical) => orientation.then_mirror(),
+                HeifProperty::Mirror(HeifMirror::Horizontal) => {
+                    orientation.then_mirror().then_rotate(180)
+                }
+                _ => orientation,
+            })
+    }
+
+    /// Reads the rotation and mirroring from a video track's transformation
+    /// matrix.
+    ///
+    /// Only `a`, `b`, `c`, and `d` matter here. A point `(x, y)` is shown at
+    /// `(a*x + c*y, b*x + d*y)`, plus a translation. Matrices that aren't
+    /// made of right angles return `None`.
+    pub fn from_track_matrix(a: i32, b: i32, c: i32, d: i32) -> Option<Self> {
+        let (mirrored, rotation) = match [a, b, c, d].map(i32::signum) {
+            [1, 0, 0, 1] => (false, Rotation::None),
+            [0, 1, -1, 0] => (false, Rotation::Clockwise90),
+            [-1, 0, 0, -1] => (false, Rotation::Clockwise180),
+            [0, -1, 1, 0] => (false, Rotation::Clockwise270),
+            [-1, 0, 0, 1] => (true, Rotation::None),
+            [0, -1, -1, 0] => (true, Rotation::Clockwise90),
+            [1, 0, 0, -1] => (true, Rotation::Clockwise180),
+            [0, 1, 1, 0] => (true, Rotation::Clockwise270),
+            other => {
+                log::debug!("Track matrix wasn't a right-angle transform: `{other:?}`");
+                return None;
+            }
+        };
+
+        Some(Self { mirrored, rotation })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::providers::heif_properties::{HeifItemProperty, HeifMirror, HeifProperty};
+
+    use super::{DisplayOrientation, Rotation};
+
+    #[test]
+    fn exif_round_trip() {
+        for value in 1..=8 {
+            let orientation = DisplayOrientation::from_exif(value).unwrap();
+            assert_eq!(orientation.to_exif(), value);
+        }
+        assert_eq!(DisplayOrientation::from_exif(9), None);
+
+        assert!(DisplayOrientation::from_exif(6).unwrap().swaps_dimensions());
+    }
+
+    /// Each Exif value is the same as some HEIF transforms.
+    #[test]
+    fn heif_transforms_match_exif() {
+        let irot = |anticlockwise_degrees| HeifItemProperty {
+            essential: true,
+            property: HeifProperty::Rotation {
+                anticlockwise_degrees,
+            },
+        };
+        let imir = |axis| HeifItemProperty {
+            essential: true,
+            property: HeifProperty::Mirror(axis),
+        };
+
+        let cases = [
+            (vec![irot(270)], 6),
+            (vec![irot(90)], 8),
+            (vec![imir(HeifMirror::Vertical)], 2),
+            (vec![imir(HeifMirror::Horizontal)], 4),
+            // rotate, then mirror left-to-right
+            (vec![irot(90), imir(HeifMirror::Vertical)], 7),
+            (vec![irot(270), imir(HeifMirror::Vertical)], 5),
+        ];
+
+        for (properties, exif) in cases {
+            assert_eq!(
+                DisplayOrientation::from_heif_properties(&properties).to_exif(),
+                exif,
+                "{properties:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn track_matrices() {
+        const ONE: i32 = 0x0001_0000;
+
+        assert_eq!(
+            DisplayOrientation::from_track_matrix(0, ONE, -ONE, 0),
+            Some(DisplayOrientation {
+                mirrored: false,
+                rotation: Rotation::Clockwise90
+            })
+        );
+        assert_eq!(
+            DisplayOrientation::from_track_matrix(ONE, 0, 0, ONE),
+            Some(DisplayOrientation::IDENTITY)
+        );
+        assert_eq!(
+            DisplayOrientation::from_track_matrix(ONE, ONE, 0, ONE),
+            None
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Typed PNG ancillary chunks.
+//!
+//! These describe the image, but aren't metadata standards themselves:
+//!
+//! - `tIME`: when the image was last modified
+//! - `pHYs`: the physical pixel size (DPI)
+//! - `sRGB`, `gAMA`, `cHRM`: color space info
+//! - `cICP`, `mDCv`, `cLLi`: HDR signalling, from PNG's third edition
+//! - `sBIT`: the original number of significant bits
+
+use winnow::{
+    Parser as _,
+    binary::{be_u16, be_u32, u8},
+    error::EmptyError,
+};
+
+/// Every supported ancillary chunk found in a PNG.
+///
+/// Each field is `None` if the chunk wasn't found, or was malformed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PngAncillaryChunks {
+    /// `tIME`: the last modification time.
+    pub time: Option<PngTime>,
+
+    /// `pHYs`: the physical pixel dimensions.
+    pub physical_dimensions: Option<PngPhysicalDimensions>,
+
+    /// `sRGB`: the image uses sRGB, with this rendering intent.
+    pub srgb: Option<PngRenderingIntent>,
+
+    /// `gAMA`: the image's gamma, like `0.45455`.
+    pub gamma: Option<f64>,
+
+    /// `cHRM`: the primary chromaticities and white point.
+    pub chromaticities: Option<PngChromaticities>,
+
+    /// `cICP`: coding-independent code points, which identify the color space
+    /// and transfer function.
+    pub cicp: Option<PngCicp>,
+
+    /// `mDCv`: the mastering display's color volume.
+    pub mastering_display: Option<PngMasteringDisplay>,
+
+    /// `cLLi`: the content light level.
+    pub content_light_level: Option<PngContentLightLevel>,
+
+    /// `sBIT`: the significant bits in each channel of the original image.
+    ///
+    /// There's one entry for each channel, which depends on the color type.
+    pub significant_bits: Option<Vec<u8>>,
+}
+
+/// The last modification time, in UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PngTime {
+    pub year: u16,
+    /// `1..=12`
+    pub month: u8,
+    /// `1..=31`
+    pub day: u8,
+    /// `0..=23`
+    pub hour: u8,
+    /// `0..=59`
+    pub minute: u8,
+    /// `0..=60`, allowing for leap seconds.
+    pub second: u8,
+}
+
+/// How large each pixel is, or its aspect ratio.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PngPhysicalDimensions {
+    pub pixels_per_unit_x: u32,
+    pub pixels_per_unit_y: u32,
+    pub unit: PngPhysicalUnit,
+}
+
+/// The unit used by [`PngPhysicalDimensions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PngPhysicalUnit {
+    /// No unit, so only the aspect ratio is known.
+    Unknown,
+
+    /// Pixels per meter.
+    Meter,
+}
+
+impl PngPhysicalDimensions {
+    /// Returns the horizontal and vertical dots per inch.
+    ///
+    /// This is `None` when the unit is unknown.
+    pub fn dpi(&self) -> Option<(f64, f64)> {
+        const INCHES_PER_METER: f64 = 1.0 / 0.0254;
+
+        match self.unit {
+            PngPhysicalUnit::Meter => Some((
+                f64::from(self.pixels_per_unit_x) / INCHES_PER_METER,
+                f64::from(self.pixels_per_unit_y) / INCHES_PER_METER,
+            )),
+            PngPhysicalUnit::Unknown => None,
+        }
+    }
+}
+
+/// The rendering intent from an `sRGB` chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PngRenderingIntent {
+    Perceptual,
+    RelativeColorimetric,
+    Saturation,
+    AbsoluteColorimetric,
+
+    /// A value not defined by the PNG spec.
+    Unknown(u8),
+}
+
+impl From<u8> for PngRenderingIntent {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Perceptual,
+            1 => Self::RelativeColorimetric,
+            2 => Self::Saturation,
+            3 => Self::AbsoluteColorimetric,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+/// CIE 1931 `(x, y)` chromaticities of the primaries and white point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PngChromaticities {
+    pub white_point: (f64, f64),
+    pub red: (f64, f64),
+    pub green: (f64, f64),
+    pub blue: (f64, f64),
+}
+
+/// Coding-independent code points, as defined in ITU-T H.273.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PngCicp {
+    /// The color primaries, like `1` (BT.709) or `9` (BT.2020).
+    pub color_primaries: u8,
+
+    /// The transfer characteristics, like `13` (sRGB), `16` (PQ), or `18`
+    /// (HLG).
+    pub transfer_characteristics: u8,
+
+    /// The matrix coefficients. PNG only allows `0` (RGB).
+    pub matrix_coefficients: u8,
+
+    /// Whether the image uses full-range values, instead of "narrow" range.
+    pub full_range: bool,
+}
+
+impl PngCicp {
+    /// Whether the transfer function is PQ (SMPTE ST 2084).
+    pub fn is_pq(&self) -> bool {
+        self.transfer_characteristics == 16
+    }
+
+    /// Whether the transfer function is HLG (ARIB STD-B67).
+    pub fn is_hlg(&self) -> bool {
+        self.transfer_characteristics == 18
+    }
+
+    /// Whether the image is HDR, meaning it uses PQ or HLG.
+    pub fn is_hdr(&self) -> bool {
+        self.is_pq() || self.is_hlg()
+    }
+}
+
+/// The color volume of the display used to master the image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PngMasteringDisplay {
+    /// CIE 1931 `(x, y)` chromaticities of the red, green, and blue
+    /// primaries.
+    pub primaries: [(f64, f64); 3],
+
+    /// CIE 1931 `(x, y)` chromaticity of the white point.
+    pub white_point: (f64, f64),
+
+    /// The display's maximum luminance, in candelas per square meter.
+    pub max_luminance: f64,
+
+    /// The display's minimum luminance, in candelas per square meter.
+    pub min_luminance: f64,
+}
+
+/// How bright the image's content is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PngContentLightLevel {
+    /// The maximum content light level (MaxCLL), in candelas per square
+    /// meter.
+    pub max_content_light_level: f64,
+
+    /// The maximum frame-average light level (MaxFALL), in candelas per
+    /// square meter.
+    pub max_frame_average_light_level: f64,
+}
+
+impl PngAncillaryChunks {
+    /// Reads a chunk, if it's one of the supported ones.
+    ///
+    /// Only the first of each chunk is kept.
+    pub(super) fn read(&mut self, ident: &[u8; 4], payload: &[u8]) {
+        match ident {
+            b"tIME" => keep_first(&mut self.time, ident, || time(payload)),
+            b"pHYs" => keep_first(&mut self.physical_dimensions, ident, || {
+                physical_dimensions(payload)
+            }),
+            b"sRGB" => keep_first(&mut self.srgb, ident, || {
+                parse(u8.map(PngRenderingIntent::from), payload)
+            }),
+            b"gAMA" => keep_first(&mut self.gamma, ident, || {
+                parse(be_u32.map(|g| f64::from(g) / 100_000.0), payload)
+            }),
+            b"cHRM" => keep_first(&mut self.chromaticities, ident, || chromaticities(payload)),
+            b"cICP" => keep_first(&mut self.cicp, ident, || cicp(payload)),
+            b"mDCv" => keep_first(&mut self.mastering_display, ident, || {
+                mastering_display(payload)
+            }),
+            b"cLLi" => keep_first(&mut self.content_light_level, ident, || {
+                content_light_level(payload)
+            }),
+            b"sBIT" => keep_first(&mut self.significant_bits, ident, || {
+                (!payload.is_empty() && payload.len() <= 4).then(|| payload.to_vec())
+            }),
+            _ => (),
+        }
+    }
+}
+
+/// Sets `field` to the parsed chunk, unless it's already set.
+fn keep_first<T>(field: &mut Option<T>, ident: &[u8; 4], parse: impl FnOnce() -> Option<T>) {
+    let ident: &str = core::str::from_utf8(ident).unwrap_or("not UTF-8");
+    if field.is_some() {
+        log::warn!("Found another `{ident}` chunk. Ignoring it.");
+        return;
+    }
+
+    *field = parse();
+    if field.is_none() {
+        log::warn!("Failed to parse `{ident}` chunk.");
+    }
+}
+
+/// Runs a parser on an entire payload.
+fn parse<'input, T>(
+    mut parser: impl winnow::Parser<&'input [u8], T, EmptyError>,
+    payload: &'input [u8],
+) -> Option<T> {
+    parser.parse(payload).ok()
+}
+
+fn time(payload: &[u8]) -> Option<PngTime> {
+    parse(
+        (be_u16, u8, u8, u8, u8, u8).map(|(year, month, day, hour, minute, second)| PngTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }),
+        payload,
+    )
+}
+
+fn physical_dimensions(payload: &[u8]) -> Option<PngPhysicalDimensions> {
+    parse(
+        (be_u32, be_u32, u8).map(|(x, y, unit)| PngPhysicalDimensions {
+            pixels_per_unit_x: x,
+            pixels_per_unit_y: y,
+            unit: match unit {
+                1 => PngPhysicalUnit::Meter,
+                _ => PngPhysicalUnit::Unknown,
+            },
+        }),
+        payload,
+    )
+}
+
+fn chromaticities(payload: &[u8]) -> Option<PngChromaticities> {
+    // each value is multiplied by 100,000
+    fn point(input: &mut &[u8]) -> winnow::Result<(f64, f64), EmptyError> {
+        (be_u32, be_u32)
+            .map(|(x, y)| (f64::from(x) / 100_000.0, f64::from(y) / 100_000.0))
+            .parse_next(input)
+    }
+
+    parse(
+        (point, point, point, point).map(|(white_point, red, green, blue)| PngChromaticities {
+            white_point,
+            red,
+            green,
+            blue,
+        }),
+        payload,
+    )
+}
+
+fn cicp(payload: &[u8]) -> Option<PngCicp> {
+    parse(
+        (u8, u8, u8, u8).map(
+            |(color_primaries, transfer_characteristics, matrix_coefficients, full_range)| {
+                PngCicp {
+                    color_primaries,
+                    transfer_characteristics,
+                    matrix_coefficients,
+                    full_range: full_range != 0,
+                }
+            },
+        ),
+        payload,
+    )
+}
+
+fn mastering_display(payload: &[u8]) -> Option<PngMasteringDisplay> {
+    // chromaticities use units of 0.00002, and luminance uses 0.0001 cd/m^2
+    fn point(input: &mut &[u8]) -> winnow::Result<(f64, f64), EmptyError> {
+        (be_u16, be_u16)
+            .map(|(x, y)| (f64::from(x) * 0.000_02, f64::from(y) * 0.000_02))
+            .parse_next(input)
+    }
+    let luminance = || be_u32.map(|l| f64::from(l) * 0.0001);
+
+    parse(
+        (point, point, point, point, luminance(), luminance()).map(
+            |(red, green, blue, white_point, max_luminance, min_luminance)| PngMasteringDisplay {
+                primaries: [red, green, blue],
+                white_point,
+                max_luminance,
+                min_luminance,
+            },
+        ),
+        payload,
+    )
+}
+
+fn content_light_level(payload: &[u8]) -> Option<PngContentLightLevel> {
+    // both use units of 0.0001 cd/m^2
+    parse(
+        (be_u32, be_u32).map(|(max_cll, max_fall)| PngContentLightLevel {
+            max_content_light_level: f64::from(max_cll) * 0.0001,
+            max_frame_average_light_level: f64::from(max_fall) * 0.0001,
+        }),
+        payload,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PngAncillaryChunks, PngPhysicalUnit, PngRenderingIntent, PngTime};
+
+    #[test]
+    fn time_and_dpi() {
+        let mut chunks = PngAncillaryChunks::default();
+        chunks.read(b"tIME", &[0x07, 0xE9, 3, 14, 15, 9, 26]);
+        chunks.read(b"pHYs", &[0, 0, 0x0B, 0x13, 0, 0, 0x0B, 0x13, 1]);
+
+        assert_eq!(
+            chunks.time,
+            Some(PngTime {
+                year: 2025,
+                month: 3,
+                day: 14,
+                hour: 15,
+                minute: 9,
+                second: 26
+            })
+        );
+
+        let phys = chunks.physical_dimensions.unwrap();
+        assert_eq!(phys.unit, PngPhysicalUnit::Meter);
+        let (x, y) = phys.dpi().unwrap();
+        assert_eq!((x.round(), y.round()), (72.0, 72.0));
+    }
+
+    #[test]
+    fn color_chunks() {
+        let mut chunks = PngAncillaryChunks::default();
+        chunks.read(b"sRGB", &[0]);
+        chunks.read(b"gAMA", &45455_u32.to_be_bytes());
+        chunks.read(
+            b"cHRM",
+            &[31270_u32, 32900, 64000, 33000, 30000, 60000, 15000, 6000]
+                .map(u32::to_be_bytes)
+                .concat(),
+        );
+
+        assert_eq!(chunks.srgb, Some(PngRenderingIntent::Perceptual));
+        assert_eq!(chunks.gamma, Some(0.45455));
+
+        let chrm = chunks.chromaticities.unwrap();
+        assert_eq!(chrm.white_point, (0.3127, 0.329));
+        assert_eq!(chrm.blue, (0.15, 0.06));
+    }
+
+    #[test]
+    fn hdr_chunks() {
+        let mut chunks = PngAncillaryChunks::default();
+
+        // BT.2020 primaries, PQ transfer, RGB, full range
+        chunks.read(b"cICP", &[9, 16, 0, 1]);
+
+        // a 1000 nit display
+        let mdcv: Vec<u8> = [
+            [35400_u16, 14600].map(u16::to_be_bytes).concat(),
+            [8500, 39850].map(u16::to_be_bytes).concat(),
+            [6550, 2300].map(u16::to_be_bytes).concat(),
+            [15635, 16450].map(u16::to_be_bytes).concat(),
+            10_000_000_u32.to_be_bytes().to_vec(),
+            50_u32.to_be_bytes().to_vec(),
+        ]
+        .concat();
+        chunks.read(b"mDCv", &mdcv);
+        chunks.read(
+            b"cLLi",
+            &[4_000_000_u32, 2_000_000].map(u32::to_be_bytes).concat(),
+        );
+
+        let cicp = chunks.cicp.unwrap();
+        assert!(cicp.is_pq() && cicp.is_hdr() && cicp.full_range);
+
+        let mdcv = chunks.mastering_display.unwrap();
+        assert!((mdcv.max_luminance - 1000.0).abs() < 1e-9);
+        assert!((mdcv.min_luminance - 0.005).abs() < 1e-9);
+        assert!((mdcv.primaries[0].0 - 0.708).abs() < 1e-9);
+
+        let clli = chunks.content_light_level.unwrap();
+        assert!((clli.max_content_light_level - 400.0).abs() < 1e-9);
+        assert!((clli.max_frame_average_light_level - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn malformed_and_repeated_chunks() {
+        let mut chunks = PngAncillaryChunks::default();
+        chunks.read(b"gAMA", &[0, 1]);
+        chunks.read(b"sBIT", &[5, 6, 5]);
+        chunks.read(b"sBIT", &[8, 8, 8]);
+
+        assert_eq!(chunks.gamma, None);
+        assert_eq!(chunks.significant_bits, Some(vec![5, 6, 5]));
+    }
+}
@@ -0,0 +1,77 @@
+//! APNG (animated PNG) chunks.
+//!
+//! - `acTL` (animation control) has the frame count and number of plays.
+//! - `fcTL` (frame control) comes before each frame, with its delay.
+
+use core::time::Duration;
+
+use winnow::{
+    Parser as _,
+    binary::{be_u16, be_u32},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::providers::animation_info::{AnimationInfo, LoopCount};
+
+/// The animation chunks found so far.
+#[derive(Clone, Debug, Default)]
+pub(super) struct ApngChunks {
+    /// The frame count and number of plays, from `acTL`.
+    control: Option<(u32, u32)>,
+
+    /// Each frame's delay, from `fcTL`.
+    frame_delays: Vec<Duration>,
+}
+
+impl ApngChunks {
+    /// Reads an `acTL` chunk.
+    pub(super) fn animation_control(&mut self, mut payload: &[u8]) {
+        if self.control.is_some() {
+            log::warn!("Found more than one `acTL` chunk. Ignoring it.");
+            return;
+        }
+
+        self.control = (be_u32, be_u32)
+            .parse_next(&mut payload)
+            .map_err(|_: EmptyError| log::warn!("`acTL` chunk was too short."))
+            .ok();
+    }
+
+    /// Reads an `fcTL` chunk.
+    ///
+    /// Its delay is a fraction of a second. A denominator of zero means
+    /// hundredths.
+    pub(super) fn frame_control(&mut self, mut payload: &[u8]) {
+        // skip the sequence number, size, and offset
+        let Ok::<_, EmptyError>((_, delay_num, delay_den)) =
+            (take(20_usize), be_u16, be_u16).parse_next(&mut payload)
+        else {
+            log::warn!("`fcTL` chunk was too short.");
+            return;
+        };
+
+        let delay_den: u16 = if delay_den == 0 { 100 } else { delay_den };
+        self.frame_delays.push(Duration::from_secs_f64(
+            f64::from(delay_num) / f64::from(delay_den),
+        ));
+    }
+
+    /// Summarizes the animation.
+    ///
+    /// Without an `acTL` chunk, the PNG is a still image.
+    pub(super) fn finish(self) -> AnimationInfo {
+        let Some((frame_count, plays)) = self.control else {
+            return AnimationInfo::still();
+        };
+
+        AnimationInfo {
+            frame_count,
+            frame_delays: self.frame_delays,
+            loop_count: match plays {
+                0 => LoopCount::Infinite,
+                plays => LoopCount::Finite(plays),
+            },
+        }
+    }
+}
@@ -0,0 +1,73 @@
+/// An error that occurs when constructing a [`Png`](super::Png) for its metadata.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub enum PngConstructionError {
+    /// The file ran out of bytes before we could check for a signature.
+    ///
+    /// It might be empty.
+    NoSignature,
+
+    /// No PNG signature was detected.
+    NotAPng {
+        /// The signature that was found instead.
+        found: [u8; 8],
+    },
+}
+
+impl core::fmt::Display for PngConstructionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NOT_A_PNG_MSG: &str = "The given file's signature indicated it was not a PNG";
+
+        match self {
+            PngConstructionError::NoSignature => {
+                f.write_str("File didn't have enough bytes for a signature.")
+            }
+
+            PngConstructionError::NotAPng { found } => match core::str::from_utf8(found) {
+                Ok(utf8_found) => write!(
+                    f,
+                    "{NOT_A_PNG_MSG}. Signature was: `{found:?}`. (UTF-8: `{utf8_found}`)"
+                ),
+                Err(_) => write!(
+                    f,
+                    "{NOT_A_PNG_MSG}. Signature was: `{found:?}`. (Not valid UTF-8.)`"
+                ),
+            },
+        }
+    }
+}
+
+impl core::error::Error for PngConstructionError {}
+
+/// An error from reading a PNG text chunk.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Hash)]
+pub enum PngTextError {
+    /// The chunk was missing a required field, like its keyword.
+    Malformed,
+
+    /// The chunk used a compression method other than zlib (`0`).
+    UnknownCompressionMethod(u8),
+
+    /// The compressed text couldn't be inflated.
+    Inflate,
+
+    /// Text that should be UTF-8 wasn't.
+    NotUtf8,
+}
+
+impl core::fmt::Display for PngTextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PngTextError::Malformed => f.write_str("The text chunk was malformed."),
+            PngTextError::UnknownCompressionMethod(method) => {
+                write!(
+                    f,
+                    "The text chunk used an unknown compression method: `{method}`"
+                )
+            }
+            PngTextError::Inflate => f.write_str("Failed to inflate compressed text."),
+            PngTextError::NotUtf8 => f.write_str("The text chunk's text wasn't UTF-8."),
+        }
+    }
+}
+
+impl core::error::Error for PngTextError {}
@@ -0,0 +1,881 @@
+//! Contains a metadata provider for the PNG format.
+
+use crate::{
+    MetadataProvider,
+    exif::{Exif, error::ExifFatalError},
+    iptc::{Iptc, error::IptcError},
+    providers::{
+        animation_info::AnimationInfo,
+        image_info::{ColorType, ImageInfo},
+    },
+    xmp::{Xmp, error::XmpError},
+};
+use winnow::{
+    binary::{be_u32, u8},
+    error::{ContextError, EmptyError, StrContext},
+    prelude::*,
+    token::{literal, take},
+};
+
+mod ancillary;
+mod animation;
+mod error;
+mod raw_profile;
+mod text;
+
+use animation::ApngChunks;
+
+pub use ancillary::{
+    PngAncillaryChunks, PngChromaticities, PngCicp, PngContentLightLevel, PngMasteringDisplay,
+    PngPhysicalDimensions, PngPhysicalUnit, PngRenderingIntent, PngTime,
+};
+pub use error::{PngConstructionError, PngTextError};
+pub use raw_profile::{RawProfile, RawProfileKind};
+pub use text::{PngText, PngTextKind};
+
+/// A signature indicating that a file is a PNG.
+pub const PNG_SIGNATURE: &[u8; 8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// PNG, or the Portable Network Graphics format, is a common image format as
+/// of writing.
+///
+/// It can store all three supported metadata standards directly in the file.
+#[derive(Clone, Debug)]
+pub struct Png {
+    exif: Option<Result<Exif, ExifFatalError>>,
+    iptc: Option<Result<Iptc, IptcError>>,
+    xmp: Option<Result<Xmp, XmpError>>,
+    image_info: Option<ImageInfo>,
+    text: Vec<PngText>,
+    raw_profiles: Vec<RawProfile>,
+    exif_origin: Option<PngMetadataOrigin>,
+    iptc_origin: Option<PngMetadataOrigin>,
+    xmp_origin: Option<PngMetadataOrigin>,
+    ancillary: PngAncillaryChunks,
+    animation_info: AnimationInfo,
+}
+
+/// Where a PNG's Exif, IPTC, or XMP was stored.
+///
+/// Writers can use this to move legacy raw profiles into `eXIf`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PngMetadataOrigin {
+    /// The standard place: an `eXIf` chunk for Exif, or an `iTXt` chunk with
+    /// the `XML:com.adobe.xmp` keyword for XMP.
+    ///
+    /// PNG has no standard place for IPTC, so it's never from here.
+    Standard,
+
+    /// An ImageMagick-style raw profile. See [`RawProfile`].
+    RawProfile {
+        /// The text chunk's keyword, like `Raw profile type exif`.
+        keyword: String,
+    },
+}
+
+impl Png {
+    /// Returns every text chunk (`tEXt`, `zTXt`, and `iTXt`) in the file, in
+    /// order.
+    ///
+    /// The XMP packet is in an `iTXt` chunk, so it's listed here as well.
+    pub fn text(&self) -> &[PngText] {
+        &self.text
+    }
+
+    /// Finds the first text chunk with the given keyword.
+    ///
+    /// Keywords are case-sensitive, like `Title` or `parameters`.
+    pub fn find_text(&self, keyword: &str) -> Option<&PngText> {
+        self.text.iter().find(|t| t.keyword == keyword)
+    }
+
+    /// Returns every ImageMagick-style raw profile in the file, decoded.
+    ///
+    /// Exif and XMP profiles are also used for [`MetadataProvider::exif`] and
+    /// [`MetadataProvider::xmp`] when the standard chunks are missing. IPTC
+    /// profiles are used for [`MetadataProvider::iptc`].
+    pub fn raw_profiles(&self) -> &[RawProfile] {
+        &self.raw_profiles
+    }
+
+    /// Returns where the Exif came from, if there is any.
+    pub fn exif_origin(&self) -> Option<&PngMetadataOrigin> {
+        self.exif_origin.as_ref()
+    }
+
+    /// Returns where the IPTC came from, if there is any.
+    pub fn iptc_origin(&self) -> Option<&PngMetadataOrigin> {
+        self.iptc_origin.as_ref()
+    }
+
+    /// Returns where the XMP came from, if there is any.
+    pub fn xmp_origin(&self) -> Option<&PngMetadataOrigin> {
+        self.xmp_origin.as_ref()
+    }
+
+    /// Returns the typed ancillary chunks, like `pHYs` (DPI), `tIME`, and the
+    /// color space and HDR chunks.
+    pub fn ancillary(&self) -> &PngAncillaryChunks {
+        &self.ancillary
+    }
+}
+
+impl MetadataProvider for Png {
+    type ConstructionError = PngConstructionError;
+
+    fn magic_number(input: &[u8]) -> bool {
+        let mut input = input;
+        parse_signature(&mut input).is_ok()
+    }
+
+    fn new(
+        input: &impl AsRef<[u8]>,
+    ) -> Result<Self, <Self as MetadataProvider>::ConstructionError> {
+        let mut input = input.as_ref();
+
+        // check if this is actually a PNG
+        parse_signature(&mut input)?;
+        log::trace!("Found a PNG signature! Continuing with chunk parsing.");
+
+        // grab metadata by parsing chunks until we've found everything
+        let GetMetadata {
+            exif,
+            image_info,
+            text,
+            ancillary,
+            apng,
+        } = get_metadata(&mut input);
+
+        // xmp is stored in an `iTXt` chunk with a special keyword
+        let xmp = text
+            .iter()
+            .find(|t| {
+                matches!(t.kind, PngTextKind::InternationalText { .. }) && t.keyword == XMP_KEYWORD
+            })
+            .and_then(|t| match &t.text {
+                Ok(xmp) => Some(Xmp::new(xmp)),
+                Err(e) => {
+                    log::warn!("Failed to read XMP from `iTXt` chunk. err: {e}");
+                    None
+                }
+            });
+
+        // older files might use raw profiles instead
+        let raw_profiles: Vec<RawProfile> = text.iter().filter_map(RawProfile::new).collect();
+        let raw_profile = |kind: RawProfileKind| {
+            raw_profiles.iter().find(|p| p.kind == kind).map(|p| {
+                log::trace!("Using raw profile `{}` for {kind:?}.", p.keyword);
+                let origin = PngMetadataOrigin::RawProfile {
+                    keyword: p.keyword.clone(),
+                };
+                (p.data.as_slice(), origin)
+            })
+        };
+
+        let (exif, exif_origin) = match exif {
+            Some(mut exif) => (
+                Some(Exif::new(&mut exif)),
+                Some(PngMetadataOrigin::Standard),
+            ),
+            None => raw_profile(RawProfileKind::Exif)
+                .map(|(mut exif, origin)| (Some(Exif::new(&mut exif)), Some(origin)))
+                .unwrap_or_default(),
+        };
+
+        // there's no standard chunk for IPTC
+        let (iptc, iptc_origin) = raw_profile(RawProfileKind::Iptc)
+            .map(|(iptc, origin)| (Some(Iptc::new_iim(iptc)), Some(origin)))
+            .unwrap_or_default();
+
+        let (xmp, xmp_origin) = match xmp {
+            Some(xmp) => (Some(xmp), Some(PngMetadataOrigin::Standard)),
+            None => raw_profile(RawProfileKind::Xmp)
+                .map(|(xmp, origin)| (Some(Xmp::new_from_bytes(xmp)), Some(origin)))
+                .unwrap_or_default(),
+        };
+
+        // return any metadata we found inside this `self`...
+        Ok(Self {
+            exif,
+            iptc,
+            xmp,
+            image_info,
+            text,
+            raw_profiles,
+            exif_origin,
+            iptc_origin,
+            xmp_origin,
+            ancillary,
+            animation_info: apng.finish(),
+        })
+    }
+
+    fn exif(&self) -> Option<Result<&Exif, &ExifFatalError>> {
+        self.exif.as_ref().map(|r| r.as_ref())
+    }
+
+    fn iptc(&self) -> Option<Result<&Iptc, &IptcError>> {
+        self.iptc.as_ref().map(|r| r.as_ref())
+    }
+
+    fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
+        self.xmp.as_ref().map(|r| r.as_ref())
+    }
+
+    fn image_info(&self) -> Option<&ImageInfo> {
+        self.image_info.as_ref()
+    }
+
+    fn animation_info(&self) -> Option<&AnimationInfo> {
+        Some(&self.animation_info)
+    }
+}
+
+fn parse_signature(input: &mut &[u8]) -> Result<(), PngConstructionError> {
+    // grab the PNG signature - should be the first eight bytes.
+    //
+    // this ensures we're working with a PNG. if we don't find the signature,
+    // we'll immediately stop parsing
+    log::trace!("Attempting to parse out the PNG signature...");
+    let signature: &[u8; 8] = take(8_usize)
+        .parse_next(input)
+        .map(|s| TryInto::try_into(s).unwrap_or_else(|_| unreachable!()))
+        .map_err(|e: ContextError| {
+            log::warn!(
+                "This \"PNG\" didn't contain its required PNG signature. \
+                Is it actually a PNG..? \
+                err: {e}"
+            );
+            PngConstructionError::NoSignature
+        })?;
+
+    // ensure the signature is correct
+    parse_png_signature.parse(signature).map_err(|e| {
+        log::warn!(
+            "Signature obtained from given file did not match a PNG! \
+            err: {e}, \
+            found: `{signature:?}`
+            "
+        );
+        PngConstructionError::NotAPng { found: *signature }
+    })?;
+
+    Ok(())
+}
+
+/// A header for a PNG "chunk"
+struct PngChunkHeader {
+    pub chunk_length: u32,    // in bytes
+    pub chunk_ident: [u8; 4], // four ascii letters
+}
+
+/// Parses out the file's PNG signature.
+fn parse_png_signature(input: &mut &[u8]) -> ModalResult<(), ContextError> {
+    literal(PNG_SIGNATURE).void().parse_next(input)
+}
+
+/// Parses out a chunk's header.
+///
+/// These are the first eight bytes on each chunk.
+fn parse_chunk_header(input: &mut &[u8]) -> ModalResult<PngChunkHeader, ContextError> {
+    let chunk_length: u32 = be_u32
+        .context(StrContext::Label("chunk length"))
+        .parse_next(input)?;
+
+    let chunk_ident: [u8; 4] = take(4_usize)
+        .context(StrContext::Label("ASCII chunk identifier"))
+        .parse_next(input)?
+        .try_into()
+        .unwrap_or_else(|e| unreachable!("winnow already said this must be 4 bytes. but err: {e}"));
+
+    Ok(PngChunkHeader {
+        chunk_length,
+        chunk_ident,
+    })
+}
+
+struct GetMetadata<'input> {
+    exif: Option<&'input [u8]>,
+    image_info: Option<ImageInfo>,
+    text: Vec<PngText>,
+    ancillary: PngAncillaryChunks,
+    apng: ApngChunks,
+}
+
+/// The `eXif` chunk contains Exif data.
+///
+/// This value is its identifier.
+pub const EXIF_CHUNK_IDENT: [u8; 4] = *b"eXIf";
+
+/// The keyword of an `iTXt` chunk containing XMP.
+pub const XMP_KEYWORD: &str = "XML:com.adobe.xmp";
+
+/// Parses through the PNG chunks to find metadata.
+///
+/// Continues until we run out of chunks. Text chunks may come after the image
+/// data, so the whole file is checked.
+fn get_metadata<'input>(input: &mut &'input [u8]) -> GetMetadata<'input> {
+    let mut metadata: GetMetadata = GetMetadata {
+        exif: None,
+        image_info: None,
+        text: Vec::new(),
+        ancillary: PngAncillaryChunks::default(),
+        apng: ApngChunks::default(),
+    };
+
+    // loop until we're out of input
+    while !input.is_empty() {
+        // parse out chunk
+        let Ok(PngChunkHeader {
+            chunk_length,
+            chunk_ident,
+        }) = parse_chunk_header.parse_next(input)
+        else {
+            log::warn!("Failed to parse PNG chunk header!");
+            break;
+        };
+
+        // log what we got
+        log::trace!(
+            "Found chunk with ident: `{}`",
+            core::str::from_utf8(&chunk_ident).unwrap_or("not UTF-8")
+        );
+
+        // grab the payload and skip the crc
+        let Ok::<_, EmptyError>(payload) = take(chunk_length).parse_next(input) else {
+            log::warn!(
+                "Couldn't find enough data inside chunk! expected: `{chunk_length}`, got: `{}`",
+                input.len()
+            );
+            break;
+        };
+        if take::<_, _, EmptyError>(4_usize)
+            .void()
+            .parse_next(input)
+            .is_err()
+        {
+            log::warn!("Chunk was missing its CRC.");
+        }
+
+        match &chunk_ident {
+            // image header
+            b"IHDR" => metadata.image_info = parse_ihdr(payload),
+
+            // animation control (APNG), which has the frame count
+            b"acTL" => {
+                metadata.apng.animation_control(payload);
+                if let Some(info) = metadata.image_info.as_mut()
+                    && let Ok::<_, EmptyError>((_, num_frames)) = be_u32.parse_peek(payload)
+                {
+                    log::trace!("Found APNG animation control chunk!");
+                    info.frame_count = Some(num_frames);
+                }
+            }
+
+            // frame control (APNG), which has each frame's delay
+            b"fcTL" => metadata.apng.frame_control(payload),
+
+            // metadata: exif
+            ident if *ident == EXIF_CHUNK_IDENT => {
+                log::trace!("Chunk had Exif data!");
+                if metadata.exif.is_none() {
+                    metadata.exif = Some(payload);
+                } else {
+                    log::warn!("Found more than one `eXIf` chunk. Ignoring it.");
+                }
+            }
+
+            // typed ancillary chunks
+            b"tIME" | b"pHYs" | b"sRGB" | b"gAMA" | b"cHRM" | b"cICP" | b"mDCv" | b"cLLi"
+            | b"sBIT" => metadata.ancillary.read(&chunk_ident, payload),
+
+            // text, including xmp
+            b"tEXt" | b"zTXt" | b"iTXt" => {
+                let text = match &chunk_ident {
+                    b"tEXt" => PngText::text(payload),
+                    b"zTXt" => PngText::compressed_text(payload),
+                    _ => PngText::international_text(payload),
+                };
+
+                match text {
+                    Ok(text) => metadata.text.push(text),
+                    Err(e) => log::warn!("Failed to parse PNG text chunk. err: {e}"),
+                }
+            }
+
+            _ => (),
+        }
+    }
+
+    metadata
+}
+
+/// Reads the `IHDR` (image header) chunk's payload.
+fn parse_ihdr(mut input: &[u8]) -> Option<ImageInfo> {
+    let (width, height, bit_depth, color_type) = (be_u32, be_u32, u8, u8)
+        .parse_next(&mut input)
+        .map_err(|_: EmptyError| log::warn!("`IHDR` chunk was too short."))
+        .ok()?;
+
+    let (color_type, channels) = match color_type {
+        0 => (Some(ColorType::Grayscale), Some(1)),
+        2 => (Some(ColorType::Rgb), Some(3)),
+        3 => (Some(ColorType::Indexed), Some(1)),
+        4 => (Some(ColorType::GrayscaleAlpha), Some(2)),
+        6 => (Some(ColorType::Rgba), Some(4)),
+        other => {
+            log::warn!("Unknown PNG color type: `{other}`");
+            (None, None)
+        }
+    };
+
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth: Some(bit_depth),
+        color_type,
+        channels,
+        frame_count: Some(1),
+        jpeg: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use raves_metadata_types::{
+        exif::{
+            Field, FieldData, FieldTag,
+            primitives::{Primitive, Rational},
+            tags::{Ifd0Tag, KnownTag},
+        },
+        iptc::IptcKeyValue,
+        xmp::{XmpElement, XmpValue},
+    };
+
+    use crate::{
+        MetadataProvider as _,
+        providers::{
+            animation_info::LoopCount,
+            image_info::{ColorType, ImageInfo},
+            png::{Png, PngMetadataOrigin, PngTextKind},
+        },
+        util::logger,
+    };
+
+    /// Makes a chunk with a fake CRC, which we don't check.
+    fn chunk(ident: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        [
+            (payload.len() as u32).to_be_bytes().as_slice(),
+            ident,
+            payload,
+            &[0; 4],
+        ]
+        .concat()
+    }
+
+    /// Checks that we can parse out a PNG signature.
+    #[test]
+    fn png_signature_parsing() {
+        _ = env_logger::builder()
+            .filter_level(log::LevelFilter::max())
+            .format_file(true)
+            .format_line_number(true)
+            .try_init();
+
+        assert_eq!(
+            Ok(()),
+            super::parse_png_signature(
+                &mut [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a].as_slice()
+            ),
+            "we should successfully parse a PNG signature",
+        )
+    }
+
+    /// Ensures that we can parse out some XMP from a PNG.
+    #[test]
+    fn png_containing_xmp_parses_correctly() {
+        _ = env_logger::builder()
+            .filter_level(log::LevelFilter::max())
+            .format_file(true)
+            .format_line_number(true)
+            .try_init();
+
+        #[rustfmt::skip]
+        let technically_a_png: Vec<u8> = [
+            // png signature
+           [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a].as_slice(),
+
+            // required: `IHDR` (image header)
+            [
+
+                0x0, 0x0, 0x0, 0xD,    // data length
+                b'I', b'H', b'D', b'R', // type
+                0x0, 0x0, 0xA, 0x0,     // res: width (2560_u32)
+                0x0, 0x0, 0x5, 0xA0,    // res: height (1440_u32)
+                8,                      // bit depth (8)
+                6,                      // color type (truecolor w/ alpha)
+                0, 0, 0,                // compression, filter, interlace (all off)
+                0xB3, 0xE4, 0x34, 0x52, // CRC32
+            ].as_slice(),
+
+            // a junk `iTXt` that we don't care about
+            [
+                0x0, 0x0, 0x0, 0x1D, // data length
+                b'i', b'T', b'X', b't', // type
+                b'S', b'o', b'f', b't', b'w', b'a', b'r', b'e', b'\0', // keyword
+                0x00, 0x00, // compression (off)
+                b'e', b'n', b'-', b'U', b'S', b'\0', // language tag
+                b'S', b'o', b'f', b't', b'w', b'a', b'r', b'e', b'\0', // translated keyword
+                b'H', b'i', b'!', // text
+                0x69, 0x5C, 0x21, 0xB2, // CRC
+            ].as_slice(),
+
+            // a good `iTXt` with useful data
+            [
+                // header + data up to XML
+                [
+                    0x0, 0x0, 0x1, 0x67, // data length (363_u32)
+                    b'i', b'T', b'X', b't', // type
+                    b'X', b'M', b'L', b':', b'c', b'o', b'm', b'.', b'a', b'd', b'o', b'b', b'e', b'.', b'x', b'm', b'p', b'\0', // keyword
+                    b'\0', // language tag (none)
+                    b'\0', // translated keyword (none)
+                    0x00, 0x00, // compression (off)
+                ].as_slice(),
+
+                // XMP UTF-8 data as a byte slice
+                r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+                    <rdf:Description rdf:about="" xmlns:my_ns="https://barretts.club">
+                    <my_ns:MyStruct>
+                        <rdf:Description />
+                    </my_ns:MyStruct>
+                    </rdf:Description>
+                </rdf:RDF>"#.as_bytes(),
+
+                [0xDC, 0xFD, 0x6E, 0x88].as_slice(), // CRC32
+            ]
+            .into_iter()
+            .flat_map(|sli| sli.iter().copied())
+            .collect::<Vec<_>>()
+            .as_slice(),
+
+            // required: `IEND`
+            [0x49, 0x45, 0x4E, 0x44].as_slice(),
+        ]
+        .into_iter()
+        .flat_map(|sli| sli.iter().copied())
+        .collect();
+
+        // with that all over, we can actually run the test ;D
+        let png: Png = Png::new(&technically_a_png).expect("is a png");
+
+        // both `iTXt` chunks are listed as text
+        assert_eq!(png.text().len(), 2);
+        let software = png.find_text("Software").expect("has junk `iTXt`");
+        assert_eq!(software.language.as_deref(), Some("en-US"));
+        assert_eq!(software.text, Ok("Hi!".into()));
+
+        let xmp = png
+            .xmp()
+            .expect("this PNG has XMP")
+            .expect("get XMP from PNG");
+
+        assert_eq!(
+            xmp.document().values_ref().len(),
+            1_usize,
+            "should only parse that one struct"
+        );
+        assert_eq!(
+            xmp.document()
+                .values_ref()
+                .first()
+                .expect("must have an item"),
+            &XmpElement {
+                namespace: "https://barretts.club".into(),
+                prefix: "my_ns".into(),
+                name: "MyStruct".into(),
+                value: XmpValue::Struct(Vec::new()),
+            },
+            "found struct should match the expected (right) side"
+        )
+    }
+
+    /// Tests parsing out Exif from a 64x64 PNG file taken on my macbook.
+    #[test]
+    fn blank_sample_with_exif() {
+        logger();
+        const BLOB: &[u8] = include_bytes!("../../../assets/providers/png/exif.png");
+
+        let png: Png = Png::new(&BLOB).expect("parse PNG");
+
+        let exif = png
+            .exif()
+            .expect("PNG contains Exif")
+            .expect("Exif is well-formed");
+
+        let a = exif.ifds.first().unwrap();
+
+        let expected_field_tag = FieldTag::Known(KnownTag::Ifd0Tag(Ifd0Tag::XResolution));
+        assert_eq!(
+            *a.fields
+                .iter()
+                .flatten()
+                .find(|f| f.tag == expected_field_tag)
+                .expect("find xres field"),
+            Field {
+                tag: expected_field_tag,
+                data: FieldData::Primitive(Primitive::Rational(Rational {
+                    numerator: 144,
+                    denominator: 1
+                }))
+            }
+        )
+    }
+
+    /// Reads the image header from the same file.
+    #[test]
+    fn blank_sample_image_info() {
+        logger();
+        const BLOB: &[u8] = include_bytes!("../../../assets/providers/png/exif.png");
+
+        let png: Png = Png::new(&BLOB).expect("parse PNG");
+
+        assert_eq!(
+            png.image_info(),
+            Some(&ImageInfo {
+                width: 64,
+                height: 64,
+                bit_depth: Some(8),
+                color_type: Some(ColorType::Rgba),
+                channels: Some(4),
+                frame_count: Some(1),
+                jpeg: None,
+            })
+        );
+    }
+
+    /// Text chunks, including compressed XMP, can be anywhere in the file.
+    #[test]
+    fn compressed_text_and_xmp() {
+        logger();
+
+        const XMP: &str = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+            <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/" dc:format="image/png" />
+        </rdf:RDF>"#;
+
+        let compressed_xmp: Vec<u8> = miniz_oxide::deflate::compress_to_vec_zlib(XMP.as_bytes(), 6);
+        let compressed_comment: Vec<u8> = miniz_oxide::deflate::compress_to_vec_zlib(b"a cat", 6);
+
+        let file: Vec<u8> = [
+            super::PNG_SIGNATURE.as_slice(),
+            &chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]),
+            &chunk(b"tEXt", b"Title\0Cat"),
+            &chunk(b"IDAT", &[]),
+            &chunk(
+                b"iTXt",
+                &[b"XML:com.adobe.xmp\0\x01\0\0\0".as_slice(), &compressed_xmp].concat(),
+            ),
+            &chunk(
+                b"zTXt",
+                &[b"Comment\0\0".as_slice(), &compressed_comment].concat(),
+            ),
+            &chunk(b"IEND", &[]),
+        ]
+        .concat();
+
+        let png: Png = Png::new(&file).expect("parse PNG");
+
+        let texts: Vec<(&str, PngTextKind)> = png
+            .text()
+            .iter()
+            .map(|t| (t.keyword.as_str(), t.kind))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                ("Title", PngTextKind::Text),
+                (
+                    "XML:com.adobe.xmp",
+                    PngTextKind::InternationalText { compressed: true }
+                ),
+                ("Comment", PngTextKind::CompressedText),
+            ]
+        );
+        assert_eq!(
+            png.find_text("Comment").map(|t| t.text.clone()),
+            Some(Ok("a cat".into()))
+        );
+
+        let xmp = png.xmp().expect("has XMP").expect("XMP is well-formed");
+        assert_eq!(xmp.document().values_ref().len(), 1);
+    }
+
+    /// Ancillary chunks before and after `IDAT` are both read.
+    #[test]
+    fn ancillary_chunks() {
+        logger();
+
+        let file: Vec<u8> = [
+            super::PNG_SIGNATURE.as_slice(),
+            &chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 0]),
+            &chunk(b"cICP", &[9, 18, 0, 1]),
+            &chunk(b"pHYs", &[0, 0, 0x2E, 0x23, 0, 0, 0x2E, 0x23, 1]),
+            &chunk(b"IDAT", &[]),
+            &chunk(b"tIME", &[0x07, 0xEA, 10, 18, 12, 0, 0]),
+            &chunk(b"IEND", &[]),
+        ]
+        .concat();
+
+        let png: Png = Png::new(&file).expect("parse PNG");
+        let ancillary = png.ancillary();
+
+        assert!(ancillary.cicp.is_some_and(|c| c.is_hlg()));
+        let (x, y) = ancillary
+            .physical_dimensions
+            .and_then(|p| p.dpi())
+            .expect("has DPI");
+        assert_eq!((x.round(), y.round()), (300.0, 300.0));
+        assert_eq!(ancillary.time.map(|t| (t.year, t.month)), Some((2026, 10)));
+        assert!(ancillary.gamma.is_none());
+    }
+
+    /// APNG frame delays come from each `fcTL` chunk.
+    #[test]
+    fn apng_animation_info() {
+        logger();
+
+        // sequence number, size, offset, then the delay and disposal
+        let frame_control = |sequence: u32, delay_num: u16, delay_den: u16| {
+            [
+                [sequence, 1, 1, 0, 0].map(u32::to_be_bytes).concat(),
+                delay_num.to_be_bytes().to_vec(),
+                delay_den.to_be_bytes().to_vec(),
+                vec![0, 0],
+            ]
+            .concat()
+        };
+
+        let file: Vec<u8> = [
+            super::PNG_SIGNATURE.as_slice(),
+            &chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]),
+            &chunk(b"acTL", &[0, 0, 0, 2, 0, 0, 0, 3]),
+            &chunk(b"fcTL", &frame_control(0, 1, 4)),
+            &chunk(b"IDAT", &[]),
+            &chunk(b"fcTL", &frame_control(1, 50, 0)),
+            &chunk(b"fdAT", &[0, 0, 0, 2]),
+            &chunk(b"IEND", &[]),
+        ]
+        .concat();
+
+        let png: Png = Png::new(&file).expect("parse PNG");
+        let animation = png.animation_info().expect("PNGs have animation info");
+
+        assert!(animation.is_animated());
+        assert_eq!(
+            animation.frame_delays,
+            [Duration::from_millis(250), Duration::from_millis(500)]
+        );
+        assert_eq!(animation.total_duration(), Duration::from_millis(750));
+        assert_eq!(animation.loop_count, LoopCount::Finite(3));
+    }
+
+    /// ImageMagick writes Exif as a hex "raw profile" in a `zTXt` chunk.
+    #[test]
+    fn raw_profile_exif() {
+        logger();
+
+        // a big-endian TIFF header, then an IFD with `Orientation` (6)
+        #[rustfmt::skip]
+        let tiff: &[u8] = &[
+            b'M', b'M', 0, 42, 0, 0, 0, 8,
+            0, 1,
+            0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0,
+            0, 0, 0, 0,
+        ];
+        let hex: String = [b"Exif\0\0".as_slice(), tiff]
+            .concat()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        let profile: String = format!("\nexif\n      32\n{hex}\n");
+        let compressed: Vec<u8> = miniz_oxide::deflate::compress_to_vec_zlib(profile.as_bytes(), 6);
+
+        let file: Vec<u8> = [
+            super::PNG_SIGNATURE.as_slice(),
+            &chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]),
+            &chunk(
+                b"zTXt",
+                &[b"Raw profile type exif\0\0".as_slice(), &compressed].concat(),
+            ),
+            &chunk(b"IEND", &[]),
+        ]
+        .concat();
+
+        let png: Png = Png::new(&file).expect("parse PNG");
+
+        assert_eq!(png.raw_profiles().len(), 1);
+        assert_eq!(
+            png.exif_origin(),
+            Some(&PngMetadataOrigin::RawProfile {
+                keyword: "Raw profile type exif".into()
+            })
+        );
+        let exif = png.exif().expect("has Exif").expect("Exif is well-formed");
+        assert_eq!(exif.ifds.len(), 1);
+
+        // PNGs use Exif `Orientation`, which is 6 here
+        assert_eq!(png.display_orientation().map(|o| o.to_exif()), Some(6));
+
+        assert!(png.xmp().is_none());
+        assert!(png.xmp_origin().is_none());
+    }
+
+    /// IPTC raw profiles are IIM, usually in a Photoshop resource block.
+    #[test]
+    fn raw_profile_iptc() {
+        logger();
+
+        // an `Object Name` dataset in resource `0x0404`
+        let iim: &[u8] = b"\x1C\x02\x05\x00\x03Cat";
+        let block: Vec<u8> = [
+            b"8BIM\x04\x04\0\0".as_slice(),
+            &(iim.len() as u32).to_be_bytes(),
+            iim,
+        ]
+        .concat();
+        let hex: String = block.iter().map(|b| format!("{b:02x}")).collect();
+        let profile: String = format!("\niptc\n      {}\n{hex}\n", block.len());
+
+        let file: Vec<u8> = [
+            super::PNG_SIGNATURE.as_slice(),
+            &chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]),
+            &chunk(
+                b"tEXt",
+                &[b"Raw profile type iptc\0".as_slice(), profile.as_bytes()].concat(),
+            ),
+            &chunk(b"IEND", &[]),
+        ]
+        .concat();
+
+        let png: Png = Png::new(&file).expect("parse PNG");
+        assert_eq!(
+            png.iptc_origin(),
+            Some(&PngMetadataOrigin::RawProfile {
+                keyword: "Raw profile type iptc".into()
+            })
+        );
+        let iptc = png.iptc().expect("has IPTC").expect("IPTC is well-formed");
+        assert_eq!(iptc.pairs, [IptcKeyValue::Title("Cat".into())]);
+    }
+
+    /// Standard chunks are used before raw profiles.
+    #[test]
+    fn standard_exif_origin() {
+        logger();
+        const BLOB: &[u8] = include_bytes!("../../../assets/providers/png/exif.png");
+
+        let png: Png = Png::new(&BLOB).expect("parse PNG");
+        assert_eq!(png.exif_origin(), Some(&PngMetadataOrigin::Standard));
+    }
+}
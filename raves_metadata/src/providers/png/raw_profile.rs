@@ -0,0 +1,182 @@
+//! ImageMagick-style "raw profiles", stored in PNG text chunks.
+//!
+//! Before `eXIf` existed, ImageMagick (and tools copying it) stored metadata
+//! as hex in a text chunk, usually `zTXt`, with a keyword like
+//! `Raw profile type exif`. The text looks like this:
+//!
+//! ```text
+//!
+//! exif
+//!       42
+//! 457869660000...
+//! ```
+//!
+//! That's a newline, the profile name, the decoded length (in bytes), and then
+//! hex digits split into lines.
+
+use super::text::PngText;
+
+/// The prefix of every raw profile's keyword.
+const KEYWORD_PREFIX: &str = "Raw profile type ";
+
+/// What a raw profile contains.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RawProfileKind {
+    /// Exif, from an `exif` profile or an `APP1` profile starting with
+    /// `Exif\0\0`.
+    Exif,
+
+    /// IPTC, usually IIM inside a Photoshop resource block (`8BIM`).
+    Iptc,
+
+    /// XMP, from an `xmp` profile or an `APP1` profile with the XMP
+    /// signature.
+    Xmp,
+
+    /// An ICC color profile, from an `icc` or `icm` profile.
+    Icc,
+
+    /// Any other profile, with its name from the keyword.
+    Other(String),
+}
+
+/// A decoded raw profile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawProfile {
+    /// What the profile contains.
+    pub kind: RawProfileKind,
+
+    /// The text chunk's keyword, like `Raw profile type exif`.
+    pub keyword: String,
+
+    /// The decoded profile.
+    ///
+    /// Signatures (like `Exif\0\0`) are removed for Exif and XMP.
+    pub data: Vec<u8>,
+}
+
+impl RawProfile {
+    /// Decodes a raw profile from a text chunk.
+    ///
+    /// Returns `None` if the chunk isn't a raw profile, or if it's malformed.
+    pub(super) fn new(text: &PngText) -> Option<Self> {
+        let name: &str = text.keyword.strip_prefix(KEYWORD_PREFIX)?;
+        let Ok(hex) = text.text.as_ref() else {
+            log::warn!("Raw profile `{name}` had no usable text.");
+            return None;
+        };
+
+        let data: Vec<u8> = decode(hex)
+            .inspect(|_| log::trace!("Decoded raw profile `{name}`."))
+            .or_else(|| {
+                log::warn!("Raw profile `{name}` was malformed.");
+                None
+            })?;
+
+        const EXIF_SIG: &[u8] = b"Exif\0\0";
+        const XMP_SIG: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+        let (kind, data): (RawProfileKind, Vec<u8>) = match name.to_ascii_lowercase().as_str() {
+            "exif" | "app1" if data.starts_with(EXIF_SIG) => {
+                (RawProfileKind::Exif, data[EXIF_SIG.len()..].to_vec())
+            }
+            "exif" => (RawProfileKind::Exif, data),
+            "app1" if data.starts_with(XMP_SIG) => {
+                (RawProfileKind::Xmp, data[XMP_SIG.len()..].to_vec())
+            }
+            "xmp" => (RawProfileKind::Xmp, data),
+            "iptc" | "8bim" => (RawProfileKind::Iptc, data),
+            "icc" | "icm" => (RawProfileKind::Icc, data),
+            _ => (RawProfileKind::Other(name.into()), data),
+        };
+
+        Some(Self {
+            kind,
+            keyword: text.keyword.clone(),
+            data,
+        })
+    }
+}
+
+/// Decodes a raw profile's text.
+fn decode(text: &str) -> Option<Vec<u8>> {
+    let mut words = text.split_ascii_whitespace();
+
+    // the name is repeated here, then the length
+    let _name: &str = words.next()?;
+    let len: usize = words.next()?.parse().ok()?;
+
+    // the rest is hex, split into lines
+    let mut hex = words.flat_map(str::bytes);
+    let mut data: Vec<u8> = Vec::with_capacity(len.min(text.len() / 2));
+    while data.len() < len {
+        let high: u8 = hex_digit(hex.next()?)?;
+        let low: u8 = hex_digit(hex.next()?)?;
+        data.push((high << 4) | low);
+    }
+
+    Some(data)
+}
+
+/// Converts one ASCII hex digit into its value.
+fn hex_digit(digit: u8) -> Option<u8> {
+    char::from(digit)
+        .to_digit(16)
+        .and_then(|d| u8::try_from(d).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RawProfile, RawProfileKind};
+    use crate::providers::png::{PngText, PngTextKind};
+
+    fn text(keyword: &str, text: &str) -> PngText {
+        PngText {
+            kind: PngTextKind::CompressedText,
+            keyword: keyword.into(),
+            language: None,
+            translated_keyword: None,
+            text: Ok(text.into()),
+        }
+    }
+
+    #[test]
+    fn exif_profile() {
+        let profile = RawProfile::new(&text(
+            "Raw profile type exif",
+            "\nexif\n      10\n457869660000\n4d4d002a\n",
+        ))
+        .unwrap();
+
+        assert_eq!(profile.kind, RawProfileKind::Exif);
+        assert_eq!(profile.data, b"MM\0*");
+    }
+
+    #[test]
+    fn app1_xmp_profile() {
+        let hex: String = b"http://ns.adobe.com/xap/1.0/\0<x/>"
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        let profile = RawProfile::new(&text(
+            "Raw profile type APP1",
+            &format!("\nAPP1\n 33\n{hex}"),
+        ))
+        .unwrap();
+
+        assert_eq!(profile.kind, RawProfileKind::Xmp);
+        assert_eq!(profile.data, b"<x/>");
+    }
+
+    #[test]
+    fn truncated_profile() {
+        assert_eq!(
+            RawProfile::new(&text("Raw profile type icc", "\nicc\n 4\nabcd\n")),
+            None
+        );
+    }
+
+    #[test]
+    fn not_a_profile() {
+        assert_eq!(RawProfile::new(&text("Comment", "\nexif\n 1\n00")), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! PNG's textual chunks: `tEXt`, `zTXt`, and `iTXt`.
+//!
+//! Each one holds a keyword and some text. Common keywords include `Title`,
+//! `Author`, `Description`, `Software`, and `Creation Time`, though tools
+//! can use their own (like `parameters` for AI image generators).
+//!
+//! - `tEXt` is uncompressed Latin-1 text.
+//! - `zTXt` is compressed Latin-1 text.
+//! - `iTXt` is UTF-8 text, optionally compressed, with a language tag and a
+//!   translated keyword.
+
+use winnow::{
+    Parser as _,
+    binary::u8,
+    error::EmptyError,
+    token::{rest, take_until},
+};
+
+use super::error::PngTextError;
+
+/// The largest text we'll inflate from a compressed chunk.
+///
+/// This stops small, malicious chunks from using lots of memory.
+const MAX_INFLATED_LEN: usize = 64 * 1024 * 1024;
+
+/// Which chunk a [`PngText`] came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PngTextKind {
+    /// `tEXt`: uncompressed Latin-1 text.
+    Text,
+
+    /// `zTXt`: compressed Latin-1 text.
+    CompressedText,
+
+    /// `iTXt`: UTF-8 text, which may be compressed.
+    InternationalText {
+        /// Whether the text was compressed.
+        compressed: bool,
+    },
+}
+
+/// One textual chunk from a PNG file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PngText {
+    /// The chunk this text came from.
+    pub kind: PngTextKind,
+
+    /// The keyword, like `Title` or `Author`.
+    pub keyword: String,
+
+    /// The text's language, as a tag like `en-US`.
+    ///
+    /// Only `iTXt` chunks have this, and it may be empty.
+    pub language: Option<String>,
+
+    /// The keyword, translated into `language`.
+    ///
+    /// Only `iTXt` chunks have this, and it may be empty.
+    pub translated_keyword: Option<String>,
+
+    /// The decoded (and, if needed, inflated) text.
+    pub text: Result<String, PngTextError>,
+}
+
+impl PngText {
+    /// Parses the payload of a `tEXt` chunk.
+    pub(super) fn text(mut input: &[u8]) -> Result<Self, PngTextError> {
+        let keyword: String = keyword(&mut input)?;
+
+        Ok(Self {
+            kind: PngTextKind::Text,
+            keyword,
+            language: None,
+            translated_keyword: None,
+            text: Ok(latin_1(input)),
+        })
+    }
+
+    /// Parses the payload of a `zTXt` chunk.
+    pub(super) fn compressed_text(mut input: &[u8]) -> Result<Self, PngTextError> {
+        let keyword: String = keyword(&mut input)?;
+        let compression_method: u8 = u8
+            .parse_next(&mut input)
+            .map_err(|_: EmptyError| PngTextError::Malformed)?;
+
+        Ok(Self {
+            kind: PngTextKind::CompressedText,
+            keyword,
+            language: None,
+            translated_keyword: None,
+            text: inflate(compression_method, input).map(|text| latin_1(&text)),
+        })
+    }
+
+    /// Parses the payload of an `iTXt` chunk.
+    pub(super) fn international_text(mut input: &[u8]) -> Result<Self, PngTextError> {
+        let input = &mut input;
+
+        let keyword: String = keyword(input)?;
+        let (compression_flag, compression_method) = (u8, u8)
+            .parse_next(input)
+            .map_err(|_: EmptyError| PngTextError::Malformed)?;
+        let language: String = null_terminated(input).map(latin_1)?;
+        let translated_keyword: String = null_terminated(input).and_then(utf_8)?;
+        let compressed: bool = compression_flag != 0;
+
+        let text: &[u8] = rest::<_, EmptyError>
+            .parse_next(input)
+            .map_err(|_| PngTextError::Malformed)?;
+        let text: Result<String, PngTextError> = if compressed {
+            inflate(compression_method, text).and_then(|text| utf_8(&text))
+        } else {
+            utf_8(text)
+        };
+
+        Ok(Self {
+            kind: PngTextKind::InternationalText { compressed },
+            keyword,
+            language: Some(language),
+            translated_keyword: Some(translated_keyword),
+            text,
+        })
+    }
+}
+
+/// Takes a null-terminated keyword.
+///
+/// Keywords are Latin-1, from one to 79 bytes long.
+fn keyword(input: &mut &[u8]) -> Result<String, PngTextError> {
+    let keyword: &[u8] = null_terminated(input)?;
+
+    if keyword.is_empty() || keyword.len() > 79 {
+        log::warn!(
+            "PNG text keyword had an invalid length: `{}`",
+            keyword.len()
+        );
+        return Err(PngTextError::Malformed);
+    }
+
+    Ok(latin_1(keyword))
+}
+
+/// Takes bytes until a `NUL`, then eats the `NUL`.
+fn null_terminated<'input>(input: &mut &'input [u8]) -> Result<&'input [u8], PngTextError> {
+    let (value, _nul) = (take_until(0.., 0_u8), u8)
+        .parse_next(input)
+        .map_err(|_: EmptyError| PngTextError::Malformed)?;
+    Ok(value)
+}
+
+/// Decodes Latin-1 (ISO/IEC 8859-1) text.
+///
+/// Each byte is the same as its Unicode code point, so this can't fail.
+fn latin_1(input: &[u8]) -> String {
+    input.iter().copied().map(char::from).collect()
+}
+
+/// Decodes UTF-8 text.
+fn utf_8(input: &[u8]) -> Result<String, PngTextError> {
+    String::from_utf8(input.to_vec()).map_err(|_| PngTextError::NotUtf8)
+}
+
+/// Inflates a zlib stream.
+///
+/// PNG only defines compression method `0`, which is zlib's deflate.
+pub(super) fn inflate(compression_method: u8, input: &[u8]) -> Result<Vec<u8>, PngTextError> {
+    if compression_method != 0 {
+        log::warn!("Unknown PNG compression method: `{compression_method}`");
+        return Err(PngTextError::UnknownCompressionMethod(compression_method));
+    }
+
+    miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(input, MAX_INFLATED_LEN).map_err(|e| {
+        log::warn!("Failed to inflate PNG text. err: {e}");
+        PngTextError::Inflate
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PngText, PngTextKind};
+    use crate::providers::png::error::PngTextError;
+
+    /// `Hello, world!` as a zlib stream.
+    const HELLO_ZLIB: &[u8] = &[
+        0x78, 0x9C, 0xF3, 0x48, 0xCD, 0xC9, 0xC9, 0xD7, 0x51, 0x28, 0xCF, 0x2F, 0xCA, 0x49, 0x51,
+        0x04, 0x00, 0x20, 0x5E, 0x04, 0x8A,
+    ];
+
+    #[test]
+    fn latin_1_text() {
+        let text = PngText::text(b"Author\0Ren\xE9e").unwrap();
+
+        assert_eq!(text.kind, PngTextKind::Text);
+        assert_eq!(text.keyword, "Author");
+        assert_eq!(text.text, Ok("Renée".into()));
+    }
+
+    #[test]
+    fn compressed_text() {
+        let payload = [b"Comment\0\0".as_slice(), HELLO_ZLIB].concat();
+        let text = PngText::compressed_text(&payload).unwrap();
+
+        assert_eq!(text.keyword, "Comment");
+        assert_eq!(text.text, Ok("Hello, world!".into()));
+    }
+
+    #[test]
+    fn compressed_international_text() {
+        let payload = [b"Title\0\x01\0fr\0Titre\0".as_slice(), HELLO_ZLIB].concat();
+        let text = PngText::international_text(&payload).unwrap();
+
+        assert_eq!(
+            text.kind,
+            PngTextKind::InternationalText { compressed: true }
+        );
+        assert_eq!(text.language.as_deref(), Some("fr"));
+        assert_eq!(text.translated_keyword.as_deref(), Some("Titre"));
+        assert_eq!(text.text, Ok("Hello, world!".into()));
+    }
+
+    #[test]
+    fn bad_compression_method() {
+        let payload = [b"Comment\0\x07".as_slice(), HELLO_ZLIB].concat();
+        let text = PngText::compressed_text(&payload).unwrap();
+
+        assert_eq!(text.text, Err(PngTextError::UnknownCompressionMethod(7)));
+    }
+
+    #[test]
+    fn missing_keyword_terminator() {
+        assert_eq!(
+            PngText::text(b"no terminator here"),
+            Err(PngTextError::Malformed)
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! `LIST/INFO` tags, from RIFF files like AVI and WAV.
+//!
+//! The `INFO` list holds one chunk per tag, like `INAM` (the title), each
+//! with a NUL-terminated string.
+//!
+//! See the Multimedia Programming Interface and Data Specifications 1.0,
+//! section 2-14.
+
+use crate::providers::shared::riff;
+
+/// The title.
+const NAME: [u8; 4] = *b"INAM";
+
+/// The artist.
+const ARTIST: [u8; 4] = *b"IART";
+
+/// A comment.
+const COMMENT: [u8; 4] = *b"ICMT";
+
+/// When the file was made, like `2025-06-01`.
+const CREATION_DATE: [u8; 4] = *b"ICRD";
+
+/// The software that wrote the file.
+const SOFTWARE: [u8; 4] = *b"ISFT";
+
+/// The copyright notice.
+const COPYRIGHT: [u8; 4] = *b"ICOP";
+
+/// The tags in a RIFF file's `LIST/INFO` chunk.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RiffInfo {
+    /// Each tag's chunk type (like `INAM`) and value, in file order.
+    pub entries: Vec<([u8; 4], String)>,
+}
+
+impl RiffInfo {
+    /// Finds the first value with the given chunk type, like `ICMT`.
+    pub fn get(&self, id: &[u8; 4]) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(entry_id, _)| entry_id == id)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The title (`INAM`).
+    pub fn name(&self) -> Option<&str> {
+        self.get(&NAME)
+    }
+
+    /// The artist (`IART`).
+    pub fn artist(&self) -> Option<&str> {
+        self.get(&ARTIST)
+    }
+
+    /// A comment (`ICMT`).
+    pub fn comment(&self) -> Option<&str> {
+        self.get(&COMMENT)
+    }
+
+    /// When the file was made (`ICRD`).
+    ///
+    /// This is usually `YYYY-MM-DD`, but some writers use other formats.
+    pub fn creation_date(&self) -> Option<&str> {
+        self.get(&CREATION_DATE)
+    }
+
+    /// The software that wrote the file (`ISFT`).
+    pub fn software(&self) -> Option<&str> {
+        self.get(&SOFTWARE)
+    }
+
+    /// The copyright notice (`ICOP`).
+    pub fn copyright(&self) -> Option<&str> {
+        self.get(&COPYRIGHT)
+    }
+}
+
+/// Finds the `LIST/INFO` chunk in a RIFF file's chunks, then reads it.
+///
+/// Returns `None` if there's no `INFO` list.
+pub(crate) fn find(chunks: &[u8]) -> Option<RiffInfo> {
+    let list: &[u8] = riff::find_list(chunks, b"INFO")?;
+
+    Some(RiffInfo {
+        entries: riff::chunks(list)
+            .map(|(chunk, value)| {
+                // values end at their first NUL, but some writers pad them
+                let end: usize = value.iter().position(|b| *b == 0).unwrap_or(value.len());
+                (
+                    chunk.fourcc,
+                    String::from_utf8_lossy(&value[..end]).into_owned(),
+                )
+            })
+            .collect(),
+    })
+}
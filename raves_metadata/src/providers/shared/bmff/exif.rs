@@ -0,0 +1,424 @@
+//! Finds Exif in movies, like MP4 and MOV files from cameras.
+//!
+//! There's no standard place for it, so each camera maker picks their own:
+//!
+//! ```text
+//! moov
+//! ├── uuid (Canon: 85c0b687-820f-11e0-8111-f4ce462b6a48)
+//! │   ├── CMT1  (IFD 0)
+//! │   ├── CMT2  (Exif IFD)
+//! │   ├── CMT3  (maker notes)
+//! │   └── CMT4  (GPS IFD)
+//! └── udta
+//!     ├── NCTG  (Nikon tags)
+//!     └── MVTG  (Fujifilm)
+//! meta      (an `Exif` item, like in HEIF)
+//! ```
+//!
+//! Each block is TIFF-structured, so it's handed to [`Exif::new`]. Nikon's
+//! `NCTG` box is the exception: it's a flat list of tags, which we sort into
+//! IFDs and rebuild as TIFF first.
+//!
+//! DJI clips aren't covered. They don't carry a TIFF block, only QuickTime
+//! text boxes (like `©xyz`) and protobuf `djmd` tracks.
+
+use raves_metadata_types::exif::ifd::IfdGroup;
+use winnow::{
+    Parser as _,
+    binary::{be_u16, be_u32},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::{
+    exif::{Exif, error::ExifFatalError},
+    providers::shared::bmff::{BoxType, child, children, ftyp::FtypBox, heif::HeifLike},
+};
+
+/// The `uuid` box Canon uses for its metadata.
+const CANON_UUID: [u8; 16] = [
+    0x85, 0xC0, 0xB6, 0x87, 0x82, 0x0F, 0x11, 0xE0, 0x81, 0x11, 0xF4, 0xCE, 0x46, 0x2B, 0x6A, 0x48,
+];
+
+/// `udta` boxes some makers use for Exif.
+const UDTA_EXIF_BOXES: &[[u8; 4]] = &[*b"MVTG"];
+
+/// Nikon's `udta` box for its tags.
+const NIKON_TAGS: [u8; 4] = *b"NCTG";
+
+/// `NCTG` tags at or above this are Exif IFD tags, plus this offset.
+const NIKON_EXIF_IFD: u32 = 0x110_0000;
+
+/// `NCTG` tags at or above this are GPS IFD tags, plus this offset.
+const NIKON_GPS_IFD: u32 = 0x120_0000;
+
+/// How far into a box we'll look for its TIFF header.
+const MAX_TIFF_HEADER_OFFSET: usize = 16;
+
+/// Finds and parses the first Exif block in a movie.
+///
+/// Returns `None` if there isn't one.
+pub fn find_exif(input: &[u8]) -> Option<Result<Exif, ExifFatalError>> {
+    let moov: Option<&[u8]> = child(input, *b"moov");
+
+    // Canon's `uuid` box
+    if let Some(canon) = moov
+        .into_iter()
+        .flat_map(children)
+        .find_map(|(ty, payload)| (ty == BoxType::Uuid(CANON_UUID)).then_some(payload))
+    {
+        log::trace!("Found Canon metadata box.");
+        if let Some(exif) = canon_exif(canon) {
+            return Some(exif);
+        }
+    }
+
+    // Nikon's `udta` tags
+    let udta: Option<&[u8]> = moov.and_then(|moov| child(moov, *b"udta"));
+    if let Some(nctg) = udta.and_then(|udta| child(udta, NIKON_TAGS)) {
+        log::trace!("Found Nikon tag box.");
+        if let Some(exif) = nikon_exif(nctg) {
+            return Some(exif);
+        }
+    }
+
+    // `udta` boxes holding TIFF blocks
+    for box_type in UDTA_EXIF_BOXES {
+        let Some(payload) = udta.and_then(|udta| child(udta, *box_type)) else {
+            continue;
+        };
+
+        match find_tiff(payload) {
+            Some(mut tiff) => return Some(Exif::new(&mut tiff)),
+            None => log::debug!(
+                "`udta/{}` box didn't hold a TIFF block, which isn't supported.",
+                str::from_utf8(box_type).unwrap_or_default()
+            ),
+        }
+    }
+
+    // `Exif` items in a top-level `meta` box
+    meta_item_exif(input)
+}
+
+/// Reads Canon's split-up Exif.
+///
+/// `CMT1` is IFD 0. The Exif and GPS IFDs are in their own TIFF blocks, so
+/// they're attached to IFD 0 as sub-IFDs.
+fn canon_exif(canon: &[u8]) -> Option<Result<Exif, ExifFatalError>> {
+    let mut cmt1: &[u8] = child(canon, *b"CMT1")?;
+    let mut exif: Exif = match Exif::new(&mut cmt1) {
+        Ok(exif) => exif,
+        Err(e) => return Some(Err(e)),
+    };
+
+    for (box_type, group) in [(*b"CMT2", IfdGroup::Exif), (*b"CMT4", IfdGroup::Gps)] {
+        let Some(mut blob) = child(canon, box_type) else {
+            continue;
+        };
+
+        match Exif::new_with_group(&mut blob, group) {
+            Ok(sub) => {
+                if let Some(ifd0) = exif.ifds.first_mut() {
+                    ifd0.sub_ifds.extend(sub.ifds.into_iter().take(1));
+                }
+            }
+            Err(e) => log::warn!("Failed to parse Canon `{group:?}` IFD. err: {e}"),
+        }
+    }
+
+    Some(Ok(exif))
+}
+
+/// One `NCTG` tag, which uses the same format codes as Exif.
+struct NikonTag<'input> {
+    tag: u16,
+    format: u16,
+    count: u16,
+    data: &'input [u8],
+}
+
+/// Reads Nikon's `NCTG` tags.
+///
+/// Each one is a big-endian tag ID, Exif format, count, and then its data.
+/// The low IDs are Nikon's own, so only the ones matching IFD 0 tags are
+/// kept. Exif and GPS IFD tags are offset, like ExifTool's `Nikon::MOV`
+/// table describes.
+fn nikon_exif(nctg: &[u8]) -> Option<Result<Exif, ExifFatalError>> {
+    let mut ifd0: Vec<NikonTag> = Vec::new();
+    let mut exif_ifd: Vec<NikonTag> = Vec::new();
+    let mut gps_ifd: Vec<NikonTag> = Vec::new();
+
+    let mut input: &[u8] = nctg;
+    while input.len() >= 8 {
+        let (id, format, count) = (be_u32::<_, EmptyError>, be_u16, be_u16)
+            .parse_next(&mut input)
+            .ok()?;
+        let Some(len) = format_size(format).map(|size| size * usize::from(count)) else {
+            log::warn!("Nikon tag `{id:#x}` has unknown format `{format}`. Stopping.");
+            break;
+        };
+        let Ok(data) = take::<_, _, EmptyError>(len).parse_next(&mut input) else {
+            log::warn!("Nikon tag `{id:#x}` was cut off. Stopping.");
+            break;
+        };
+
+        let (ifd, tag): (&mut Vec<NikonTag>, u16) = match id {
+            0x01 => (&mut ifd0, 0x010F),     // Make
+            0x02 => (&mut ifd0, 0x0110),     // Model
+            0x03 => (&mut ifd0, 0x0131),     // Software
+            0x11 => (&mut exif_ifd, 0x9004), // CreateDate
+            0x12 => (&mut exif_ifd, 0x9003), // DateTimeOriginal
+            NIKON_EXIF_IFD..NIKON_GPS_IFD if id - NIKON_EXIF_IFD <= 0xFFFF => {
+                (&mut exif_ifd, (id - NIKON_EXIF_IFD) as u16)
+            }
+            NIKON_GPS_IFD.. if id - NIKON_GPS_IFD <= 0xFFFF => {
+                (&mut gps_ifd, (id - NIKON_GPS_IFD) as u16)
+            }
+            _ => {
+                log::trace!("Skipping Nikon tag `{id:#x}`.");
+                continue;
+            }
+        };
+        ifd.push(NikonTag {
+            tag,
+            format,
+            count,
+            data,
+        });
+    }
+
+    if ifd0.is_empty() && exif_ifd.is_empty() && gps_ifd.is_empty() {
+        log::debug!("`udta/NCTG` box didn't hold any Exif tags.");
+        return None;
+    }
+
+    let mut exif: Exif = match Exif::new(&mut nikon_tiff(ifd0).as_slice()) {
+        Ok(exif) => exif,
+        Err(e) => return Some(Err(e)),
+    };
+    for (tags, group) in [(exif_ifd, IfdGroup::Exif), (gps_ifd, IfdGroup::Gps)] {
+        if tags.is_empty() {
+            continue;
+        }
+
+        match Exif::new_with_group(&mut nikon_tiff(tags).as_slice(), group) {
+            Ok(sub) => {
+                if let Some(ifd0) = exif.ifds.first_mut() {
+                    ifd0.sub_ifds.extend(sub.ifds.into_iter().take(1));
+                }
+            }
+            Err(e) => log::warn!("Failed to parse Nikon `{group:?}` IFD. err: {e}"),
+        }
+    }
+
+    Some(Ok(exif))
+}
+
+/// The size of one value in the given Exif format.
+fn format_size(format: u16) -> Option<usize> {
+    match format {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+/// Builds a big-endian TIFF block with one IFD holding the given tags.
+fn nikon_tiff(mut tags: Vec<NikonTag>) -> Vec<u8> {
+    tags.sort_by_key(|t| t.tag);
+
+    // header, then the entry count, entries, and next IFD offset
+    let data_start: usize = 8 + 2 + tags.len() * 12 + 4;
+    let mut ifd: Vec<u8> = [b"MM\0*".as_slice(), &8_u32.to_be_bytes()].concat();
+    let mut data: Vec<u8> = Vec::new();
+
+    ifd.extend((tags.len() as u16).to_be_bytes());
+    for t in &tags {
+        ifd.extend(t.tag.to_be_bytes());
+        ifd.extend(t.format.to_be_bytes());
+        ifd.extend(u32::from(t.count).to_be_bytes());
+
+        // small values go in the entry itself
+        if t.data.len() <= 4 {
+            let mut value: [u8; 4] = [0; 4];
+            value[..t.data.len()].copy_from_slice(t.data);
+            ifd.extend(value);
+        } else {
+            ifd.extend(((data_start + data.len()) as u32).to_be_bytes());
+            data.extend_from_slice(t.data);
+            data.resize(data.len() + data.len() % 2, 0);
+        }
+    }
+    ifd.extend(0_u32.to_be_bytes());
+
+    [ifd, data].concat()
+}
+
+/// Grabs Exif from an `Exif` item, using the HEIF item parser.
+fn meta_item_exif(input: &[u8]) -> Option<Result<Exif, ExifFatalError>> {
+    if !children(input).any(|(ty, _)| ty == BoxType::Id(*b"meta")) {
+        return None;
+    }
+
+    // the items work just like they do in HEIF, whatever the brand is
+    let major_brand: [u8; 4] = FtypBox::new(&mut &*input)?.major_brand;
+    HeifLike::parse(&mut &*input, &[major_brand])
+        .inspect_err(|e| log::debug!("Failed to read `meta` items. err: {e}"))
+        .ok()?
+        .exif
+}
+
+/// Finds the TIFF header near the start of a box.
+///
+/// Some boxes start with `Exif\0\0` or a few bytes of their own header.
+fn find_tiff(payload: &[u8]) -> Option<&[u8]> {
+    (0..=MAX_TIFF_HEADER_OFFSET.min(payload.len()))
+        .map(|offset| &payload[offset..])
+        .find(|rest| rest.starts_with(b"II*\0") || rest.starts_with(b"MM\0*"))
+}
+
+#[cfg(test)]
+mod tests {
+    use raves_metadata_types::exif::{
+        Field, FieldData, FieldTag,
+        primitives::Primitive,
+        tags::{ExifIfdTag, Ifd0Tag, KnownTag},
+    };
+
+    use crate::{
+        exif::{Exif, error::ExifFieldError},
+        providers::shared::bmff::tests::bx,
+        util::logger,
+    };
+
+    use super::CANON_UUID;
+
+    /// Makes a little-endian TIFF block with one `SHORT` field.
+    fn tiff(tag: u16, value: u16) -> Vec<u8> {
+        [
+            b"II*\0\x08\0\0\0\x01\0".as_slice(),
+            &tag.to_le_bytes(),
+            &3_u16.to_le_bytes(),
+            &1_u32.to_le_bytes(),
+            &value.to_le_bytes(),
+            &[0, 0],
+            &0_u32.to_le_bytes(),
+        ]
+        .concat()
+    }
+
+    /// Grabs a `SHORT` field's value.
+    fn short(fields: &[Result<Field, ExifFieldError>], tag: KnownTag) -> Option<u16> {
+        fields.iter().flatten().find_map(|field| match field.data {
+            FieldData::Primitive(Primitive::Short(value)) if field.tag == FieldTag::Known(tag) => {
+                Some(value)
+            }
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn canon() {
+        logger();
+
+        let uuid: Vec<u8> = [b"uuid".as_slice(), &CANON_UUID].concat();
+        let canon = bx(
+            &uuid,
+            &[
+                bx(b"CMT1", &tiff(0x0112, 6)),
+                bx(b"CMT2", &tiff(0x8827, 400)),
+            ]
+            .concat(),
+        );
+        let file = bx(b"moov", &canon);
+
+        let exif: Exif = super::find_exif(&file).unwrap().unwrap();
+        let ifd0 = &exif.ifds[0];
+        assert_eq!(
+            short(&ifd0.fields, KnownTag::Ifd0Tag(Ifd0Tag::Orientation)),
+            Some(6)
+        );
+
+        // the Exif IFD is attached to IFD 0
+        assert_eq!(
+            short(
+                &ifd0.sub_ifds[0].fields,
+                KnownTag::ExifIfdTag(ExifIfdTag::PhotographicSensitivity)
+            ),
+            Some(400)
+        );
+    }
+
+    #[test]
+    fn udta() {
+        logger();
+
+        // the TIFF block can come after a short header
+        let mvtg: Vec<u8> = [[0_u8; 16].as_slice(), &tiff(0x0112, 3)].concat();
+        let file = bx(b"moov", &bx(b"udta", &bx(b"MVTG", &mvtg)));
+
+        let exif: Exif = super::find_exif(&file).unwrap().unwrap();
+        assert_eq!(
+            short(
+                &exif.ifds[0].fields,
+                KnownTag::Ifd0Tag(Ifd0Tag::Orientation)
+            ),
+            Some(3)
+        );
+
+        // boxes without TIFF are skipped
+        let file = bx(b"moov", &bx(b"udta", &bx(b"MVTG", &[1; 32])));
+        assert!(super::find_exif(&file).is_none());
+    }
+
+    #[test]
+    fn nikon() {
+        logger();
+
+        /// Makes one `NCTG` tag.
+        fn tag(id: u32, format: u16, count: u16, data: &[u8]) -> Vec<u8> {
+            [
+                id.to_be_bytes().as_slice(),
+                &format.to_be_bytes(),
+                &count.to_be_bytes(),
+                data,
+            ]
+            .concat()
+        }
+
+        let nctg: Vec<u8> = [
+            tag(0x01, 2, 6, b"NIKON\0"),
+            tag(0x13, 4, 1, &[0, 0, 1, 0]), // frame count, which is skipped
+            tag(0x110_8827, 3, 1, &[0x03, 0x20]),
+            tag(0x110_829D, 5, 1, &[0, 0, 0, 28, 0, 0, 0, 10]),
+            tag(0x120_0000, 1, 4, &[2, 3, 0, 0]),
+        ]
+        .concat();
+        let file = bx(b"moov", &bx(b"udta", &bx(b"NCTG", &nctg)));
+
+        let exif: Exif = super::find_exif(&file).unwrap().unwrap();
+        let ifd0 = &exif.ifds[0];
+        let make = ifd0.fields.iter().flatten().next().unwrap();
+        assert_eq!(make.tag, FieldTag::Known(KnownTag::Ifd0Tag(Ifd0Tag::Make)));
+
+        // the Exif and GPS IFDs are attached to IFD 0
+        assert_eq!(ifd0.sub_ifds.len(), 2);
+        assert_eq!(
+            short(
+                &ifd0.sub_ifds[0].fields,
+                KnownTag::ExifIfdTag(ExifIfdTag::PhotographicSensitivity)
+            ),
+            Some(800)
+        );
+        assert_eq!(ifd0.sub_ifds[0].fields.len(), 2);
+        assert_eq!(ifd0.sub_ifds[1].fields.len(), 1);
+
+        // a box without Exif tags is skipped
+        let nctg: Vec<u8> = tag(0x13, 4, 1, &[0, 0, 1, 0]);
+        let file = bx(b"moov", &bx(b"udta", &bx(b"NCTG", &nctg)));
+        assert!(super::find_exif(&file).is_none());
+    }
+}
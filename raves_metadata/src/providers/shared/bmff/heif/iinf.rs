@@ -25,7 +25,7 @@ pub struct FullBox {
     pub extends: BoxHeader,
 
     pub version: u8,
-    pub _flags: [u8; 3],
+    pub flags: [u8; 3],
 }
 
 impl FullBox {
@@ -52,7 +52,7 @@ impl FullBox {
         Ok(FullBox {
             extends,
             version,
-            _flags: flags,
+            flags,
         })
     }
 }
@@ -103,10 +103,10 @@ impl ItemInfoBox {
 pub struct ItemInfoEntry {
     /// This class extends `FullBox`, meaning we gotta parse a `FullBox` out
     /// before we get this class parsed out.
-    pub _extends: FullBox,
+    pub extends: FullBox,
 
     pub _item_protection_index: u16,
-    pub _item_name: String,
+    pub item_name: String,
 
     /// Version-specific info.
     pub version_specific: ItemInfoEntryVersioned,
@@ -142,9 +142,9 @@ impl ItemInfoEntry {
                     item_info_entry::content_encoding.parse_next(input)?;
 
                 Self {
-                    _extends: extends,
+                    extends,
                     _item_protection_index: item_protection_index,
-                    _item_name: item_name.to_string(),
+                    item_name: item_name.to_string(),
                     version_specific: ItemInfoEntryVersioned::V0 {
                         item_id,
                         _content_type: content_type,
@@ -176,9 +176,9 @@ impl ItemInfoEntry {
                     .map(|arr| if arr == *b"    " { None } else { Some(arr) })?;
 
                 Self {
-                    _extends: extends,
+                    extends,
                     _item_protection_index: item_protection_index,
-                    _item_name: item_name.to_string(),
+                    item_name: item_name.to_string(),
                     version_specific: ItemInfoEntryVersioned::V1 {
                         item_id,
                         _content_type: content_type,
@@ -204,9 +204,9 @@ impl ItemInfoEntry {
                     item_info_entry::mime_or_uri(input, item_type)?;
 
                 Self {
-                    _extends: extends,
+                    extends,
                     _item_protection_index: item_protection_index,
-                    _item_name: item_name.to_string(),
+                    item_name: item_name.to_string(),
                     version_specific: ItemInfoEntryVersioned::V2 {
                         item_type,
                         item_id,
@@ -231,9 +231,9 @@ impl ItemInfoEntry {
                     item_info_entry::mime_or_uri(input, item_type)?;
 
                 Self {
-                    _extends: extends,
+                    extends,
                     _item_protection_index: item_protection_index,
-                    _item_name: item_name.to_string(),
+                    item_name: item_name.to_string(),
                     version_specific: ItemInfoEntryVersioned::V3 {
                         item_type,
                         item_id,
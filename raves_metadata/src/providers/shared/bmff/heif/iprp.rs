@@ -0,0 +1,181 @@
+//! The `iprp` box holds item properties, like an image's size.
+//!
+//! It contains an `ipco` box, which lists every property once, and `ipma`
+//! boxes, which associate items with properties from that list.
+
+use winnow::{
+    ModalResult, Parser as _,
+    binary::{be_u16, be_u32, u8},
+    combinator::{fail, peek},
+    error::ContextError,
+    token::take,
+};
+
+use crate::providers::shared::{
+    bmff::{BoxHeader, BoxType, heif::iinf::FullBox},
+    desc,
+};
+
+/// A box containing item properties and their associations.
+#[derive(Clone, Debug, Default)]
+pub struct ItemPropertiesBox {
+    /// Every property in the `ipco` box, in order.
+    pub properties: Vec<ItemProperty>,
+
+    /// Which properties each item has.
+    pub associations: Vec<ItemPropertyAssociation>,
+}
+
+/// One property from the `ipco` box.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemProperty {
+    /// The property's box type, like `ispe` or `pixi`.
+    pub box_type: BoxType,
+
+    /// The property's payload, without its box header.
+    pub payload: Vec<u8>,
+}
+
+/// The properties associated with one item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemPropertyAssociation {
+    pub item_id: u32,
+
+    /// Each property's one-based index into the `ipco` box, and whether
+    /// readers must understand it.
+    ///
+    /// An index of zero means "no property".
+    pub entries: Vec<(bool, u16)>,
+}
+
+impl ItemPropertiesBox {
+    pub fn new(input: &mut &[u8]) -> ModalResult<Self, ContextError> {
+        // peek to check box type
+        let header: BoxHeader = peek(BoxHeader::new)
+            .context(desc("box header"))
+            .parse_next(input)?;
+
+        if header.box_type != BoxType::Id(*b"iprp") {
+            log::error!(
+                "Box of type `{:?}` is not an `ItemPropertiesBox`.",
+                header.box_type
+            );
+            fail.context(desc("not an ItemPropertiesBox"))
+                .parse_next(input)?;
+        }
+
+        _ = BoxHeader::new.parse_next(input)?;
+        let mut payload: &[u8] = match header.payload(input) {
+            Some(payload) => payload,
+            None => fail.context(desc("iprp payload")).parse_next(input)?,
+        };
+
+        let mut iprp = Self::default();
+        while !payload.is_empty() {
+            let header: BoxHeader = peek(BoxHeader::new)
+                .context(desc("iprp child header"))
+                .parse_next(&mut payload)?;
+
+            match header.box_type {
+                ty if ty == BoxType::Id(*b"ipco") => {
+                    _ = BoxHeader::new.parse_next(&mut payload)?;
+                    let Some(mut ipco) = header.payload(&mut payload) else {
+                        break;
+                    };
+                    iprp.properties = properties(&mut ipco)?;
+                }
+
+                ty if ty == BoxType::Id(*b"ipma") => {
+                    iprp.associations.extend(associations(&mut payload)?);
+                }
+
+                other => {
+                    log::trace!("Skipping unknown box in `iprp`: `{other:?}`");
+                    _ = BoxHeader::new
+                        .parse_next(&mut payload)
+                        .ok()
+                        .and_then(|header| header.eat_payload(&mut payload));
+                }
+            }
+        }
+
+        Ok(iprp)
+    }
+
+    /// Finds the first property of the given type associated with an item.
+    pub fn find(&self, item_id: u32, box_type: [u8; 4]) -> Option<&ItemProperty> {
+        self.associations
+            .iter()
+            .filter(|a| a.item_id == item_id)
+            .flat_map(|a| a.entries.iter())
+            .filter_map(|(_essential, index)| {
+                usize::from(*index)
+                    .checked_sub(1)
+                    .and_then(|i| self.properties.get(i))
+            })
+            .find(|p| p.box_type == BoxType::Id(box_type))
+    }
+}
+
+/// Parses the property boxes inside `ipco`.
+fn properties(input: &mut &[u8]) -> ModalResult<Vec<ItemProperty>, ContextError> {
+    let mut properties = Vec::new();
+
+    while !input.is_empty() {
+        let header: BoxHeader = BoxHeader::new
+            .context(desc("property box header"))
+            .parse_next(input)?;
+        let Some(payload) = header.payload(input) else {
+            break;
+        };
+
+        properties.push(ItemProperty {
+            box_type: header.box_type,
+            payload: payload.to_vec(),
+        });
+    }
+
+    Ok(properties)
+}
+
+/// Parses one `ipma` box.
+fn associations(input: &mut &[u8]) -> ModalResult<Vec<ItemPropertyAssociation>, ContextError> {
+    let full_box: FullBox = FullBox::new.context(desc("ipma")).parse_next(input)?;
+    let len: u64 = full_box
+        .extends
+        .payload_len()
+        .map(|len| len.saturating_sub(4))
+        .unwrap_or(input.len() as u64);
+    let payload: &mut &[u8] = &mut take(len).context(desc("ipma payload")).parse_next(input)?;
+
+    // version 0 uses 16-bit item IDs, and flag bit 0 means 16-bit indices
+    let large_indices: bool = full_box.flags[2] & 1 != 0;
+
+    let entry_count: u32 = be_u32.context(desc("entry count")).parse_next(payload)?;
+    let mut associations = Vec::new();
+    for _ in 0..entry_count {
+        let item_id: u32 = if full_box.version == 0 {
+            be_u16.map(u32::from).parse_next(payload)?
+        } else {
+            be_u32.parse_next(payload)?
+        };
+
+        let association_count: u8 = u8.context(desc("association count")).parse_next(payload)?;
+        let entries = (0..association_count)
+            .map(|_| {
+                if large_indices {
+                    be_u16
+                        .map(|v| (v & 0x8000 != 0, v & 0x7FFF))
+                        .parse_next(payload)
+                } else {
+                    u8.map(|v| (v & 0x80 != 0, u16::from(v & 0x7F)))
+                        .parse_next(payload)
+                }
+            })
+            .collect::<ModalResult<Vec<(bool, u16)>, ContextError>>()?;
+
+        associations.push(ItemPropertyAssociation { item_id, entries });
+    }
+
+    Ok(associations)
+}
@@ -0,0 +1,112 @@
+//! The `iref` box links items together.
+//!
+//! For example, a `cdsc` reference says that one item (like XMP) describes
+//! another, and a `dimg` reference lists the images an item is derived from.
+
+use winnow::{
+    ModalResult, Parser as _,
+    binary::{be_u16, be_u32},
+    combinator::{fail, peek},
+    error::ContextError,
+    token::take,
+};
+
+use crate::providers::shared::{
+    bmff::{BoxHeader, BoxType, heif::iinf::FullBox},
+    desc,
+};
+
+/// A box that lists references between items.
+#[derive(Clone, Debug, Default)]
+pub struct ItemReferenceBox {
+    pub references: Vec<ItemReference>,
+}
+
+/// One typed reference from an item to others.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemReference {
+    /// The reference type, like `cdsc`, `auxl`, or `dimg`.
+    pub reference_type: [u8; 4],
+
+    /// The item doing the referencing.
+    pub from_item_id: u32,
+
+    /// The referenced items, in order.
+    pub to_item_ids: Vec<u32>,
+}
+
+impl ItemReferenceBox {
+    pub fn new(input: &mut &[u8]) -> ModalResult<Self, ContextError> {
+        // peek to check box type
+        let full_box: FullBox = peek(FullBox::new)
+            .context(desc("full box"))
+            .parse_next(input)?;
+
+        if full_box.extends.box_type != BoxType::Id(*b"iref") {
+            log::error!(
+                "Box of type `{:?}` is not an `ItemReferenceBox`.",
+                full_box.extends.box_type
+            );
+            fail.context(desc("not an ItemReferenceBox"))
+                .parse_next(input)?;
+        }
+
+        // take the full box, then its payload (without version and flags)
+        _ = FullBox::new.parse_next(input)?;
+        let len: u64 = full_box
+            .extends
+            .payload_len()
+            .map(|len| len.saturating_sub(4))
+            .unwrap_or(input.len() as u64);
+        let mut payload: &[u8] = take(len).context(desc("iref payload")).parse_next(input)?;
+
+        // version 0 uses 16-bit item IDs
+        let item_id = |input: &mut &[u8]| -> ModalResult<u32, ContextError> {
+            if full_box.version == 0 {
+                be_u16.map(u32::from).parse_next(input)
+            } else {
+                be_u32.parse_next(input)
+            }
+        };
+
+        let mut references = Vec::new();
+        while !payload.is_empty() {
+            let header: BoxHeader = BoxHeader::new
+                .context(desc("reference box header"))
+                .parse_next(&mut payload)?;
+            let Some(mut reference) = header.payload(&mut payload) else {
+                break;
+            };
+
+            let BoxType::Id(reference_type) = header.box_type else {
+                log::warn!("Item reference used a UUID type. Skipping it.");
+                continue;
+            };
+
+            let from_item_id = item_id(&mut reference)?;
+            let count: u16 = be_u16
+                .context(desc("reference count"))
+                .parse_next(&mut reference)?;
+            let to_item_ids = (0..count)
+                .map(|_| item_id(&mut reference))
+                .collect::<ModalResult<Vec<u32>, ContextError>>()?;
+
+            references.push(ItemReference {
+                reference_type,
+                from_item_id,
+                to_item_ids,
+            });
+        }
+
+        Ok(Self { references })
+    }
+
+    /// Returns the items referenced from `from_item_id` with the given type.
+    pub fn targets(&self, reference_type: [u8; 4], from_item_id: u32) -> &[u32] {
+        self.references
+            .iter()
+            .find(|r| r.reference_type == reference_type && r.from_item_id == from_item_id)
+            .map(|r| r.to_item_ids.as_slice())
+            .unwrap_or_default()
+    }
+}
@@ -5,29 +5,41 @@
 //!
 //! <https://github.com/spacestation93/heif_howto>
 
-use std::{collections::HashMap, fmt::Write as _};
+use std::{borrow::Cow, collections::HashMap, fmt::Write as _};
 
 use winnow::{Parser as _, binary::be_u32, combinator::peek, error::EmptyError};
 
 use crate::{
     exif::{Exif, error::ExifFatalError},
-    providers::shared::{
-        bmff::{
-            BoxHeader, BoxType,
-            ftyp::FtypBox,
-            heif::{
-                iinf::{FullBox, ItemInfoBox, ItemInfoEntry},
-                iloc::{ConstructionMethod, ItemExtent, ItemLocationBox, ItemLocationEntry},
-                pitm::PrimaryItemBox,
+    providers::{
+        depth_map::{self, DepthMap, DepthMapCodec, DepthMapKind, DepthMapSource},
+        gain_map::GainMapInfo,
+        heif_items::{HeifItem, HeifItemReference, HeifItemStorage, HeifItems, HeifReferenceKind},
+        heif_properties::{HeifItemProperty, HeifProperty},
+        image_info::{ColorType, ImageInfo},
+        orientation::DisplayOrientation,
+        shared::{
+            bmff::{
+                BoxHeader, BoxType,
+                ftyp::FtypBox,
+                heif::{
+                    iinf::{FullBox, ItemInfoBox, ItemInfoEntry},
+                    iloc::{ConstructionMethod, ItemExtent, ItemLocationBox, ItemLocationEntry},
+                    iprp::ItemPropertiesBox,
+                    iref::ItemReferenceBox,
+                    pitm::PrimaryItemBox,
+                },
             },
+            desc,
         },
-        desc,
     },
     xmp::{Xmp, error::XmpError},
 };
 
 mod iinf;
 mod iloc;
+mod iprp;
+mod iref;
 mod pitm;
 mod search;
 
@@ -36,6 +48,10 @@ mod search;
 pub struct HeifLike {
     pub exif: Option<Result<Exif, ExifFatalError>>,
     pub xmp: Option<Result<Xmp, XmpError>>,
+    pub gain_map: Option<GainMapInfo>,
+    pub depth_maps: Vec<DepthMap>,
+    pub image_info: Option<ImageInfo>,
+    pub items: HeifItems,
 }
 
 impl HeifLike {
@@ -46,6 +62,15 @@ impl HeifLike {
         parse_heif_like(input, supported_ftyp_entries)
     }
 
+    /// Applies the primary item's transforms, like `irot` and `imir`.
+    ///
+    /// Returns `None` when there's no primary item.
+    pub fn display_orientation(&self) -> Option<DisplayOrientation> {
+        self.items
+            .primary_item()
+            .map(|item| DisplayOrientation::from_heif_properties(&item.properties))
+    }
+
     /// Helper associated function called by other HEIF-likes to parse the
     /// magic number.
     pub fn parse_magic_number(input: &[u8], supported_ftyp_entries: &[[u8; 4]]) -> bool {
@@ -124,6 +149,10 @@ fn parse_heif_like<'input>(
             return Ok(HeifLike {
                 exif: None,
                 xmp: None,
+                gain_map: None,
+                depth_maps: Vec::new(),
+                image_info: None,
+                items: HeifItems::default(),
             });
         }
 
@@ -154,15 +183,15 @@ fn parse_heif_like<'input>(
     // - `ItemLocationBox` says where things will be in the file
     // - `PrimaryItemBox` notes which item is the "primary" one
     // - `ItemDataBox` contains metadata, if `construction_method` specifies
+    // - `ItemReferenceBox` links items together (ex: gain maps to images)
+    // - `ItemPropertiesBox` describes items (ex: their dimensions)
     let mut maybe_item_info: Option<ItemInfoBox> = None;
     let mut maybe_item_location: Option<ItemLocationBox> = None;
     let mut maybe_item_data: Option<&[u8]> = None;
     let mut maybe_primary_item: Option<PrimaryItemBox> = None;
+    let mut item_references = ItemReferenceBox::default();
+    let mut item_properties = ItemPropertiesBox::default();
     while !meta_blob.is_empty() {
-        if maybe_item_info.is_some() && maybe_item_location.is_some() {
-            break;
-        }
-
         // parse next box (without consuming its data)
         let box_header: BoxHeader = match peek(BoxHeader::new).parse_next(meta_blob) {
             Ok(bh) => bh,
@@ -205,7 +234,7 @@ fn parse_heif_like<'input>(
                     .context(desc("item data box header"))
                     .parse_next(meta_blob)
                     .ok()
-                    .and_then(|header: BoxHeader| header.payload(input))
+                    .and_then(|header: BoxHeader| header.payload(meta_blob))
                 {
                     maybe_item_data = Some(blob);
                 } else {
@@ -227,14 +256,37 @@ fn parse_heif_like<'input>(
                 );
             }
 
+            // ItemReferenceBox (`iref`)
+            ty if ty == BoxType::Id(*b"iref") => {
+                let start: &[u8] = meta_blob;
+                match ItemReferenceBox::new.parse_next(meta_blob) {
+                    Ok(iref) => item_references = iref,
+                    Err(e) => {
+                        log::warn!("Failed to parse `ItemReferenceBox` inside `MetaBox`. err: {e}");
+                        *meta_blob = start;
+                        skip_box(meta_blob);
+                    }
+                }
+            }
+
+            // ItemPropertiesBox (`iprp`)
+            ty if ty == BoxType::Id(*b"iprp") => {
+                let start: &[u8] = meta_blob;
+                match ItemPropertiesBox::new.parse_next(meta_blob) {
+                    Ok(iprp) => item_properties = iprp,
+                    Err(e) => {
+                        log::warn!(
+                            "Failed to parse `ItemPropertiesBox` inside `MetaBox`. err: {e}"
+                        );
+                        *meta_blob = start;
+                        skip_box(meta_blob);
+                    }
+                }
+            }
+
             unsupported_box_type => {
                 log::trace!("Skipping unsupported box type: `{unsupported_box_type:?}`");
-
-                // skip it
-                _ = BoxHeader::new
-                    .parse_next(meta_blob)
-                    .ok()
-                    .and_then(|header| header.eat_payload(meta_blob));
+                skip_box(meta_blob);
             }
         }
     }
@@ -243,6 +295,18 @@ fn parse_heif_like<'input>(
     log::trace!("Item data found? {}", maybe_item_data.is_some());
     log::trace!("Primary item found? {}", maybe_primary_item.is_some());
 
+    let image_info: Option<ImageInfo> = maybe_primary_item
+        .as_ref()
+        .and_then(|primary| find_image_info(primary.item_id, &item_properties));
+
+    let graph: HeifItems = item_graph(
+        maybe_item_info.as_ref(),
+        maybe_item_location.as_ref(),
+        &item_references,
+        maybe_primary_item.as_ref(),
+        &item_properties,
+    );
+
     // ensure we have item info
     let Some(item_info) = maybe_item_info else {
         log::debug!(
@@ -252,6 +316,10 @@ fn parse_heif_like<'input>(
         return Ok(HeifLike {
             exif: None,
             xmp: None,
+            gain_map: None,
+            depth_maps: Vec::new(),
+            image_info,
+            items: graph,
         });
     };
 
@@ -264,31 +332,38 @@ fn parse_heif_like<'input>(
         return Ok(HeifLike {
             exif: None,
             xmp: None,
+            gain_map: None,
+            depth_maps: Vec::new(),
+            image_info,
+            items: graph,
         });
     };
 
-    let metadata_blobs = find_metadata(
-        original_input,
-        item_info,
-        item_location,
-        maybe_item_data,
-        maybe_primary_item,
-    )
-    .inspect_err(|e| log::error!("Failed to parse final metadata blobs. err: {e}"))
-    .inspect(|t| {
-        log::trace!("Found Exif? {}", t.exif.is_some());
-        log::trace!("Found XMP? {}", t.xmp.is_some());
-    })?;
+    let items: Vec<ItemData> = collect_items(item_info, item_location);
+
+    let metadata_blobs = find_metadata(original_input, &items, maybe_item_data, &graph)
+        .inspect_err(|e| log::error!("Failed to parse final metadata blobs. err: {e}"))
+        .inspect(|t| {
+            log::trace!("Found Exif? {}", t.exif.is_some());
+            log::trace!("Found XMP? {}", t.xmp.is_some());
+        })?;
+
+    let gain_map = find_gain_map(original_input, &items, maybe_item_data, &item_references);
+    let depth_maps = find_depth_maps(original_input, &items, maybe_item_data, &graph);
 
     Ok(HeifLike {
-        exif: metadata_blobs.exif.map(|mut raw| Exif::new(&mut raw)),
-        xmp: metadata_blobs.xmp.map(Xmp::new_from_bytes),
+        exif: metadata_blobs.exif.map(|raw| Exif::new(&mut raw.as_ref())),
+        xmp: metadata_blobs.xmp.map(|raw| Xmp::new_from_bytes(&raw)),
+        gain_map,
+        depth_maps,
+        image_info,
+        items: graph,
     })
 }
 
 struct FindMetadataReturnValues<'input> {
-    exif: Option<&'input [u8]>,
-    xmp: Option<&'input [u8]>,
+    exif: Option<Cow<'input, [u8]>>,
+    xmp: Option<Cow<'input, [u8]>>,
 }
 
 #[derive(Clone)]
@@ -298,19 +373,107 @@ struct ItemData {
     item_info: ItemInfoEntry,
 }
 
-fn find_metadata<'input>(
-    // blobs:
-    //
-    // 1. original file blob (for file-based indexing)
-    // 2. original `meta` blob
-    original_file_blob: &'input [u8],
+/// Skips over the next box, including its payload.
+fn skip_box(input: &mut &[u8]) {
+    _ = BoxHeader::new
+        .parse_next(input)
+        .ok()
+        .and_then(|header| header.eat_payload(input));
+}
 
-    // data on _what_ will be _where_ in the blobs
-    item_info: ItemInfoBox,
-    item_location: ItemLocationBox,
-    maybe_item_data: Option<&'input [u8]>,
-    _maybe_primary_item: Option<PrimaryItemBox>,
-) -> Result<FindMetadataReturnValues<'input>, HeifLikeConstructionError> {
+/// Builds the item graph from the `iinf`, `iloc`, `iref`, `pitm`, and `iprp`
+/// boxes.
+fn item_graph(
+    maybe_item_info: Option<&ItemInfoBox>,
+    maybe_item_location: Option<&ItemLocationBox>,
+    item_references: &ItemReferenceBox,
+    maybe_primary_item: Option<&PrimaryItemBox>,
+    item_properties: &ItemPropertiesBox,
+) -> HeifItems {
+    let location = |id: u32| {
+        maybe_item_location.and_then(|iloc| iloc.items.iter().find(|loc| loc.item_id == id))
+    };
+
+    // parse each property once, then hand out copies to the items using it
+    let properties: Vec<HeifProperty> = item_properties
+        .properties
+        .iter()
+        .map(|property| HeifProperty::new(&property.box_type, &property.payload))
+        .collect();
+    let properties_of = |id: u32| -> Vec<HeifItemProperty> {
+        item_properties
+            .associations
+            .iter()
+            .filter(|a| a.item_id == id)
+            .flat_map(|a| a.entries.iter())
+            .filter_map(|(essential, index)| {
+                // an index of zero means "no property"
+                let property = usize::from(*index)
+                    .checked_sub(1)
+                    .and_then(|i| properties.get(i));
+                if property.is_none() && *index != 0 {
+                    log::warn!("Item `#{id}` had an unknown property index: `{index}`");
+                }
+
+                property.map(|property| HeifItemProperty {
+                    essential: *essential,
+                    property: property.clone(),
+                })
+            })
+            .collect()
+    };
+
+    let items: Vec<HeifItem> = maybe_item_info
+        .map(|iinf| iinf.item_infos.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|info| {
+            let location: Option<&ItemLocationEntry> = location(info.item_id());
+            HeifItem {
+                id: info.item_id(),
+                item_type: info.item_type(),
+                name: info.item_name.clone(),
+                content_type: info.mime(),
+                hidden: info.extends.flags[2] & 1 != 0,
+                storage: location.map(|loc| match loc.construction_method {
+                    ConstructionMethod::Set0 => HeifItemStorage::File,
+                    ConstructionMethod::Idat => HeifItemStorage::ItemData,
+                    ConstructionMethod::Item => HeifItemStorage::Item,
+                }),
+                extents: location
+                    .map(|loc| {
+                        loc.extents
+                            .iter()
+                            .map(|extent| {
+                                let start: u64 =
+                                    loc.base_offset.saturating_add(extent.extent_offset);
+                                start..start.saturating_add(extent.extent_length)
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                properties: properties_of(info.item_id()),
+            }
+        })
+        .collect();
+
+    HeifItems {
+        primary_item_id: maybe_primary_item.map(|pitm| pitm.item_id),
+        items,
+        references: item_references
+            .references
+            .iter()
+            .map(|reference| HeifItemReference {
+                kind: HeifReferenceKind::from(reference.reference_type),
+                from_item_id: reference.from_item_id,
+                to_item_ids: reference.to_item_ids.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Matches each item's info with its location.
+fn collect_items(item_info: ItemInfoBox, item_location: ItemLocationBox) -> Vec<ItemData> {
     // make an index of what items we've got
     let item_infos_len = item_info.item_infos.len();
     let mut item_infos: HashMap<u32, ItemInfoEntry> = item_info.item_infos.into_iter().fold(
@@ -353,70 +516,136 @@ fn find_metadata<'input>(
         })
         .collect();
     log::debug!("After filtering, we have `{}` items!", items.len());
+    items
+}
 
-    // we'll want to find both exif and xmp data
-    let mut ret = FindMetadataReturnValues {
-        exif: None,
-        xmp: None,
-    };
+/// The kinds of metadata items we read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MetadataKind {
+    Exif,
+    Xmp,
+}
 
-    // try to find both exif and xmp in there.
-    for (i, item) in items.iter().enumerate() {
-        log::debug!(
-            "Processing item {}/{}: ID={}, item_type={:?}",
-            i + 1,
-            items.len(),
-            item.item_id,
-            item.item_info.item_type()
-        );
+impl MetadataKind {
+    /// Checks whether an item holds Exif or XMP.
+    fn of(item_info: &ItemInfoEntry) -> Option<Self> {
+        if item_info.item_type() == Some(*b"Exif") {
+            return Some(Self::Exif);
+        }
 
-        // stop looping (so... return) if we've found both
-        if ret.exif.is_some() && ret.xmp.is_some() {
-            break;
+        // xmp (using mime)
+        if let Some(mime) = item_info.mime()
+            && (mime == "application/rdf+xml" || mime == "application/xmp+xml")
+        {
+            return Some(Self::Xmp);
         }
 
-        // based on item's construction method, we'll choose what to do...
-        match item.item_location.construction_method {
-            // easy!
-            //
-            // go to this offset and read a box...
-            ConstructionMethod::Set0 => {
-                log::trace!("Construction method: File offsets (Set0)");
+        // xmp (when using item)
+        if let Some(item_type) = item_info.item_type()
+            && [b"xif\0", b"XMP ", b"xmp "].contains(&&item_type)
+        {
+            return Some(Self::Xmp);
+        }
 
-                // update `ret` w/ the parsed item
-                update_with_item(&mut ret, item.clone(), original_file_blob)?;
-            }
+        None
+    }
+}
 
-            // oooh. little bit harder.
-            //
-            // use the `idat` stream above and find it in there
-            ConstructionMethod::Idat => {
-                log::trace!("Construction method: Item data");
+/// Finds the Exif and XMP describing the primary image.
+///
+/// Files with several images, like bursts or derived images, can have
+/// metadata for each of them. Each metadata item is ranked by what it
+/// describes (`cdsc`), and the best one of each kind wins:
+///
+/// 1. the primary image
+/// 2. one of the primary image's sources, like a `grid`'s tiles
+/// 3. nothing in particular
+///
+/// Metadata describing other images is skipped.
+fn find_metadata<'input>(
+    // blobs:
+    //
+    // 1. original file blob (for file-based indexing)
+    // 2. the `idat` box's payload
+    original_file_blob: &'input [u8],
 
-                // ensure we have the item data
-                let Some(item_data) = maybe_item_data else {
-                    log::warn!("File specified that `idat` should be present, but it wasn't...");
-                    continue;
-                };
+    // data on _what_ will be _where_ in the blobs
+    items: &[ItemData],
+    maybe_item_data: Option<&'input [u8]>,
+    graph: &HeifItems,
+) -> Result<FindMetadataReturnValues<'input>, HeifLikeConstructionError> {
+    let mut exif: Option<(u8, Cow<'input, [u8]>)> = None;
+    let mut xmp: Option<(u8, Cow<'input, [u8]>)> = None;
 
-                // update `ret` w/ the parsed item
-                update_with_item(&mut ret, item.clone(), item_data)?;
-            }
+    for item in items {
+        let Some(kind) = MetadataKind::of(&item.item_info) else {
+            continue;
+        };
+        let Some(rank) = metadata_rank(graph, item.item_id) else {
+            log::debug!(
+                "Skipping metadata item `#{}`, which describes another image.",
+                item.item_id
+            );
+            continue;
+        };
+        log::trace!(
+            "Found {kind:?} item `#{}` with rank `{rank}`.",
+            item.item_id
+        );
 
-            // not doing this right now, but this is the `extent`-based one.
-            //
-            // basically, imagine pointers to random items and having to read
-            // off those. sounds annoying.
-            //
-            // if you have a need for this, please submit an issue with a test
-            // file you own the rights to (e.g., CC0). I'll implement it! :D
-            ConstructionMethod::Item => {
-                log::trace!("Construction method: Item");
-            }
+        // only replace what we've got with better-ranked items
+        let best = match kind {
+            MetadataKind::Exif => &mut exif,
+            MetadataKind::Xmp => &mut xmp,
+        };
+        if best
+            .as_ref()
+            .is_some_and(|(best_rank, _)| *best_rank <= rank)
+        {
+            continue;
         }
+
+        let Some((bytes, _)) = item_bytes(item, original_file_blob, maybe_item_data)? else {
+            continue;
+        };
+        let bytes: Cow<'input, [u8]> = match kind {
+            MetadataKind::Exif => match skip_exif_header(bytes) {
+                Some(bytes) => bytes,
+                None => continue,
+            },
+            MetadataKind::Xmp => bytes,
+        };
+
+        *best = Some((rank, bytes));
     }
 
-    Ok(ret)
+    Ok(FindMetadataReturnValues {
+        exif: exif.map(|(_, bytes)| bytes),
+        xmp: xmp.map(|(_, bytes)| bytes),
+    })
+}
+
+/// Ranks a metadata item by what it describes. Lower is better.
+///
+/// Returns `None` when the item describes some other image. Without a
+/// primary item, every item is ranked the same.
+fn metadata_rank(graph: &HeifItems, item_id: u32) -> Option<u8> {
+    let Some(primary_item_id) = graph.primary_item_id else {
+        return Some(0);
+    };
+
+    let described: &[u32] = graph.references_from(item_id, HeifReferenceKind::ContentDescription);
+    let sources: &[u32] = graph.references_from(primary_item_id, HeifReferenceKind::DerivedImage);
+
+    if described.contains(&primary_item_id) {
+        Some(0)
+    } else if described.iter().any(|id| sources.contains(id)) {
+        Some(1)
+    } else if described.is_empty() {
+        Some(2)
+    } else {
+        None
+    }
 }
 
 /// Creates the range we'll use to slice above.
@@ -438,102 +667,310 @@ fn make_slice_range(
             .map_err(|_| HeifLikeConstructionError::ParserBugSlicesTooSmall)?)
 }
 
-/// Updates the metadata return values with the given item.
-fn update_with_item<'input>(
-    ret: &mut FindMetadataReturnValues<'input>,
-    item: ItemData,
-    blob: &'input [u8],
-) -> Result<(), HeifLikeConstructionError> {
-    log::trace!("Updating found metadata w/ item. ID: `#{}`", item.item_id);
-
-    // ensure only one extent present
-    let [single_extent] = item.item_location.extents.as_slice() else {
-        log::error!("Multiple extents are not currently supported.");
-        if cfg!(debug_assertions) {
-            panic!("Multiple extents are not currently supported.");
-        } else {
-            return Ok(());
-        }
-    };
-
-    // grab our single extent as a blob (via slicing)
-    let slice_range = make_slice_range(&item, single_extent)
-        .inspect_err(|e| log::error!("Failed to make slice range! err: {e}"))?;
-    log::trace!("Slice range: {slice_range:?}");
-
-    let blob: &mut &[u8] = &mut &blob[slice_range];
-
-    // exif
-    if item.item_info.item_type() == Some(*b"Exif") {
-        let blob_len: usize = blob.len();
-
-        // handle some literal nonsense
-        //
-        // (some images can omit the required header)
-        let first_two_bytes: [u8; 2] = [blob[0], blob[1]];
-        let exif_tiff_header_offset = if (first_two_bytes == *b"MM" || first_two_bytes == *b"II")
-            && blob_len < u16::from_be_bytes(first_two_bytes).into()
+/// Skips the offset at the start of an Exif item's payload.
+///
+/// The offset says how far the TIFF header is from the end of the offset
+/// itself, which is usually zero.
+fn skip_exif_header(blob: Cow<'_, [u8]>) -> Option<Cow<'_, [u8]>> {
+    // handle some literal nonsense
+    //
+    // (some images can omit the required header)
+    let offset: usize = match blob.as_ref() {
+        [first @ (b'M' | b'I'), second, ..]
+            if first == second
+                && blob.len() < usize::from(u16::from_be_bytes([*first, *second])) =>
         {
             log::warn!(
                 "Malformed Exif header detected. Missing `exif_tiff_header_offset`. \
                 Assuming value of zero..."
             );
             0
-        } else {
-            // parse out the u32 explaining how many bytes to skip
-            let Ok::<_, EmptyError>(exif_tiff_header_offset) = be_u32
+        }
+
+        // parse out the u32 explaining how many bytes to skip
+        bytes => {
+            let Ok::<_, EmptyError>((_, exif_tiff_header_offset)) = be_u32
                 .context(desc("exif_tiff_header_offset"))
-                .parse_next(blob)
-                .map(|off| off as usize)
+                .parse_peek(bytes)
             else {
                 log::error!("Failed to grab `exif_tiff_header_offset` for Exif! Skipping...");
-                return Ok(());
+                return None;
             };
-            exif_tiff_header_offset
+            log::trace!("`exif_tiff_header_offset` is: `{exif_tiff_header_offset}`");
+            usize::try_from(exif_tiff_header_offset)
+                .ok()
+                .and_then(|offset| offset.checked_add(4))
+                .unwrap_or(usize::MAX)
+        }
+    };
+
+    // check bounds
+    if blob.len() < offset {
+        log::warn!(
+            "`exif_tiff_header_offset` was larger than the blob. \
+            blob len: `{}`, \
+            offset: `{offset}`",
+            blob.len()
+        );
+        return None;
+    }
+
+    // move to offset (or start from beginning)
+    Some(match blob {
+        Cow::Borrowed(bytes) => Cow::Borrowed(&bytes[offset..]),
+        Cow::Owned(mut bytes) => {
+            bytes.drain(..offset);
+            Cow::Owned(bytes)
+        }
+    })
+}
+
+/// Finds an HDR gain map's parameters and image.
+///
+/// ISO 21496-1 files use a `tmap` item, which is derived (`dimg`) from the
+/// base image and the gain map image. Apple uses an auxiliary image, which
+/// is described (`cdsc`) by its own XMP item.
+fn find_gain_map(
+    original_file_blob: &[u8],
+    items: &[ItemData],
+    maybe_item_data: Option<&[u8]>,
+    item_references: &ItemReferenceBox,
+) -> Option<GainMapInfo> {
+    let item = |id: u32| items.iter().find(|item| item.item_id == id);
+    let location = |id: u32| {
+        item(id)
+            .and_then(|item| {
+                item_bytes(item, original_file_blob, maybe_item_data)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|(_, range)| range)
+    };
+
+    // ISO 21496-1 (`tmap`)
+    let tone_map = items.iter().find_map(|item| {
+        if item.item_info.item_type() != Some(*b"tmap") {
+            return None;
+        }
+
+        // the box starts with a version, which must be zero
+        let (data, _) = item_bytes(item, original_file_blob, maybe_item_data).ok()??;
+        let [0, metadata @ ..] = data.as_ref() else {
+            log::warn!("`tmap` item had an unknown version.");
+            return None;
         };
 
-        // check bounds
-        log::trace!("`exif_tiff_header_offset` is: `{exif_tiff_header_offset}`");
-        if blob_len < exif_tiff_header_offset {
-            log::warn!(
-                "`exif_tiff_header_offset` was larger than the blob. \
-                blob len: `{blob_len}`, \
-                offset: `{exif_tiff_header_offset}`",
-            );
-            if cfg!(debug_assertions) {
-                panic!();
+        let gain_map_id = *item_references.targets(*b"dimg", item.item_id).get(1)?;
+        Some(GainMapInfo {
+            location: location(gain_map_id),
+            ..GainMapInfo::from_iso_21496(metadata)?
+        })
+    });
+
+    // XMP items describing a gain map image (Apple)
+    tone_map.or_else(|| {
+        items.iter().find_map(|item| {
+            let is_xmp = item
+                .item_info
+                .mime()
+                .is_some_and(|m| m == "application/rdf+xml" || m == "application/xmp+xml");
+            if !is_xmp {
+                return None;
             }
+
+            let (data, _) = item_bytes(item, original_file_blob, maybe_item_data).ok()??;
+            let xmp = Xmp::new_from_bytes(&data).ok()?;
+            let info = GainMapInfo::from_xmp(xmp.document())?;
+
+            let described = item_references.targets(*b"cdsc", item.item_id);
+            Some(GainMapInfo {
+                location: described.first().and_then(|id| location(*id)),
+                ..info
+            })
+        })
+    })
+}
+
+/// Finds depth maps and mattes, which are auxiliary image items.
+///
+/// Each one's `auxC` property says what it is. Its depth parameters come
+/// from any XMP item describing (`cdsc`) it.
+fn find_depth_maps(
+    original_file_blob: &[u8],
+    items: &[ItemData],
+    maybe_item_data: Option<&[u8]>,
+    graph: &HeifItems,
+) -> Vec<DepthMap> {
+    let bytes = |id: u32| {
+        items
+            .iter()
+            .find(|item| item.item_id == id)
+            .and_then(|item| {
+                item_bytes(item, original_file_blob, maybe_item_data)
+                    .ok()
+                    .flatten()
+            })
+    };
+
+    graph
+        .items
+        .iter()
+        .filter_map(|aux| {
+            let aux_type: &str = aux.aux_type()?;
+            let kind = DepthMapKind::from_aux_type(aux_type)?;
+            log::trace!("Found depth item `#{}` of type `{aux_type}`.", aux.id);
+
+            let parameters = items
+                .iter()
+                .filter(|item| MetadataKind::of(&item.item_info) == Some(MetadataKind::Xmp))
+                .filter(|item| {
+                    graph
+                        .references_from(item.item_id, HeifReferenceKind::ContentDescription)
+                        .contains(&aux.id)
+                })
+                .find_map(|item| depth_map::parameters_from_xmp_bytes(&bytes(item.item_id)?.0));
+
+            Some(DepthMap {
+                kind,
+                source: DepthMapSource::HeifItem {
+                    item_id: aux.id,
+                    for_item_id: graph
+                        .references_from(aux.id, HeifReferenceKind::Auxiliary)
+                        .first()
+                        .copied(),
+                    aux_type: aux_type.into(),
+                },
+                location: bytes(aux.id).and_then(|(_, range)| range),
+                data: None,
+                codec: aux.item_type.map(DepthMapCodec::HeifItemType),
+                dimensions: aux.spatial_extent(),
+                parameters,
+            })
+        })
+        .collect()
+}
+
+/// Reads an image item's size (`ispe`) and pixel info (`pixi`) properties.
+///
+/// The coded image is assumed to be YCbCr when it has three channels, which
+/// is true for almost all HEVC and AV1 images.
+fn find_image_info(item_id: u32, item_properties: &ItemPropertiesBox) -> Option<ImageInfo> {
+    // `ispe` is a full box, then a width and height
+    let ispe = item_properties.find(item_id, *b"ispe")?;
+    let (_, (_version_and_flags, width, height)) = (be_u32, be_u32, be_u32)
+        .parse_peek(ispe.payload.as_slice())
+        .map_err(|_: EmptyError| log::warn!("`ispe` property was too short."))
+        .ok()?;
+
+    // `pixi` is a full box, then a channel count and each channel's bit depth
+    let bits_per_channel: Option<&[u8]> =
+        item_properties
+            .find(item_id, *b"pixi")
+            .and_then(|pixi| match pixi.payload.as_slice() {
+                [_, _, _, _, count, bits @ ..] => bits.get(..usize::from(*count)),
+                _ => None,
+            });
+
+    let channels: Option<u8> = bits_per_channel.and_then(|bits| bits.len().try_into().ok());
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth: bits_per_channel.and_then(|bits| bits.iter().copied().max()),
+        color_type: match channels {
+            Some(1) => Some(ColorType::Grayscale),
+            Some(3) => Some(ColorType::YCbCr),
+            _ => None,
+        },
+        channels,
+        frame_count: Some(1),
+        jpeg: None,
+    })
+}
+
+/// An item's bytes, and its range in the file (if it's stored there as one
+/// extent).
+type ItemBytes<'input> = (Cow<'input, [u8]>, Option<core::ops::Range<usize>>);
+
+/// Grabs an item's bytes.
+///
+/// Items split into several extents are joined together. When the item is
+/// one extent stored directly in the file, its range in the file is also
+/// returned.
+fn item_bytes<'input>(
+    item: &ItemData,
+    original_file_blob: &'input [u8],
+    maybe_item_data: Option<&'input [u8]>,
+) -> Result<Option<ItemBytes<'input>>, HeifLikeConstructionError> {
+    // based on item's construction method, we'll choose where to read from...
+    let blob: &[u8] = match item.item_location.construction_method {
+        // easy!
+        //
+        // these are offsets into the file
+        ConstructionMethod::Set0 => original_file_blob,
+
+        // use the `idat` stream and find it in there
+        ConstructionMethod::Idat => {
+            let Some(item_data) = maybe_item_data else {
+                log::warn!("File specified that `idat` should be present, but it wasn't...");
+                return Ok(None);
+            };
+            item_data
         }
 
-        // move to offset (or start from beginning)
-        ret.exif = Some(&blob[exif_tiff_header_offset..]);
+        // not doing this right now, but this is the `extent`-based one.
+        //
+        // basically, imagine pointers to random items and having to read
+        // off those. sounds annoying.
+        //
+        // if you have a need for this, please submit an issue with a test
+        // file you own the rights to (e.g., CC0). I'll implement it! :D
+        ConstructionMethod::Item => {
+            log::debug!(
+                "Item `#{}` is built from other items, which isn't supported.",
+                item.item_id
+            );
+            return Ok(None);
+        }
+    };
 
-        log::trace!("Updated w/ Exif!");
-        return Ok(());
-    }
-    log::debug!("passed Exif");
+    let ranges: Vec<core::ops::Range<usize>> = item
+        .item_location
+        .extents
+        .iter()
+        .map(|extent| make_slice_range(item, extent))
+        .collect::<Result<_, _>>()?;
+    log::trace!("Item `#{}` has ranges: {ranges:?}", item.item_id);
+
+    let slice = |range: &core::ops::Range<usize>| {
+        let bytes: Option<&[u8]> = blob.get(range.clone());
+        if bytes.is_none() {
+            log::warn!(
+                "Item `#{}` had an extent outside its data. range: `{range:?}`",
+                item.item_id
+            );
+        }
+        bytes
+    };
 
-    // xmp (using mime)
-    if let Some(mime) = item.item_info.mime()
-        && (mime == "application/rdf+xml" || mime == "application/xmp+xml")
-    {
-        log::trace!("Updated w/ XMP when using MIME!");
-        ret.xmp = Some(blob);
-        return Ok(());
-    }
-    log::debug!("passed XMP (MIME)");
+    Ok(match ranges.as_slice() {
+        [] => {
+            log::warn!("Item `#{}` had no extents.", item.item_id);
+            None
+        }
 
-    // xmp (when using item)
-    if let Some(item_type) = item.item_info.item_type()
-        && [b"xif\0", b"XMP ", b"xmp "].contains(&&item_type)
-    {
-        log::trace!("Updated w/ XMP using item type: {item_type:?}");
-        ret.xmp = Some(blob);
-        return Ok(());
-    }
-    log::debug!("passed XMP (item)");
+        [range] => slice(range).map(|bytes| {
+            let in_file = item.item_location.construction_method == ConstructionMethod::Set0;
+            (Cow::Borrowed(bytes), in_file.then(|| range.clone()))
+        }),
 
-    Ok(())
+        ranges => ranges
+            .iter()
+            .try_fold(Vec::new(), |mut joined, range| {
+                joined.extend_from_slice(slice(range)?);
+                Some(joined)
+            })
+            .map(|joined| (Cow::Owned(joined), None)),
+    })
 }
 
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
@@ -559,13 +996,6 @@ pub enum HeifLikeConstructionError {
     /// Failed to parse the `PrimaryItemBox`.
     CantParsePrimaryItemBox,
 
-    /// Multiple extents aren't currently supported.
-    ///
-    /// They're annoying to implement. If you own the rights to a file and can
-    /// include it here for testing, please create an issue and I will
-    /// implement multi-extent parsing.
-    ParserBugMultipleExtentsNotSupported { extent_ct: u32 },
-
     /// Rust slices cannot represent giant files on 32-bit systems.
     ///
     /// This is a limitation of the parser. Please make an issue if you see
@@ -594,12 +1024,6 @@ impl core::fmt::Display for HeifLikeConstructionError {
             Self::CantParseItemLocationBox => f.write_str("Failed to parse `ItemLocationBox`."),
             Self::CantParsePrimaryItemBox => f.write_str("Failed to parse `PrimaryItemBox`."),
 
-            Self::ParserBugMultipleExtentsNotSupported { extent_ct } => write!(
-                f,
-                "This library does not currently support multi-extent parsing. \
-                Please see variant docs for more info. \
-                num. of extents: `{extent_ct}`"
-            ),
             Self::ParserBugSlicesTooSmall => f.write_str(
                 "Slice cannot represent this range on your system. \
                 Please see variant docs for more info.",
@@ -609,3 +1033,354 @@ impl core::fmt::Display for HeifLikeConstructionError {
 }
 
 impl core::error::Error for HeifLikeConstructionError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        exif::Endianness,
+        providers::{
+            depth_map::{DepthFormat, DepthMapCodec, DepthMapKind, DepthMapSource, DepthUnits},
+            heif_items::{HeifItemStorage, HeifReferenceKind},
+            heif_properties::{HeifItemProperty, HeifProperty},
+            shared::bmff::tests::{bx, full},
+        },
+        util::logger,
+    };
+
+    use super::HeifLike;
+
+    /// Makes a version 2 `infe` entry.
+    fn infe(id: u16, item_type: &[u8; 4], hidden: bool) -> Vec<u8> {
+        let payload = [id.to_be_bytes().as_slice(), &[0, 0], item_type, b"\0"].concat();
+        full(b"infe", 2, hidden.into(), &payload)
+    }
+
+    /// Makes a burst-like file with two images, each with its own Exif.
+    ///
+    /// - item 1: an image
+    /// - item 2: a hidden thumbnail of item 1
+    /// - item 3: little-endian Exif, describing item 2
+    /// - item 4: big-endian Exif, describing item 1, in two extents
+    fn two_images(primary_item_id: u16) -> Vec<u8> {
+        let little: &[u8] = b"\0\0\0\0II*\0\x08\0\0\0";
+        let big: &[u8] = b"\0\0\0\0MM\0*\0\0\0\x08";
+
+        // the big-endian Exif's halves are stored out of order
+        let idat: Vec<u8> = [little, &big[6..], &big[..6]].concat();
+
+        // each extent is a 32-bit offset and length in `idat`
+        let location = |id: u16, extents: &[(u32, u32)]| {
+            let extents = extents
+                .iter()
+                .flat_map(|(offset, len)| [offset.to_be_bytes(), len.to_be_bytes()])
+                .collect::<Vec<_>>()
+                .concat();
+            [
+                id.to_be_bytes().as_slice(),
+                &[0, 1, 0, 0],
+                &(extents.len() as u16 / 8).to_be_bytes(),
+                &extents,
+            ]
+            .concat()
+        };
+        let iloc = full(
+            b"iloc",
+            1,
+            0,
+            &[
+                [0x44, 0x00, 0, 2].as_slice(),
+                &location(3, &[(0, 12)]),
+                &location(4, &[(18, 6), (12, 6)]),
+            ]
+            .concat(),
+        );
+
+        let iinf = full(
+            b"iinf",
+            0,
+            0,
+            &[
+                [0, 4].as_slice(),
+                &infe(1, b"hvc1", false),
+                &infe(2, b"hvc1", true),
+                &infe(3, b"Exif", false),
+                &infe(4, b"Exif", false),
+            ]
+            .concat(),
+        );
+
+        let iref = full(
+            b"iref",
+            0,
+            0,
+            &[
+                bx(b"thmb", &[0, 2, 0, 1, 0, 1]),
+                bx(b"cdsc", &[0, 3, 0, 1, 0, 2]),
+                bx(b"cdsc", &[0, 4, 0, 1, 0, 1]),
+            ]
+            .concat(),
+        );
+
+        // item 1 is 64x48 and rotated, which readers must understand
+        let ipco = bx(
+            b"ipco",
+            &[
+                full(b"ispe", 0, 0, &[0, 0, 0, 64, 0, 0, 0, 48]),
+                bx(b"irot", &[1]),
+            ]
+            .concat(),
+        );
+        let ipma = full(b"ipma", 0, 0, &[0, 0, 0, 1, 0, 1, 2, 0x01, 0x82]);
+        let iprp = bx(b"iprp", &[ipco, ipma].concat());
+
+        let meta = full(
+            b"meta",
+            0,
+            0,
+            &[
+                full(b"pitm", 0, 0, &primary_item_id.to_be_bytes()),
+                iinf,
+                iloc,
+                iref,
+                iprp,
+                bx(b"idat", &idat),
+            ]
+            .concat(),
+        );
+
+        [bx(b"ftyp", b"heic\0\0\0\0mif1heic"), meta].concat()
+    }
+
+    #[test]
+    fn item_graph() {
+        logger();
+
+        let file = two_images(1);
+        let heif = HeifLike::parse(&mut file.as_slice(), &[*b"heic"]).unwrap();
+        let items = &heif.items;
+
+        assert_eq!(items.primary_item_id, Some(1));
+        assert_eq!(items.items.len(), 4);
+        assert_eq!(items.primary_item().map(|item| item.hidden), Some(false));
+        assert_eq!(items.item(2).map(|item| item.hidden), Some(true));
+
+        let primary = items.primary_item().unwrap();
+        assert_eq!(primary.spatial_extent(), Some((64, 48)));
+        assert_eq!(
+            primary.properties[1],
+            HeifItemProperty {
+                essential: true,
+                property: HeifProperty::Rotation {
+                    anticlockwise_degrees: 90
+                },
+            }
+        );
+        assert!(!primary.properties[0].essential);
+
+        // rotating 90 degrees anti-clockwise is Exif orientation 8
+        assert_eq!(heif.display_orientation().map(|o| o.to_exif()), Some(8));
+        assert!(items.item(2).unwrap().properties.is_empty());
+
+        let exif = items.item(4).unwrap();
+        assert_eq!(exif.item_type, Some(*b"Exif"));
+        assert_eq!(exif.storage, Some(HeifItemStorage::ItemData));
+        assert_eq!(exif.extents, [18..24, 12..18]);
+
+        assert_eq!(items.references_from(2, HeifReferenceKind::Thumbnail), [1]);
+        assert_eq!(
+            items
+                .thumbnails_of(1)
+                .map(|item| item.id)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+        assert_eq!(
+            items
+                .metadata_for(1)
+                .map(|item| item.id)
+                .collect::<Vec<_>>(),
+            [4]
+        );
+    }
+
+    /// Exif describing another image shouldn't be used, even if it's first.
+    #[test]
+    fn metadata_describes_primary_item() {
+        logger();
+
+        // the primary's Exif is split in two, and is big-endian
+        let file = two_images(1);
+        let heif = HeifLike::parse(&mut file.as_slice(), &[*b"heic"]).unwrap();
+        let exif = heif.exif.unwrap().unwrap();
+        assert_eq!(exif.endianness, Endianness::Big);
+
+        // the other image's Exif is little-endian
+        let file = two_images(2);
+        let heif = HeifLike::parse(&mut file.as_slice(), &[*b"heic"]).unwrap();
+        let exif = heif.exif.unwrap().unwrap();
+        assert_eq!(exif.endianness, Endianness::Little);
+    }
+
+    /// Makes a portrait photo with a depth map and its XMP.
+    ///
+    /// - item 1: the primary image
+    /// - item 2: a 32x24 depth map, auxiliary to item 1
+    /// - item 3: XMP describing item 2
+    fn portrait() -> Vec<u8> {
+        let xmp: &[u8] = br#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"><rdf:Description rdf:about="" xmlns:GDepth="http://ns.google.com/photos/1.0/depthmap/" GDepth:Format="RangeLinear" GDepth:Near="0.5" GDepth:Far="4" GDepth:Units="Meters"/></rdf:RDF></x:xmpmeta>"#;
+
+        let iloc = full(
+            b"iloc",
+            1,
+            0,
+            &[
+                [0x44, 0x00, 0, 1, 0, 3, 0, 1, 0, 0, 0, 1].as_slice(),
+                &0_u32.to_be_bytes(),
+                &(xmp.len() as u32).to_be_bytes(),
+            ]
+            .concat(),
+        );
+
+        let mime = [
+            [0, 3, 0, 0].as_slice(),
+            b"mime",
+            b"\0",
+            b"application/rdf+xml\0\0",
+        ]
+        .concat();
+        let iinf = full(
+            b"iinf",
+            0,
+            0,
+            &[
+                [0, 3].as_slice(),
+                &infe(1, b"hvc1", false),
+                &infe(2, b"hvc1", true),
+                &full(b"infe", 2, 0, &mime),
+            ]
+            .concat(),
+        );
+
+        let iref = full(
+            b"iref",
+            0,
+            0,
+            &[
+                bx(b"auxl", &[0, 2, 0, 1, 0, 1]),
+                bx(b"cdsc", &[0, 3, 0, 1, 0, 2]),
+            ]
+            .concat(),
+        );
+
+        let ipco = bx(
+            b"ipco",
+            &[
+                full(b"ispe", 0, 0, &[0, 0, 0, 32, 0, 0, 0, 24]),
+                full(b"auxC", 0, 0, b"urn:mpeg:hevc:2015:auxid:2\0"),
+            ]
+            .concat(),
+        );
+        let ipma = full(b"ipma", 0, 0, &[0, 0, 0, 1, 0, 2, 2, 0x01, 0x82]);
+        let iprp = bx(b"iprp", &[ipco, ipma].concat());
+
+        let meta = full(
+            b"meta",
+            0,
+            0,
+            &[
+                full(b"pitm", 0, 0, &[0, 1]),
+                iinf,
+                iloc,
+                iref,
+                iprp,
+                bx(b"idat", xmp),
+            ]
+            .concat(),
+        );
+
+        [bx(b"ftyp", b"heic\0\0\0\0mif1heic"), meta].concat()
+    }
+
+    #[test]
+    fn depth_map() {
+        logger();
+
+        let file = portrait();
+        let heif = HeifLike::parse(&mut file.as_slice(), &[*b"heic"]).unwrap();
+
+        // the depth map's XMP isn't the primary image's
+        assert!(heif.xmp.is_none());
+
+        let [depth] = heif.depth_maps.as_slice() else {
+            panic!("expected one depth map, got: {:?}", heif.depth_maps);
+        };
+        assert_eq!(depth.kind, DepthMapKind::Depth);
+        assert_eq!(
+            depth.source,
+            DepthMapSource::HeifItem {
+                item_id: 2,
+                for_item_id: Some(1),
+                aux_type: "urn:mpeg:hevc:2015:auxid:2".into(),
+            }
+        );
+        assert_eq!(depth.codec, Some(DepthMapCodec::HeifItemType(*b"hvc1")));
+        assert_eq!(depth.dimensions, Some((32, 24)));
+
+        let parameters = depth.parameters.as_ref().unwrap();
+        assert_eq!(parameters.format, Some(DepthFormat::RangeLinear));
+        assert_eq!(parameters.near, Some(0.5));
+        assert_eq!(parameters.far, Some(4.0));
+        assert_eq!(parameters.units, Some(DepthUnits::Meters));
+    }
+
+    /// Broken `iref` and `iprp` boxes are skipped, so boxes after them are
+    /// still read.
+    #[test]
+    fn broken_boxes_are_skipped() {
+        logger();
+
+        let exif: &[u8] = b"\0\0\0\0MM\0*\0\0\0\x08";
+        let iloc = full(
+            b"iloc",
+            1,
+            0,
+            &[
+                [0x44, 0x00, 0, 1, 0, 1, 0, 1, 0, 0, 0, 1].as_slice(),
+                &0_u32.to_be_bytes(),
+                &(exif.len() as u32).to_be_bytes(),
+            ]
+            .concat(),
+        );
+
+        // `ipma` says it has an entry, but it's empty
+        let iprp = bx(b"iprp", &full(b"ipma", 0, 0, &[0, 0, 0, 1]));
+        // `cdsc` is cut off before its reference count
+        let iref = full(b"iref", 0, 0, &bx(b"cdsc", &[0]));
+
+        let meta = full(
+            b"meta",
+            0,
+            0,
+            &[
+                iprp,
+                iref,
+                full(b"pitm", 0, 0, &[0, 1]),
+                full(
+                    b"iinf",
+                    0,
+                    0,
+                    &[[0, 1].as_slice(), &infe(1, b"Exif", false)].concat(),
+                ),
+                iloc,
+                bx(b"idat", exif),
+            ]
+            .concat(),
+        );
+        let file = [bx(b"ftyp", b"heic\0\0\0\0mif1heic"), meta].concat();
+
+        let heif = HeifLike::parse(&mut file.as_slice(), &[*b"heic"]).unwrap();
+        assert_eq!(heif.items.primary_item_id, Some(1));
+        let exif = heif.exif.unwrap().unwrap();
+        assert_eq!(exif.endianness, Endianness::Big);
+    }
+}
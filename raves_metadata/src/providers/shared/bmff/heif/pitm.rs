@@ -14,7 +14,7 @@ use crate::providers::shared::{
 pub struct PrimaryItemBox {
     pub _extends_full_box: FullBox,
 
-    pub _item_id: u32, // u16 if ver. 0
+    pub item_id: u32, // u16 if ver. 0
 }
 
 impl PrimaryItemBox {
@@ -39,7 +39,7 @@ impl PrimaryItemBox {
 
         // return self
         Ok(Self {
-            _item_id: if extends_full_box.version == 0 {
+            item_id: if extends_full_box.version == 0 {
                 be_u16.context(desc("item id (u16)")).parse_next(input)? as u32
             } else {
                 be_u32.context(desc("item id (u32)")).parse_next(input)?
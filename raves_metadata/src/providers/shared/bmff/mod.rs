@@ -10,9 +10,13 @@ use std::fmt::Write as _;
 pub use box_header::BoxHeader;
 
 mod box_header;
+pub mod exif;
 pub mod ftyp;
 pub mod heif;
+pub mod sample_table;
 pub mod search;
+pub mod sequence;
+pub mod track;
 
 /// The box UUID used for XMP.
 pub const XMP_UUID: [u8; 16] = [
@@ -70,3 +74,62 @@ pub enum BoxSize {
     /// the file.
     Eof,
 }
+
+/// Iterates over the boxes in `input`, with their payloads.
+pub fn children(mut input: &[u8]) -> impl Iterator<Item = (BoxType, &[u8])> {
+    core::iter::from_fn(move || {
+        if input.is_empty() {
+            return None;
+        }
+
+        let header: BoxHeader = BoxHeader::new(&mut input)
+            .inspect_err(|e| log::warn!("Failed to parse box header. err: {e}"))
+            .ok()?;
+        let payload: &[u8] = header.payload(&mut input)?;
+        Some((header.box_type, payload))
+    })
+}
+
+/// Finds the first box of the given type in `input`.
+pub fn child(input: &[u8], box_type: [u8; 4]) -> Option<&[u8]> {
+    children(input)
+        .find(|(ty, _)| *ty == BoxType::Id(box_type))
+        .map(|(_, payload)| payload)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    /// Makes a box from its type and payload.
+    ///
+    /// `box_type` is usually four bytes. `uuid` boxes put their UUID right
+    /// after it, like `[b"uuid".as_slice(), &uuid].concat()`.
+    pub(crate) fn bx(box_type: &[u8], payload: &[u8]) -> Vec<u8> {
+        [
+            ((payload.len() + 4 + box_type.len()) as u32)
+                .to_be_bytes()
+                .as_slice(),
+            box_type,
+            payload,
+        ]
+        .concat()
+    }
+
+    /// Makes a full box from its type, version, flags, and payload.
+    pub(crate) fn full(box_type: &[u8; 4], version: u8, flags: u8, payload: &[u8]) -> Vec<u8> {
+        bx(
+            box_type,
+            &[[version, 0, 0, flags].as_slice(), payload].concat(),
+        )
+    }
+
+    /// Makes a box holding big-endian words.
+    pub(crate) fn words(box_type: &[u8; 4], words: &[u32]) -> Vec<u8> {
+        bx(
+            box_type,
+            &words
+                .iter()
+                .flat_map(|w| w.to_be_bytes())
+                .collect::<Vec<_>>(),
+        )
+    }
+}
@@ -0,0 +1,251 @@
+//! Finds where each of a track's samples is stored, and when it plays.
+//!
+//! Samples are grouped into chunks, which are stored one after another:
+//!
+//! ```text
+//! stbl
+//! ├── stts         (each sample's duration, run-length encoded)
+//! ├── stsc         (how many samples each chunk has)
+//! ├── stsz         (each sample's size)
+//! └── stco or co64 (each chunk's offset in the file)
+//! ```
+
+use winnow::{
+    Parser as _,
+    binary::{be_u32, be_u64},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::providers::shared::bmff::{child, track};
+
+/// The most samples we'll read from a sample table.
+///
+/// Each entry can claim billions of samples, so this limits allocations.
+pub const MAX_SAMPLES: usize = 1 << 20;
+
+/// One of a track's samples.
+///
+/// Times are in the track's media timescale. See [`track::timescale`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Sample {
+    /// Where the sample starts in the file.
+    pub offset: u64,
+
+    /// The sample's length, in bytes.
+    pub size: u32,
+
+    /// When the sample starts playing.
+    pub start: u64,
+
+    /// How long the sample plays for.
+    pub duration: u32,
+}
+
+/// Lists a track's samples, in order.
+///
+/// Returns `None` if the track is missing its sample sizes, chunk offsets,
+/// or sample-to-chunk table.
+pub fn samples(trak: &[u8]) -> Option<Vec<Sample>> {
+    let stbl: &[u8] = track::sample_table(trak)?;
+
+    let sizes: Vec<u32> = child(stbl, *b"stsz").and_then(sample_sizes)?;
+    let chunk_offsets: Vec<u64> = match child(stbl, *b"stco") {
+        Some(stco) => entries(stco, be_u32.map(u64::from))?,
+        None => entries(child(stbl, *b"co64")?, be_u64)?,
+    };
+    let sample_to_chunk: Vec<(u32, u32)> = entries(
+        child(stbl, *b"stsc")?,
+        (be_u32, be_u32, be_u32)
+            .map(|(first_chunk, samples_per_chunk, _)| (first_chunk, samples_per_chunk)),
+    )?;
+    let durations: Vec<(u32, u32)> = child(stbl, *b"stts")
+        .and_then(|stts| entries(stts, (be_u32, be_u32)))
+        .unwrap_or_default();
+
+    // each sample's duration, which is zero when `stts` is short
+    let mut durations = durations
+        .into_iter()
+        .flat_map(|(count, delta)| core::iter::repeat_n(delta, count as usize));
+
+    let mut sizes = sizes.into_iter();
+    let mut samples: Vec<Sample> = Vec::new();
+    let mut start: u64 = 0;
+
+    // the `stsc` entry for the current chunk. it only moves forward, since
+    // each entry covers chunks until the next one's first chunk
+    let mut entry: usize = 0;
+    for (chunk_index, chunk_offset) in chunk_offsets.into_iter().enumerate() {
+        // chunk numbers start at one
+        let chunk: u32 = u32::try_from(chunk_index + 1).unwrap_or(u32::MAX);
+        while sample_to_chunk
+            .get(entry + 1)
+            .is_some_and(|(first_chunk, _)| *first_chunk <= chunk)
+        {
+            entry += 1;
+        }
+        let samples_per_chunk: u32 = sample_to_chunk
+            .get(entry)
+            .filter(|(first_chunk, _)| *first_chunk <= chunk)
+            .map_or(0, |(_, samples_per_chunk)| *samples_per_chunk);
+
+        let mut offset: u64 = chunk_offset;
+        for _ in 0..samples_per_chunk {
+            let Some(size) = sizes.next() else {
+                return Some(samples);
+            };
+            let duration: u32 = durations.next().unwrap_or_default();
+
+            samples.push(Sample {
+                offset,
+                size,
+                start,
+                duration,
+            });
+            offset = offset.saturating_add(u64::from(size));
+            start = start.saturating_add(u64::from(duration));
+        }
+    }
+
+    if sizes.next().is_some() {
+        log::warn!("Sample table had more samples than its chunks hold.");
+    }
+    Some(samples)
+}
+
+/// Reads each sample's size from an `stsz` box.
+///
+/// When every sample is the same size, it's given once instead.
+fn sample_sizes(mut stsz: &[u8]) -> Option<Vec<u32>> {
+    let input = &mut stsz;
+    let (_, sample_size, sample_count) = (take::<_, _, EmptyError>(4_usize), be_u32, be_u32)
+        .parse_next(input)
+        .inspect_err(|_| log::warn!("`stsz` box was too short."))
+        .ok()?;
+
+    let sample_count: usize = (sample_count as usize).min(MAX_SAMPLES);
+    if sample_size != 0 {
+        return Some(vec![sample_size; sample_count]);
+    }
+
+    let sizes: Vec<u32> = core::iter::from_fn(|| be_u32::<_, EmptyError>.parse_next(input).ok())
+        .take(sample_count)
+        .collect();
+    if sizes.len() < sample_count {
+        log::warn!("`stsz` box was shorter than its sample count.");
+    }
+    Some(sizes)
+}
+
+/// Reads a table box: a full box header, an entry count, then the entries.
+fn entries<'input, O>(
+    mut payload: &'input [u8],
+    mut entry: impl winnow::Parser<&'input [u8], O, EmptyError>,
+) -> Option<Vec<O>> {
+    let input = &mut payload;
+    let (_, entry_count) = (take::<_, _, EmptyError>(4_usize), be_u32)
+        .parse_next(input)
+        .inspect_err(|_| log::warn!("Sample table box was too short."))
+        .ok()?;
+
+    let entry_count: usize = (entry_count as usize).min(MAX_SAMPLES);
+    let entries: Vec<O> = core::iter::from_fn(|| entry.parse_next(input).ok())
+        .take(entry_count)
+        .collect();
+    if entries.len() < entry_count {
+        log::warn!("Sample table box was shorter than its entry count.");
+    }
+    Some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::providers::shared::bmff::tests::{bx, words};
+
+    use super::Sample;
+
+    #[test]
+    fn chunks_and_sizes() {
+        // five samples: two chunks of two, then one of one
+        let stbl = bx(
+            b"stbl",
+            &[
+                words(b"stts", &[0, 2, 4, 100, 1, 50]),
+                words(b"stsc", &[0, 2, 1, 2, 1, 3, 1, 1]),
+                words(b"stsz", &[0, 0, 5, 10, 20, 30, 40, 50]),
+                words(b"stco", &[0, 3, 1000, 2000, 3000]),
+            ]
+            .concat(),
+        );
+        let trak = bx(b"mdia", &bx(b"minf", &stbl));
+
+        let sample = |offset, size, start, duration| Sample {
+            offset,
+            size,
+            start,
+            duration,
+        };
+        assert_eq!(
+            super::samples(&trak),
+            Some(vec![
+                sample(1000, 10, 0, 100),
+                sample(1010, 20, 100, 100),
+                sample(2000, 30, 200, 100),
+                sample(2030, 40, 300, 100),
+                sample(3000, 50, 400, 50),
+            ])
+        );
+    }
+
+    #[test]
+    fn large_offsets() {
+        // one chunk past 4 GiB, with two 7-byte samples and no timing
+        let co64: Vec<u8> = [
+            [0_u8; 4].as_slice(),
+            &1_u32.to_be_bytes(),
+            &(1_u64 << 33).to_be_bytes(),
+        ]
+        .concat();
+        let stbl = bx(
+            b"stbl",
+            &[
+                words(b"stsc", &[0, 1, 1, 2, 1]),
+                words(b"stsz", &[0, 7, 2]),
+                bx(b"co64", &co64),
+            ]
+            .concat(),
+        );
+        let trak = bx(b"mdia", &bx(b"minf", &stbl));
+
+        let offsets: Option<Vec<u64>> =
+            super::samples(&trak).map(|samples| samples.iter().map(|s| s.offset).collect());
+        assert_eq!(offsets, Some(vec![1 << 33, (1 << 33) + 7]));
+    }
+
+    /// Each chunk's `stsc` entry is found without rescanning the table.
+    #[test]
+    fn many_chunks() {
+        const CHUNKS: u32 = 100_000;
+
+        // every chunk is empty, except the last one
+        let stsc: Vec<u32> = [0, CHUNKS]
+            .into_iter()
+            .chain((1..=CHUNKS).flat_map(|chunk| [chunk, u32::from(chunk == CHUNKS), 1]))
+            .collect();
+        let stco: Vec<u32> = [0, CHUNKS].into_iter().chain(1..=CHUNKS).collect();
+        let stbl = bx(
+            b"stbl",
+            &[
+                words(b"stsc", &stsc),
+                words(b"stsz", &[0, 4, 1]),
+                words(b"stco", &stco),
+            ]
+            .concat(),
+        );
+        let trak = bx(b"mdia", &bx(b"minf", &stbl));
+
+        let offsets: Option<Vec<u64>> =
+            super::samples(&trak).map(|samples| samples.iter().map(|s| s.offset).collect());
+        assert_eq!(offsets, Some(vec![u64::from(CHUNKS)]));
+    }
+}
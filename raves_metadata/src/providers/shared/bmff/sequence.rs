@@ -0,0 +1,234 @@
+//! Reads timing info from an image sequence, like an animated AVIF (`avis`).
+//!
+//! Sequences are stored like video, in the `moov` box:
+//!
+//! ```text
+//! moov
+//! └── trak
+//!     ├── tkhd      (track duration)
+//!     ├── edts/elst (whether the track repeats)
+//!     └── mdia
+//!         ├── mdhd  (media timescale)
+//!         ├── hdlr  (`pict` for image sequences)
+//!         └── minf/stbl/stts (each sample's duration)
+//! ```
+
+use core::time::Duration;
+
+use winnow::{
+    Parser as _,
+    binary::{be_u32, be_u64, u8},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::providers::{
+    animation_info::{AnimationInfo, LoopCount},
+    shared::bmff::{child, sample_table::MAX_SAMPLES, track},
+};
+
+/// Finds the first image sequence track in the file, then reads its timing.
+///
+/// Returns `None` if the file has no `moov` box, or no `pict` track.
+pub fn animation_info(input: &[u8]) -> Option<AnimationInfo> {
+    track::tracks(input).find_map(track)
+}
+
+/// Reads one `trak` box, if it's an image sequence.
+fn track(trak: &[u8]) -> Option<AnimationInfo> {
+    // image sequences use the `pict` handler
+    let handler: [u8; 4] = track::handler(trak)?;
+    if handler != *b"pict" {
+        log::trace!("Skipping track with handler `{handler:?}`.");
+        return None;
+    }
+
+    let media_timescale: u32 = track::timescale(trak)?;
+    let stts: &[u8] = track::sample_table(trak).and_then(|stbl| child(stbl, *b"stts"))?;
+    let frame_delays: Vec<Duration> = sample_durations(stts, media_timescale)?;
+
+    let loop_count: LoopCount = loop_count(trak);
+
+    Some(AnimationInfo {
+        frame_count: frame_delays.len().try_into().unwrap_or(u32::MAX),
+        frame_delays,
+        loop_count,
+    })
+}
+
+/// Reads each sample's duration from an `stts` (decoding time to sample) box.
+///
+/// It's run-length encoded, with a sample count and delta for each entry.
+fn sample_durations(mut stts: &[u8], timescale: u32) -> Option<Vec<Duration>> {
+    let input = &mut stts;
+    let (_, entry_count) = (take::<_, _, EmptyError>(4_usize), be_u32)
+        .parse_next(input)
+        .ok()?;
+
+    let mut durations: Vec<Duration> = Vec::new();
+    for _ in 0..entry_count {
+        let Ok::<_, EmptyError>((sample_count, sample_delta)) = (be_u32, be_u32).parse_next(input)
+        else {
+            log::warn!("`stts` box was shorter than its entry count.");
+            break;
+        };
+
+        let delay: Duration =
+            Duration::from_secs_f64(f64::from(sample_delta) / f64::from(timescale));
+        let remaining: usize = MAX_SAMPLES.saturating_sub(durations.len());
+        let sample_count: usize = usize::try_from(sample_count).unwrap_or(usize::MAX);
+        if sample_count > remaining {
+            log::warn!("Image sequence had too many samples. Truncating.");
+        }
+        durations.extend(core::iter::repeat_n(delay, sample_count.min(remaining)));
+    }
+
+    Some(durations)
+}
+
+/// Finds how many times the track plays.
+///
+/// The edit list (`elst`) has flag `1` when the track repeats. It then
+/// repeats until the track duration (from `tkhd`) is over, which is "forever"
+/// when that duration is all ones. Both durations use the movie's timescale,
+/// so it isn't needed here.
+///
+/// Without an edit list, there's no loop count. Browsers loop those forever,
+/// so that's what we report.
+fn loop_count(trak: &[u8]) -> LoopCount {
+    let Some(elst) = child(trak, *b"edts").and_then(|edts| child(edts, *b"elst")) else {
+        return LoopCount::Infinite;
+    };
+
+    let input = &mut &elst[..];
+    let Ok::<_, EmptyError>((version, _, flags, _entry_count)) =
+        (u8, take(2_usize), u8, be_u32).parse_next(input)
+    else {
+        log::warn!("`elst` box was too short.");
+        return LoopCount::Infinite;
+    };
+
+    if flags & 1 == 0 {
+        return LoopCount::Finite(1);
+    }
+
+    // the first entry's duration is one repetition
+    let segment_duration: Option<u64> = if version == 1 {
+        be_u64::<_, EmptyError>.parse_next(input).ok()
+    } else {
+        be_u32::<_, EmptyError>
+            .map(u64::from)
+            .parse_next(input)
+            .ok()
+    };
+
+    // the track duration is infinite if all its bits are set
+    let track_duration: Option<u64> = child(trak, *b"tkhd").and_then(|tkhd| {
+        let input = &mut &tkhd[..];
+        let version: u8 = u8::<_, EmptyError>.parse_next(input).ok()?;
+        if version == 1 {
+            (take::<_, _, EmptyError>(3 + 8 + 8 + 4 + 4_usize), be_u64)
+                .parse_next(input)
+                .ok()
+                .map(|(_, duration)| duration)
+        } else {
+            (take::<_, _, EmptyError>(3 + 4 + 4 + 4 + 4_usize), be_u32)
+                .parse_next(input)
+                .ok()
+                .map(|(_, duration)| match duration {
+                    u32::MAX => u64::MAX,
+                    duration => duration.into(),
+                })
+        }
+    });
+
+    match (track_duration, segment_duration) {
+        (Some(u64::MAX), _) => LoopCount::Infinite,
+        (Some(track), Some(segment)) if segment != 0 => {
+            LoopCount::Finite(track.div_ceil(segment).try_into().unwrap_or(u32::MAX))
+        }
+        _ => LoopCount::Infinite,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::providers::{animation_info::LoopCount, shared::bmff::tests::bx};
+
+    /// Makes an image sequence with the given edit list flags and track
+    /// duration.
+    fn sequence(elst_flags: u8, track_duration: u32) -> Vec<u8> {
+        // version 0: flags, times, then the timescale
+        let header_with_timescale =
+            |timescale: u32| [[0_u8; 12].as_slice(), &timescale.to_be_bytes(), &[0; 4]].concat();
+
+        // flags, times, track ID, reserved, then duration
+        let tkhd: Vec<u8> = [[0_u8; 20].as_slice(), &track_duration.to_be_bytes()].concat();
+
+        // one entry, with a duration of 300 (in the movie timescale)
+        let elst: Vec<u8> = [
+            [0, 0, 0, elst_flags].as_slice(),
+            &1_u32.to_be_bytes(),
+            &300_u32.to_be_bytes(),
+            &[0; 8],
+        ]
+        .concat();
+
+        // two frames of 100, then one of 50 (in the media timescale)
+        let stts: Vec<u8> = [0_u32, 2, 2, 100, 1, 50].map(u32::to_be_bytes).concat();
+
+        let trak: Vec<u8> = [
+            bx(b"tkhd", &tkhd),
+            bx(b"edts", &bx(b"elst", &elst)),
+            bx(
+                b"mdia",
+                &[
+                    bx(b"mdhd", &header_with_timescale(1000)),
+                    bx(b"hdlr", b"\0\0\0\0\0\0\0\0pict"),
+                    bx(b"minf", &bx(b"stbl", &bx(b"stts", &stts))),
+                ]
+                .concat(),
+            ),
+        ]
+        .concat();
+
+        [
+            bx(b"ftyp", b"avis\0\0\0\0"),
+            bx(b"moov", &bx(b"trak", &trak)),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn repeating_sequence() {
+        let info = super::animation_info(&sequence(1, 900)).unwrap();
+
+        assert_eq!(info.frame_count, 3);
+        assert_eq!(
+            info.frame_delays,
+            [
+                Duration::from_millis(100),
+                Duration::from_millis(100),
+                Duration::from_millis(50)
+            ]
+        );
+        assert_eq!(info.total_duration(), Duration::from_millis(250));
+        assert_eq!(info.loop_count, LoopCount::Finite(3));
+    }
+
+    #[test]
+    fn infinite_and_single_play() {
+        let infinite = super::animation_info(&sequence(1, u32::MAX)).unwrap();
+        assert_eq!(infinite.loop_count, LoopCount::Infinite);
+
+        let once = super::animation_info(&sequence(0, 300)).unwrap();
+        assert_eq!(once.loop_count, LoopCount::Finite(1));
+    }
+
+    #[test]
+    fn no_sequence() {
+        assert_eq!(super::animation_info(&[]), None);
+    }
+}
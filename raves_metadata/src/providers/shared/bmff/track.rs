@@ -0,0 +1,169 @@
+//! Reads info about a movie's tracks, like in MP4 and MOV files.
+//!
+//! Each track is a `trak` box in the `moov` box:
+//!
+//! ```text
+//! moov
+//! └── trak
+//!     ├── tkhd      (transformation matrix)
+//!     └── mdia
+//!         ├── mdhd  (media timescale)
+//!         ├── hdlr  (`vide` for video)
+//!         └── minf/stbl/stsd (sample format, like `avc1` or `gpmd`)
+//! ```
+
+use winnow::{
+    Parser as _,
+    binary::{be_i32, be_u32, u8},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::providers::{
+    orientation::DisplayOrientation,
+    shared::bmff::{BoxType, child, children},
+};
+
+/// Iterates over each track's `trak` payload.
+///
+/// Returns nothing if the file has no `moov` box.
+pub fn tracks(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    child(input, *b"moov")
+        .into_iter()
+        .flat_map(children)
+        .filter(|(ty, _)| *ty == BoxType::Id(*b"trak"))
+        .map(|(_, trak)| trak)
+}
+
+/// Reads a track's handler type, like `vide`, `soun`, or `pict`.
+pub fn handler(trak: &[u8]) -> Option<[u8; 4]> {
+    // skip the full box header and `pre_defined` field
+    child(trak, *b"mdia")
+        .and_then(|mdia| child(mdia, *b"hdlr"))
+        .and_then(|hdlr| hdlr.get(8..12))
+        .and_then(|handler| handler.try_into().ok())
+}
+
+/// Reads a track's media timescale, in units per second, from its `mdhd`
+/// box.
+///
+/// It starts with a full box header, then the creation and modification
+/// times, which are 64-bit in version 1.
+pub fn timescale(trak: &[u8]) -> Option<u32> {
+    let mut mdhd: &[u8] = child(trak, *b"mdia").and_then(|mdia| child(mdia, *b"mdhd"))?;
+    let input = &mut mdhd;
+    let version: u8 = u8::<_, EmptyError>.parse_next(input).ok()?;
+    let times_len: usize = if version == 1 { 16 } else { 8 };
+
+    (take::<_, _, EmptyError>(3 + times_len), be_u32)
+        .parse_next(input)
+        .ok()
+        .map(|(_, timescale)| timescale)
+        .filter(|timescale| *timescale != 0)
+}
+
+/// Finds a track's sample table (`stbl`) box.
+pub fn sample_table(trak: &[u8]) -> Option<&[u8]> {
+    child(trak, *b"mdia")
+        .and_then(|mdia| child(mdia, *b"minf"))
+        .and_then(|minf| child(minf, *b"stbl"))
+}
+
+/// Reads the format of a track's first sample entry, like `avc1` or `gpmd`.
+pub fn sample_entry(trak: &[u8]) -> Option<[u8; 4]> {
+    // skip the full box header and entry count, then the entry's size
+    sample_table(trak)
+        .and_then(|stbl| child(stbl, *b"stsd"))
+        .and_then(|stsd| stsd.get(12..16))
+        .and_then(|format| format.try_into().ok())
+}
+
+/// Finds the first video track's `trak` payload.
+pub fn video(input: &[u8]) -> Option<&[u8]> {
+    tracks(input).find(|trak| handler(trak) == Some(*b"vide"))
+}
+
+/// Reads the first video track's orientation from its `tkhd` matrix.
+pub fn video_orientation(input: &[u8]) -> Option<DisplayOrientation> {
+    video(input)
+        .and_then(|trak| child(trak, *b"tkhd"))
+        .and_then(matrix_orientation)
+}
+
+/// Reads the transformation matrix from a `tkhd` box.
+///
+/// It comes after the times, track ID, duration (which are 64-bit in
+/// version 1), layer, alternate group, and volume.
+fn matrix_orientation(mut tkhd: &[u8]) -> Option<DisplayOrientation> {
+    let input = &mut tkhd;
+    let version: u8 = u8::<_, EmptyError>.parse_next(input).ok()?;
+    let skipped: usize = if version == 1 {
+        3 + 8 + 8 + 4 + 4 + 8
+    } else {
+        3 + 4 + 4 + 4 + 4 + 4
+    };
+
+    // then 8 reserved bytes, and four 16-bit fields
+    let (_, a, b, _u, c, d) = (
+        take::<_, _, EmptyError>(skipped + 8 + 8),
+        be_i32,
+        be_i32,
+        be_i32,
+        be_i32,
+        be_i32,
+    )
+        .parse_next(input)
+        .inspect_err(|_| log::warn!("`tkhd` box was too short."))
+        .ok()?;
+
+    DisplayOrientation::from_track_matrix(a, b, c, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::providers::{
+        orientation::{DisplayOrientation, Rotation},
+        shared::bmff::tests::bx,
+    };
+
+    /// Makes a track with a handler and matrix.
+    fn trak(handler: &[u8; 4], matrix: [i32; 9]) -> Vec<u8> {
+        let tkhd: Vec<u8> = [
+            [0_u8; 4 + 20 + 8 + 8].as_slice(),
+            &matrix.map(i32::to_be_bytes).concat(),
+            &[0; 8],
+        ]
+        .concat();
+        let hdlr: Vec<u8> = [[0_u8; 8].as_slice(), handler, &[0; 13]].concat();
+
+        bx(
+            b"trak",
+            &[bx(b"tkhd", &tkhd), bx(b"mdia", &bx(b"hdlr", &hdlr))].concat(),
+        )
+    }
+
+    #[test]
+    fn rotated_video_track() {
+        const ONE: i32 = 0x0001_0000;
+        const W: i32 = 0x4000_0000;
+
+        // the sound track is ignored
+        let file: Vec<u8> = bx(
+            b"moov",
+            &[
+                trak(b"soun", [ONE, 0, 0, 0, ONE, 0, 0, 0, W]),
+                trak(b"vide", [0, ONE, 0, -ONE, 0, 0, 1080 * ONE, 0, W]),
+            ]
+            .concat(),
+        );
+
+        assert_eq!(
+            super::video_orientation(&file),
+            Some(DisplayOrientation {
+                mirrored: false,
+                rotation: Rotation::Clockwise90
+            })
+        );
+        assert_eq!(super::video_orientation(&[]), None);
+    }
+}
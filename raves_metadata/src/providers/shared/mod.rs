@@ -17,6 +17,7 @@ use winnow::{
 };
 
 pub mod bmff;
+pub mod riff;
 
 /// Creates a string description for `winnow` context.
 pub const fn desc(s: &'static str) -> StrContext {
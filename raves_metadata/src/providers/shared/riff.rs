@@ -0,0 +1,248 @@
+//! RIFF, the chunk-based container used by WebP, AVI, and WAV.
+//!
+//! A RIFF file is one big chunk, which holds a form type and more chunks:
+//!
+//! ```text
+//! RIFF [size] WEBP      (or `AVI `, `WAVE`)
+//! ├── VP8X
+//! ├── LIST [size] INFO  (a list, with its own chunks)
+//! │   ├── INAM
+//! │   └── ICMT
+//! └── XMP
+//! ```
+//!
+//! Each chunk is a four-character code, a little-endian length, then its
+//! payload. Payloads with an odd length are followed by a padding byte.
+
+use winnow::{
+    ModalResult, Parser as _,
+    binary::le_u32,
+    error::{ContextError, EmptyError},
+    token::{literal, take},
+};
+
+use crate::providers::shared::desc;
+
+/// The chunk type for lists of chunks.
+pub const LIST: [u8; 4] = *b"LIST";
+
+/// How deep we'll go into nested `LIST` chunks.
+///
+/// RIFF itself has no limit, but the forms we read barely nest: AVI's deepest
+/// is a `strl` inside `hdrl`, and WAV and WebP only have `INFO`. Each level
+/// is another call to [`find`], so sixteen is plenty for real files while
+/// capping how much stack a crafted one can use.
+const MAX_LIST_DEPTH: usize = 16;
+
+/// The header at the start of every RIFF file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RiffHeader {
+    /// How large the file says it is.
+    ///
+    /// This counts everything after the size field itself, so it's eight
+    /// bytes less than the real file size.
+    pub file_size: u32,
+
+    /// What kind of file it is, like `WEBP`, `AVI `, or `WAVE`.
+    pub form_type: [u8; 4],
+}
+
+/// A chunk's header.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub struct RiffChunk {
+    pub fourcc: [u8; 4],
+    pub len: u32,
+}
+
+/// Parses out the RIFF header, which should be the first thing in the file.
+pub fn header(input: &mut &[u8]) -> ModalResult<RiffHeader, ContextError> {
+    literal(b"RIFF")
+        .context(desc("`RIFF` signature"))
+        .void()
+        .parse_next(input)?;
+    let file_size: u32 = le_u32.context(desc("file size")).parse_next(input)?;
+    let form_type: [u8; 4] = fourcc.context(desc("form type")).parse_next(input)?;
+
+    Ok(RiffHeader {
+        file_size,
+        form_type,
+    })
+}
+
+/// Parses out a four-character code.
+fn fourcc(input: &mut &[u8]) -> ModalResult<[u8; 4], ContextError> {
+    let slice: &[u8] = take(4_usize).parse_next(input)?;
+
+    // note: `slice` must be four elements long.
+    //
+    // `winnow` requires `slice` to be 4 elements long already, so we can do
+    // this nonsense pretty easily :)
+    let Ok(fourcc) = slice.try_into() else {
+        unreachable!(
+            "fourcc slice is known to be `n` elements long. please report this - it's a bug!"
+        );
+    };
+    Ok(fourcc)
+}
+
+/// Parses out a chunk header.
+///
+/// The format is simple:
+///
+/// - let fourcc = input.read(4)
+/// - let len = input.read_u32()
+/// - let data = input.read(chunk_len)
+///
+/// Chunks with an odd length are followed by a padding byte. This only
+/// parses the header, so use [`chunk_with_payload`] to take both.
+pub fn chunk(input: &mut &[u8]) -> ModalResult<RiffChunk, ContextError> {
+    // grab the chunk identifier (fourcc), then see how long the chunk is
+    let fourcc: [u8; 4] = fourcc.parse_next(input)?;
+    let len: u32 = le_u32.parse_next(input)?;
+
+    Ok(RiffChunk { fourcc, len })
+}
+
+/// Parses out a chunk header, its payload, and its padding byte.
+///
+/// The padding byte comes after the payload, when the payload's length is
+/// odd. Some writers skip it on the last chunk, so a missing one at the end
+/// of the input is fine.
+pub fn chunk_with_payload<'input>(
+    input: &mut &'input [u8],
+) -> ModalResult<(RiffChunk, &'input [u8]), ContextError> {
+    let chunk: RiffChunk = chunk.context(desc("chunk header")).parse_next(input)?;
+    let payload: &[u8] = take(chunk.len)
+        .context(desc("chunk payload"))
+        .parse_next(input)?;
+
+    if !chunk.len.is_multiple_of(2) && take::<_, _, EmptyError>(1_usize).parse_next(input).is_err()
+    {
+        log::debug!("Last chunk was missing its padding byte.");
+    }
+
+    Ok((chunk, payload))
+}
+
+/// Reads each chunk in `input`, in order.
+///
+/// This stops at the end of `input`, or at the first malformed chunk.
+pub fn chunks(mut input: &[u8]) -> impl Iterator<Item = (RiffChunk, &[u8])> {
+    core::iter::from_fn(move || {
+        if input.is_empty() {
+            return None;
+        }
+
+        chunk_with_payload(&mut input)
+            .inspect_err(|e| {
+                log::error!(
+                    "Invalid RIFF chunk! Returning results before erroneous chunk. err: {e}"
+                )
+            })
+            .ok()
+    })
+}
+
+/// Splits a `LIST` chunk's payload into its list type (like `INFO`) and its
+/// chunks.
+pub fn list(payload: &[u8]) -> Option<([u8; 4], &[u8])> {
+    let (list_type, contents) = payload.split_first_chunk::<4>()?;
+    Some((*list_type, contents))
+}
+
+/// Finds the first chunk with the given type, looking inside `LIST`s, too.
+pub fn find_chunk<'input>(input: &'input [u8], fourcc: &[u8; 4]) -> Option<&'input [u8]> {
+    find(input, 0, &|chunk, _| chunk.fourcc == *fourcc)
+}
+
+/// Finds the contents of the first `LIST` with the given type, looking inside
+/// other `LIST`s, too.
+pub fn find_list<'input>(input: &'input [u8], list_type: &[u8; 4]) -> Option<&'input [u8]> {
+    find(input, 0, &|chunk, payload| {
+        chunk.fourcc == LIST && payload.starts_with(list_type)
+    })
+    .map(|payload| &payload[4..])
+}
+
+/// Finds the first chunk (depth-first) that `matches`.
+fn find<'input>(
+    input: &'input [u8],
+    depth: usize,
+    matches: &dyn Fn(&RiffChunk, &[u8]) -> bool,
+) -> Option<&'input [u8]> {
+    if depth > MAX_LIST_DEPTH {
+        log::warn!("RIFF `LIST`s were nested too deeply. Not going any further.");
+        return None;
+    }
+
+    chunks(input).find_map(|(chunk, payload)| {
+        if matches(&chunk, payload) {
+            return Some(payload);
+        }
+
+        (chunk.fourcc == LIST)
+            .then(|| list(payload))
+            .flatten()
+            .and_then(|(_, contents)| find(contents, depth + 1, matches))
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{chunk_with_payload, find_chunk, find_list};
+
+    /// Makes a chunk from its type and payload, with padding.
+    pub(crate) fn chunk(fourcc: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let padding: &[u8] = if payload.len() % 2 == 1 { &[0] } else { &[] };
+        [
+            fourcc.as_slice(),
+            &(payload.len() as u32).to_le_bytes(),
+            payload,
+            padding,
+        ]
+        .concat()
+    }
+
+    /// Makes a RIFF file with the given form type and chunks.
+    pub(crate) fn riff(form_type: &[u8; 4], chunks: &[u8]) -> Vec<u8> {
+        chunk(b"RIFF", &[form_type.as_slice(), chunks].concat())
+    }
+
+    #[test]
+    fn odd_chunks_are_padded() {
+        let input: &[u8] = b"ALPH\x03\0\0\0abc\0EXIF\x01\0\0\0z";
+        let input = &mut &input[..];
+
+        let (alph, payload) = chunk_with_payload(input).unwrap();
+        assert_eq!((&alph.fourcc, payload), (b"ALPH", b"abc".as_slice()));
+
+        // the last chunk is missing its padding, which is fine
+        let (exif, payload) = chunk_with_payload(input).unwrap();
+        assert_eq!((&exif.fourcc, payload), (b"EXIF", b"z".as_slice()));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn truncated_payload() {
+        assert!(chunk_with_payload(&mut &b"VP8 \x10\0\0\0short"[..]).is_err());
+    }
+
+    #[test]
+    fn nested_lists() {
+        // LIST hdrl { avih (odd, so padded), LIST strl { strh } }, LIST INFO { INAM }
+        let input: &[u8] = b"LIST\x22\0\0\0hdrl\
+            avih\x01\0\0\0a\0\
+            LIST\x0C\0\0\0strl\
+            strh\0\0\0\0\
+            LIST\x12\0\0\0INFO\
+            INAM\x06\0\0\0Title\0";
+
+        assert_eq!(find_chunk(input, b"strh"), Some(b"".as_slice()));
+        assert_eq!(find_chunk(input, b"INAM"), Some(b"Title\0".as_slice()));
+        assert_eq!(
+            find_list(input, b"INFO"),
+            Some(b"INAM\x06\0\0\0Title\0".as_slice())
+        );
+        assert_eq!(find_chunk(input, b"_PMX"), None);
+    }
+}
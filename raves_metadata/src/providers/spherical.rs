@@ -0,0 +1,303 @@
+//! 360-degree (spherical) video, from Google's Spherical Video metadata.
+//!
+//! There are two versions, both stored in the video track:
+//!
+//! - Version 1 is XML in a `uuid` box, right in the `trak` box.
+//! - Version 2 uses boxes in the video's sample entry, like `avc1`:
+//!
+//! ```text
+//! avc1
+//! ├── st3d          (stereo mode)
+//! └── sv3d
+//!     ├── svhd      (the software that wrote it)
+//!     └── proj
+//!         ├── prhd  (initial pose)
+//!         └── equi  (or `cbmp`, `mshp`: the projection)
+//! ```
+//!
+//! See <https://github.com/google/spatial-media/tree/master/docs>.
+
+use std::borrow::Cow;
+
+use winnow::{Parser as _, binary::be_i32, error::EmptyError, token::take};
+
+use crate::providers::shared::bmff::{BoxType, child, children, track};
+
+/// The `uuid` box holding Spherical Video V1 XML.
+const V1_UUID: [u8; 16] = [
+    0xFF, 0xCC, 0x82, 0x63, 0xF8, 0x55, 0x4A, 0x93, 0x88, 0x14, 0x58, 0x7A, 0x02, 0x52, 0x1F, 0xDD,
+];
+
+/// The size of a visual sample entry's fields, before its child boxes.
+///
+/// That's the sample entry's reserved bytes and data reference index (8),
+/// then the visual fields, like width, height, and resolution (70).
+const VISUAL_SAMPLE_ENTRY_LEN: usize = 8 + 70;
+
+/// How a 360-degree video is stored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SphericalVideo {
+    /// Which version of the metadata this came from.
+    pub version: SphericalVersion,
+
+    /// How the sphere is laid out in each frame.
+    pub projection: Projection,
+
+    /// Whether each frame holds one view or one for each eye.
+    pub stereo_mode: StereoMode,
+
+    /// Which way the viewer faces at first.
+    pub pose: Pose,
+
+    /// The software that stitched or wrote the video, if it says.
+    pub software: Option<String>,
+}
+
+/// The version of Google's Spherical Video metadata.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SphericalVersion {
+    /// XML in a `uuid` box.
+    V1,
+
+    /// `st3d` and `sv3d` boxes.
+    V2,
+}
+
+/// How the sphere is laid out in each frame.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Projection {
+    /// Latitude and longitude map to the frame's height and width.
+    Equirectangular,
+
+    /// Six cube faces.
+    Cubemap,
+
+    /// A custom mesh.
+    Mesh,
+
+    /// Any other projection.
+    Other(String),
+}
+
+/// Whether each frame holds one view or one for each eye.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StereoMode {
+    /// One view.
+    #[default]
+    Mono,
+
+    /// The left eye's view is on top.
+    TopBottom,
+
+    /// The left eye's view is on the left.
+    LeftRight,
+
+    /// Any other mode.
+    Other(String),
+}
+
+/// Which way the viewer faces at first, in degrees.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pose {
+    /// Clockwise rotation around the up axis.
+    pub yaw: f64,
+
+    /// Counter-clockwise rotation around the right axis.
+    pub pitch: f64,
+
+    /// Counter-clockwise rotation around the forward axis.
+    pub roll: f64,
+}
+
+/// Finds spherical video metadata in the video track.
+///
+/// Version 2 is preferred, since it's newer.
+pub(crate) fn find(input: &[u8]) -> Option<SphericalVideo> {
+    let trak: &[u8] = track::video(input)?;
+    v2(trak).or_else(|| v1(trak))
+}
+
+/// Reads Spherical Video V2 boxes from the video's sample entry.
+fn v2(trak: &[u8]) -> Option<SphericalVideo> {
+    // skip the `stsd` full box header and entry count
+    let stsd: &[u8] = track::sample_table(trak).and_then(|stbl| child(stbl, *b"stsd"))?;
+    let (_, entry) = children(stsd.get(8..)?).next()?;
+    let entry_boxes: &[u8] = entry.get(VISUAL_SAMPLE_ENTRY_LEN..)?;
+
+    let sv3d: &[u8] = child(entry_boxes, *b"sv3d")?;
+    let proj: Option<&[u8]> = child(sv3d, *b"proj");
+    log::trace!("Found Spherical Video V2 boxes.");
+
+    // equirectangular is the default, like in V1
+    let projection: Projection = proj
+        .and_then(|proj| {
+            children(proj).find_map(|(ty, _)| match ty {
+                BoxType::Id(id) if id == *b"equi" => Some(Projection::Equirectangular),
+                BoxType::Id(id) if id == *b"cbmp" => Some(Projection::Cubemap),
+                BoxType::Id(id) if id == *b"mshp" => Some(Projection::Mesh),
+                _ => None,
+            })
+        })
+        .unwrap_or(Projection::Equirectangular);
+
+    // `st3d` is a full box, then the mode
+    let stereo_mode: StereoMode = match child(entry_boxes, *b"st3d").and_then(|st3d| st3d.get(4)) {
+        None | Some(0) => StereoMode::Mono,
+        Some(1) => StereoMode::TopBottom,
+        Some(2) => StereoMode::LeftRight,
+        Some(other) => StereoMode::Other(other.to_string()),
+    };
+
+    Some(SphericalVideo {
+        version: SphericalVersion::V2,
+        projection,
+        stereo_mode,
+        pose: proj
+            .and_then(|proj| child(proj, *b"prhd"))
+            .and_then(pose)
+            .unwrap_or_default(),
+        software: child(sv3d, *b"svhd")
+            .and_then(|svhd| svhd.get(4..))
+            .and_then(|source| source.split(|b| *b == 0).next())
+            .and_then(|source| str::from_utf8(source).ok())
+            .filter(|source| !source.is_empty())
+            .map(str::to_string),
+    })
+}
+
+/// Reads the pose from a `prhd` box.
+///
+/// It's a full box, then the yaw, pitch, and roll, in 16.16 fixed-point
+/// degrees.
+fn pose(mut prhd: &[u8]) -> Option<Pose> {
+    let (_, yaw, pitch, roll) = (take::<_, _, EmptyError>(4_usize), be_i32, be_i32, be_i32)
+        .parse_next(&mut prhd)
+        .inspect_err(|_| log::warn!("`prhd` box was too short."))
+        .ok()?;
+
+    let degrees = |fixed: i32| f64::from(fixed) / 65536.0;
+    Some(Pose {
+        yaw: degrees(yaw),
+        pitch: degrees(pitch),
+        roll: degrees(roll),
+    })
+}
+
+/// Reads Spherical Video V1 XML from the `trak`'s `uuid` box.
+fn v1(trak: &[u8]) -> Option<SphericalVideo> {
+    let xml: &[u8] = children(trak)
+        .find_map(|(ty, payload)| (ty == BoxType::Uuid(V1_UUID)).then_some(payload))?;
+    log::trace!("Found Spherical Video V1 XML.");
+
+    let root = xmltree::Element::parse(xml)
+        .inspect_err(|e| log::warn!("Spherical Video V1 XML didn't parse. err: {e}"))
+        .ok()?;
+    let text = |name: &str| -> Option<Cow<'_, str>> { root.get_child(name)?.get_text() };
+    let degrees = |name: &str| {
+        text(name)
+            .and_then(|t| t.trim().parse().ok())
+            .unwrap_or(0.0)
+    };
+
+    if text("Spherical").is_some_and(|spherical| spherical.trim() != "true") {
+        return None;
+    }
+
+    Some(SphericalVideo {
+        version: SphericalVersion::V1,
+        projection: match text("ProjectionType").as_deref().map(str::trim) {
+            Some("equirectangular") | None => Projection::Equirectangular,
+            Some(other) => Projection::Other(other.into()),
+        },
+        stereo_mode: match text("StereoMode").as_deref().map(str::trim) {
+            Some("mono") | None => StereoMode::Mono,
+            Some("top-bottom") => StereoMode::TopBottom,
+            Some("left-right") => StereoMode::LeftRight,
+            Some(other) => StereoMode::Other(other.into()),
+        },
+        pose: Pose {
+            yaw: degrees("InitialViewHeadingDegrees"),
+            pitch: degrees("InitialViewPitchDegrees"),
+            roll: degrees("InitialViewRollDegrees"),
+        },
+        software: text("StitchingSoftware").map(|software| software.trim().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{providers::shared::bmff::tests::bx, util::logger};
+
+    use super::{Pose, Projection, SphericalVersion, SphericalVideo, StereoMode, V1_UUID};
+
+    /// Makes a video track with some boxes in its `trak` and sample entry.
+    fn video(trak_boxes: &[u8], entry_boxes: &[u8]) -> Vec<u8> {
+        let hdlr = bx(b"hdlr", &[[0_u8; 8].as_slice(), b"vide", &[0; 13]].concat());
+        let avc1 = bx(b"avc1", &[[0_u8; 78].as_slice(), entry_boxes].concat());
+        let stsd = bx(
+            b"stsd",
+            &[[0_u8, 0, 0, 0, 0, 0, 0, 1].as_slice(), &avc1].concat(),
+        );
+        let mdia = bx(b"mdia", &[hdlr, bx(b"minf", &bx(b"stbl", &stsd))].concat());
+
+        bx(b"moov", &bx(b"trak", &[trak_boxes, &mdia].concat()))
+    }
+
+    #[test]
+    fn v2() {
+        logger();
+
+        let fixed = |degrees: i32| (degrees << 16).to_be_bytes();
+        let prhd: Vec<u8> = [[0_u8; 4].as_slice(), &fixed(90), &fixed(-10), &fixed(0)].concat();
+        let sv3d = bx(
+            b"sv3d",
+            &[
+                bx(b"svhd", b"\0\0\0\0Spatial Media\0"),
+                bx(
+                    b"proj",
+                    &[bx(b"prhd", &prhd), bx(b"equi", &[0; 20])].concat(),
+                ),
+            ]
+            .concat(),
+        );
+        let st3d = bx(b"st3d", &[0, 0, 0, 0, 1]);
+        let file = video(&[], &[st3d, sv3d].concat());
+
+        assert_eq!(
+            super::find(&file),
+            Some(SphericalVideo {
+                version: SphericalVersion::V2,
+                projection: Projection::Equirectangular,
+                stereo_mode: StereoMode::TopBottom,
+                pose: Pose {
+                    yaw: 90.0,
+                    pitch: -10.0,
+                    roll: 0.0
+                },
+                software: Some("Spatial Media".into()),
+            })
+        );
+
+        // regular videos aren't spherical
+        assert_eq!(super::find(&video(&[], &[])), None);
+    }
+
+    #[test]
+    fn v1() {
+        logger();
+
+        let xml: &[u8] = br#"<?xml version="1.0"?><rdf:SphericalVideo xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:GSpherical="http://ns.google.com/videos/1.0/spherical/"><GSpherical:Spherical>true</GSpherical:Spherical><GSpherical:Stitched>true</GSpherical:Stitched><GSpherical:StitchingSoftware>Spherical Metadata Tool</GSpherical:StitchingSoftware><GSpherical:ProjectionType>equirectangular</GSpherical:ProjectionType><GSpherical:StereoMode>left-right</GSpherical:StereoMode><GSpherical:InitialViewHeadingDegrees>180</GSpherical:InitialViewHeadingDegrees></rdf:SphericalVideo>"#;
+        let uuid: Vec<u8> = [b"uuid".as_slice(), &V1_UUID].concat();
+        let file = video(&bx(&uuid, xml), &[]);
+
+        let spherical = super::find(&file).unwrap();
+        assert_eq!(spherical.version, SphericalVersion::V1);
+        assert_eq!(spherical.projection, Projection::Equirectangular);
+        assert_eq!(spherical.stereo_mode, StereoMode::LeftRight);
+        assert_eq!(spherical.pose.yaw, 180.0);
+        assert_eq!(
+            spherical.software.as_deref(),
+            Some("Spherical Metadata Tool")
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! Provider implementation for WAV (Waveform Audio) files.
+//!
+//! WAV is a RIFF file with the `WAVE` form type. Besides `LIST/INFO` tags
+//! and `_PMX` XMP, recorders often add Broadcast WAV (`bext`) and `iXML`
+//! chunks:
+//!
+//! ```text
+//! RIFF WAVE
+//! ├── fmt
+//! ├── bext        (description, originator, and timecode)
+//! ├── iXML        (production info, like the scene and take)
+//! ├── LIST INFO   (tags)
+//! ├── data        (the audio itself)
+//! └── _PMX        (XMP)
+//! ```
+
+use winnow::{
+    Parser as _,
+    binary::{le_i16, le_u16, le_u32},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::{
+    MetadataProvider,
+    exif::{Exif, error::ExifFatalError},
+    providers::{
+        riff_info::{self, RiffInfo},
+        shared::riff::{self, RiffHeader},
+    },
+    xmp::{Xmp, error::XmpError},
+};
+
+/// A WAV (Waveform Audio) file.
+#[derive(Clone, Debug)]
+pub struct Wav {
+    /// From the `LIST/INFO` chunk.
+    info: Option<RiffInfo>,
+
+    /// From the `bext` chunk.
+    bext: Option<BroadcastExtension>,
+
+    /// From the `iXML` chunk.
+    ixml: Option<String>,
+
+    /// From the `_PMX` chunk.
+    xmp: Option<Result<Xmp, XmpError>>,
+}
+
+/// The Broadcast WAV extension (`bext`) chunk.
+///
+/// See EBU Tech 3285.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BroadcastExtension {
+    /// A description of the recording.
+    pub description: String,
+
+    /// Who made the recording, like the recorder's maker.
+    pub originator: String,
+
+    /// A unique reference from the originator.
+    pub originator_reference: String,
+
+    /// When the recording was made, as `yyyy-mm-dd`.
+    pub origination_date: String,
+
+    /// When the recording was made, as `hh:mm:ss`.
+    pub origination_time: String,
+
+    /// How many samples passed since midnight when the recording started.
+    pub time_reference: u64,
+
+    /// The version of the `bext` chunk.
+    pub version: u16,
+
+    /// A SMPTE UMID (unique material identifier), from version 1 on.
+    ///
+    /// It's all zeros when not set.
+    pub umid: [u8; 64],
+
+    /// Loudness values, from version 2 on.
+    pub loudness: Option<Loudness>,
+
+    /// How the audio was made, with one line for each step.
+    pub coding_history: String,
+}
+
+/// Loudness values from a `bext` chunk, in hundredths of their unit.
+///
+/// For example, `-2300` is -23.00 LUFS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Loudness {
+    /// Integrated loudness, in LUFS.
+    pub value: i16,
+
+    /// Loudness range, in LU.
+    pub range: i16,
+
+    /// Maximum true peak level, in dBTP.
+    pub max_true_peak_level: i16,
+
+    /// Highest momentary loudness, in LUFS.
+    pub max_momentary_loudness: i16,
+
+    /// Highest short-term loudness, in LUFS.
+    pub max_short_term_loudness: i16,
+}
+
+impl Wav {
+    /// Returns the `LIST/INFO` tags, like the title and software.
+    ///
+    /// See [`crate::providers::riff_info`].
+    pub fn info(&self) -> Option<&RiffInfo> {
+        self.info.as_ref()
+    }
+
+    /// Returns the Broadcast WAV (`bext`) chunk.
+    pub fn bext(&self) -> Option<&BroadcastExtension> {
+        self.bext.as_ref()
+    }
+
+    /// Returns the `iXML` chunk's XML.
+    pub fn ixml(&self) -> Option<&str> {
+        self.ixml.as_deref()
+    }
+}
+
+impl MetadataProvider for Wav {
+    type ConstructionError = WavConstructionError;
+
+    fn magic_number(input: &[u8]) -> bool {
+        riff::header(&mut &input[..]).is_ok_and(|header| &header.form_type == b"WAVE")
+    }
+
+    /// Reads the given data as a WAV file.
+    fn new(
+        input: &impl AsRef<[u8]>,
+    ) -> Result<Self, <Self as MetadataProvider>::ConstructionError> {
+        let mut input: &[u8] = input.as_ref();
+
+        let header: RiffHeader =
+            riff::header(&mut input).map_err(|_| WavConstructionError::NoHeader)?;
+        if &header.form_type != b"WAVE" {
+            return Err(WavConstructionError::NotAWav(header.form_type));
+        }
+
+        Ok(Self {
+            info: riff_info::find(input),
+            bext: riff::find_chunk(input, b"bext").and_then(bext),
+            ixml: riff::find_chunk(input, b"iXML").map(string),
+            xmp: riff::find_chunk(input, b"_PMX").map(Xmp::new_from_bytes),
+        })
+    }
+
+    /// WAV doesn't store Exif in a standard way.
+    fn exif(&self) -> Option<Result<&Exif, &ExifFatalError>> {
+        None
+    }
+
+    fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
+        self.xmp.as_ref().map(|r| r.as_ref())
+    }
+}
+
+/// Reads a `bext` chunk.
+///
+/// Its fields are fixed-size, then the coding history fills the rest.
+fn bext(mut payload: &[u8]) -> Option<BroadcastExtension> {
+    let input = &mut payload;
+    let text = |len: usize| take::<_, _, EmptyError>(len).map(string);
+
+    // every version has the same layout. older ones just leave some of it
+    // reserved
+    let (
+        (description, originator, originator_reference, origination_date, origination_time),
+        (time_reference_low, time_reference_high, version, umid),
+        loudness,
+        _reserved,
+    ) = (
+        (text(256), text(32), text(32), text(10), text(8)),
+        (le_u32, le_u32, le_u16, take(64_usize)),
+        (le_i16, le_i16, le_i16, le_i16, le_i16),
+        take(180_usize),
+    )
+        .parse_next(input)
+        .inspect_err(|_: &EmptyError| log::warn!("`bext` chunk was too short."))
+        .ok()?;
+
+    Some(BroadcastExtension {
+        description,
+        originator,
+        originator_reference,
+        origination_date,
+        origination_time,
+        time_reference: u64::from(time_reference_high) << 32 | u64::from(time_reference_low),
+        version,
+        umid: umid.try_into().ok()?,
+        loudness: (version >= 2).then_some(Loudness {
+            value: loudness.0,
+            range: loudness.1,
+            max_true_peak_level: loudness.2,
+            max_momentary_loudness: loudness.3,
+            max_short_term_loudness: loudness.4,
+        }),
+        coding_history: string(input),
+    })
+}
+
+/// Reads a string, which ends at its first NUL (if any).
+fn string(bytes: &[u8]) -> String {
+    let end: usize = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// An error that occurred when parsing a WAV.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub enum WavConstructionError {
+    /// Failed to find the required RIFF header.
+    NoHeader,
+
+    /// The RIFF header's form type wasn't `WAVE`. It holds the form type it
+    /// had instead.
+    NotAWav([u8; 4]),
+}
+
+impl core::fmt::Display for WavConstructionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WavConstructionError::NoHeader => {
+                f.write_str("The required RIFF header was not found.")
+            }
+
+            WavConstructionError::NotAWav(form_type) => write!(
+                f,
+                "The RIFF header indicated that this file was not a WAV. \
+                Instead, it's a: `{}`",
+                String::from_utf8_lossy(form_type),
+            ),
+        }
+    }
+}
+
+impl core::error::Error for WavConstructionError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        MetadataProvider as _,
+        magic_number::MagicNumber,
+        providers::{
+            shared::riff::tests::{chunk, riff},
+            wav::{Loudness, Wav},
+        },
+        util::logger,
+    };
+
+    /// Pads a string with NULs.
+    fn padded(s: &[u8], len: usize) -> Vec<u8> {
+        let mut v = s.to_vec();
+        v.resize(len, 0);
+        v
+    }
+
+    /// A field recorder's Broadcast WAV, with a voice memo's tags.
+    #[test]
+    fn broadcast_wav() {
+        logger();
+
+        let bext: Vec<u8> = [
+            padded(b"Interview, take 3", 256),
+            padded(b"ZOOM F6", 32),
+            padded(b"USID0001", 32),
+            b"2025-06-01".to_vec(),
+            b"09:30:00".to_vec(),
+            // 48 kHz, one hour after midnight
+            (48_000_u64 * 3600).to_le_bytes().to_vec(),
+            2_u16.to_le_bytes().to_vec(),
+            vec![0; 64],
+            [-2300_i16, 500, -100, -1800, -2000]
+                .map(i16::to_le_bytes)
+                .concat(),
+            vec![0; 180],
+            b"A=PCM,F=48000,W=24,M=mono\r\n".to_vec(),
+        ]
+        .concat();
+        let info = chunk(
+            b"LIST",
+            &[b"INFO".as_slice(), &chunk(b"ICMT", b"Voice memo\0")].concat(),
+        );
+        let chunks: Vec<u8> = [
+            chunk(b"fmt ", &[0; 16]),
+            chunk(b"bext", &bext),
+            chunk(b"iXML", b"<BWFXML><SCENE>12</SCENE></BWFXML>"),
+            info,
+            chunk(b"data", &[0; 7]),
+        ]
+        .concat();
+        let file: Vec<u8> = riff(b"WAVE", &chunks);
+
+        assert_eq!(MagicNumber::new(&file), Some(MagicNumber::Wav));
+        let wav = Wav::new(&file).unwrap();
+
+        assert_eq!(wav.info().and_then(|i| i.comment()), Some("Voice memo"));
+        assert_eq!(wav.ixml(), Some("<BWFXML><SCENE>12</SCENE></BWFXML>"));
+        assert!(wav.xmp().is_none());
+
+        let bext = wav.bext().expect("has `bext`");
+        assert_eq!(bext.description, "Interview, take 3");
+        assert_eq!(bext.originator, "ZOOM F6");
+        assert_eq!(bext.originator_reference, "USID0001");
+        assert_eq!(bext.origination_date, "2025-06-01");
+        assert_eq!(bext.origination_time, "09:30:00");
+        assert_eq!(bext.time_reference, 172_800_000);
+        assert_eq!(
+            bext.loudness,
+            Some(Loudness {
+                value: -2300,
+                range: 500,
+                max_true_peak_level: -100,
+                max_momentary_loudness: -1800,
+                max_short_term_loudness: -2000,
+            })
+        );
+        assert_eq!(bext.coding_history, "A=PCM,F=48000,W=24,M=mono\r\n");
+    }
+}
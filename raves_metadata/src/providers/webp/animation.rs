@@ -0,0 +1,60 @@
+//! Reads animation timings from a WebP's `ANIM` and `ANMF` chunks.
+//!
+//! Only "extended" WebPs can be animated. Their `VP8X` header has an
+//! animation flag, then the file contains:
+//!
+//! - `ANIM`: the background color and loop count
+//! - `ANMF`: one for each frame, with its offset, size, and duration
+
+use core::time::Duration;
+
+use winnow::{
+    Parser as _,
+    binary::{le_u16, le_u24},
+    error::EmptyError,
+    token::take,
+};
+
+use crate::providers::animation_info::{AnimationInfo, LoopCount};
+
+use super::{chunk::WebpChunk, extended::ExtendedHeader};
+
+/// Reads animation info from the file's chunks.
+pub fn animation_info(
+    chunks: &[(WebpChunk, &[u8])],
+    extended_header: Option<&ExtendedHeader>,
+) -> AnimationInfo {
+    if !extended_header.is_some_and(|header| header.is_animated) {
+        return AnimationInfo::still();
+    }
+
+    let mut loop_count = LoopCount::Infinite;
+    let mut frame_delays: Vec<Duration> = Vec::new();
+
+    for (chunk, mut payload) in chunks.iter().copied() {
+        match &chunk.fourcc {
+            // background color, then loop count. zero loops means "forever"
+            b"ANIM" => match (take(4_usize), le_u16).parse_next(&mut payload) {
+                Ok::<_, EmptyError>((_, 0)) => loop_count = LoopCount::Infinite,
+                Ok((_, loops)) => loop_count = LoopCount::Finite(loops.into()),
+                Err(_) => log::warn!("`ANIM` chunk was too short."),
+            },
+
+            // offset and size, then the duration in milliseconds
+            b"ANMF" => match (take(12_usize), le_u24).parse_next(&mut payload) {
+                Ok::<_, EmptyError>((_, millis)) => {
+                    frame_delays.push(Duration::from_millis(millis.into()))
+                }
+                Err(_) => log::warn!("`ANMF` chunk was too short."),
+            },
+
+            _ => (),
+        }
+    }
+
+    AnimationInfo {
+        frame_count: frame_delays.len().try_into().unwrap_or(u32::MAX),
+        frame_delays,
+        loop_count,
+    }
+}
@@ -0,0 +1,143 @@
+//! Reads the image size from a WebP's first chunk.
+//!
+//! That's one of:
+//!
+//! - `VP8 `: a lossy image, with a VP8 key frame header
+//! - `VP8L`: a lossless image, with its own bit-packed header
+//! - `VP8X`: the "extended" header, which holds the canvas size
+
+use winnow::{
+    Parser as _,
+    binary::{le_u16, le_u24, le_u32},
+    error::EmptyError,
+    token::literal,
+};
+
+use crate::providers::image_info::{ColorType, ImageInfo};
+
+use super::{chunk::WebpChunk, extended::ExtendedHeader};
+
+/// WebP always uses eight bits per channel.
+const BIT_DEPTH: u8 = 8;
+
+/// Reads image info from the first chunk.
+///
+/// For extended files, the canvas comes from the parsed `VP8X` header, and
+/// the `ANMF` chunks are counted as frames.
+pub fn image_info(
+    chunks: &[(WebpChunk, &[u8])],
+    extended_header: Option<&ExtendedHeader>,
+) -> Option<ImageInfo> {
+    let (first_chunk, payload) = chunks.first()?;
+
+    match &first_chunk.fourcc {
+        b"VP8 " => lossy(payload),
+        b"VP8L" => lossless(payload),
+        b"VP8X" => extended_header.map(|header| extended(header, chunks)),
+        other => {
+            log::warn!("WebP started with an unknown chunk: `{other:?}`");
+            None
+        }
+    }
+}
+
+/// Reads a VP8 key frame header.
+fn lossy(mut input: &[u8]) -> Option<ImageInfo> {
+    let input = &mut input;
+
+    // the frame tag's low bit is zero for key frames, which have a size
+    let frame_tag: u32 = le_u24
+        .parse_next(input)
+        .map_err(missing("VP8 frame tag"))
+        .ok()?;
+    if frame_tag & 1 != 0 {
+        log::warn!("VP8 frame wasn't a key frame.");
+        return None;
+    }
+
+    literal::<_, _, EmptyError>([0x9D, 0x01, 0x2A])
+        .void()
+        .parse_next(input)
+        .map_err(missing("VP8 start code"))
+        .ok()?;
+
+    // the top two bits of each are scaling, which doesn't change the size
+    let (width, height) = (le_u16, le_u16)
+        .parse_next(input)
+        .map_err(missing("VP8 dimensions"))
+        .ok()?;
+
+    Some(ImageInfo {
+        width: (width & 0x3FFF).into(),
+        height: (height & 0x3FFF).into(),
+        bit_depth: Some(BIT_DEPTH),
+        color_type: Some(ColorType::YCbCr),
+        channels: Some(3),
+        frame_count: Some(1),
+        jpeg: None,
+    })
+}
+
+/// Reads a VP8L header.
+fn lossless(mut input: &[u8]) -> Option<ImageInfo> {
+    let input = &mut input;
+
+    literal::<_, _, EmptyError>(0x2F)
+        .void()
+        .parse_next(input)
+        .map_err(missing("VP8L signature"))
+        .ok()?;
+
+    // 14 bits each for width and height (minus one), then one for alpha
+    let bits: u32 = le_u32
+        .parse_next(input)
+        .map_err(missing("VP8L header"))
+        .ok()?;
+    let has_alpha: bool = bits & (1 << 28) != 0;
+
+    Some(ImageInfo {
+        width: (bits & 0x3FFF) + 1,
+        height: ((bits >> 14) & 0x3FFF) + 1,
+        bit_depth: Some(BIT_DEPTH),
+        color_type: Some(if has_alpha {
+            ColorType::Rgba
+        } else {
+            ColorType::Rgb
+        }),
+        channels: Some(if has_alpha { 4 } else { 3 }),
+        frame_count: Some(1),
+        jpeg: None,
+    })
+}
+
+/// Reads the canvas size from a `VP8X` header.
+///
+/// The pixel format isn't stored here, so only alpha is reported.
+///
+/// Frames are counted from the `ANMF` chunks, even when the animation flag
+/// says otherwise. There's always at least one.
+fn extended(header: &ExtendedHeader, chunks: &[(WebpChunk, &[u8])]) -> ImageInfo {
+    let frames: usize = chunks.iter().filter(|(c, _)| &c.fourcc == b"ANMF").count();
+    if header.is_animated != (frames > 0) {
+        log::warn!(
+            "`VP8X` flags disagree with the file. `ANMF` chunks found? {}",
+            frames > 0
+        );
+    }
+    let frame_count: usize = frames.max(1);
+
+    ImageInfo {
+        width: header.canvas_width,
+        height: header.canvas_height,
+        bit_depth: Some(BIT_DEPTH),
+        color_type: header.has_alpha.then_some(ColorType::Rgba),
+        channels: header.has_alpha.then_some(4),
+        frame_count: Some(frame_count.try_into().unwrap_or(u32::MAX)),
+        jpeg: None,
+    }
+}
+
+/// Logs which part of a header was missing.
+fn missing(what: &str) -> impl Fn(EmptyError) + '_ {
+    move |_| log::warn!("WebP ended before the {what}.")
+}
@@ -1 +1,70 @@
+//! The `VP8X` chunk, which starts "extended" WebP files.
+//!
+//! It's ten bytes long:
+//!
+//! - one byte of feature flags: `Rsv Rsv I L E X A R`
+//! - three reserved bytes
+//! - the canvas width, minus one (24 bits)
+//! - the canvas height, minus one (24 bits)
 
+use winnow::{
+    Parser as _,
+    binary::{le_u24, u8},
+    error::EmptyError,
+    token::take,
+};
+
+/// An extended WebP's header, from its `VP8X` chunk.
+///
+/// Writers don't always set these flags correctly, so the metadata chunks are
+/// found whether or not their flags are set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExtendedHeader {
+    /// The width of the canvas, in pixels.
+    pub canvas_width: u32,
+
+    /// The height of the canvas, in pixels.
+    pub canvas_height: u32,
+
+    /// `I`: the file has an ICC profile, in an `ICCP` chunk.
+    pub has_icc_profile: bool,
+
+    /// `L`: the image has alpha, either in an `ALPH` chunk or a lossless
+    /// bitstream.
+    pub has_alpha: bool,
+
+    /// `E`: the file has Exif, in an `EXIF` chunk.
+    pub has_exif: bool,
+
+    /// `X`: the file has XMP, in an `XMP ` chunk.
+    pub has_xmp: bool,
+
+    /// `A`: the image is animated, with `ANIM` and `ANMF` chunks.
+    pub is_animated: bool,
+}
+
+impl ExtendedHeader {
+    const ICC_PROFILE: u8 = 0b0010_0000;
+    const ALPHA: u8 = 0b0001_0000;
+    const EXIF: u8 = 0b0000_1000;
+    const XMP: u8 = 0b0000_0100;
+    const ANIMATION: u8 = 0b0000_0010;
+
+    /// Parses a `VP8X` chunk's payload.
+    pub(super) fn new(mut input: &[u8]) -> Option<Self> {
+        let (flags, _reserved, width, height) = (u8, take(3_usize), le_u24, le_u24)
+            .parse_next(&mut input)
+            .map_err(|_: EmptyError| log::warn!("`VP8X` chunk was too short."))
+            .ok()?;
+
+        Some(Self {
+            canvas_width: width + 1,
+            canvas_height: height + 1,
+            has_icc_profile: flags & Self::ICC_PROFILE != 0,
+            has_alpha: flags & Self::ALPHA != 0,
+            has_exif: flags & Self::EXIF != 0,
+            has_xmp: flags & Self::XMP != 0,
+            is_animated: flags & Self::ANIMATION != 0,
+        })
+    }
+}
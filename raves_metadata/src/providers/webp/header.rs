@@ -1,53 +1,63 @@
 use winnow::{
-    ModalResult, Parser as _,
-    binary::le_u32,
+    ModalResult,
     error::{ContextError, ErrMode, StrContext},
-    token::literal,
 };
 
+use crate::providers::shared::riff::{self, RiffHeader};
+
+/// The RIFF header at the start of every WebP file.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
 pub struct WebpFileHeader {
     /// Represents how large the file is.
     ///
     /// This value will have a maximum of `(u32::MAX - 10)`.
-    _file_size: u32,
+    file_size: u32,
+}
+
+impl WebpFileHeader {
+    /// Returns the file size from the RIFF header.
+    ///
+    /// This counts everything after the size field itself, so it's eight
+    /// bytes less than the real file size.
+    pub fn file_size(&self) -> u32 {
+        self.file_size
+    }
 }
 
 /// Parses out the WebP file header block.
 ///
 /// This should be the first thing in the file.
 pub fn webp_file_header(input: &mut &[u8]) -> ModalResult<WebpFileHeader, ContextError> {
-    // first, we should ensure there's `RIFF` (in ASCII) at the beginning
-    const RIFF: &[u8] = b"RIFF";
-    literal(RIFF).void().parse_next(input)?;
-
-    // then, we should have a u32
-    const FILE_SIZE_MAX: u32 = u32::MAX - 10_u32;
-    let file_size: u32 = le_u32.parse_next(input)?;
+    // the RIFF header has the file size and form type
+    let RiffHeader {
+        file_size,
+        form_type,
+    } = riff::header(input)?;
 
     // ensure it's the right size...
+    const FILE_SIZE_MAX: u32 = u32::MAX - 10_u32;
     if file_size > FILE_SIZE_MAX {
         log::error!(
             "File size was reported as `{file_size}` bytes, but maximum is `{FILE_SIZE_MAX}`! \
             Cannot continue parsing..."
         );
-        return Err({
-            let mut ce = ContextError::new();
-            ce.push(StrContext::Expected(
-                winnow::error::StrContextValue::StringLiteral(
-                    "File size was too large to be WebP.",
-                ),
-            ));
-            ErrMode::Cut(ce)
-        });
+        return Err(cut("File size was too large to be WebP."));
     }
 
-    // now, check for the `WEBP` ASCII at the end
-    const WEBP: &[u8] = b"WEBP";
-    literal(WEBP).void().parse_next(input)?;
+    // now, check for the `WEBP` form type
+    if &form_type != b"WEBP" {
+        return Err(cut("`WEBP` form type"));
+    }
 
     // return the file size in the repr struct
-    Ok(WebpFileHeader {
-        _file_size: file_size,
-    })
+    Ok(WebpFileHeader { file_size })
+}
+
+/// Makes an error that stops parsing.
+fn cut(expected: &'static str) -> ErrMode<ContextError> {
+    let mut ce = ContextError::new();
+    ce.push(StrContext::Expected(
+        winnow::error::StrContextValue::StringLiteral(expected),
+    ));
+    ErrMode::Cut(ce)
 }
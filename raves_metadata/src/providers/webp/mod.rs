@@ -1,15 +1,18 @@
 //! WebP-related types.
 
-use winnow::{Parser as _, binary::u8, error::EmptyError, token::take};
-
 use crate::{
     MetadataProvider,
     exif::{Exif, error::ExifFatalError},
+    providers::{animation_info::AnimationInfo, image_info::ImageInfo, shared::riff},
     xmp::{Xmp, error::XmpError},
 };
 
-use self::{chunk::RiffChunk, error::WebpConstructionError, header::WebpFileHeader};
+use self::error::WebpConstructionError;
+
+pub use self::{chunk::WebpChunk, extended::ExtendedHeader, header::WebpFileHeader};
 
+mod animation;
+mod canvas;
 mod chunk;
 mod error;
 mod extended;
@@ -18,10 +21,41 @@ mod header;
 /// A WebP file.
 #[derive(Clone, Debug)]
 pub struct Webp {
-    _header: WebpFileHeader,
-    // relevant_chunks: Vec<(RiffChunk, &'file [u8])>,
+    header: WebpFileHeader,
+    extended_header: Option<ExtendedHeader>,
+    chunks: Vec<WebpChunk>,
+    icc_profile: Option<Vec<u8>>,
     exif: Option<Result<Exif, ExifFatalError>>,
     xmp: Option<Result<Xmp, XmpError>>,
+    image_info: Option<ImageInfo>,
+    animation_info: Option<AnimationInfo>,
+}
+
+impl Webp {
+    /// Returns the RIFF file header.
+    pub fn header(&self) -> &WebpFileHeader {
+        &self.header
+    }
+
+    /// Returns the `VP8X` header, if this is an "extended" WebP.
+    ///
+    /// Simple (lossy or lossless) files don't have one.
+    pub fn extended_header(&self) -> Option<&ExtendedHeader> {
+        self.extended_header.as_ref()
+    }
+
+    /// Returns every chunk in the file, in order.
+    ///
+    /// This includes image chunks (`VP8 `, `VP8L`, `ALPH`), animation chunks
+    /// (`ANIM`, `ANMF`), and metadata chunks (`ICCP`, `EXIF`, `XMP `).
+    pub fn chunks(&self) -> &[WebpChunk] {
+        &self.chunks
+    }
+
+    /// Returns the ICC color profile, from the `ICCP` chunk.
+    pub fn icc_profile(&self) -> Option<&[u8]> {
+        self.icc_profile.as_deref()
+    }
 }
 
 impl MetadataProvider for Webp {
@@ -41,87 +75,30 @@ impl MetadataProvider for Webp {
     fn new(
         input: &impl AsRef<[u8]>,
     ) -> Result<Self, <Self as MetadataProvider>::ConstructionError> {
-        // this does a little parsing, then disposes of the file...
-        let mut input = input.as_ref();
+        let file: &[u8] = input.as_ref();
+        let mut input: &[u8] = file;
 
         // first, look for the header.
         let header =
             header::webp_file_header(&mut input).map_err(|_| WebpConstructionError::NoHeader)?;
 
-        // all WebPs should have at least one chunk
-        let first_chunk = chunk::chunk(&mut input).map_err(|_| WebpConstructionError::NoChunks)?;
-
-        // create an empty type for the file based on those two
-        let mut s = Self {
-            _header: header,
-            exif: None,
-            xmp: None,
-        };
-
-        let mut relevant_chunks = const { Vec::new() };
-
-        // if it's an "extended" WebP, then it'll use the extended file format.
-        //
-        // that means it has a file feature info chunk, `VP8X`! if the file
-        // doesn't have that chunk, then it has no metadata, and isn't useful
-        // to us whatsoever.
-        if &first_chunk.fourcc != b"VP8X" {
-            log::debug!(
-                "Not in 'extended' format: no metadata is present. No relevant chunks exist."
-            );
-            return Ok(s);
-        }
-
-        // then, get info about the file.
+        // then, collect every chunk in the file.
         //
-        // this is arranged in a manner explained in the WebP docs. see:
+        // the format is explained in the WebP docs. see:
         // https://developers.google.com/speed/webp/docs/riff_container
-        let file_info_flags: u8 = u8
-            .parse_next(&mut input)
-            .map_err(|_: EmptyError| WebpConstructionError::MalformedExtendedHeader)?;
-
-        // check the `E` (Exif) and `X` (XMP) presence bits
-        let (has_exif, has_xmp) = (
-            file_info_flags & 0b_0000_1000 != 0,
-            file_info_flags & 0b_0001_0000 != 0,
-        );
-
-        // map the bools into a list of chunks we care about
-        let required_chunks: &[[u8; 4]] = match (has_exif, has_xmp) {
-            (false, false) => {
-                log::debug!("The provided WebP file has no metadata.");
-                return Ok(s);
-            }
-            (false, true) => &[*b"XMP "],
-            (true, false) => &[*b"EXIF"],
-            (true, true) => &[*b"EXIF", *b"XMP "],
-        };
-
-        // consume the remaining 3 bytes of header + 6 bytes of img size
-        take(9_usize)
-            .parse_next(&mut input)
-            .map_err(|_: EmptyError| {
-                log::error!(
-                    "Couldn't consume remaining 'extended' bytes! \
-                    This is a bug! Please report it."
-                );
-                WebpConstructionError::MalformedExtendedHeader
-            })?;
-
-        // account for any padding in the first chunk
-        if first_chunk.len & 1 != 0 {
-            _ = take::<_, _, EmptyError>(1_usize)
-                .void()
-                .parse_next(&mut input);
-        }
-
-        // loop the rest of the file, collecting only chunks we care about.
+        let mut chunks: Vec<(WebpChunk, &[u8])> = Vec::new();
         while !input.is_empty() {
-            log::info!("loopin");
-
-            // grab the chunk header
-            let chunk: RiffChunk = match chunk::chunk(&mut input) {
-                Ok(c) => c,
+            let offset: u64 = (file.len() - input.len()) as u64;
+
+            match riff::chunk_with_payload(&mut input) {
+                Ok((chunk, payload)) => chunks.push((
+                    WebpChunk {
+                        fourcc: chunk.fourcc,
+                        offset,
+                        len: chunk.len,
+                    },
+                    payload,
+                )),
                 Err(e) => {
                     log::error!(
                         "Invalid RIFF chunk in WebP file! Returning \
@@ -129,49 +106,60 @@ impl MetadataProvider for Webp {
                     );
                     break;
                 }
-            };
-
-            // something something borrow checker something
-            let chunk_len: u32 = chunk.len;
-
-            // if it's something we care about, add it and its data to the
-            // relevent chunks list.
-            //
-            // otherwise, take its data and move on!
-            if required_chunks.contains(&chunk.fourcc) {
-                // grab the chunk data
-                let Ok::<_, EmptyError>(chunk_data) = take(chunk.len).parse_next(&mut input) else {
-                    log::warn!(
-                        "Failed to take chunk's length of data. expected len of `{}`, but was only `{}`.",
-                        chunk.len,
-                        input.len()
-                    );
-                    continue;
-                };
-
-                // add it to the vec
-                relevant_chunks.push((chunk, chunk_data));
-            } else {
-                _ = take::<_, _, EmptyError>(chunk.len)
-                    .void()
-                    .parse_next(&mut input);
-            }
-
-            // if the chunk has an odd length, we'll use its padding byte
-            if !chunk_len.is_multiple_of(2) {
-                _ = take::<_, _, EmptyError>(1_usize)
-                    .void()
-                    .parse_next(&mut input);
             }
         }
 
-        const EXIF_CHUNK_HEADER: [u8; 4] = *b"EXIF";
-        const XMP_CHUNK_HEADER: [u8; 4] = *b"XMP ";
+        // all WebPs should have at least one chunk
+        let Some((first_chunk, first_payload)) = chunks.first().copied() else {
+            return Err(WebpConstructionError::NoChunks);
+        };
+
+        // if it's an "extended" WebP, then it starts with a file feature info
+        // chunk, `VP8X`
+        let extended_header: Option<ExtendedHeader> = if &first_chunk.fourcc == b"VP8X" {
+            Some(
+                ExtendedHeader::new(first_payload)
+                    .ok_or(WebpConstructionError::MalformedExtendedHeader)?,
+            )
+        } else {
+            log::debug!("Not in 'extended' format. Metadata isn't expected.");
+            None
+        };
 
-        s.exif = find_chunk(EXIF_CHUNK_HEADER, &relevant_chunks).map(|mut r| Exif::new(&mut r));
-        s.xmp = find_chunk(XMP_CHUNK_HEADER, &relevant_chunks).map(Xmp::new_from_bytes);
+        // find metadata chunks, even when the flags say they aren't there.
+        //
+        // some encoders forget to set them!
+        let find_chunk = |fourcc: &[u8; 4], flag: Option<bool>| {
+            let found: Option<&[u8]> = chunks
+                .iter()
+                .find(|(c, _)| &c.fourcc == fourcc)
+                .map(|(_, payload)| *payload);
+            if found.is_some() != flag.unwrap_or(false) {
+                log::warn!(
+                    "`VP8X` flags disagree with the file. `{}` chunk found? {}",
+                    core::str::from_utf8(fourcc).unwrap_or("not UTF-8"),
+                    found.is_some()
+                );
+            }
+            found
+        };
 
-        Ok(s)
+        let exif = find_chunk(b"EXIF", extended_header.map(|h| h.has_exif))
+            .map(|mut raw| Exif::new(&mut raw));
+        let xmp = find_chunk(b"XMP ", extended_header.map(|h| h.has_xmp)).map(Xmp::new_from_bytes);
+        let icc_profile =
+            find_chunk(b"ICCP", extended_header.map(|h| h.has_icc_profile)).map(<[u8]>::to_vec);
+
+        Ok(Self {
+            header,
+            image_info: canvas::image_info(&chunks, extended_header.as_ref()),
+            animation_info: Some(animation::animation_info(&chunks, extended_header.as_ref())),
+            extended_header,
+            chunks: chunks.into_iter().map(|(chunk, _)| chunk).collect(),
+            icc_profile,
+            exif,
+            xmp,
+        })
     }
 
     fn exif(&self) -> Option<Result<&Exif, &ExifFatalError>> {
@@ -181,27 +169,20 @@ impl MetadataProvider for Webp {
     fn xmp(&self) -> Option<Result<&Xmp, &XmpError>> {
         self.xmp.as_ref().map(|r| r.as_ref())
     }
-}
 
-/// Attempts to find the needle in the list of chunks.
-///
-/// - `needle` is the wanted chunk's header.
-/// - `chunks` comes from the `Webp`'s `relevant_chunks` field.
-fn find_chunk<'vec_ref, 'file: 'vec_ref>(
-    needle: [u8; 4],
-    chunks: &'vec_ref [(RiffChunk, &'file [u8])],
-) -> Option<&'file [u8]> {
-    for (RiffChunk { fourcc, .. }, blob) in chunks {
-        if *fourcc == needle {
-            return Some(blob);
-        }
+    fn image_info(&self) -> Option<&ImageInfo> {
+        self.image_info.as_ref()
     }
 
-    None
+    fn animation_info(&self) -> Option<&AnimationInfo> {
+        self.animation_info.as_ref()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use core::time::Duration;
+
     use raves_metadata_types::{
         exif::{
             Endianness, Field, FieldData, FieldTag,
@@ -214,7 +195,11 @@ mod tests {
     use crate::{
         MetadataProvider,
         exif::{Exif, Ifd},
-        providers::webp::{chunk::RiffChunk, error::WebpConstructionError, find_chunk},
+        providers::{
+            animation_info::LoopCount,
+            image_info::ColorType,
+            webp::{ExtendedHeader, WebpChunk, error::WebpConstructionError},
+        },
         util::logger,
     };
 
@@ -366,6 +351,73 @@ mod tests {
         // there isn't any metadata
         assert!(webp.exif.is_none());
         assert!(webp.xmp.is_none());
+
+        // but it's a lossy image, so its size is in the VP8 header
+        let info = webp.image_info().expect("VP8 chunk has a size");
+        assert_eq!((info.width, info.height), (550, 368));
+        assert_eq!(info.color_type, Some(ColorType::YCbCr));
+        assert_eq!(info.frame_count, Some(1));
+
+        let animation = webp.animation_info().expect("has animation info");
+        assert!(!animation.is_animated());
+    }
+
+    #[test]
+    fn lossless_image_info() {
+        logger();
+
+        // 300x200 (stored minus one), with the alpha bit set
+        let bits: u32 = 299 | (199 << 14) | (1 << 28);
+        let vp8l: Vec<u8> = [[0x2F].as_slice(), &bits.to_le_bytes()].concat();
+        let bytes = &make_webp_sample(vec![(b"VP8L", vp8l.as_slice())]);
+
+        let webp: Webp = Webp::new(bytes).unwrap();
+        let info = webp.image_info().expect("VP8L chunk has a size");
+        assert_eq!((info.width, info.height), (300, 200));
+        assert_eq!(info.color_type, Some(ColorType::Rgba));
+    }
+
+    #[test]
+    fn animated_image_info() {
+        logger();
+
+        #[rustfmt::skip]
+        let vp8x: &[u8] = &[
+            0b0000_0010, // animation flag
+            0, 0, 0, // reserved
+            0x3F, 0x01, 0x00, // canvas width (320, minus one)
+            0xEF, 0x00, 0x00, // canvas height (240, minus one)
+        ];
+        // offset and size, then a duration of 100 ms (and an odd length)
+        let mut frame: [u8; 17] = [0_u8; 17];
+        frame[12] = 100;
+
+        let bytes = &make_webp_sample(vec![
+            (b"VP8X", vp8x),
+            (b"ANIM", [0, 0, 0, 0, 4, 0].as_slice()),
+            (b"ANMF", frame.as_slice()),
+            (b"ANMF", [0_u8; 16].as_slice()),
+            (b"ANMF", frame[..16].as_ref()),
+        ]);
+
+        let webp: Webp = Webp::new(bytes).unwrap();
+        let info = webp.image_info().expect("VP8X chunk has a size");
+        assert_eq!((info.width, info.height), (320, 240));
+        assert_eq!(info.color_type, None);
+        assert_eq!(info.frame_count, Some(3));
+
+        let animation = webp.animation_info().expect("has animation info");
+        assert!(animation.is_animated());
+        assert_eq!(
+            animation.frame_delays,
+            [
+                Duration::from_millis(100),
+                Duration::ZERO,
+                Duration::from_millis(100)
+            ]
+        );
+        assert_eq!(animation.total_duration(), Duration::from_millis(200));
+        assert_eq!(animation.loop_count, LoopCount::Finite(4));
     }
 
     #[test]
@@ -463,27 +515,117 @@ mod tests {
         );
     }
 
-    /// The `find_chunk` function should be able to find all the needles.
+    /// Metadata chunks should be found, even when the `VP8X` flags don't
+    /// mention them.
+    #[test]
+    fn metadata_found_when_flags_lie() {
+        logger();
+
+        const XMP_DATA: &str = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+            <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/" dc:format="image/webp" />
+        </rdf:RDF>"#;
+
+        let vp8x_chunk_data = vp8x(false, false);
+        let bytes = &make_webp_sample(vec![
+            (b"VP8X", &vp8x_chunk_data),
+            (b"VP8 ", &[0x00]),
+            (b"XMP ", XMP_DATA.as_bytes()),
+        ]);
+
+        let webp: Webp = Webp::new(bytes).unwrap();
+        assert!(!webp.extended_header().unwrap().has_xmp);
+        assert!(webp.xmp().is_some_and(|xmp| xmp.is_ok()));
+    }
+
+    /// The `VP8X` header and chunk list should be exposed.
     #[test]
-    fn find_chunk_finds_needles() {
+    fn extended_header_and_chunks() {
         logger();
 
-        let fourcc_list = [b"1234", b"AAAA", b"\0\0\0\0", b"Eggs"];
+        #[rustfmt::skip]
+        let vp8x: &[u8] = &[
+            0b0011_0100, // ICC, alpha, and XMP flags
+            0, 0, 0, // reserved
+            0x3F, 0x01, 0x00, // canvas width (320, minus one)
+            0xEF, 0x00, 0x00, // canvas height (240, minus one)
+        ];
+        let bytes = &make_webp_sample(vec![
+            (b"VP8X", vp8x),
+            (b"ICCP", [1_u8, 2, 3].as_slice()),
+            (b"ALPH", [0_u8; 2].as_slice()),
+            (b"VP8 ", [0_u8; 1].as_slice()),
+        ]);
+
+        let webp: Webp = Webp::new(bytes).unwrap();
+        assert_eq!(webp.header().file_size(), bytes.len() as u32 - 8);
+        assert_eq!(
+            webp.extended_header(),
+            Some(&ExtendedHeader {
+                canvas_width: 320,
+                canvas_height: 240,
+                has_icc_profile: true,
+                has_alpha: true,
+                has_exif: false,
+                has_xmp: true,
+                is_animated: false,
+            })
+        );
+
+        assert_eq!(webp.icc_profile(), Some([1_u8, 2, 3].as_slice()));
 
-        let chunks = fourcc_list.map(|needle| {
-            (
-                RiffChunk {
-                    fourcc: *needle,
-                    len: 0_u32,
+        // offsets skip the file header, chunk headers, and padding
+        assert_eq!(
+            webp.chunks(),
+            &[
+                WebpChunk {
+                    fourcc: *b"VP8X",
+                    offset: 12,
+                    len: 10
                 },
-                needle.as_slice(),
-            )
-        });
+                WebpChunk {
+                    fourcc: *b"ICCP",
+                    offset: 30,
+                    len: 3
+                },
+                WebpChunk {
+                    fourcc: *b"ALPH",
+                    offset: 42,
+                    len: 2
+                },
+                WebpChunk {
+                    fourcc: *b"VP8 ",
+                    offset: 52,
+                    len: 1
+                },
+            ]
+        );
+    }
 
-        for needle in fourcc_list {
-            let maybe_blob = find_chunk(*needle, chunks.as_slice());
-            assert_eq!(maybe_blob, Some(needle.as_slice()));
-        }
+    #[test]
+    fn animated_flag_without_frames() {
+        logger();
+
+        let vp8x: &[u8] = &[0b0000_0010, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let bytes = &make_webp_sample(vec![(b"VP8X", vp8x)]);
+
+        let webp: Webp = Webp::new(bytes).unwrap();
+        let info = webp.image_info().expect("VP8X chunk has a size");
+        assert_eq!(info.frame_count, Some(1));
+    }
+
+    #[test]
+    fn frames_without_animated_flag() {
+        logger();
+
+        let bytes = &make_webp_sample(vec![
+            (b"VP8X", vp8x(false, false).as_slice()),
+            (b"ANMF", [0_u8; 16].as_slice()),
+            (b"ANMF", [0_u8; 16].as_slice()),
+        ]);
+
+        let webp: Webp = Webp::new(bytes).unwrap();
+        let info = webp.image_info().expect("VP8X chunk has a size");
+        assert_eq!(info.frame_count, Some(2));
     }
 
     /// helper: create the `VP8X` chunk (required for "extended" WebP)
@@ -494,7 +636,7 @@ mod tests {
         };
 
         let xmp_bit: u8 = match has_xmp {
-            true => 0b0001_0000,
+            true => 0b0000_0100,
             false => 0b0000_0000,
         };
 
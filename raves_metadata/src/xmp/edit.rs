@@ -0,0 +1,650 @@
+//! Schema-aware editing for [`XmpDocument`].
+//!
+//! Each edit is addressed by a property's namespace URI and name. Before a
+//! value is accepted, we check its shape against the property's entry in
+//! `XMP_PARSING_MAP`, if it has one. Properties without a schema are accepted
+//! as-is.
+
+use raves_metadata_types::xmp::{
+    XmpElement, XmpPrimitive, XmpValue, XmpValueStructField,
+    parse_table::XMP_PARSING_MAP,
+    parse_types::{XmpKind as Kind, XmpKindStructField as Field, XmpPrimitiveKind as Prim},
+};
+
+use crate::xmp::{
+    RDF_NAMESPACE, XmpDocument,
+    error::XmpEditError,
+    lang::{new_language_alternative, set_language},
+};
+
+/// Prefixes we'll use for well-known namespaces when a document doesn't
+/// already define one.
+///
+/// Each entry is in `(namespace, prefix)` form.
+pub(crate) const KNOWN_PREFIXES: &[(&str, &str)] = &[
+    ("adobe:ns:meta/", "x"),
+    ("http://www.w3.org/1999/02/22-rdf-syntax-ns#", "rdf"),
+    ("http://www.w3.org/XML/1998/namespace", "xml"),
+    ("http://ns.adobe.com/xap/1.0/", "xmp"),
+    ("http://ns.adobe.com/xap/1.0/mm/", "xmpMM"),
+    ("http://ns.adobe.com/xap/1.0/bj/", "xmpBJ"),
+    ("http://ns.adobe.com/xap/1.0/t/pg/", "xmpTPg"),
+    ("http://ns.adobe.com/xmp/1.0/DynamicMedia/", "xmpDM"),
+    ("http://ns.adobe.com/xap/1.0/rights/", "xmpRights"),
+    ("http://ns.adobe.com/pdf/1.3/", "pdf"),
+    ("http://ns.adobe.com/photoshop/1.0/", "photoshop"),
+    ("http://ns.adobe.com/camera-raw-settings/1.0/", "crs"),
+    ("http://ns.adobe.com/exif/1.0/", "exif"),
+    ("http://ns.adobe.com/exif/1.0/aux/", "aux"),
+    ("http://cipa.jp/exif/1.0/", "exifEX"),
+    ("http://ns.adobe.com/tiff/1.0/", "tiff"),
+    ("http://purl.org/dc/elements/1.1/", "dc"),
+    (
+        "http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/",
+        "Iptc4xmpCore",
+    ),
+    ("http://iptc.org/std/Iptc4xmpExt/2008-02-29/", "Iptc4xmpExt"),
+    ("http://ns.adobe.com/xap/1.0/sType/Dimensions#", "stDim"),
+    ("http://ns.adobe.com/xap/1.0/sType/ResourceEvent#", "stEvt"),
+    ("http://ns.adobe.com/xap/1.0/sType/ResourceRef#", "stRef"),
+    ("http://ns.adobe.com/xap/1.0/sType/Version#", "stVer"),
+    ("http://ns.adobe.com/xap/1.0/sType/Job#", "stJob"),
+    ("http://ns.adobe.com/xap/1.0/sType/Font#", "stFnt"),
+    ("http://ns.adobe.com/xmp/sType/Area#", "stArea"),
+    (
+        "http://www.metadataworkinggroup.com/schemas/regions/",
+        "mwg-rs",
+    ),
+    (
+        "http://www.metadataworkinggroup.com/schemas/keywords/",
+        "mwg-kw",
+    ),
+    ("http://ns.microsoft.com/photo/1.2/", "MP"),
+    ("http://ns.microsoft.com/photo/1.2/t/RegionInfo#", "MPRI"),
+    ("http://ns.microsoft.com/photo/1.2/t/Region#", "MPReg"),
+    ("http://ns.google.com/photos/1.0/camera/", "GCamera"),
+    ("http://ns.google.com/photos/1.0/container/", "Container"),
+    ("http://ns.google.com/photos/1.0/container/item/", "Item"),
+    ("http://ns.google.com/photos/1.0/depthmap/", "GDepth"),
+    ("http://ns.google.com/photos/1.0/image/", "GImage"),
+    ("http://ns.google.com/photos/1.0/panorama/", "GPano"),
+    ("http://ns.adobe.com/hdr-gain-map/1.0/", "hdrgm"),
+    ("http://ns.apple.com/HDRGainMap/1.0/", "HDRGainMap"),
+];
+
+impl XmpDocument {
+    /// Finds a prefix to use for the given namespace URI.
+    ///
+    /// If the document already uses the namespace, its existing prefix is
+    /// reused. Otherwise, we'll pick the namespace's conventional prefix, or,
+    /// failing that, generate one (`ns1`, `ns2`, ...) that isn't taken.
+    ///
+    /// ```
+    /// use raves_metadata::xmp::Xmp;
+    ///
+    /// let xmp = Xmp::new(r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    ///     <rdf:Description xmlns:my_ns="https://github.com/onkoe" my_ns:Thing="hi" />
+    /// </rdf:RDF>"#).unwrap();
+    ///
+    /// let doc = xmp.document();
+    /// assert_eq!(doc.prefix_for("https://github.com/onkoe"), "my_ns");
+    /// assert_eq!(doc.prefix_for("http://purl.org/dc/elements/1.1/"), "dc");
+    /// assert_eq!(doc.prefix_for("https://example.com/unknown/"), "ns1");
+    /// ```
+    pub fn prefix_for(&self, namespace: &str) -> String {
+        // reuse whatever the document already calls this namespace
+        if let Some(existing) = self.0.iter().find(|e| e.namespace == namespace) {
+            return existing.prefix.clone();
+        }
+
+        // a prefix is "taken" if some other namespace is already using it
+        let taken = |prefix: &str| {
+            self.0
+                .iter()
+                .any(|e| e.prefix == prefix && e.namespace != namespace)
+        };
+
+        if let Some((_, prefix)) = KNOWN_PREFIXES.iter().find(|(ns, _)| *ns == namespace)
+            && !taken(prefix)
+        {
+            return (*prefix).into();
+        }
+
+        (1_u32..)
+            .map(|n| format!("ns{n}"))
+            .find(|candidate| !taken(candidate))
+            .unwrap_or_else(|| unreachable!("there are infinitely many candidate prefixes"))
+    }
+
+    /// Returns the property with the given namespace URI and name, if it's
+    /// present.
+    pub fn get(&self, namespace: &str, name: &str) -> Option<&XmpElement> {
+        self.0
+            .iter()
+            .find(|e| e.namespace == namespace && e.name == name)
+    }
+
+    /// Sets a property to the given value.
+    ///
+    /// If the property already exists, its value is replaced. Otherwise, it's
+    /// added to the document, and its prefix is picked with
+    /// [`XmpDocument::prefix_for`].
+    ///
+    /// When the property has a known schema, `value` must match its shape.
+    ///
+    /// ```
+    /// use raves_metadata::xmp::{Xmp, types::{XmpPrimitive, XmpValue}};
+    ///
+    /// let mut xmp = Xmp::new(r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    ///     <rdf:Description />
+    /// </rdf:RDF>"#).unwrap();
+    ///
+    /// const XMP_BASIC: &str = "http://ns.adobe.com/xap/1.0/";
+    ///
+    /// // `xmp:Rating` is a `Real`, so this is fine...
+    /// xmp.document_mut()
+    ///     .set(XMP_BASIC, "Rating", XmpValue::Simple(XmpPrimitive::Real(4.0)))
+    ///     .unwrap();
+    ///
+    /// // ...but this isn't!
+    /// assert!(
+    ///     xmp.document_mut()
+    ///         .set(XMP_BASIC, "Rating", XmpValue::Simple(XmpPrimitive::Boolean(true)))
+    ///         .is_err()
+    /// );
+    ///
+    /// let rating = xmp.document().get(XMP_BASIC, "Rating").unwrap();
+    /// assert_eq!(rating.prefix, "xmp");
+    /// assert_eq!(rating.value, XmpValue::Simple(XmpPrimitive::Real(4.0)));
+    /// ```
+    pub fn set(
+        &mut self,
+        namespace: &str,
+        name: &str,
+        value: XmpValue,
+    ) -> Result<(), XmpEditError> {
+        if let Some(schema) = XMP_PARSING_MAP.get(&(namespace, name)) {
+            validate(&value, schema, namespace, name)?;
+        }
+
+        match self.get_mut(namespace, name) {
+            Some(existing) => existing.value = value,
+            None => {
+                let prefix = self.prefix_for(namespace);
+                self.0.push(XmpElement {
+                    namespace: namespace.into(),
+                    prefix,
+                    name: name.into(),
+                    value,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes a property from the document, returning it if it was present.
+    pub fn remove(&mut self, namespace: &str, name: &str) -> Option<XmpElement> {
+        let index = self
+            .0
+            .iter()
+            .position(|e| e.namespace == namespace && e.name == name)?;
+
+        Some(self.0.remove(index))
+    }
+
+    /// Appends an item to an array property.
+    ///
+    /// If the property isn't present yet, it's created as the kind of array
+    /// its schema describes. Properties without a schema become unordered
+    /// arrays (`rdf:Bag`).
+    ///
+    /// The item must match the schema of the array's items, if known.
+    pub fn append_to_array(
+        &mut self,
+        namespace: &str,
+        name: &str,
+        item: XmpValue,
+    ) -> Result<(), XmpEditError> {
+        let schema = XMP_PARSING_MAP.get(&(namespace, name));
+
+        // check the item against the array's item schema
+        match schema {
+            Some(Kind::UnorderedArray(item_kind) | Kind::OrderedArray(item_kind)) => {
+                validate(&item, item_kind, namespace, name)?
+            }
+            Some(_) => {
+                return Err(XmpEditError::NotAnArray {
+                    property: name.into(),
+                });
+            }
+            None => (),
+        }
+
+        let li = XmpElement {
+            namespace: RDF_NAMESPACE.into(),
+            prefix: "rdf".into(),
+            name: "li".into(),
+            value: item,
+        };
+
+        // add to the existing array, if there is one
+        if let Some(existing) = self.get_mut(namespace, name) {
+            return match existing.value {
+                XmpValue::UnorderedArray(ref mut items) | XmpValue::OrderedArray(ref mut items) => {
+                    items.push(li);
+                    Ok(())
+                }
+                _ => Err(XmpEditError::NotAnArray {
+                    property: name.into(),
+                }),
+            };
+        }
+
+        // otherwise, make a new one
+        let value = match schema {
+            Some(Kind::OrderedArray(_)) => XmpValue::OrderedArray(vec![li]),
+            _ => XmpValue::UnorderedArray(vec![li]),
+        };
+        let prefix = self.prefix_for(namespace);
+        self.0.push(XmpElement {
+            namespace: namespace.into(),
+            prefix,
+            name: name.into(),
+            value,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the text for one language of a language alternative property,
+    /// like `dc:title`.
+    ///
+    /// `lang` is an RFC 3066 language tag, or `x-default`. The `x-default`
+    /// item is kept consistent with the others - see
+    /// [`set_language`] for the rules.
+    pub fn set_localized_text(
+        &mut self,
+        namespace: &str,
+        name: &str,
+        lang: &str,
+        text: &str,
+    ) -> Result<(), XmpEditError> {
+        if let Some(schema) = XMP_PARSING_MAP.get(&(namespace, name))
+            && *schema != Kind::Alternatives(&Kind::Simple(Prim::Text))
+        {
+            return Err(XmpEditError::NotLanguageAlternative {
+                property: name.into(),
+            });
+        }
+
+        let Some(existing) = self.get_mut(namespace, name) else {
+            let prefix = self.prefix_for(namespace);
+            self.0.push(XmpElement {
+                namespace: namespace.into(),
+                prefix,
+                name: name.into(),
+                value: new_language_alternative(lang, text),
+            });
+            return Ok(());
+        };
+
+        if !set_language(&mut existing.value, lang, text) {
+            return Err(XmpEditError::NotLanguageAlternative {
+                property: name.into(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns a mutable reference to the property with the given namespace
+    /// URI and name, if it's present.
+    fn get_mut(&mut self, namespace: &str, name: &str) -> Option<&mut XmpElement> {
+        self.0
+            .iter_mut()
+            .find(|e| e.namespace == namespace && e.name == name)
+    }
+}
+
+/// Checks that a value has the shape described by `kind`.
+///
+/// `namespace` is the namespace of the property (or struct) that holds the
+/// value. `property` names it for error messages.
+fn validate(
+    value: &XmpValue,
+    kind: &'static Kind,
+    namespace: &str,
+    property: &str,
+) -> Result<(), XmpEditError> {
+    let mismatch = || XmpEditError::SchemaMismatch {
+        property: property.into(),
+        expected: kind,
+    };
+
+    match (kind, value) {
+        (Kind::Simple(prim), XmpValue::Simple(p)) => match (prim, p) {
+            (Prim::Boolean, XmpPrimitive::Boolean(_))
+            | (Prim::Date, XmpPrimitive::Date(_))
+            | (Prim::Integer, XmpPrimitive::Integer(_))
+            | (Prim::Real, XmpPrimitive::Real(_))
+            | (Prim::Text, XmpPrimitive::Text(_)) => Ok(()),
+            _ => Err(mismatch()),
+        },
+
+        (Kind::Struct(schema_fields), XmpValue::Struct(fields)) => {
+            for field in fields {
+                let schema_field =
+                    find_field(schema_fields, field, namespace).ok_or_else(|| {
+                        XmpEditError::UnknownStructField {
+                            property: property.into(),
+                            field: field.ident().clone(),
+                        }
+                    })?;
+                validate_field(field, schema_field, namespace)?;
+            }
+            Ok(())
+        }
+
+        (Kind::StructUnspecifiedFields { required_fields }, XmpValue::Struct(fields)) => {
+            for required in *required_fields {
+                let present = fields
+                    .iter()
+                    .find(|f| find_field(core::slice::from_ref(required), f, namespace).is_some())
+                    .ok_or(XmpEditError::MissingStructField {
+                        property: property.into(),
+                        field: required.ident.name(),
+                    })?;
+                validate_field(present, required, namespace)?;
+            }
+            Ok(())
+        }
+
+        (
+            Kind::Union {
+                always,
+                discriminant: schema_discriminant,
+                optional,
+            },
+            XmpValue::Union {
+                discriminant,
+                expected_fields,
+                unexpected_fields: _,
+            },
+        ) => {
+            validate_field(discriminant, schema_discriminant, namespace)?;
+
+            // only check the fields the discriminant tells us about
+            let tag = match discriminant.as_ref() {
+                XmpValueStructField::Value {
+                    value: XmpValue::Simple(XmpPrimitive::Text(tag)),
+                    ..
+                } => Some(tag.as_str()),
+                _ => None,
+            };
+            let variant_fields = optional
+                .iter()
+                .find(|(d, _)| Some(*d) == tag)
+                .map(|(_, fields)| *fields)
+                .unwrap_or(&[]);
+
+            for field in expected_fields {
+                let schema_field = find_field(always, field, namespace)
+                    .or_else(|| find_field(variant_fields, field, namespace))
+                    .ok_or_else(|| XmpEditError::UnknownStructField {
+                        property: property.into(),
+                        field: field.ident().clone(),
+                    })?;
+                validate_field(field, schema_field, namespace)?;
+            }
+            Ok(())
+        }
+
+        (Kind::UnorderedArray(item_kind), XmpValue::UnorderedArray(items))
+        | (Kind::OrderedArray(item_kind), XmpValue::OrderedArray(items)) => items
+            .iter()
+            .try_for_each(|item| validate(&item.value, item_kind, namespace, property)),
+
+        (Kind::Alternatives(item_kind), XmpValue::Alternatives { chosen, list }) => {
+            // the chosen value must be one of the alternatives
+            if !list.iter().any(|(key, _)| *key == chosen.0) {
+                return Err(mismatch());
+            }
+
+            list.iter()
+                .try_for_each(|(_, item)| validate(&item.value, item_kind, namespace, property))
+        }
+
+        _ => Err(mismatch()),
+    }
+}
+
+/// Validates one struct field against its schema.
+fn validate_field(
+    field: &XmpValueStructField,
+    schema_field: &'static Field,
+    parent_namespace: &str,
+) -> Result<(), XmpEditError> {
+    let namespace = field.namespace().map_or(parent_namespace, |ns| ns.as_str());
+
+    let value = match field {
+        XmpValueStructField::Element { element, .. } => &element.value,
+        XmpValueStructField::Value { value, .. } => value,
+    };
+
+    validate(value, schema_field.ty, namespace, field.ident())
+}
+
+/// Finds the schema for a struct field.
+///
+/// Fields that use their parent's namespace may either leave their namespace
+/// blank or spell it out.
+fn find_field(
+    schema_fields: &'static [Field],
+    field: &XmpValueStructField,
+    parent_namespace: &str,
+) -> Option<&'static Field> {
+    let field_ns = field.namespace().map(|ns| ns.as_str());
+
+    schema_fields.iter().find(|schema_field| {
+        schema_field.ident.name() == field.ident()
+            && match schema_field.ident.ns() {
+                Some(ns) => field_ns == Some(ns),
+                None => field_ns.is_none() || field_ns == Some(parent_namespace),
+            }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use raves_metadata_types::xmp::{XmpElement, XmpPrimitive, XmpValue, XmpValueStructField};
+
+    use crate::{
+        util::logger,
+        xmp::{Xmp, error::XmpEditError},
+    };
+
+    const DC: &str = "http://purl.org/dc/elements/1.1/";
+    const IPTC4XMP_CORE: &str = "http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/";
+
+    fn text(t: &str) -> XmpValue {
+        XmpValue::Simple(XmpPrimitive::Text(t.into()))
+    }
+
+    fn blank() -> Xmp {
+        Xmp::new(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+                <rdf:Description />
+            </rdf:RDF>"#,
+        )
+        .unwrap()
+    }
+
+    /// Prefixes are reused when the document already has one, and new ones
+    /// shouldn't collide with existing prefixes.
+    #[test]
+    fn prefixes_are_registered() {
+        logger();
+
+        let mut xmp = Xmp::new(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+                <rdf:Description xmlns:purl="http://purl.org/dc/elements/1.1/" xmlns:dc="https://example.com/not-dc/" purl:source="a" dc:Thing="b" />
+            </rdf:RDF>"#,
+        )
+        .unwrap();
+        let doc = xmp.document_mut();
+
+        // `dc` is taken by another namespace, so we'll keep using `purl`
+        doc.set(DC, "coverage", text("everywhere")).unwrap();
+        assert_eq!(doc.get(DC, "coverage").unwrap().prefix, "purl");
+
+        // an unknown namespace gets a generated prefix
+        doc.set("https://example.com/mine/", "Thing", text("c"))
+            .unwrap();
+        assert_eq!(
+            doc.get("https://example.com/mine/", "Thing")
+                .unwrap()
+                .prefix,
+            "ns1"
+        );
+        assert_eq!(doc.prefix_for("https://example.com/other/"), "ns2");
+    }
+
+    /// Setting, replacing, and removing a property.
+    #[test]
+    fn set_and_remove() {
+        logger();
+
+        let mut xmp = blank();
+        let doc = xmp.document_mut();
+
+        doc.set(DC, "source", text("a")).unwrap();
+        doc.set(DC, "source", text("b")).unwrap();
+        assert_eq!(doc.values_ref().len(), 1);
+        assert_eq!(doc.get(DC, "source").unwrap().value, text("b"));
+
+        assert_eq!(
+            doc.set(DC, "source", XmpValue::Simple(XmpPrimitive::Integer(1))),
+            Err(XmpEditError::SchemaMismatch {
+                property: "source".into(),
+                expected: &raves_metadata_types::xmp::parse_types::XmpKind::Simple(
+                    raves_metadata_types::xmp::parse_types::XmpPrimitiveKind::Text
+                ),
+            })
+        );
+
+        assert_eq!(doc.remove(DC, "source").map(|e| e.value), Some(text("b")));
+        assert!(doc.remove(DC, "source").is_none());
+        assert!(doc.values_ref().is_empty());
+    }
+
+    /// Struct values are checked field-by-field.
+    #[test]
+    fn structs_are_validated() {
+        logger();
+
+        let mut xmp = blank();
+        let doc = xmp.document_mut();
+
+        let field = |name: &str, v: &str| XmpValueStructField::Value {
+            ident: name.into(),
+            namespace: Some(IPTC4XMP_CORE.into()),
+            value: text(v),
+        };
+
+        doc.set(
+            IPTC4XMP_CORE,
+            "CreatorContactInfo",
+            XmpValue::Struct(vec![field("CiEmailWork", "me@example.com")]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            doc.set(
+                IPTC4XMP_CORE,
+                "CreatorContactInfo",
+                XmpValue::Struct(vec![field("CiFavoriteColor", "green")]),
+            ),
+            Err(XmpEditError::UnknownStructField {
+                property: "CreatorContactInfo".into(),
+                field: "CiFavoriteColor".into()
+            })
+        );
+    }
+
+    /// Appending creates arrays of the right kind.
+    #[test]
+    fn append_to_arrays() {
+        logger();
+
+        let mut xmp = blank();
+        let doc = xmp.document_mut();
+
+        doc.append_to_array(DC, "subject", text("cat")).unwrap();
+        doc.append_to_array(DC, "subject", text("cute")).unwrap();
+        doc.append_to_array(DC, "creator", text("Barrett")).unwrap();
+
+        let li = |v: &str| XmpElement {
+            namespace: crate::xmp::RDF_NAMESPACE.into(),
+            prefix: "rdf".into(),
+            name: "li".into(),
+            value: text(v),
+        };
+
+        assert_eq!(
+            doc.get(DC, "subject").unwrap().value,
+            XmpValue::UnorderedArray(vec![li("cat"), li("cute")])
+        );
+        assert_eq!(
+            doc.get(DC, "creator").unwrap().value,
+            XmpValue::OrderedArray(vec![li("Barrett")])
+        );
+
+        // `dc:source` isn't an array
+        assert_eq!(
+            doc.append_to_array(DC, "source", text("nope")),
+            Err(XmpEditError::NotAnArray {
+                property: "source".into()
+            })
+        );
+    }
+
+    /// Localized text makes a default when needed and keeps it in sync.
+    #[test]
+    fn localized_text() {
+        logger();
+
+        let mut xmp = blank();
+        let doc = xmp.document_mut();
+
+        doc.set_localized_text(DC, "title", "en-US", "Hello")
+            .unwrap();
+        doc.set_localized_text(DC, "title", "fr", "Bonjour")
+            .unwrap();
+        doc.set_localized_text(DC, "title", "x-default", "Hi")
+            .unwrap();
+
+        let XmpValue::Alternatives { chosen, list } = &doc.get(DC, "title").unwrap().value else {
+            panic!("should be alternatives");
+        };
+        assert_eq!(chosen.0, "x-default");
+        assert_eq!(chosen.1.value, text("Hi"));
+        assert_eq!(
+            list.iter()
+                .map(|(lang, e)| (lang.as_str(), e.value.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("x-default", text("Hi")),
+                ("en-US", text("Hi")),
+                ("fr", text("Bonjour")),
+            ]
+        );
+
+        assert_eq!(
+            doc.set_localized_text(DC, "source", "en", "nope"),
+            Err(XmpEditError::NotLanguageAlternative {
+                property: "source".into()
+            })
+        );
+    }
+}
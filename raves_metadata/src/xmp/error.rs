@@ -134,6 +134,127 @@ impl From<xmltree::ParseError> for XmpError {
     }
 }
 
+/// An error that happened while editing an [`XmpDocument`](crate::xmp::XmpDocument).
+///
+/// Edits are checked against the schemas in `raves_metadata_types` before
+/// they're applied, so the document isn't changed when one of these occurs.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum XmpEditError {
+    /// The given value didn't have the shape the property's schema requires.
+    SchemaMismatch {
+        /// The property (or struct field) that was being edited.
+        property: String,
+
+        /// The kind the schema expected to find.
+        expected: &'static XmpKind,
+    },
+
+    /// A struct value contained a field that isn't in its schema.
+    UnknownStructField {
+        /// The property (or struct field) that was being edited.
+        property: String,
+
+        /// The unexpected field's name.
+        field: String,
+    },
+
+    /// A struct value was missing a field that its schema requires.
+    MissingStructField {
+        /// The property (or struct field) that was being edited.
+        property: String,
+
+        /// The name of the missing field.
+        field: &'static str,
+    },
+
+    /// Tried to append to a property that isn't an array.
+    NotAnArray {
+        /// The property that was being edited.
+        property: String,
+    },
+
+    /// Tried to set localized text on a property that isn't a language
+    /// alternative.
+    NotLanguageAlternative {
+        /// The property that was being edited.
+        property: String,
+    },
+}
+
+impl core::fmt::Display for XmpEditError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            XmpEditError::SchemaMismatch { property, expected } => write!(
+                f,
+                "The value given for `{property}` didn't match its schema. \
+                    expected: {expected:?}",
+            ),
+            XmpEditError::UnknownStructField { property, field } => write!(
+                f,
+                "The struct given for `{property}` had a field, `{field}`, \
+                    that isn't in its schema.",
+            ),
+            XmpEditError::MissingStructField { property, field } => write!(
+                f,
+                "The struct given for `{property}` was missing required field `{field}`.",
+            ),
+            XmpEditError::NotAnArray { property } => {
+                write!(f, "Can't append to `{property}`, as it isn't an array.")
+            }
+            XmpEditError::NotLanguageAlternative { property } => write!(
+                f,
+                "Can't set localized text on `{property}`, as it isn't a \
+                    language alternative.",
+            ),
+        }
+    }
+}
+
+impl core::error::Error for XmpEditError {}
+
+/// An error that happened while reading an XMP path, like `dc:title[1]`.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+pub enum XmpPathError {
+    /// The path was empty.
+    Empty,
+
+    /// The path couldn't be parsed.
+    InvalidSyntax {
+        /// The byte offset in the path where parsing failed.
+        position: usize,
+
+        /// What we expected to find there.
+        expected: &'static str,
+    },
+
+    /// An array index was zero. XMP array indices start at one.
+    ZeroIndex,
+
+    /// A prefix in the path didn't map to any known namespace.
+    UnknownPrefix(String),
+}
+
+impl core::fmt::Display for XmpPathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            XmpPathError::Empty => f.write_str("The XMP path was empty."),
+            XmpPathError::InvalidSyntax { position, expected } => write!(
+                f,
+                "The XMP path had invalid syntax at byte `{position}`. expected: {expected}",
+            ),
+            XmpPathError::ZeroIndex => {
+                f.write_str("XMP array indices start at `1`, but the path used `0`.")
+            }
+            XmpPathError::UnknownPrefix(prefix) => write!(
+                f,
+                "The XMP path used prefix `{prefix}`, but no namespace is known for it.",
+            ),
+        }
+    }
+}
+
+impl core::error::Error for XmpPathError {}
+
 /// This error occurred in internal parsing.
 ///
 /// We use it for better diagnostics. Note that these are usually converted
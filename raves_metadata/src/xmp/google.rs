@@ -0,0 +1,439 @@
+//! Typed access to Google's camera XMP schemas.
+//!
+//! Pixel phones (and many other Android cameras) write these:
+//!
+//! - `GCamera`, which marks Motion Photos,
+//! - `Container`, which lists the media items appended to a file,
+//! - `GDepth` and `GImage`, which hold a depth map and an extra image, and
+//! - `GPano`, which describes photo spheres and other panoramas.
+//!
+//! All of them are in the parse table, so you can also read them with
+//! [`XmpDocument::get`] or [`XmpDocument::query`].
+
+use core::ops::Range;
+
+use raves_metadata_types::xmp::{XmpPrimitive, XmpValue};
+
+use crate::xmp::{
+    XmpDocument,
+    path::{XmpNode, struct_field},
+};
+
+const G_CAMERA: &str = "http://ns.google.com/photos/1.0/camera/";
+const CONTAINER: &str = "http://ns.google.com/photos/1.0/container/";
+const ITEM: &str = "http://ns.google.com/photos/1.0/container/item/";
+const G_PANO: &str = "http://ns.google.com/photos/1.0/panorama/";
+
+/// Info about a Motion Photo (a still image with a short video).
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct MotionPhotoInfo {
+    /// The version of the Motion Photo format.
+    ///
+    /// For the older "MicroVideo" format, this is `GCamera:MicroVideoVersion`
+    /// instead.
+    pub version: Option<i64>,
+
+    /// Where the still image sits in the video, in microseconds.
+    ///
+    /// This is `None` if the camera didn't say (or said `-1`).
+    pub presentation_timestamp_us: Option<i64>,
+
+    /// For the older "MicroVideo" format, how far the video starts from the
+    /// end of the file, in bytes.
+    pub micro_video_offset: Option<u64>,
+}
+
+/// A media item in a Google container file, from `Container:Directory`.
+///
+/// The first item is the primary image. Every other item is appended to the
+/// file after it, in directory order.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct ContainerItem {
+    /// The item's MIME type, like `video/mp4`.
+    pub mime: String,
+
+    /// What the item is for, like `Primary`, `MotionPhoto`, or `GainMap`.
+    pub semantic: String,
+
+    /// The item's length in bytes.
+    ///
+    /// The primary item usually leaves this out, so it's `0`.
+    pub length: u64,
+
+    /// Bytes of padding before the next item.
+    pub padding: u64,
+
+    /// An optional label, which other items can use to refer to this one.
+    pub label: Option<String>,
+
+    /// Where the item is, if it's not simply appended to the file.
+    pub uri: Option<String>,
+}
+
+/// How a panorama should be displayed, from `GPano`.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Panorama {
+    /// The projection, which is currently always `equirectangular`.
+    pub projection_type: Option<String>,
+
+    /// Whether a panorama viewer should be used to show the image.
+    pub use_panorama_viewer: Option<bool>,
+
+    /// Where this image sits in the full panorama, if it's been cropped.
+    pub cropped_area: Option<PanoramaCrop>,
+
+    /// The camera's orientation when the panorama was captured.
+    pub pose: PanoramaAngles,
+
+    /// The direction a viewer should start looking in.
+    pub initial_view: PanoramaAngles,
+
+    /// The horizontal field of view a viewer should start with, in degrees.
+    pub initial_horizontal_fov_degrees: Option<f64>,
+}
+
+/// Where a cropped panorama image sits within the full panorama.
+///
+/// All values are in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct PanoramaCrop {
+    /// The width of this image.
+    pub image_width: u64,
+
+    /// The height of this image.
+    pub image_height: u64,
+
+    /// The width of the full panorama.
+    pub full_width: u64,
+
+    /// The height of the full panorama.
+    pub full_height: u64,
+
+    /// The distance from the full panorama's left edge to this image's.
+    pub left: u64,
+
+    /// The distance from the full panorama's top edge to this image's.
+    pub top: u64,
+}
+
+/// A heading, pitch, and roll, each in degrees.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Default)]
+pub struct PanoramaAngles {
+    /// The compass heading, from `0` to `360`.
+    pub heading: Option<f64>,
+
+    /// The pitch, from `-90` (down) to `90` (up).
+    pub pitch: Option<f64>,
+
+    /// The roll, from `-180` to `180`.
+    pub roll: Option<f64>,
+}
+
+impl XmpDocument {
+    /// Returns Motion Photo info, if this document marks one.
+    ///
+    /// Both the current format (`GCamera:MotionPhoto`) and the older
+    /// "MicroVideo" format (`GCamera:MicroVideo`) are understood.
+    pub fn motion_photo_info(&self) -> Option<MotionPhotoInfo> {
+        let integer = |name| integer(self.get(G_CAMERA, name).map(XmpNode::Element));
+        let timestamp = |name| integer(name).filter(|t| *t >= 0);
+
+        if integer("MotionPhoto") == Some(1) {
+            return Some(MotionPhotoInfo {
+                version: integer("MotionPhotoVersion"),
+                presentation_timestamp_us: timestamp("MotionPhotoPresentationTimestampUs"),
+                micro_video_offset: None,
+            });
+        }
+
+        if integer("MicroVideo") == Some(1) {
+            return Some(MotionPhotoInfo {
+                version: integer("MicroVideoVersion"),
+                presentation_timestamp_us: timestamp("MicroVideoPresentationTimestampUs"),
+                micro_video_offset: integer("MicroVideoOffset").and_then(|o| o.try_into().ok()),
+            });
+        }
+
+        None
+    }
+
+    /// Returns the items listed in `Container:Directory`, in order.
+    ///
+    /// Items without a MIME type or semantic are skipped.
+    pub fn container_items(&self) -> Vec<ContainerItem> {
+        let Some(directory) = self.get(CONTAINER, "Directory") else {
+            return Vec::new();
+        };
+
+        let (XmpValue::OrderedArray(entries) | XmpValue::UnorderedArray(entries)) =
+            &directory.value
+        else {
+            log::warn!("`Container:Directory` wasn't an array. Ignoring it.");
+            return Vec::new();
+        };
+
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let item = struct_field(&entry.value, CONTAINER, "Item", CONTAINER)?.value();
+                let field = |name| struct_field(item, ITEM, name, CONTAINER);
+                let size = |name| {
+                    integer(field(name))
+                        .and_then(|n| u64::try_from(n).ok())
+                        .unwrap_or(0)
+                };
+
+                Some(ContainerItem {
+                    mime: text(field("Mime"))?,
+                    semantic: text(field("Semantic"))?,
+                    length: size("Length"),
+                    padding: size("Padding"),
+                    label: text(field("Label")),
+                    uri: text(field("URI")),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns each `Container:Directory` item after the primary image, with
+    /// where it is in the file.
+    ///
+    /// Those items are appended one after another, starting after the primary
+    /// image (which ends at `image_end`) and its padding.
+    pub fn container_item_ranges(&self, image_end: usize) -> Vec<(ContainerItem, Range<usize>)> {
+        let items = self.container_items();
+        let mut start = image_end.saturating_add(items.first().map_or(0, |i| i.padding as usize));
+
+        items
+            .into_iter()
+            .skip(1)
+            .map(|item| {
+                let end = start.saturating_add(item.length as usize);
+                let range = start..end;
+                start = end.saturating_add(item.padding as usize);
+                (item, range)
+            })
+            .collect()
+    }
+
+    /// Returns panorama (photo sphere) info, if there's any `GPano` data.
+    pub fn panorama(&self) -> Option<Panorama> {
+        let node = |name| self.get(G_PANO, name).map(XmpNode::Element);
+        let real = |name| real(node(name));
+        let pixels = |name| integer(node(name)).and_then(|n| u64::try_from(n).ok());
+
+        let panorama = Panorama {
+            projection_type: text(node("ProjectionType")),
+            use_panorama_viewer: match node("UsePanoramaViewer").map(|n| n.value()) {
+                Some(XmpValue::Simple(XmpPrimitive::Boolean(b))) => Some(*b),
+                _ => None,
+            },
+            cropped_area: (|| {
+                Some(PanoramaCrop {
+                    image_width: pixels("CroppedAreaImageWidthPixels")?,
+                    image_height: pixels("CroppedAreaImageHeightPixels")?,
+                    full_width: pixels("FullPanoWidthPixels")?,
+                    full_height: pixels("FullPanoHeightPixels")?,
+                    left: pixels("CroppedAreaLeftPixels")?,
+                    top: pixels("CroppedAreaTopPixels")?,
+                })
+            })(),
+            pose: PanoramaAngles {
+                heading: real("PoseHeadingDegrees"),
+                pitch: real("PosePitchDegrees"),
+                roll: real("PoseRollDegrees"),
+            },
+            initial_view: PanoramaAngles {
+                heading: real("InitialViewHeadingDegrees"),
+                pitch: real("InitialViewPitchDegrees"),
+                roll: real("InitialViewRollDegrees"),
+            },
+            initial_horizontal_fov_degrees: real("InitialHorizontalFOVDegrees"),
+        };
+
+        // only say there's a panorama if we found something
+        let any_gpano = self.values_ref().iter().any(|v| v.namespace == G_PANO);
+        any_gpano.then_some(panorama)
+    }
+}
+
+/// Grabs some text.
+fn text(node: Option<XmpNode<'_>>) -> Option<String> {
+    node?.as_text().map(|t| t.to_string())
+}
+
+/// Grabs an integer, even when it was stored as text.
+fn integer(node: Option<XmpNode<'_>>) -> Option<i64> {
+    match node?.value() {
+        XmpValue::Simple(XmpPrimitive::Integer(i)) => Some(*i),
+        XmpValue::Simple(XmpPrimitive::Text(t)) => t.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Grabs a real number, even when it was stored as an integer or text.
+fn real(node: Option<XmpNode<'_>>) -> Option<f64> {
+    match node?.value() {
+        XmpValue::Simple(XmpPrimitive::Real(r)) => Some(*r),
+        XmpValue::Simple(XmpPrimitive::Integer(i)) => Some(*i as f64),
+        XmpValue::Simple(XmpPrimitive::Text(t)) => t.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        util::logger,
+        xmp::{
+            Xmp,
+            google::{ContainerItem, MotionPhotoInfo, PanoramaAngles, PanoramaCrop},
+        },
+    };
+
+    /// A Pixel Motion Photo lists its video in the container directory.
+    #[test]
+    fn pixel_motion_photo() {
+        logger();
+
+        let xmp = Xmp::new(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:GCamera="http://ns.google.com/photos/1.0/camera/"
+        xmlns:Container="http://ns.google.com/photos/1.0/container/"
+        xmlns:Item="http://ns.google.com/photos/1.0/container/item/"
+      GCamera:MotionPhoto="1"
+      GCamera:MotionPhotoVersion="1"
+      GCamera:MotionPhotoPresentationTimestampUs="968644">
+      <Container:Directory>
+        <rdf:Seq>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Mime="image/jpeg" Item:Semantic="Primary" Item:Length="0" Item:Padding="0"/>
+          </rdf:li>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Mime="video/mp4" Item:Semantic="MotionPhoto" Item:Length="2932405" Item:Padding="0"/>
+          </rdf:li>
+        </rdf:Seq>
+      </Container:Directory>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>"#,
+        )
+        .unwrap();
+        let doc = xmp.document();
+
+        assert_eq!(
+            doc.motion_photo_info(),
+            Some(MotionPhotoInfo {
+                version: Some(1),
+                presentation_timestamp_us: Some(968644),
+                micro_video_offset: None,
+            })
+        );
+
+        assert_eq!(
+            doc.container_items(),
+            vec![
+                ContainerItem {
+                    mime: "image/jpeg".into(),
+                    semantic: "Primary".into(),
+                    length: 0,
+                    padding: 0,
+                    label: None,
+                    uri: None,
+                },
+                ContainerItem {
+                    mime: "video/mp4".into(),
+                    semantic: "MotionPhoto".into(),
+                    length: 2932405,
+                    padding: 0,
+                    label: None,
+                    uri: None,
+                },
+            ]
+        );
+
+        let ranges = doc.container_item_ranges(1000);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].0.semantic, "MotionPhoto");
+        assert_eq!(ranges[0].1, 1000..2933405);
+
+        assert!(doc.panorama().is_none());
+    }
+
+    /// Older phones used the "MicroVideo" properties instead.
+    #[test]
+    fn micro_video() {
+        logger();
+
+        let xmp = Xmp::new(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description xmlns:GCamera="http://ns.google.com/photos/1.0/camera/"
+      GCamera:MicroVideo="1"
+      GCamera:MicroVideoVersion="1"
+      GCamera:MicroVideoOffset="4022143"
+      GCamera:MicroVideoPresentationTimestampUs="-1" />
+</rdf:RDF>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            xmp.document().motion_photo_info(),
+            Some(MotionPhotoInfo {
+                version: Some(1),
+                presentation_timestamp_us: None,
+                micro_video_offset: Some(4022143),
+            })
+        );
+    }
+
+    /// Photo spheres have cropping and pose info.
+    #[test]
+    fn photo_sphere() {
+        logger();
+
+        let xmp = Xmp::new(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description xmlns:GPano="http://ns.google.com/photos/1.0/panorama/"
+      GPano:ProjectionType="equirectangular"
+      GPano:UsePanoramaViewer="True"
+      GPano:CroppedAreaImageWidthPixels="4000"
+      GPano:CroppedAreaImageHeightPixels="2000"
+      GPano:FullPanoWidthPixels="5000"
+      GPano:FullPanoHeightPixels="2500"
+      GPano:CroppedAreaLeftPixels="500"
+      GPano:CroppedAreaTopPixels="250"
+      GPano:PoseHeadingDegrees="350.0"
+      GPano:PosePitchDegrees="-2.5"
+      GPano:InitialViewHeadingDegrees="90" />
+</rdf:RDF>"#,
+        )
+        .unwrap();
+
+        let pano = xmp.document().panorama().unwrap();
+        assert_eq!(pano.projection_type.as_deref(), Some("equirectangular"));
+        assert_eq!(pano.use_panorama_viewer, Some(true));
+        assert_eq!(
+            pano.cropped_area,
+            Some(PanoramaCrop {
+                image_width: 4000,
+                image_height: 2000,
+                full_width: 5000,
+                full_height: 2500,
+                left: 500,
+                top: 250,
+            })
+        );
+        assert_eq!(
+            pano.pose,
+            PanoramaAngles {
+                heading: Some(350.0),
+                pitch: Some(-2.5),
+                roll: None,
+            }
+        );
+        assert_eq!(pano.initial_view.heading, Some(90.0));
+        assert!(xmp.document().motion_photo_info().is_none());
+    }
+}
@@ -0,0 +1,330 @@
+//! Helpers for language alternatives, like `dc:title`.
+//!
+//! A language alternative is an `rdf:Alt` whose items each have an `xml:lang`
+//! qualifier. One of them should be `x-default`, which readers fall back to
+//! when they don't find a better match.
+//!
+//! Language tags are compared without regard to case, as XMP requires.
+
+use raves_metadata_types::xmp::{XmpElement, XmpPrimitive, XmpValue};
+
+use crate::xmp::{RDF_NAMESPACE, XmpDocument};
+
+/// The `xml:lang` value of an alternative's default item.
+const X_DEFAULT: &str = "x-default";
+
+/// Picks the best item in a language alternative for the given locales.
+///
+/// `preferred` lists BCP 47 language tags, most wanted first. For each one,
+/// we'll look for:
+///
+/// 1. an item with exactly that tag (`fr-CA` for `fr-CA`), then
+/// 2. an item with the same language (`fr`, then `fr-FR`, for `fr-CA`).
+///
+/// If no preferred locale matches, the `x-default` item is used. Failing that,
+/// we'll take the first item.
+///
+/// Returns `None` if `value` isn't an alternative, or is empty.
+///
+/// ```
+/// use raves_metadata::xmp::{Xmp, lang::select_language};
+///
+/// let xmp = Xmp::new(r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+///     <rdf:Description xmlns:dc="http://purl.org/dc/elements/1.1/">
+///         <dc:title>
+///             <rdf:Alt>
+///                 <rdf:li xml:lang="x-default">Cat</rdf:li>
+///                 <rdf:li xml:lang="fr-FR">Chat</rdf:li>
+///                 <rdf:li xml:lang="de">Katze</rdf:li>
+///             </rdf:Alt>
+///         </dc:title>
+///     </rdf:Description>
+/// </rdf:RDF>"#).unwrap();
+///
+/// let title = &xmp.document().values_ref()[0].value;
+///
+/// let (lang, _) = select_language(title, &["fr-CA", "de"]).unwrap();
+/// assert_eq!(lang, "fr-FR");
+///
+/// let (lang, _) = select_language(title, &["ja"]).unwrap();
+/// assert_eq!(lang, "x-default");
+/// ```
+pub fn select_language<'v>(
+    value: &'v XmpValue,
+    preferred: &[&str],
+) -> Option<(&'v str, &'v XmpElement)> {
+    let XmpValue::Alternatives { list, .. } = value else {
+        return None;
+    };
+
+    let find = |pred: &dyn Fn(&str) -> bool| {
+        list.iter()
+            .find(|(lang, _)| pred(lang))
+            .map(|(lang, element)| (lang.as_str(), element))
+    };
+
+    for wanted in preferred {
+        let wanted_language = primary_language(wanted);
+
+        let found = find(&|lang| lang.eq_ignore_ascii_case(wanted))
+            .or_else(|| find(&|lang| lang.eq_ignore_ascii_case(wanted_language)))
+            .or_else(|| {
+                find(&|lang| {
+                    lang != X_DEFAULT
+                        && primary_language(lang).eq_ignore_ascii_case(wanted_language)
+                })
+            });
+
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    find(&|lang| lang.eq_ignore_ascii_case(X_DEFAULT)).or_else(|| find(&|_| true))
+}
+
+/// Sets one language's text in a language alternative.
+///
+/// This follows the rules in XMP Part 1 (§8.2.2) and the XMP Toolkit, which
+/// keep the `x-default` item consistent with the others:
+///
+/// - Setting `x-default` also updates any item that shared its old text.
+/// - Setting another language also updates `x-default` if the two had the
+///   same text, or if `x-default` was the only item.
+/// - If there's no `x-default` item yet, one is made with the new text.
+///
+/// The `x-default` item is always kept first.
+///
+/// Returns `false`, without changing anything, if `value` isn't an
+/// alternative.
+pub fn set_language(value: &mut XmpValue, lang: &str, text: &str) -> bool {
+    let XmpValue::Alternatives { chosen, list } = value else {
+        return false;
+    };
+
+    set_in_list(list, lang, text);
+
+    // keep the chosen value pointing at the default
+    if let Some((lang, element)) = list.iter().find(|(l, _)| l.eq_ignore_ascii_case(X_DEFAULT)) {
+        *chosen = (lang.clone(), Box::new(element.clone()));
+    }
+
+    true
+}
+
+/// Makes a new language alternative with one language set.
+pub(crate) fn new_language_alternative(lang: &str, text: &str) -> XmpValue {
+    let mut list = Vec::new();
+    set_in_list(&mut list, lang, text);
+
+    XmpValue::Alternatives {
+        chosen: (X_DEFAULT.into(), Box::new(li(text))),
+        list,
+    }
+}
+
+impl XmpDocument {
+    /// Picks the best translation of a language alternative property, like
+    /// `dc:title`.
+    ///
+    /// See [`select_language`] for how the best match is chosen.
+    pub fn localized_text(
+        &self,
+        namespace: &str,
+        name: &str,
+        preferred: &[&str],
+    ) -> Option<(&str, &XmpElement)> {
+        select_language(&self.get(namespace, name)?.value, preferred)
+    }
+}
+
+/// Applies the `x-default` rules from [`set_language`] to a list of items.
+fn set_in_list(list: &mut Vec<(String, XmpElement)>, lang: &str, text: &str) {
+    let text_of = |element: &XmpElement| match element.value {
+        XmpValue::Simple(XmpPrimitive::Text(ref t)) => Some(t.clone()),
+        _ => None,
+    };
+
+    // move the default to the front, if it's there
+    if let Some(index) = list
+        .iter()
+        .position(|(l, _)| l.eq_ignore_ascii_case(X_DEFAULT))
+    {
+        let default = list.remove(index);
+        list.insert(0, default);
+    }
+    let has_default = list
+        .first()
+        .is_some_and(|(l, _)| l.eq_ignore_ascii_case(X_DEFAULT));
+    let old_default = list
+        .first()
+        .filter(|_| has_default)
+        .and_then(|(_, e)| text_of(e));
+
+    // setting the default
+    if lang.eq_ignore_ascii_case(X_DEFAULT) {
+        if old_default.is_some() {
+            for (_, element) in list.iter_mut().skip(1) {
+                if text_of(element) == old_default {
+                    *element = li(text);
+                }
+            }
+            list[0].1 = li(text);
+        } else {
+            list.insert(0, (X_DEFAULT.into(), li(text)));
+        }
+        return;
+    }
+
+    // setting a specific language
+    let existing = list.iter().position(|(l, _)| l.eq_ignore_ascii_case(lang));
+    let old_text = existing.and_then(|i| text_of(&list[i].1));
+
+    let update_default = match existing {
+        Some(_) => has_default && old_default.is_some() && old_default == old_text,
+        None => has_default && list.len() == 1,
+    };
+
+    match existing {
+        Some(i) => list[i].1 = li(text),
+        None => list.push((lang.into(), li(text))),
+    }
+
+    if update_default {
+        list[0].1 = li(text);
+    } else if !has_default {
+        list.insert(0, (X_DEFAULT.into(), li(text)));
+    }
+}
+
+/// Makes an `rdf:li` item with the given text.
+fn li(text: &str) -> XmpElement {
+    XmpElement {
+        namespace: RDF_NAMESPACE.into(),
+        prefix: "rdf".into(),
+        name: "li".into(),
+        value: XmpValue::Simple(XmpPrimitive::Text(text.into())),
+    }
+}
+
+/// Grabs the primary language subtag (e.g. `fr` from `fr-CA`).
+fn primary_language(tag: &str) -> &str {
+    tag.split(['-', '_']).next().unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use raves_metadata_types::xmp::{XmpPrimitive, XmpValue};
+
+    use crate::util::logger;
+
+    use super::{new_language_alternative, select_language, set_language};
+
+    /// Returns `(lang, text)` pairs from an alternative.
+    fn pairs(value: &XmpValue) -> Vec<(String, String)> {
+        let XmpValue::Alternatives { list, .. } = value else {
+            panic!("not an alternative: {value:#?}");
+        };
+
+        list.iter()
+            .map(|(lang, e)| match &e.value {
+                XmpValue::Simple(XmpPrimitive::Text(t)) => (lang.clone(), t.clone()),
+                other => panic!("not text: {other:#?}"),
+            })
+            .collect()
+    }
+
+    fn owned(v: &[(&str, &str)]) -> Vec<(String, String)> {
+        v.iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect()
+    }
+
+    /// Selection goes: exact, language-only, `x-default`, then first.
+    #[test]
+    fn selection_fallbacks() {
+        logger();
+
+        let mut value = new_language_alternative("x-default", "default");
+        set_language(&mut value, "en-US", "american");
+        set_language(&mut value, "en-GB", "british");
+        set_language(&mut value, "pt", "portuguese");
+        set_language(&mut value, "pt-BR", "brazilian");
+
+        let pick = |preferred: &[&str]| select_language(&value, preferred).unwrap().0.to_string();
+
+        assert_eq!(pick(&["en-gb"]), "en-GB");
+        assert_eq!(pick(&["en-AU"]), "en-US");
+        assert_eq!(pick(&["pt-PT"]), "pt");
+        assert_eq!(pick(&["ja", "pt-BR"]), "pt-BR");
+        assert_eq!(pick(&["ja"]), "x-default");
+        assert_eq!(pick(&[]), "x-default");
+
+        // with no default, we'll take the first item
+        let no_default = XmpValue::Alternatives {
+            chosen: ("x-default".into(), Box::new(super::li("a"))),
+            list: vec![("de".into(), super::li("a")), ("fr".into(), super::li("b"))],
+        };
+        assert_eq!(select_language(&no_default, &["ja"]).unwrap().0, "de");
+
+        assert!(select_language(&XmpValue::OrderedArray(vec![]), &["en"]).is_none());
+    }
+
+    /// The `x-default` item follows the rules in XMP Part 1.
+    #[test]
+    fn default_stays_consistent() {
+        logger();
+
+        // the first language also makes a default
+        let mut value = new_language_alternative("en", "Hello");
+        assert_eq!(
+            pairs(&value),
+            owned(&[("x-default", "Hello"), ("en", "Hello")])
+        );
+
+        // changing a language that matched the default updates both
+        set_language(&mut value, "en", "Howdy");
+        assert_eq!(
+            pairs(&value),
+            owned(&[("x-default", "Howdy"), ("en", "Howdy")])
+        );
+
+        // adding another language leaves the default alone
+        set_language(&mut value, "fr", "Bonjour");
+        assert_eq!(
+            pairs(&value),
+            owned(&[("x-default", "Howdy"), ("en", "Howdy"), ("fr", "Bonjour")])
+        );
+
+        // setting the default updates items that mirrored it
+        set_language(&mut value, "x-default", "Hi");
+        assert_eq!(
+            pairs(&value),
+            owned(&[("x-default", "Hi"), ("en", "Hi"), ("fr", "Bonjour")])
+        );
+
+        // the chosen value tracks the default
+        let XmpValue::Alternatives { chosen, .. } = &value else {
+            unreachable!()
+        };
+        assert_eq!(chosen.0, "x-default");
+        assert_eq!(
+            chosen.1.value,
+            XmpValue::Simple(XmpPrimitive::Text("Hi".into()))
+        );
+
+        // if the default was the only item, it follows the new language
+        let mut value = new_language_alternative("x-default", "Hi");
+        set_language(&mut value, "de", "Hallo");
+        assert_eq!(
+            pairs(&value),
+            owned(&[("x-default", "Hallo"), ("de", "Hallo")])
+        );
+
+        assert!(!set_language(
+            &mut XmpValue::Simple(XmpPrimitive::Text("nope".into())),
+            "en",
+            "nope"
+        ));
+    }
+}
@@ -25,8 +25,13 @@ use crate::xmp::{
     value::{XmpElementExt as _, prims::parse_primitive},
 };
 
+mod edit;
 pub mod error;
+pub mod google;
 mod heuristics;
+pub mod lang;
+pub mod path;
+pub mod regions;
 mod value;
 
 /// Re-exports of the XMP types from `raves_metadata_types`.
@@ -55,12 +60,11 @@ impl XmpDocument {
     /// changes.
     ///
     /// Also, values you may set might not be valid XMP - please use this
-    /// method with care.
+    /// method with care. [`XmpDocument::set`] and friends check values
+    /// against their schemas before accepting them.
     pub fn values_mut(&mut self) -> &mut [XmpElement] {
         &mut self.0
     }
-
-    // TODO: add some better ways to mutate the document's values
 }
 
 /// An XMP parser.
@@ -110,6 +114,13 @@ impl Xmp {
         &self.document
     }
 
+    /// Returns a mutable reference to the underlying XML document.
+    ///
+    /// Use this to edit the document's properties.
+    pub fn document_mut(&mut self) -> &mut XmpDocument {
+        &mut self.document
+    }
+
     /// Combines this XMP document with another one.
     ///
     /// Required to implement things like Extended XMP in JPEG.
@@ -0,0 +1,582 @@
+//! Queries for nested XMP properties.
+//!
+//! Paths use the same syntax as Adobe's XMP Toolkit. Some examples:
+//!
+//! - `dc:creator`: a top-level property
+//! - `Iptc4xmpCore:CreatorContactInfo/Iptc4xmpCore:CiEmailWork`: a struct field
+//! - `dc:subject[2]`: the second item in an array (indices start at one!)
+//! - `dc:subject[last()]`: the last item in an array
+//! - `dc:title[?xml:lang="fr"]`: one language from a language alternative
+//! - `xmpMM:History[stEvt:action="saved"]`: the first struct in an array with
+//!   a matching field
+//!
+//! Quoted values may use either quote style. Inside them, a backslash escapes
+//! the next character, like `\"` or `\\`.
+//!
+//! Prefixes are resolved with a map given by the caller. Any prefix missing
+//! from that map falls back to the prefixes used by the document itself, then
+//! to the conventional prefixes of well-known namespaces.
+
+use raves_metadata_types::xmp::{XmpElement, XmpPrimitive, XmpValue, XmpValueStructField};
+
+use crate::xmp::{XmpDocument, edit::KNOWN_PREFIXES, error::XmpPathError};
+
+/// Something found by following an XMP path.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum XmpNode<'doc> {
+    /// A property, array item, or struct field with its own identifiers.
+    Element(&'doc XmpElement),
+
+    /// A struct field holding a primitive value.
+    ///
+    /// These don't keep an `XmpElement` around, so you'll just get its value.
+    Value(&'doc XmpValue),
+}
+
+impl<'doc> XmpNode<'doc> {
+    /// Returns the value at this node.
+    pub fn value(&self) -> &'doc XmpValue {
+        match self {
+            XmpNode::Element(element) => &element.value,
+            XmpNode::Value(value) => value,
+        }
+    }
+
+    /// Returns the element at this node, if it has one.
+    pub fn element(&self) -> Option<&'doc XmpElement> {
+        match self {
+            XmpNode::Element(element) => Some(element),
+            XmpNode::Value(_) => None,
+        }
+    }
+
+    /// Returns the text at this node, if it's a text primitive.
+    pub fn as_text(&self) -> Option<&'doc str> {
+        match self.value() {
+            XmpValue::Simple(XmpPrimitive::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl XmpDocument {
+    /// Finds the node at the given XMP path.
+    ///
+    /// Prefixes are resolved using the document's own prefixes, then the
+    /// conventional prefixes of well-known namespaces.
+    ///
+    /// Returns `Ok(None)` when the path is valid, but nothing is there.
+    ///
+    /// ```
+    /// use raves_metadata::xmp::Xmp;
+    ///
+    /// let xmp = Xmp::new(r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    ///     <rdf:Description xmlns:dc="http://purl.org/dc/elements/1.1/">
+    ///         <dc:title>
+    ///             <rdf:Alt>
+    ///                 <rdf:li xml:lang="x-default">Cat</rdf:li>
+    ///                 <rdf:li xml:lang="fr">Chat</rdf:li>
+    ///             </rdf:Alt>
+    ///         </dc:title>
+    ///     </rdf:Description>
+    /// </rdf:RDF>"#).unwrap();
+    ///
+    /// let title = xmp.document().query(r#"dc:title[?xml:lang="fr"]"#).unwrap().unwrap();
+    /// assert_eq!(title.as_text(), Some("Chat"));
+    /// ```
+    pub fn query(&self, path: &str) -> Result<Option<XmpNode<'_>>, XmpPathError> {
+        self.query_with_prefixes(path, &[])
+    }
+
+    /// Finds the node at the given XMP path, using your own prefixes.
+    ///
+    /// `prefixes` is a list of `(prefix, namespace)` pairs. These are checked
+    /// before the document's own prefixes.
+    pub fn query_with_prefixes(
+        &self,
+        path: &str,
+        prefixes: &[(&str, &str)],
+    ) -> Result<Option<XmpNode<'_>>, XmpPathError> {
+        let steps = parse_path(path)?;
+
+        // find the namespace for a prefix
+        let resolve = |prefix: &str| -> Result<String, XmpPathError> {
+            prefixes
+                .iter()
+                .find(|(p, _)| *p == prefix)
+                .map(|(_, ns)| (*ns).to_string())
+                .or_else(|| {
+                    self.0
+                        .iter()
+                        .find(|e| e.prefix == prefix)
+                        .map(|e| e.namespace.clone())
+                })
+                .or_else(|| {
+                    KNOWN_PREFIXES
+                        .iter()
+                        .find(|(_, p)| *p == prefix)
+                        .map(|(ns, _)| (*ns).to_string())
+                })
+                .ok_or_else(|| XmpPathError::UnknownPrefix(prefix.into()))
+        };
+
+        let mut steps = steps.into_iter();
+
+        // the first step is always a top-level property
+        let Some(Step::Field { prefix, name }) = steps.next() else {
+            unreachable!("the path parser always starts with a field step");
+        };
+        let namespace = resolve(&prefix)?;
+        let Some(mut current) = self.get(&namespace, &name).map(XmpNode::Element) else {
+            return Ok(None);
+        };
+        let mut current_ns = namespace;
+
+        for step in steps {
+            let next = match step {
+                Step::Field { prefix, name } => {
+                    let ns = resolve(&prefix)?;
+                    let found = struct_field(current.value(), &ns, &name, &current_ns);
+                    current_ns = ns;
+                    found
+                }
+
+                Step::Index(index) => array_items(current.value())
+                    .and_then(|mut items| items.nth(index - 1))
+                    .map(XmpNode::Element),
+
+                Step::Last => array_items(current.value())
+                    .and_then(|items| items.last())
+                    .map(XmpNode::Element),
+
+                Step::Lang(lang) => match current.value() {
+                    XmpValue::Alternatives { list, .. } => list
+                        .iter()
+                        .find(|(key, _)| key.eq_ignore_ascii_case(&lang))
+                        .map(|(_, element)| XmpNode::Element(element)),
+                    _ => None,
+                },
+
+                Step::FieldValue {
+                    prefix,
+                    name,
+                    value,
+                } => {
+                    let ns = resolve(&prefix)?;
+                    array_items(current.value())
+                        .and_then(|mut items| {
+                            items.find(|item| {
+                                struct_field(&item.value, &ns, &name, &current_ns)
+                                    .and_then(|field| field.as_text())
+                                    == Some(value.as_str())
+                            })
+                        })
+                        .map(XmpNode::Element)
+                }
+            };
+
+            match next {
+                Some(node) => current = node,
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(current))
+    }
+}
+
+/// Returns the items of any kind of array.
+fn array_items(value: &XmpValue) -> Option<Box<dyn Iterator<Item = &XmpElement> + '_>> {
+    match value {
+        XmpValue::UnorderedArray(items) | XmpValue::OrderedArray(items) => {
+            Some(Box::new(items.iter()))
+        }
+        XmpValue::Alternatives { list, .. } => Some(Box::new(list.iter().map(|(_, e)| e))),
+        _ => None,
+    }
+}
+
+/// Finds a field on a struct (or union) value.
+///
+/// Fields without a namespace are assumed to use their parent's namespace.
+pub(crate) fn struct_field<'doc>(
+    value: &'doc XmpValue,
+    namespace: &str,
+    name: &str,
+    parent_namespace: &str,
+) -> Option<XmpNode<'doc>> {
+    let matches = |field: &&XmpValueStructField| {
+        field.ident() == name
+            && field.namespace().map_or(parent_namespace, |ns| ns.as_str()) == namespace
+    };
+
+    let field = match value {
+        XmpValue::Struct(fields) => fields.iter().find(matches),
+        XmpValue::Union {
+            discriminant,
+            expected_fields,
+            unexpected_fields,
+        } => core::iter::once(discriminant.as_ref())
+            .chain(expected_fields)
+            .chain(unexpected_fields)
+            .find(matches),
+        _ => None,
+    }?;
+
+    Some(match field {
+        XmpValueStructField::Element { element, .. } => XmpNode::Element(element),
+        XmpValueStructField::Value { value, .. } => XmpNode::Value(value),
+    })
+}
+
+/// One step in a path.
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    /// `prefix:name`
+    Field { prefix: String, name: String },
+
+    /// `[n]`, where `n` starts at one.
+    Index(usize),
+
+    /// `[last()]`
+    Last,
+
+    /// `[?xml:lang="value"]`
+    Lang(String),
+
+    /// `[prefix:name="value"]`
+    FieldValue {
+        prefix: String,
+        name: String,
+        value: String,
+    },
+}
+
+/// Parses a path into its steps.
+fn parse_path(path: &str) -> Result<Vec<Step>, XmpPathError> {
+    if path.is_empty() {
+        return Err(XmpPathError::Empty);
+    }
+
+    let mut p = PathParser { path, pos: 0 };
+    let mut steps = Vec::new();
+
+    loop {
+        let (prefix, name) = p.qualified_name()?;
+        steps.push(Step::Field { prefix, name });
+
+        // any number of selectors may follow a name
+        while p.eat("[") {
+            steps.push(p.selector()?);
+        }
+
+        if p.pos == path.len() {
+            return Ok(steps);
+        }
+        if !p.eat("/") {
+            return Err(p.error("`/`, `[`, or the end of the path"));
+        }
+    }
+}
+
+/// A tiny cursor over the path string.
+struct PathParser<'a> {
+    path: &'a str,
+    pos: usize,
+}
+
+impl PathParser<'_> {
+    fn rest(&self) -> &str {
+        &self.path[self.pos..]
+    }
+
+    fn error(&self, expected: &'static str) -> XmpPathError {
+        XmpPathError::InvalidSyntax {
+            position: self.pos,
+            expected,
+        }
+    }
+
+    /// Skips `s` if it's next, returning whether it was.
+    fn eat(&mut self, s: &str) -> bool {
+        let found = self.rest().starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    /// Parses an XML name, stopping at any path punctuation.
+    fn name(&mut self) -> Result<String, XmpPathError> {
+        let len = self
+            .rest()
+            .find(['/', '[', ']', ':', '=', '"', '?'])
+            .unwrap_or(self.rest().len());
+
+        if len == 0 {
+            return Err(self.error("a name"));
+        }
+
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+        Ok(name)
+    }
+
+    /// Parses a `prefix:name` pair.
+    fn qualified_name(&mut self) -> Result<(String, String), XmpPathError> {
+        let prefix = self.name()?;
+        if !self.eat(":") {
+            return Err(self.error("`:` after a prefix"));
+        }
+        let name = self.name()?;
+        Ok((prefix, name))
+    }
+
+    /// Parses a quoted string. Either quote style is fine.
+    ///
+    /// A backslash escapes the character after it, so `\"` and `\\` put a
+    /// quote or backslash in the value.
+    fn quoted(&mut self) -> Result<String, XmpPathError> {
+        let quote = match self.rest().chars().next() {
+            Some(q @ ('"' | '\'')) => q,
+            _ => return Err(self.error("a quoted value")),
+        };
+        self.pos += 1;
+
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                c if c == quote => {
+                    self.pos += i + 1;
+                    return Ok(value);
+                }
+                c => value.push(c),
+            }
+        }
+
+        self.pos = self.path.len();
+        Err(self.error("a closing quote"))
+    }
+
+    /// Parses the inside of `[...]`, including the closing bracket.
+    fn selector(&mut self) -> Result<Step, XmpPathError> {
+        let step = if self.eat("last()") {
+            Step::Last
+        } else if self.eat("?") {
+            let (prefix, name) = self.qualified_name()?;
+            if prefix != "xml" || name != "lang" {
+                return Err(self.error("the `xml:lang` qualifier"));
+            }
+            if !self.eat("=") {
+                return Err(self.error("`=`"));
+            }
+            Step::Lang(self.quoted()?)
+        } else if self.rest().starts_with(|c: char| c.is_ascii_digit()) {
+            let len = self
+                .rest()
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(self.rest().len());
+            let index: usize = self.rest()[..len]
+                .parse()
+                .map_err(|_| self.error("an index that fits in a `usize`"))?;
+            if index == 0 {
+                return Err(XmpPathError::ZeroIndex);
+            }
+            self.pos += len;
+            Step::Index(index)
+        } else {
+            let (prefix, name) = self.qualified_name()?;
+            if !self.eat("=") {
+                return Err(self.error("`=`"));
+            }
+            Step::FieldValue {
+                prefix,
+                name,
+                value: self.quoted()?,
+            }
+        };
+
+        if !self.eat("]") {
+            return Err(self.error("`]`"));
+        }
+        Ok(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use raves_metadata_types::xmp::{XmpPrimitive, XmpValue};
+
+    use crate::{
+        util::logger,
+        xmp::{Xmp, error::XmpPathError},
+    };
+
+    use super::{Step, parse_path};
+
+    const SAMPLE: &str = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+        <rdf:Description
+            xmlns:dc="http://purl.org/dc/elements/1.1/"
+            xmlns:Iptc4xmpCore="http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/"
+            xmlns:xmpMM="http://ns.adobe.com/xap/1.0/mm/"
+            xmlns:stEvt="http://ns.adobe.com/xap/1.0/sType/ResourceEvent#">
+            <dc:subject>
+                <rdf:Bag>
+                    <rdf:li>cat</rdf:li>
+                    <rdf:li>cute</rdf:li>
+                    <rdf:li>hat</rdf:li>
+                </rdf:Bag>
+            </dc:subject>
+            <dc:title>
+                <rdf:Alt>
+                    <rdf:li xml:lang="x-default">Cat</rdf:li>
+                    <rdf:li xml:lang="fr-FR">Chat</rdf:li>
+                </rdf:Alt>
+            </dc:title>
+            <Iptc4xmpCore:CreatorContactInfo rdf:parseType="Resource">
+                <Iptc4xmpCore:CiEmailWork>me@example.com</Iptc4xmpCore:CiEmailWork>
+            </Iptc4xmpCore:CreatorContactInfo>
+            <xmpMM:History>
+                <rdf:Seq>
+                    <rdf:li rdf:parseType="Resource">
+                        <stEvt:action>created</stEvt:action>
+                    </rdf:li>
+                    <rdf:li rdf:parseType="Resource">
+                        <stEvt:action>saved</stEvt:action>
+                        <stEvt:softwareAgent>raves</stEvt:softwareAgent>
+                    </rdf:li>
+                </rdf:Seq>
+            </xmpMM:History>
+        </rdf:Description>
+    </rdf:RDF>"#;
+
+    /// Paths parse into the steps we expect.
+    #[test]
+    fn parses_paths() {
+        logger();
+
+        assert_eq!(
+            parse_path(r#"ns:a/ns:b[2][last()][?xml:lang='en'][ns:c="d"]"#),
+            Ok(vec![
+                Step::Field {
+                    prefix: "ns".into(),
+                    name: "a".into()
+                },
+                Step::Field {
+                    prefix: "ns".into(),
+                    name: "b".into()
+                },
+                Step::Index(2),
+                Step::Last,
+                Step::Lang("en".into()),
+                Step::FieldValue {
+                    prefix: "ns".into(),
+                    name: "c".into(),
+                    value: "d".into()
+                },
+            ])
+        );
+
+        // quotes and backslashes can be escaped
+        assert_eq!(
+            parse_path(r#"ns:a[ns:b="say \"hi\" \\ 'bye'"]"#),
+            Ok(vec![
+                Step::Field {
+                    prefix: "ns".into(),
+                    name: "a".into()
+                },
+                Step::FieldValue {
+                    prefix: "ns".into(),
+                    name: "b".into(),
+                    value: r#"say "hi" \ 'bye'"#.into()
+                },
+            ])
+        );
+        assert_eq!(
+            parse_path(r#"ns:a[?xml:lang="en\"]"#),
+            Err(XmpPathError::InvalidSyntax {
+                position: 21,
+                expected: "a closing quote"
+            })
+        );
+
+        assert_eq!(parse_path(""), Err(XmpPathError::Empty));
+        assert_eq!(parse_path("ns:a[0]"), Err(XmpPathError::ZeroIndex));
+        assert_eq!(
+            parse_path("ns:a/"),
+            Err(XmpPathError::InvalidSyntax {
+                position: 5,
+                expected: "a name"
+            })
+        );
+        assert_eq!(
+            parse_path("ns:a[1"),
+            Err(XmpPathError::InvalidSyntax {
+                position: 6,
+                expected: "`]`"
+            })
+        );
+    }
+
+    /// Paths resolve against a real document.
+    #[test]
+    fn resolves_paths() {
+        logger();
+
+        let xmp = Xmp::new(SAMPLE).unwrap();
+        let doc = xmp.document();
+        let text = |path: &str| doc.query(path).unwrap().and_then(|n| n.as_text());
+
+        assert_eq!(text("dc:subject[2]"), Some("cute"));
+        assert_eq!(text("dc:subject[last()]"), Some("hat"));
+        assert_eq!(text("dc:subject[4]"), None);
+        assert_eq!(text(r#"dc:title[?xml:lang="fr-fr"]"#), Some("Chat"));
+        assert_eq!(text(r#"dc:title[?xml:lang="x-default"]"#), Some("Cat"));
+        assert_eq!(
+            text("Iptc4xmpCore:CreatorContactInfo/Iptc4xmpCore:CiEmailWork"),
+            Some("me@example.com")
+        );
+        assert_eq!(
+            text(r#"xmpMM:History[stEvt:action="saved"]/stEvt:softwareAgent"#),
+            Some("raves")
+        );
+        assert_eq!(text("xmpMM:History[1]/stEvt:action"), Some("created"));
+
+        // missing things aren't errors
+        assert_eq!(doc.query("dc:rights"), Ok(None));
+        assert_eq!(text("dc:subject/dc:nope"), None);
+
+        // ...but unknown prefixes are
+        assert_eq!(
+            doc.query("nope:thing"),
+            Err(XmpPathError::UnknownPrefix("nope".into()))
+        );
+    }
+
+    /// Callers can bring their own prefixes.
+    #[test]
+    fn custom_prefixes() {
+        logger();
+
+        let xmp = Xmp::new(SAMPLE).unwrap();
+
+        let node = xmp
+            .document()
+            .query_with_prefixes(
+                "purl:subject[1]",
+                &[("purl", "http://purl.org/dc/elements/1.1/")],
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            node.value(),
+            &XmpValue::Simple(XmpPrimitive::Text("cat".into()))
+        );
+    }
+}
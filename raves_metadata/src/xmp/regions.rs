@@ -0,0 +1,502 @@
+//! Regions of interest (like faces) stored in XMP.
+//!
+//! There are three common ways to store these:
+//!
+//! - MWG Regions (`mwg-rs:Regions`), written by Lightroom, Picasa, digiKam,
+//!   and most others,
+//! - Microsoft Photo people tags (`MP:RegionInfo`), from Windows Photos, and
+//! - IPTC Image Regions (`Iptc4xmpExt:ImageRegion`).
+//!
+//! Each uses its own coordinate system. [`XmpDocument::regions`] reads all of
+//! them into one list of rectangles with relative coordinates.
+
+use raves_metadata_types::xmp::{XmpPrimitive, XmpValue};
+
+use crate::xmp::{
+    XmpDocument,
+    lang::select_language,
+    path::{XmpNode, struct_field},
+};
+
+const MWG_RS: &str = "http://www.metadataworkinggroup.com/schemas/regions/";
+const ST_AREA: &str = "http://ns.adobe.com/xmp/sType/Area#";
+const MP: &str = "http://ns.microsoft.com/photo/1.2/";
+const MPRI: &str = "http://ns.microsoft.com/photo/1.2/t/RegionInfo#";
+const MPREG: &str = "http://ns.microsoft.com/photo/1.2/t/Region#";
+const IPTC4XMP_EXT: &str = "http://iptc.org/std/Iptc4xmpExt/2008-02-29/";
+const EXIF: &str = "http://ns.adobe.com/exif/1.0/";
+const TIFF: &str = "http://ns.adobe.com/tiff/1.0/";
+
+/// A rectangular region of an image, like a tagged face.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct XmpRegion {
+    /// Where this region was found.
+    pub source: XmpRegionSource,
+
+    /// The name of whatever is in the region, like a person's name.
+    pub name: Option<String>,
+
+    /// What kind of region this is, like `Face` or `Pet`.
+    ///
+    /// For IPTC regions, this is the last part of the region's first content
+    /// type identifier (like `human`), or that content type's name.
+    pub kind: Option<String>,
+
+    /// The region's rectangle.
+    pub rect: RelativeRect,
+}
+
+impl XmpRegion {
+    /// Checks whether this region marks a face (or person).
+    pub fn is_face(&self) -> bool {
+        match self.source {
+            XmpRegionSource::Microsoft => true,
+            XmpRegionSource::Mwg | XmpRegionSource::Iptc => self
+                .kind
+                .as_deref()
+                .is_some_and(|k| k.eq_ignore_ascii_case("face") || k.eq_ignore_ascii_case("human")),
+        }
+    }
+}
+
+/// The XMP schema that a region came from.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub enum XmpRegionSource {
+    /// `mwg-rs:Regions`
+    Mwg,
+
+    /// `MP:RegionInfo`
+    Microsoft,
+
+    /// `Iptc4xmpExt:ImageRegion`
+    Iptc,
+}
+
+/// A rectangle in relative coordinates.
+///
+/// Each value is a fraction of the image's size, from `0.0` to `1.0`. The
+/// origin is the top-left corner of the image.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct RelativeRect {
+    /// The left edge.
+    pub x: f64,
+
+    /// The top edge.
+    pub y: f64,
+
+    /// The width.
+    pub width: f64,
+
+    /// The height.
+    pub height: f64,
+}
+
+impl XmpDocument {
+    /// Returns every rectangular region in the document.
+    ///
+    /// Regions that aren't rectangles (like points or circles) are skipped.
+    /// IPTC regions given in pixels are only returned when the document also
+    /// says how large the image is.
+    ///
+    /// ```
+    /// use raves_metadata::xmp::{Xmp, regions::XmpRegionSource};
+    ///
+    /// let xmp = Xmp::new(r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    ///     <rdf:Description
+    ///         xmlns:MP="http://ns.microsoft.com/photo/1.2/"
+    ///         xmlns:MPRI="http://ns.microsoft.com/photo/1.2/t/RegionInfo#"
+    ///         xmlns:MPReg="http://ns.microsoft.com/photo/1.2/t/Region#">
+    ///         <MP:RegionInfo rdf:parseType="Resource">
+    ///             <MPRI:Regions>
+    ///                 <rdf:Bag>
+    ///                     <rdf:li MPReg:Rectangle="0.25, 0.5, 0.125, 0.25" MPReg:PersonDisplayName="Barrett" />
+    ///                 </rdf:Bag>
+    ///             </MPRI:Regions>
+    ///         </MP:RegionInfo>
+    ///     </rdf:Description>
+    /// </rdf:RDF>"#).unwrap();
+    ///
+    /// let faces = xmp.document().face_regions();
+    /// assert_eq!(faces[0].source, XmpRegionSource::Microsoft);
+    /// assert_eq!(faces[0].name.as_deref(), Some("Barrett"));
+    /// assert_eq!(faces[0].rect.x, 0.25);
+    /// ```
+    pub fn regions(&self) -> Vec<XmpRegion> {
+        let mut regions = Vec::new();
+        regions.extend(self.mwg_regions());
+        regions.extend(self.microsoft_regions());
+        regions.extend(self.iptc_regions());
+        regions
+    }
+
+    /// Returns only the regions that mark faces.
+    ///
+    /// See [`XmpRegion::is_face`].
+    pub fn face_regions(&self) -> Vec<XmpRegion> {
+        self.regions().into_iter().filter(|r| r.is_face()).collect()
+    }
+
+    /// Reads `mwg-rs:Regions`.
+    ///
+    /// MWG areas are given by their center point, so we move that to the
+    /// top-left corner.
+    fn mwg_regions(&self) -> Vec<XmpRegion> {
+        let Some(info) = self.get(MWG_RS, "Regions") else {
+            return Vec::new();
+        };
+
+        items(struct_field(&info.value, MWG_RS, "RegionList", MWG_RS))
+            .filter_map(|item| {
+                let area = struct_field(item, MWG_RS, "Area", MWG_RS)?;
+                let field = |name| number(struct_field(area.value(), ST_AREA, name, ST_AREA));
+
+                // only "normalized" is allowed by the spec
+                if let Some(unit) = text(struct_field(area.value(), ST_AREA, "unit", ST_AREA))
+                    && unit != "normalized"
+                {
+                    log::warn!("MWG region had unknown unit `{unit}`. Skipping it.");
+                    return None;
+                }
+
+                let (x, y, w, h) = (field("x")?, field("y")?, field("w")?, field("h")?);
+
+                Some(XmpRegion {
+                    source: XmpRegionSource::Mwg,
+                    name: text(struct_field(item, MWG_RS, "Name", MWG_RS)),
+                    kind: text(struct_field(item, MWG_RS, "Type", MWG_RS)),
+                    rect: RelativeRect {
+                        x: x - w / 2.0,
+                        y: y - h / 2.0,
+                        width: w,
+                        height: h,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Reads `MP:RegionInfo`.
+    ///
+    /// Each rectangle is a string: `"x, y, w, h"`.
+    fn microsoft_regions(&self) -> Vec<XmpRegion> {
+        let Some(info) = self.get(MP, "RegionInfo") else {
+            return Vec::new();
+        };
+
+        items(struct_field(&info.value, MPRI, "Regions", MP))
+            .filter_map(|item| {
+                let rectangle = text(struct_field(item, MPREG, "Rectangle", MPRI))?;
+                let parts = rectangle
+                    .split(',')
+                    .map(|p| p.trim().parse::<f64>().ok())
+                    .collect::<Option<Vec<f64>>>()?;
+
+                let [x, y, width, height] = parts[..] else {
+                    log::warn!("Microsoft region had a weird rectangle: `{rectangle}`");
+                    return None;
+                };
+
+                Some(XmpRegion {
+                    source: XmpRegionSource::Microsoft,
+                    name: text(struct_field(item, MPREG, "PersonDisplayName", MPRI)),
+                    kind: Some("Face".into()),
+                    rect: RelativeRect {
+                        x,
+                        y,
+                        width,
+                        height,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Reads `Iptc4xmpExt:ImageRegion`.
+    fn iptc_regions(&self) -> Vec<XmpRegion> {
+        let Some(regions) = self.get(IPTC4XMP_EXT, "ImageRegion") else {
+            return Vec::new();
+        };
+
+        // pixel units need the image's size
+        let dimension = |exif_name, tiff_name| {
+            number(self.get(EXIF, exif_name).map(XmpNode::Element))
+                .or_else(|| number(self.get(TIFF, tiff_name).map(XmpNode::Element)))
+                .filter(|d| *d > 0.0)
+        };
+        let image_size = dimension("PixelXDimension", "ImageWidth")
+            .zip(dimension("PixelYDimension", "ImageLength"));
+
+        let field = |value, name| struct_field(value, IPTC4XMP_EXT, name, IPTC4XMP_EXT);
+
+        items(Some(XmpNode::Element(regions)))
+            .filter_map(|item| {
+                let boundary = field(item, "RegionBoundary")?;
+                let boundary_field = |name| field(boundary.value(), name);
+
+                if text(boundary_field("rbShape"))
+                    .is_some_and(|shape| !shape.eq_ignore_ascii_case("rectangle"))
+                {
+                    return None;
+                }
+
+                let (x, y, w, h) = (
+                    number(boundary_field("rbX"))?,
+                    number(boundary_field("rbY"))?,
+                    number(boundary_field("rbW"))?,
+                    number(boundary_field("rbH"))?,
+                );
+
+                let rect = match text(boundary_field("rbUnit")).as_deref() {
+                    Some("relative") => RelativeRect {
+                        x,
+                        y,
+                        width: w,
+                        height: h,
+                    },
+                    Some("pixel") => {
+                        let Some((image_w, image_h)) = image_size else {
+                            log::warn!(
+                                "IPTC region was in pixels, but the image's size isn't known."
+                            );
+                            return None;
+                        };
+                        RelativeRect {
+                            x: x / image_w,
+                            y: y / image_h,
+                            width: w / image_w,
+                            height: h / image_h,
+                        }
+                    }
+                    other => {
+                        log::warn!("IPTC region had unknown unit: `{other:?}`");
+                        return None;
+                    }
+                };
+
+                // the first content type describes the region
+                let kind = items(field(item, "rCtype")).next().and_then(|entity| {
+                    items(field(entity, "Identifier"))
+                        .next()
+                        .and_then(|id| match id {
+                            XmpValue::Simple(XmpPrimitive::Text(id)) => {
+                                id.rsplit('/').next().map(|s| s.to_string())
+                            }
+                            _ => None,
+                        })
+                        .or_else(|| localized(field(entity, "Name")))
+                });
+
+                Some(XmpRegion {
+                    source: XmpRegionSource::Iptc,
+                    name: localized(field(item, "Name")),
+                    kind,
+                    rect,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Iterates over the values of an array's items.
+fn items<'doc>(node: Option<XmpNode<'doc>>) -> impl Iterator<Item = &'doc XmpValue> {
+    let items = match node.map(|n| n.value()) {
+        Some(XmpValue::UnorderedArray(items) | XmpValue::OrderedArray(items)) => items.as_slice(),
+        _ => &[],
+    };
+
+    items.iter().map(|item| &item.value)
+}
+
+/// Grabs some text.
+fn text(node: Option<XmpNode<'_>>) -> Option<String> {
+    node?.as_text().map(|t| t.to_string())
+}
+
+/// Grabs the default text of a language alternative.
+fn localized(node: Option<XmpNode<'_>>) -> Option<String> {
+    let (_, element) = select_language(node?.value(), &[])?;
+    text(Some(XmpNode::Element(element)))
+}
+
+/// Grabs a number, even when it was stored as text.
+fn number(node: Option<XmpNode<'_>>) -> Option<f64> {
+    match node?.value() {
+        XmpValue::Simple(XmpPrimitive::Real(r)) => Some(*r),
+        XmpValue::Simple(XmpPrimitive::Integer(i)) => Some(*i as f64),
+        XmpValue::Simple(XmpPrimitive::Text(t)) => t.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        util::logger,
+        xmp::{
+            Xmp,
+            regions::{RelativeRect, XmpRegion, XmpRegionSource},
+        },
+    };
+
+    /// Regions from all three schemas are read into the same form.
+    #[test]
+    fn all_region_sources() {
+        logger();
+
+        let xmp = Xmp::new(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description
+        xmlns:mwg-rs="http://www.metadataworkinggroup.com/schemas/regions/"
+        xmlns:stDim="http://ns.adobe.com/xap/1.0/sType/Dimensions#"
+        xmlns:stArea="http://ns.adobe.com/xmp/sType/Area#"
+        xmlns:Iptc4xmpExt="http://iptc.org/std/Iptc4xmpExt/2008-02-29/"
+        xmlns:exif="http://ns.adobe.com/exif/1.0/"
+        exif:PixelXDimension="200"
+        exif:PixelYDimension="100">
+        <mwg-rs:Regions rdf:parseType="Resource">
+            <mwg-rs:AppliedToDimensions stDim:w="200" stDim:h="100" stDim:unit="pixel" />
+            <mwg-rs:RegionList>
+                <rdf:Bag>
+                    <rdf:li>
+                        <rdf:Description mwg-rs:Name="Alice" mwg-rs:Type="Face">
+                            <mwg-rs:Area stArea:x="0.5" stArea:y="0.5" stArea:w="0.25" stArea:h="0.5" stArea:unit="normalized" />
+                        </rdf:Description>
+                    </rdf:li>
+                    <rdf:li>
+                        <rdf:Description mwg-rs:Type="Focus">
+                            <mwg-rs:Area stArea:x="0.5" stArea:y="0.5" stArea:w="0.5" stArea:h="0.5" stArea:unit="normalized" />
+                        </rdf:Description>
+                    </rdf:li>
+                    <rdf:li>
+                        <rdf:Description mwg-rs:Type="Face">
+                            <mwg-rs:Area stArea:x="0.5" stArea:y="0.5" stArea:unit="normalized" />
+                        </rdf:Description>
+                    </rdf:li>
+                </rdf:Bag>
+            </mwg-rs:RegionList>
+        </mwg-rs:Regions>
+        <Iptc4xmpExt:ImageRegion>
+            <rdf:Bag>
+                <rdf:li rdf:parseType="Resource">
+                    <Iptc4xmpExt:RegionBoundary rdf:parseType="Resource">
+                        <Iptc4xmpExt:rbShape>rectangle</Iptc4xmpExt:rbShape>
+                        <Iptc4xmpExt:rbUnit>pixel</Iptc4xmpExt:rbUnit>
+                        <Iptc4xmpExt:rbX>20</Iptc4xmpExt:rbX>
+                        <Iptc4xmpExt:rbY>10</Iptc4xmpExt:rbY>
+                        <Iptc4xmpExt:rbW>50</Iptc4xmpExt:rbW>
+                        <Iptc4xmpExt:rbH>25</Iptc4xmpExt:rbH>
+                    </Iptc4xmpExt:RegionBoundary>
+                    <Iptc4xmpExt:Name>
+                        <rdf:Alt>
+                            <rdf:li xml:lang="x-default">Bob</rdf:li>
+                        </rdf:Alt>
+                    </Iptc4xmpExt:Name>
+                    <Iptc4xmpExt:rCtype>
+                        <rdf:Bag>
+                            <rdf:li rdf:parseType="Resource">
+                                <Iptc4xmpExt:Identifier>
+                                    <rdf:Bag>
+                                        <rdf:li>http://cv.iptc.org/newscodes/imageregiontype/human</rdf:li>
+                                    </rdf:Bag>
+                                </Iptc4xmpExt:Identifier>
+                            </rdf:li>
+                        </rdf:Bag>
+                    </Iptc4xmpExt:rCtype>
+                </rdf:li>
+            </rdf:Bag>
+        </Iptc4xmpExt:ImageRegion>
+    </rdf:Description>
+</rdf:RDF>"#,
+        )
+        .unwrap();
+
+        let regions = xmp.document().regions();
+        assert_eq!(
+            regions,
+            vec![
+                XmpRegion {
+                    source: XmpRegionSource::Mwg,
+                    name: Some("Alice".into()),
+                    kind: Some("Face".into()),
+                    rect: RelativeRect {
+                        x: 0.375,
+                        y: 0.25,
+                        width: 0.25,
+                        height: 0.5
+                    }
+                },
+                XmpRegion {
+                    source: XmpRegionSource::Mwg,
+                    name: None,
+                    kind: Some("Focus".into()),
+                    rect: RelativeRect {
+                        x: 0.25,
+                        y: 0.25,
+                        width: 0.5,
+                        height: 0.5
+                    }
+                },
+                XmpRegion {
+                    source: XmpRegionSource::Iptc,
+                    name: Some("Bob".into()),
+                    kind: Some("human".into()),
+                    rect: RelativeRect {
+                        x: 0.1,
+                        y: 0.1,
+                        width: 0.25,
+                        height: 0.25
+                    }
+                },
+            ]
+        );
+
+        let faces = xmp.document().face_regions();
+        assert_eq!(
+            faces.iter().map(|f| f.name.as_deref()).collect::<Vec<_>>(),
+            vec![Some("Alice"), Some("Bob")]
+        );
+    }
+
+    /// Keyword hierarchies are recursive, so let's make sure that parses.
+    #[test]
+    fn mwg_keywords_parse_with_schema() {
+        logger();
+
+        let xmp = Xmp::new(
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description xmlns:mwg-kw="http://www.metadataworkinggroup.com/schemas/keywords/">
+        <mwg-kw:Keywords rdf:parseType="Resource">
+            <mwg-kw:Hierarchy>
+                <rdf:Bag>
+                    <rdf:li rdf:parseType="Resource">
+                        <mwg-kw:Keyword>Animals</mwg-kw:Keyword>
+                        <mwg-kw:Children>
+                            <rdf:Bag>
+                                <rdf:li rdf:parseType="Resource">
+                                    <mwg-kw:Keyword>Cat</mwg-kw:Keyword>
+                                    <mwg-kw:Applied>True</mwg-kw:Applied>
+                                </rdf:li>
+                            </rdf:Bag>
+                        </mwg-kw:Children>
+                    </rdf:li>
+                </rdf:Bag>
+            </mwg-kw:Hierarchy>
+        </mwg-kw:Keywords>
+    </rdf:Description>
+</rdf:RDF>"#,
+        )
+        .unwrap();
+
+        let doc = xmp.document();
+        let node = doc
+            .query("mwg-kw:Keywords/mwg-kw:Hierarchy[1]/mwg-kw:Children[1]/mwg-kw:Applied")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            node.value(),
+            &raves_metadata_types::xmp::XmpValue::Simple(
+                raves_metadata_types::xmp::XmpPrimitive::Boolean(true)
+            )
+        );
+    }
+}
@@ -2,7 +2,7 @@ use raves_metadata_types::xmp::{
     XmpElement, XmpPrimitive, XmpValue, XmpValueStructField,
     parse_types::{XmpKind as Kind, XmpKindStructField as Field},
 };
-use xmltree::Element;
+use xmltree::{AttributeName, Element};
 
 use crate::xmp::{
     RDF_NAMESPACE,
@@ -37,8 +37,8 @@ pub fn value_struct(
         })
     };
 
-    // parse attribute fields, if any
-    let attr_fields = element.attributes.iter().flat_map(|(keys, value)| {
+    // helper closure: parse an attribute as a field
+    let parse_attr_field = |(keys, value): (&AttributeName, &String)| {
         // unwrap the namespace + name
         let (ns, name) = (&keys.namespace_ref(), &keys.local_name);
 
@@ -96,7 +96,10 @@ pub fn value_struct(
             namespace: (*ns).map(|n| n.into()),
             value: XmpValue::Simple(XmpPrimitive::Text(value.into())),
         })
-    });
+    };
+
+    // parse attribute fields, if any
+    let attr_fields = element.attributes.iter().flat_map(parse_attr_field);
 
     // now, let's check if we're a container, or if we have a container.
     //
@@ -123,6 +126,17 @@ pub fn value_struct(
         return element.to_xmp_element(XmpValue::Struct(attr_fields.collect()));
     };
 
+    // an inner `rdf:Description` can hold fields as attributes, too
+    let container_attr_fields = (!core::ptr::eq(fields_container, element))
+        .then(|| {
+            fields_container
+                .attributes
+                .iter()
+                .flat_map(parse_attr_field)
+        })
+        .into_iter()
+        .flatten();
+
     // alright, we do have fields to parse.
     //
     // let's handle those!
@@ -150,7 +164,12 @@ pub fn value_struct(
         });
 
     // pop it all into a new XmpValue
-    element.to_xmp_element(XmpValue::Struct(attr_fields.chain(inner_fields).collect()))
+    element.to_xmp_element(XmpValue::Struct(
+        attr_fields
+            .chain(container_attr_fields)
+            .chain(inner_fields)
+            .collect(),
+    ))
 }
 
 /// Parses an element as a struct field.
@@ -202,6 +221,8 @@ pub fn value_struct_field(
 
 #[cfg(test)]
 mod tests {
+    use raves_metadata_types::xmp::XmpValue;
+
     use crate::xmp::Xmp;
 
     /// The parser should be able to handle several different layouts of
@@ -254,5 +275,13 @@ mod tests {
         .unwrap();
 
         assert_eq!(xmp.document().values_ref().len(), 5);
+
+        // every layout should give us both fields
+        for value in xmp.document().values_ref() {
+            let XmpValue::Struct(fields) = &value.value else {
+                panic!("`{}` should be a struct: {value:#?}", value.name);
+            };
+            assert_eq!(fields.len(), 2, "`{}` is missing fields", value.name);
+        }
     }
 }
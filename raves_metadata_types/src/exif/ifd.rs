@@ -50,6 +50,17 @@ pub enum IfdGroup {
     #[doc(alias = "InteroperabilityIFD")]
     #[doc(alias = "InteropIFD")]
     Interop,
+
+    /// The Multi-Picture Format (MPF) IFDs describe the extra images stored
+    /// after a JPEG's primary image.
+    ///
+    /// These aren't in the Exif blob. Instead, they're in a JPEG's `APP2`
+    /// segment, which uses the same structure. Both the MP Index IFD and the
+    /// MP Attribute IFD use this group.
+    #[doc(alias = "MPF")]
+    #[doc(alias = "MPIndexIFD")]
+    #[doc(alias = "MPAttributeIFD")]
+    Mpf,
 }
 
 impl IfdGroup {
@@ -65,6 +76,7 @@ impl IfdGroup {
             Self::Exif => true,
             Self::Gps => true,
             Self::Interop => true,
+            Self::Mpf => true,
         }
     }
 }
@@ -143,6 +143,7 @@ pub enum KnownTag {
     ExifIfdTag(ExifIfdTag),
     GpsIfdTag(GpsIfdTag),
     InteropIfdTag(InteropIfdTag),
+    MpfTag(MpfTag),
 }
 
 impl KnownTag {
@@ -163,6 +164,7 @@ impl KnownTag {
             KnownTag::ExifIfdTag(k) => k.count(),
             KnownTag::GpsIfdTag(k) => k.count(),
             KnownTag::InteropIfdTag(k) => k.count(),
+            KnownTag::MpfTag(k) => k.count(),
         }
     }
 
@@ -180,6 +182,7 @@ impl KnownTag {
             KnownTag::ExifIfdTag(_) => ExifIfdTag::ifd_group(),
             KnownTag::GpsIfdTag(_) => GpsIfdTag::ifd_group(),
             KnownTag::InteropIfdTag(_) => InteropIfdTag::ifd_group(),
+            KnownTag::MpfTag(_) => MpfTag::ifd_group(),
         }
     }
 
@@ -197,6 +200,7 @@ impl KnownTag {
             KnownTag::ExifIfdTag(k) => *k as u16,
             KnownTag::GpsIfdTag(k) => *k as u16,
             KnownTag::InteropIfdTag(k) => *k as u16,
+            KnownTag::MpfTag(k) => *k as u16,
         }
     }
 
@@ -214,6 +218,7 @@ impl KnownTag {
             KnownTag::ExifIfdTag(k) => k.tag_name(),
             KnownTag::GpsIfdTag(k) => k.tag_name(),
             KnownTag::InteropIfdTag(k) => k.tag_name(),
+            KnownTag::MpfTag(k) => k.tag_name(),
         }
     }
 
@@ -234,6 +239,7 @@ impl KnownTag {
             KnownTag::ExifIfdTag(k) => k.types(),
             KnownTag::GpsIfdTag(k) => k.types(),
             KnownTag::InteropIfdTag(k) => k.types(),
+            KnownTag::MpfTag(k) => k.types(),
         }
     }
 }
@@ -249,6 +255,7 @@ impl TryFrom<(IfdGroup, u16)> for KnownTag {
             IfdGroup::Exif => ExifIfdTag::try_from(tag_id).map(KnownTag::ExifIfdTag),
             IfdGroup::Gps => GpsIfdTag::try_from(tag_id).map(KnownTag::GpsIfdTag),
             IfdGroup::Interop => InteropIfdTag::try_from(tag_id).map(KnownTag::InteropIfdTag),
+            IfdGroup::Mpf => MpfTag::try_from(tag_id).map(KnownTag::MpfTag),
         }
     }
 }
@@ -1165,3 +1172,123 @@ make_key_list_for_group!(enum InteropIfdTag => IfdGroup::Interop,
        count: Pc::Any,
    },
 );
+
+/*
+ *
+ *
+ *
+ *
+ *
+ *
+ *
+ *  Multi-Picture Format (CIPA DC-007) Attribute List
+ *
+ *
+ *
+ *
+ *
+ *
+ *
+ *
+ */
+make_key_list_for_group!(enum MpfTag => IfdGroup::Mpf,
+    //
+    // MP Index IFD
+    MpfVersion = 0xB000 => {
+        name: "MP Format version",
+        types: &[Pt::Undefined],
+        count: Pc::Known(4),
+    },
+    NumberOfImages = 0xB001 => {
+        name: "Number of images",
+        types: &[Pt::Long],
+        count: Pc::Known(1),
+    },
+    MpEntry = 0xB002 => {
+        name: "MP entry",
+        types: &[Pt::Undefined],
+        count: Pc::Any,
+    },
+    ImageUidList = 0xB003 => {
+        name: "Individual image unique ID list",
+        types: &[Pt::Undefined],
+        count: Pc::Any,
+    },
+    TotalFrames = 0xB004 => {
+        name: "Total number of captured frames",
+        types: &[Pt::Long],
+        count: Pc::Known(1),
+    },
+    //
+    // MP Attribute IFD
+    MpIndividualNum = 0xB101 => {
+        name: "MP individual image number",
+        types: &[Pt::Long],
+        count: Pc::Known(1),
+    },
+    PanOrientation = 0xB201 => {
+        name: "Panorama scanning orientation",
+        types: &[Pt::Long],
+        count: Pc::Known(1),
+    },
+    PanOverlapH = 0xB202 => {
+        name: "Panorama horizontal overlap",
+        types: &[Pt::Rational],
+        count: Pc::Known(1),
+    },
+    PanOverlapV = 0xB203 => {
+        name: "Panorama vertical overlap",
+        types: &[Pt::Rational],
+        count: Pc::Known(1),
+    },
+    BaseViewpointNum = 0xB204 => {
+        name: "Base viewpoint number",
+        types: &[Pt::Long],
+        count: Pc::Known(1),
+    },
+    ConvergenceAngle = 0xB205 => {
+        name: "Convergence angle",
+        types: &[Pt::SRational],
+        count: Pc::Known(1),
+    },
+    BaselineLength = 0xB206 => {
+        name: "Baseline length",
+        types: &[Pt::Rational],
+        count: Pc::Known(1),
+    },
+    VerticalDivergence = 0xB207 => {
+        name: "Divergence angle",
+        types: &[Pt::SRational],
+        count: Pc::Known(1),
+    },
+    AxisDistanceX = 0xB208 => {
+        name: "Horizontal axis distance",
+        types: &[Pt::SRational],
+        count: Pc::Known(1),
+    },
+    AxisDistanceY = 0xB209 => {
+        name: "Vertical axis distance",
+        types: &[Pt::SRational],
+        count: Pc::Known(1),
+    },
+    AxisDistanceZ = 0xB20A => {
+        name: "Collimation axis distance",
+        types: &[Pt::SRational],
+        count: Pc::Known(1),
+    },
+    YawAngle = 0xB20B => {
+        name: "Yaw angle",
+        types: &[Pt::SRational],
+        count: Pc::Known(1),
+    },
+    PitchAngle = 0xB20C => {
+        name: "Pitch angle",
+        types: &[Pt::SRational],
+        count: Pc::Known(1),
+    },
+    RollAngle = 0xB20D => {
+        name: "Roll angle",
+        types: &[Pt::SRational],
+        count: Pc::Known(1),
+    },
+);
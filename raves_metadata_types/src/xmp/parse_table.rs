@@ -647,5 +647,233 @@ fn map(m: &mut FxHashMap<XmpNamespaceNamePair, Kind>) {
         );
     }
 
+    /*
+     * IPTC Extension namespace
+     *
+     * "The IPTC Extension schema extends the IPTC Core schema by a set of
+     * properties for more specific metadata."
+     *
+     * note: only image regions are listed here for now.
+     */
+    {
+        const IPTC4XMP_EXT: &str = "http://iptc.org/std/Iptc4xmpExt/2008-02-29/";
+
+        i(
+            P((IPTC4XMP_EXT, "ImageRegion")),
+            Kind::UnorderedArray(&types::IMAGE_REGION),
+        );
+    }
+
+    //
+    //
+    //
+    //
+    //
+    // BEGIN THIRD-PARTY NAMESPACES
+    //
+    // these aren't from Adobe or IPTC, but are widely written by photo
+    // managers and cameras.
+    //
+
+    /*
+     * MWG Regions namespace
+     *
+     * "The Region schema [...] describes areas of interest within an image,
+     * such as faces or the focus area."
+     *
+     * Written by Lightroom, Picasa, digiKam, and many others.
+     */
+    {
+        const MWG_RS: &str = "http://www.metadataworkinggroup.com/schemas/regions/";
+
+        i(P((MWG_RS, "Regions")), types::REGION_INFO);
+    }
+
+    /*
+     * MWG Keywords namespace
+     *
+     * "The Keywords schema [...] stores hierarchical keywords."
+     */
+    {
+        const MWG_KW: &str = "http://www.metadataworkinggroup.com/schemas/keywords/";
+
+        i(P((MWG_KW, "Keywords")), types::KEYWORD_INFO);
+    }
+
+    /*
+     * Microsoft Photo namespace (v1.2)
+     *
+     * Used by Windows Photo Gallery (and friends) to store people tags.
+     */
+    {
+        const MP: &str = "http://ns.microsoft.com/photo/1.2/";
+
+        i(P((MP, "RegionInfo")), types::MICROSOFT_REGION_INFO);
+    }
+
+    /*
+     * Google Camera namespace
+     *
+     * Written by Google Camera (and others) to mark Motion Photos and other
+     * special capture types.
+     */
+    {
+        const G_CAMERA: &str = "http://ns.google.com/photos/1.0/camera/";
+
+        i(P((G_CAMERA, "BurstID")), Kind::Simple(Prim::Text));
+        i(P((G_CAMERA, "BurstPrimary")), Kind::Simple(Prim::Integer));
+        i(P((G_CAMERA, "MicroVideo")), Kind::Simple(Prim::Integer));
+        i(
+            P((G_CAMERA, "MicroVideoOffset")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(
+            P((G_CAMERA, "MicroVideoPresentationTimestampUs")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(
+            P((G_CAMERA, "MicroVideoVersion")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(P((G_CAMERA, "MotionPhoto")), Kind::Simple(Prim::Integer));
+        i(
+            P((G_CAMERA, "MotionPhotoPresentationTimestampUs")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(
+            P((G_CAMERA, "MotionPhotoVersion")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(P((G_CAMERA, "SpecialTypeID")), Kind::Simple(Prim::Text));
+    }
+
+    /*
+     * Google Container namespace
+     *
+     * "The Container element is [...] an ordered directory of media items
+     * in the container."
+     *
+     * The media items follow the primary image, in directory order.
+     */
+    {
+        const CONTAINER: &str = "http://ns.google.com/photos/1.0/container/";
+
+        i(
+            P((CONTAINER, "Directory")),
+            Kind::OrderedArray(&types::CONTAINER_DIRECTORY_ENTRY),
+        );
+    }
+
+    /*
+     * Google Depth Map namespace
+     *
+     * Stores a depth map (in `GDepth:Data`, often in extended XMP) alongside
+     * how to read it.
+     */
+    {
+        const G_DEPTH: &str = "http://ns.google.com/photos/1.0/depthmap/";
+
+        i(P((G_DEPTH, "Confidence")), Kind::Simple(Prim::Text));
+        i(P((G_DEPTH, "ConfidenceMime")), types::MIME_TYPE);
+        i(P((G_DEPTH, "Data")), Kind::Simple(Prim::Text));
+        i(P((G_DEPTH, "Far")), Kind::Simple(Prim::Real));
+        i(P((G_DEPTH, "Format")), Kind::Simple(Prim::Text));
+        i(P((G_DEPTH, "ImageHeight")), Kind::Simple(Prim::Real));
+        i(P((G_DEPTH, "ImageWidth")), Kind::Simple(Prim::Real));
+        i(P((G_DEPTH, "Manufacturer")), Kind::Simple(Prim::Text));
+        i(P((G_DEPTH, "MeasureType")), Kind::Simple(Prim::Text));
+        i(P((G_DEPTH, "Mime")), types::MIME_TYPE);
+        i(P((G_DEPTH, "Model")), Kind::Simple(Prim::Text));
+        i(P((G_DEPTH, "Near")), Kind::Simple(Prim::Real));
+        i(P((G_DEPTH, "Software")), types::AGENT_NAME);
+        i(P((G_DEPTH, "Units")), Kind::Simple(Prim::Text));
+    }
+
+    /*
+     * Google Image namespace
+     *
+     * Stores a second image (usually the original, before a portrait blur)
+     * as base64 in `GImage:Data`.
+     */
+    {
+        const G_IMAGE: &str = "http://ns.google.com/photos/1.0/image/";
+
+        i(P((G_IMAGE, "Data")), Kind::Simple(Prim::Text));
+        i(P((G_IMAGE, "Mime")), types::MIME_TYPE);
+    }
+
+    /*
+     * Google Photo Sphere namespace
+     *
+     * "Photo Sphere XMP Metadata [...] describes how to display a
+     * panorama."
+     */
+    {
+        const G_PANO: &str = "http://ns.google.com/photos/1.0/panorama/";
+
+        i(P((G_PANO, "CaptureSoftware")), types::AGENT_NAME);
+        i(
+            P((G_PANO, "CroppedAreaImageHeightPixels")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(
+            P((G_PANO, "CroppedAreaImageWidthPixels")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(
+            P((G_PANO, "CroppedAreaLeftPixels")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(
+            P((G_PANO, "CroppedAreaTopPixels")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(P((G_PANO, "ExposureLockUsed")), Kind::Simple(Prim::Boolean));
+        i(P((G_PANO, "FirstPhotoDate")), Kind::Simple(Prim::Date));
+        i(
+            P((G_PANO, "FullPanoHeightPixels")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(
+            P((G_PANO, "FullPanoWidthPixels")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(P((G_PANO, "InitialCameraDolly")), Kind::Simple(Prim::Real));
+        i(
+            P((G_PANO, "InitialHorizontalFOVDegrees")),
+            Kind::Simple(Prim::Real),
+        );
+        i(
+            P((G_PANO, "InitialVerticalFOVDegrees")),
+            Kind::Simple(Prim::Real),
+        );
+        i(
+            P((G_PANO, "InitialViewHeadingDegrees")),
+            Kind::Simple(Prim::Real),
+        );
+        i(
+            P((G_PANO, "InitialViewPitchDegrees")),
+            Kind::Simple(Prim::Real),
+        );
+        i(
+            P((G_PANO, "InitialViewRollDegrees")),
+            Kind::Simple(Prim::Real),
+        );
+        i(P((G_PANO, "LastPhotoDate")), Kind::Simple(Prim::Date));
+        i(P((G_PANO, "PoseHeadingDegrees")), Kind::Simple(Prim::Real));
+        i(P((G_PANO, "PosePitchDegrees")), Kind::Simple(Prim::Real));
+        i(P((G_PANO, "PoseRollDegrees")), Kind::Simple(Prim::Real));
+        i(P((G_PANO, "ProjectionType")), Kind::Simple(Prim::Text));
+        i(
+            P((G_PANO, "SourcePhotosCount")),
+            Kind::Simple(Prim::Integer),
+        );
+        i(P((G_PANO, "StitchingSoftware")), types::AGENT_NAME);
+        i(
+            P((G_PANO, "UsePanoramaViewer")),
+            Kind::Simple(Prim::Boolean),
+        );
+    }
+
     // ...
 }
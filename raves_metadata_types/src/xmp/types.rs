@@ -17,6 +17,54 @@ pub const ANCESTOR: Kind = Kind::Struct(&[Field {
     },
     ty: &URI,
 }]);
+pub const AREA: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://ns.adobe.com/xmp/sType/Area#";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "x",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "y",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "w",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "h",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "d",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "unit",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+    ]
+});
 pub const BEAT_SPLICE_STRETCH: Kind = Kind::Struct(&[
     Field {
         ident: Ident::Namespaced {
@@ -248,6 +296,65 @@ pub const CONTACT_INFO: Kind = Kind::Struct({
         },
     ]
 });
+/// One entry in a Google `Container:Directory`.
+///
+/// Each entry only wraps a `Container:Item`.
+pub const CONTAINER_DIRECTORY_ENTRY: Kind = Kind::Struct(&[Field {
+    ident: Ident::Namespaced {
+        field_name: "Item",
+        namespace: "http://ns.google.com/photos/1.0/container/",
+    },
+    ty: &CONTAINER_ITEM,
+}]);
+/// A media item stored in a Google container file (like a Motion Photo).
+pub const CONTAINER_ITEM: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://ns.google.com/photos/1.0/container/item/";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Mime",
+                namespace: NAMESPACE,
+            },
+            ty: &MIME_TYPE,
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Semantic",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Length",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Integer),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Padding",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Integer),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Label",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "URI",
+                namespace: NAMESPACE,
+            },
+            ty: &URI,
+        },
+    ]
+});
 pub const CUE_POINT_PARAM: Kind = Kind::Struct({
     const NAMESPACE: &str = "http://ns.adobe.com/xmp/1.0/DynamicMedia/";
     &[
@@ -317,6 +424,26 @@ pub const DIMENSIONS: Kind = Kind::Struct(&[
         ty: &Kind::Simple(Prim::Text),
     },
 ]);
+pub const ENTITY: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://iptc.org/std/Iptc4xmpExt/2008-02-29/";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Identifier",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::UnorderedArray(&URI),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Name",
+                namespace: NAMESPACE,
+            },
+            ty: &LANGUAGE_ALTERNATIVE,
+        },
+    ]
+});
 pub const FLASH: Kind = Kind::Struct({
     const NAMESPACE: &str = "http://ns.adobe.com/exif/1.0/";
 
@@ -428,6 +555,47 @@ pub const FRAME_RATE: Kind = Kind::StructUnspecifiedFields {
     required_fields: &[],
 };
 pub const GUID: Kind = Kind::Simple(Prim::Text);
+pub const IMAGE_REGION: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://iptc.org/std/Iptc4xmpExt/2008-02-29/";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "RegionBoundary",
+                namespace: NAMESPACE,
+            },
+            ty: &REGION_BOUNDARY,
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rId",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Name",
+                namespace: NAMESPACE,
+            },
+            ty: &LANGUAGE_ALTERNATIVE,
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rCtype",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::UnorderedArray(&ENTITY),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rRole",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::UnorderedArray(&ENTITY),
+        },
+    ]
+});
 pub const JOB: Kind = Kind::Struct(&[
     Field {
         ident: Ident::Namespaced {
@@ -451,6 +619,57 @@ pub const JOB: Kind = Kind::Struct(&[
         ty: &URL,
     },
 ]);
+pub const KEYWORD_INFO: Kind = Kind::Struct(&[Field {
+    ident: Ident::Namespaced {
+        field_name: "Hierarchy",
+        namespace: "http://www.metadataworkinggroup.com/schemas/keywords/",
+    },
+    ty: &Kind::UnorderedArray(&KEYWORD_STRUCT),
+}]);
+
+/// Makes the schema for a keyword with children nested a few levels deep.
+///
+/// Each keyword may hold more keywords as its children. A `Kind` can't refer
+/// to itself, though, as it's printed and compared recursively. So, we unroll
+/// the hierarchy once per token given. Anything deeper is parsed generically.
+macro_rules! keyword_struct {
+    () => {
+        Kind::Struct(&[KEYWORD_FIELD, KEYWORD_APPLIED_FIELD])
+    };
+    ($_level:tt $($rest:tt)*) => {
+        Kind::Struct(&[
+            KEYWORD_FIELD,
+            KEYWORD_APPLIED_FIELD,
+            Field {
+                ident: Ident::Namespaced {
+                    field_name: "Children",
+                    namespace: KEYWORD_NAMESPACE,
+                },
+                ty: &Kind::UnorderedArray(&keyword_struct!($($rest)*)),
+            },
+        ])
+    };
+}
+const KEYWORD_NAMESPACE: &str = "http://www.metadataworkinggroup.com/schemas/keywords/";
+const KEYWORD_FIELD: Field = Field {
+    ident: Ident::Namespaced {
+        field_name: "Keyword",
+        namespace: KEYWORD_NAMESPACE,
+    },
+    ty: &Kind::Simple(Prim::Text),
+};
+const KEYWORD_APPLIED_FIELD: Field = Field {
+    ident: Ident::Namespaced {
+        field_name: "Applied",
+        namespace: KEYWORD_NAMESPACE,
+    },
+    ty: &Kind::Simple(Prim::Boolean),
+};
+
+/// A node in a keyword hierarchy.
+///
+/// Children are typed up to eight levels down.
+pub const KEYWORD_STRUCT: Kind = keyword_struct!(_ _ _ _ _ _ _ _);
 /// note: The type isn't explicitly stated to be "Text" on this element,
 /// but we can safely assume so from real-world samples.
 #[doc(alias = "LANGUAGE_ALTERNATIVES")]
@@ -604,6 +823,60 @@ pub const MARKER: Kind = Kind::Struct({
         },
     ]
 });
+pub const MICROSOFT_REGION: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://ns.microsoft.com/photo/1.2/t/Region#";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Rectangle",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "PersonDisplayName",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "PersonEmailDigest",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "PersonLiveIdCID",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+    ]
+});
+pub const MICROSOFT_REGION_INFO: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://ns.microsoft.com/photo/1.2/t/RegionInfo#";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Regions",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::UnorderedArray(&MICROSOFT_REGION),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "DateRegionsValid",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Date),
+        },
+    ]
+});
 pub const MIME_TYPE: Kind = Kind::Simple(Prim::Text);
 /// Opto-electronic conversion function + spatial frequency response
 /// information.
@@ -665,6 +938,158 @@ pub const PROJECT_LINK: Kind = Kind::Struct({
 });
 pub const PROPER_NAME: Kind = Kind::Simple(Prim::Text);
 pub const RATIONAL: Kind = Kind::Simple(Prim::Text);
+pub const REGION_BOUNDARY: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://iptc.org/std/Iptc4xmpExt/2008-02-29/";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rbShape",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rbUnit",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rbX",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rbY",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rbW",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rbH",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rbRx",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "rbVertices",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::OrderedArray(&Kind::Struct(&[
+                Field {
+                    ident: Ident::Namespaced {
+                        field_name: "rbX",
+                        namespace: NAMESPACE,
+                    },
+                    ty: &Kind::Simple(Prim::Real),
+                },
+                Field {
+                    ident: Ident::Namespaced {
+                        field_name: "rbY",
+                        namespace: NAMESPACE,
+                    },
+                    ty: &Kind::Simple(Prim::Real),
+                },
+            ])),
+        },
+    ]
+});
+pub const REGION_INFO: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://www.metadataworkinggroup.com/schemas/regions/";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "AppliedToDimensions",
+                namespace: NAMESPACE,
+            },
+            ty: &DIMENSIONS,
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "RegionList",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::UnorderedArray(&REGION_STRUCT),
+        },
+    ]
+});
+pub const REGION_STRUCT: Kind = Kind::Struct({
+    const NAMESPACE: &str = "http://www.metadataworkinggroup.com/schemas/regions/";
+
+    &[
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Area",
+                namespace: NAMESPACE,
+            },
+            ty: &AREA,
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Type",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Name",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Description",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "FocusUsage",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "BarCodeValue",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Text),
+        },
+        Field {
+            ident: Ident::Namespaced {
+                field_name: "Rotation",
+                namespace: NAMESPACE,
+            },
+            ty: &Kind::Simple(Prim::Real),
+        },
+    ]
+});
 pub const RESAMPLE_STRETCH: Kind = Kind::Struct(&[Field {
     ident: Ident::Namespaced {
         field_name: "quality",